@@ -16,16 +16,27 @@ use crate::controller::{
     ControllerBackend, ControllerCapabilities, ControllerKind, ControllerResponse, RealtimeCommand,
 };
 use crate::gcode::file::GCodeFile;
+use crate::grbl::streamer::CharCountStreamer;
+use crate::controller::marlin::MarlinFramer;
+use crate::controller::detect::{FirmwareListener, ListenerStep};
+use crate::controller::faults::{self, FaultInfo, FaultKind, RecoveryCommand};
+use crate::config::profile_sync::{self, ProfileReader, SyncSource};
+use crate::serial::watchdog::{self, LinkWatchdog, PortIdentity, Reconnector};
+use crate::controller::jog::{self as continuous_jog, ContinuousJog, JogLimits, JogStop};
+use crate::controller::files::{self as controller_files, FileDialect, FileListing, UploadMethod, UploadSession, UploadStep};
+use crate::controller::interlock::{InterlockEvent, InterlockMonitor};
+use crate::grbl::wcs::{CoordSystem, NamedOffset, WcsTable};
 use crate::grbl::types::*;
 use crate::imaging;
 use crate::laser::driver::{
     DriverProgramSender, DriverValidationSeverity, LaserDriverProfile, available_driver_profiles,
-    create_driver, effective_driver_profile,
+    create_driver, create_export_driver, effective_driver_profile,
 };
 use crate::laser::job::{JobMetadata, LaserJob, LaserJobPart, RasterPartConfig};
-use crate::laser::pipeline::prepare_program;
+use crate::laser::pipeline::{prepare_machine_file, prepare_program};
 use crate::preview::renderer::PreviewRenderer;
 use crate::serial::connection::{self, SerialConnection, SerialMsg};
+use crate::serial::fluidnc::FileStore;
 use crate::theme;
 use crate::ui;
 use crate::ui::camera::CameraCalibration;
@@ -37,11 +48,16 @@ use crate::ui::preflight::{PreflightContext, PreflightReport, PreflightSeverity}
 
 struct RuntimeProgramSender<'a> {
     conn: &'a SerialConnection,
+    /// Marlin line numbering/checksums, when enabled for this job
+    framer: Option<&'a mut MarlinFramer>,
 }
 
 impl DriverProgramSender for RuntimeProgramSender<'_> {
     fn send_line(&mut self, line: &str) {
-        self.conn.send(line);
+        match self.framer.as_deref_mut() {
+            Some(framer) => self.conn.send(&framer.frame(line)),
+            None => self.conn.send(line),
+        }
     }
 }
 
@@ -65,6 +81,8 @@ fn play_notification_sound() {
     }
 }
 const STATUS_POLL_MS: u64 = 250;
+/// Quiet time after a profile edit before the job is re-planned
+const ESTIMATION_SETTLE: Duration = Duration::from_millis(300);
 const LEFT_PANEL_WIDTH: f32 = 280.0;
 
 #[derive(Debug, PartialEq, Clone, Copy, serde::Serialize, serde::Deserialize)]
@@ -91,6 +109,8 @@ pub struct All4LaserApp {
     connection_mode: ui::connection::ConnectionMode,
     network_host: String,
     network_port: String,
+    session_path: String,
+    record_session: bool,
 
     // GCode
     loaded_file: Option<GCodeFile>,
@@ -111,6 +131,11 @@ pub struct All4LaserApp {
     // Jog
     jog_step: f32,
     jog_feed: f32,
+    /// Jog buttons and arrow keys move while held instead of stepping
+    jog_continuous: bool,
+    continuous_jog: Option<ContinuousJog>,
+    jog_hold_key: Option<JogDirection>,
+    jog_hold_button: Option<JogDirection>,
 
     // Import Dialog
     import_state: Option<ui::image_dialog::ImageImportState>,
@@ -158,6 +183,8 @@ pub struct All4LaserApp {
 
     // Error Notification
     last_error: Option<String>,
+    /// Catalogue entry behind `last_error`, for cause/recovery and one-click actions
+    last_fault: Option<&'static FaultInfo>,
 
     // GCode Editor
     gcode_editor: ui::gcode_editor::GCodeEditorState,
@@ -197,9 +224,19 @@ pub struct All4LaserApp {
     framing_active: bool,
     framing_wait_idle: bool,
     framing_power: f32,
+    binary_job_active: bool,
+    binary_job_seen_run: bool,
+    streamer: CharCountStreamer,
+    marlin_framer: MarlinFramer,
 
     // Estimation
     estimation: crate::gcode::estimation::EstimationResult,
+    /// Corrections learned from this machine's finished jobs
+    calibration: crate::gcode::calibration::Calibration,
+    /// Re-plan once profile edits settle; a dragged limit changes on every frame
+    estimation_due: Option<Instant>,
+    job_clock: Option<crate::gcode::calibration::JobClock>,
+    estimate_report: ui::estimate_report::EstimateReportState,
 
     // Camera
     camera_state: ui::camera::CameraState,
@@ -256,6 +293,48 @@ pub struct All4LaserApp {
     // Update checker
     update_available: Option<String>,
     update_receiver: Option<crossbeam_channel::Receiver<String>>,
+
+    /// `$$` restore in progress (Save to Board or backup restore)
+    settings_restore: Option<crate::grbl::settings::SettingsRestore>,
+    /// Collects the reply to the identification query sent on connect
+    firmware_listener: Option<FirmwareListener>,
+    /// Lid/water interlock state from the `Pn:` inputs
+    interlocks: InterlockMonitor,
+    /// `$#` offsets and the active system from `$G`
+    wcs: WcsTable,
+    /// Read `$#` once the controller is idle after connecting
+    wcs_query_pending: bool,
+    /// Coordinate system the current job is pinned to
+    job_wcs: Option<CoordSystem>,
+    /// Last acknowledged runtime line of a job that stopped early (power loss, disconnect)
+    last_checkpoint: Option<usize>,
+    /// Plan of the running resume, to count the checkpoint in lines of the full job
+    resumed_from: Option<crate::gcode::resume::ResumePlan>,
+    /// Lines run ahead of the prepared job (air assist), left out of checkpoints
+    program_lead_in: usize,
+    resume_state: ui::resume::ResumeDialogState,
+    /// Controller kind the chosen link needs, while asking before switching the profile to it
+    controller_kind_prompt: Option<ControllerKind>,
+    file_browser: ui::file_browser::FileBrowserState,
+    profile_sync: ui::profile_sync::ProfileSyncState,
+    profile_reader: Option<ProfileReader>,
+    link_watchdog: LinkWatchdog,
+    /// Retry schedule while an unexpectedly lost link is being restored
+    reconnect: Option<Reconnector>,
+    /// Serial port of the current link, to find it again after a drop
+    port_identity: Option<PortIdentity>,
+    /// Log the controller state from the first status report after a reconnect
+    reconcile_pending: bool,
+    file_listing: Option<FileListing>,
+    file_upload: Option<UploadSession>,
+    /// HTTP upload in flight (FluidNC); finishes with a connection message
+    file_http_upload: bool,
+    /// Uploaded file and its expected size range, checked against the next listing
+    file_verify: Option<(String, (u64, u64))>,
+    // Controller auto-detect: (port, probe result) from the background probe
+    detect_receiver: Option<
+        crossbeam_channel::Receiver<(String, Result<crate::controller::detect::FirmwareInfo, String>)>,
+    >,
 }
 
 impl All4LaserApp {
@@ -275,6 +354,8 @@ impl All4LaserApp {
             connection_mode: ui::connection::ConnectionMode::Serial,
             network_host: "192.168.1.100".to_string(),
             network_port: "23".to_string(),
+            session_path: String::new(),
+            record_session: false,
             loaded_file: None,
             program_lines: std::sync::Arc::new(Vec::new()),
             prepared_program_lines: std::sync::Arc::new(Vec::new()),
@@ -287,6 +368,10 @@ impl All4LaserApp {
             console_state: ui::console::ConsoleState::default(),
             jog_step: 1.0,
             jog_feed: 1000.0,
+            jog_continuous: false,
+            continuous_jog: None,
+            jog_hold_key: None,
+            jog_hold_button: None,
             import_state: None,
             settings_state: None,
             preferences_state: ui::preferences::PreferencesState::default(),
@@ -312,6 +397,7 @@ impl All4LaserApp {
             notify_job_done: false,
             notify_sound_enabled: true,
             last_error: None,
+            last_fault: None,
             gcode_editor: ui::gcode_editor::GCodeEditorState::default(),
             shortcuts: ui::shortcuts::ShortcutsState::default(),
             preflight_state: ui::preflight::PreflightState::default(),
@@ -331,10 +417,18 @@ impl All4LaserApp {
             framing_active: false,
             framing_wait_idle: false,
             framing_power: 10.0,
+            binary_job_active: false,
+            binary_job_seen_run: false,
+            streamer: CharCountStreamer::default(),
+            marlin_framer: MarlinFramer::default(),
             last_poll: Instant::now(),
             feed_override_pct: 100.0,
             spindle_override_pct: 100.0,
             estimation: crate::gcode::estimation::EstimationResult::default(),
+            calibration: crate::gcode::calibration::Calibration::default(),
+            estimation_due: None,
+            job_clock: None,
+            estimate_report: ui::estimate_report::EstimateReportState::default(),
             camera_state: ui::camera::CameraState::default(),
             camera_live: CameraLiveState::default(),
             circular_array_state: ui::circular_array::CircularArrayState::default(),
@@ -362,6 +456,29 @@ impl All4LaserApp {
             about_open: false,
             update_available: None,
             update_receiver: None,
+            settings_restore: None,
+            firmware_listener: None,
+            interlocks: InterlockMonitor::default(),
+            wcs: WcsTable::default(),
+            wcs_query_pending: false,
+            job_wcs: None,
+            last_checkpoint: None,
+            resumed_from: None,
+            program_lead_in: 0,
+            controller_kind_prompt: None,
+            resume_state: ui::resume::ResumeDialogState::default(),
+            file_browser: ui::file_browser::FileBrowserState::default(),
+            profile_sync: ui::profile_sync::ProfileSyncState::default(),
+            profile_reader: None,
+            link_watchdog: LinkWatchdog::default(),
+            reconnect: None,
+            port_identity: None,
+            reconcile_pending: false,
+            file_listing: None,
+            file_upload: None,
+            file_http_upload: false,
+            file_verify: None,
+            detect_receiver: None,
         };
 
         // Update first layer color based on loaded theme
@@ -449,7 +566,7 @@ impl All4LaserApp {
             offset_x: self.job_transform.offset_x,
             offset_y: self.job_transform.offset_y,
             rotation_deg: self.job_transform.rotation,
-            machine_profile: Some(self.machine_profile.clone()),
+            machine_profile: Some(self.project_machine_profile()),
             camera_enabled: self.camera_state.enabled,
             camera_opacity: self.camera_state.opacity,
             camera_calibration: self.camera_state.calibration.clone(),
@@ -461,10 +578,11 @@ impl All4LaserApp {
                 .selected_preset_name()
                 .map(str::to_string),
             checkpoint_line: if self.running {
-                Some(self.program_index)
+                Some(self.checkpoint_line())
             } else {
-                None
+                self.last_checkpoint
             },
+            job_wcs: self.job_wcs,
             project_notes: self.project_notes.clone(),
             shapes: self.drawing_state.shapes.clone(),
             layers: self.layers.clone(),
@@ -472,6 +590,14 @@ impl All4LaserApp {
         }
     }
 
+    /// Machine settings as stored in a project; the measured job history stays with the machine
+    fn project_machine_profile(&self) -> MachineProfile {
+        MachineProfile {
+            job_timings: Vec::new(),
+            ..self.machine_profile.clone()
+        }
+    }
+
     fn apply_recovery(&mut self, recovery: crate::config::project::ProjectFile) {
         self.job_transform.offset_x = recovery.offset_x;
         self.job_transform.offset_y = recovery.offset_y;
@@ -484,6 +610,8 @@ impl All4LaserApp {
         }
         self.active_layer_idx = recovery.active_layer_idx.min(self.layers.len().saturating_sub(1));
         self.drawing_state.current.layer_idx = self.active_layer_idx;
+        // The checkpoint counts lines of the program as prepared for this system
+        self.job_wcs = recovery.job_wcs;
 
         // Restore editable drawing shapes
         if !recovery.shapes.is_empty() {
@@ -492,19 +620,21 @@ impl All4LaserApp {
             // Regenerate GCode from the restored shapes so preview matches
             self.regenerate_drawing_gcode();
             self.needs_auto_fit = true;
+            self.last_checkpoint = recovery.checkpoint_line;
         } else if let Some(content) = recovery.gcode_content {
             // Fallback: restore raw GCode if no shapes were saved (legacy recovery files)
             let lines: Vec<String> = content.lines().map(String::from).collect();
             let name = recovery.gcode_path.as_deref().unwrap_or("recovered");
             let file = crate::gcode::file::GCodeFile::from_lines(name, &lines);
             self.set_loaded_file(file, lines);
-            // If we have a checkpoint, set the program index so user can resume (F36)
-            if let Some(line) = recovery.checkpoint_line {
-                self.program_index = line.min(self.program_lines.len());
-                self.log(format!("Job checkpoint restored at line {}.", line));
-            }
+            self.last_checkpoint = recovery.checkpoint_line;
             self.needs_auto_fit = true;
         }
+        // Offer to continue the interrupted job from its checkpoint (F36)
+        if let Some(line) = self.last_checkpoint {
+            self.log(format!("Job checkpoint restored at line {}.", line + 1));
+            self.open_resume_dialog();
+        }
 
         crate::config::project::ProjectFile::clear_recovery();
         self.log("Session recovered from auto-save.".into());
@@ -685,7 +815,49 @@ impl All4LaserApp {
     }
 
     fn controller_capabilities(&self) -> ControllerCapabilities {
-        self.controller_backend.capabilities()
+        let caps = self.controller_backend.capabilities();
+        match &self.machine_profile.detected_firmware {
+            Some(fw) if fw.family.controller_kind() == self.machine_profile.controller_kind => {
+                caps.for_firmware(fw)
+            }
+            _ => caps,
+        }
+    }
+
+    fn feed_firmware_listener(&mut self, raw: &str) {
+        let Some(listener) = self.firmware_listener.as_mut() else {
+            return;
+        };
+        if listener.expired() {
+            self.firmware_listener = None;
+            return;
+        }
+        match listener.push(raw) {
+            ListenerStep::Waiting => {}
+            ListenerStep::Send(query) => {
+                if let Some(conn) = self.connection.as_ref() {
+                    conn.send(query);
+                }
+            }
+            ListenerStep::Identified(mut info) => {
+                self.firmware_listener = None;
+                if matches!(
+                    self.connection_mode,
+                    ui::connection::ConnectionMode::Serial | ui::connection::ConnectionMode::Rfc2217
+                ) {
+                    info.baud_rate =
+                        Some(ui::connection::get_baud(&self.baud_rates, self.selected_baud));
+                }
+                self.log(format!("Firmware: {}", info.summary()));
+                if self.machine_profile.detected_firmware.as_ref() != Some(&info) {
+                    self.machine_profile.detected_firmware = Some(info);
+                    self.save_active_machine_profile();
+                }
+            }
+            ListenerStep::Unknown => {
+                self.firmware_listener = None;
+            }
+        }
     }
 
     fn send_realtime_or_warn(&mut self, command: RealtimeCommand, action_label: &str) -> bool {
@@ -714,11 +886,17 @@ impl All4LaserApp {
     fn show_error(&mut self, msg: String) {
         self.log(format!("ERROR: {}", msg));
         self.last_error = Some(msg);
+        self.last_fault = None;
     }
 
     fn poll_serial(&mut self) {
-        // Poll for status periodically
-        if self.is_connected() && self.last_poll.elapsed() > Duration::from_millis(STATUS_POLL_MS) {
+        // Poll for status periodically; a line-polled firmware would write the query into an uploading file
+        let upload_blocks_poll = self.file_upload.is_some()
+            && self.controller_backend.realtime_byte(RealtimeCommand::StatusReport).is_none();
+        if self.is_connected()
+            && !upload_blocks_poll
+            && self.last_poll.elapsed() > Duration::from_millis(STATUS_POLL_MS)
+        {
             self.send_realtime(RealtimeCommand::StatusReport);
             self.last_poll = Instant::now();
         }
@@ -733,11 +911,18 @@ impl All4LaserApp {
         } else {
             Vec::new()
         };
+        if !msgs.is_empty() {
+            self.link_watchdog.note_traffic(Instant::now());
+        }
 
         for msg in msgs {
             match msg {
                 SerialMsg::Parsed { raw, response } => {
-                    self.log(raw);
+                    self.feed_firmware_listener(&raw);
+                    self.feed_file_listing(&raw);
+                    self.feed_profile_reader(&raw);
+                    self.wcs.apply_line(&raw);
+                    self.log(raw.clone());
                     match response {
                         ControllerResponse::Grbl(response) => match response {
                             GrblResponse::Status(state) => {
@@ -753,7 +938,47 @@ impl All4LaserApp {
                                         state.mpos.x, state.mpos.y,
                                     ));
                                 }
+                                let pins = state.pins;
                                 self.grbl_state = state;
+                                self.update_interlocks(pins);
+                                if !self.running
+                                    && let Some(jog) = self.continuous_jog.as_mut()
+                                {
+                                    let lines = jog.on_position(self.grbl_state.mpos);
+                                    if let Some(conn) = self.connection.as_ref() {
+                                        for line in &lines {
+                                            conn.send(line);
+                                        }
+                                    }
+                                }
+                                if std::mem::take(&mut self.reconcile_pending) {
+                                    self.reconcile_after_reconnect();
+                                }
+                                if self.wcs_query_pending
+                                    && self.firmware_listener.is_none()
+                                    && self.grbl_state.status == MacStatus::Idle
+                                    && !self.running
+                                {
+                                    self.query_work_offsets();
+                                }
+
+                                // Uploaded jobs finish when the controller drops back to idle.
+                                if self.running && self.binary_job_active {
+                                    if let Some(percent) = self.grbl_state.sd_percent {
+                                        let len = self.runtime_program_len();
+                                        self.program_index =
+                                            ((percent / 100.0) * len as f32).round().clamp(0.0, len as f32) as usize;
+                                    }
+                                    match self.grbl_state.status {
+                                        MacStatus::Run | MacStatus::Hold => {
+                                            self.binary_job_seen_run = true;
+                                        }
+                                        MacStatus::Idle if self.binary_job_seen_run => {
+                                            self.handle_program_completed();
+                                        }
+                                        _ => {}
+                                    }
+                                }
 
                                 if self.framing_active {
                                     if self.grbl_state.status == MacStatus::Run {
@@ -768,7 +993,52 @@ impl All4LaserApp {
                                 }
                             }
                             GrblResponse::Ok => {
-                                if self.running && self.program_index < self.runtime_program_len() {
+                                if !self.running
+                                    && let Some(restore) = self.settings_restore.as_mut()
+                                {
+                                    let step = restore.on_ok();
+                                    self.handle_settings_restore_step(step);
+                                } else if !self.running
+                                    && let Some(upload) = self.file_upload.as_mut()
+                                {
+                                    let step = upload.on_ok();
+                                    self.handle_file_upload_step(step);
+                                } else if !self.running
+                                    && let Some(jog) = self.continuous_jog.as_mut()
+                                {
+                                    let lines = jog.on_ok();
+                                    if let Some(conn) = self.connection.as_ref() {
+                                        for line in &lines {
+                                            conn.send(line);
+                                        }
+                                    }
+                                } else if self.running
+                                    && self.marlin_framing_active()
+                                    && self.marlin_framer.acknowledge_reset()
+                                {
+                                    // The M110 is through; line 1 can go
+                                    self.send_next_program_line();
+                                } else if self.running
+                                    && self.marlin_framing_active()
+                                    && let Some(line) = self.marlin_framer.next_resend()
+                                {
+                                    self.log(format!("[RESEND] {line}"));
+                                    if let Some(conn) = self.connection.as_ref() {
+                                        conn.send(&line);
+                                    }
+                                } else if self.binary_job_active {
+                                    // Uploaded jobs report progress through status reports
+                                } else if self.char_counting_active() {
+                                    self.streamer.acknowledge();
+                                    if self.running && self.program_index < self.runtime_program_len() {
+                                        self.fill_stream_buffer();
+                                    } else if self.send_waiting_commands()
+                                        && self.running
+                                        && self.streamer.is_idle()
+                                    {
+                                        self.handle_program_completed();
+                                    }
+                                } else if self.running && self.program_index < self.runtime_program_len() {
                                     self.send_next_program_line();
                                 } else if self.running
                                     && self.program_index >= self.runtime_program_len()
@@ -777,12 +1047,44 @@ impl All4LaserApp {
                                 }
                             }
                             GrblResponse::Error(code) => {
-                                if self.running {
-                                    self.handle_program_failed(format!("Controller error:{code}"));
+                                let fault = self.describe_fault(FaultKind::Error, code, &raw);
+                                if let Some(info) = fault {
+                                    self.log(format!("[ERROR] {} - {}", info.summary(), info.cause));
+                                }
+                                let error = fault
+                                    .map(FaultInfo::summary)
+                                    .unwrap_or_else(|| format!("Controller error:{code}"));
+                                if !self.running && (self.file_upload.is_some() || self.file_http_upload) {
+                                    self.abort_file_upload(error);
+                                } else if !self.running
+                                    && let Some(restore) = self.settings_restore.as_mut()
+                                {
+                                    let step = restore.on_error(code);
+                                    self.handle_settings_restore_step(step);
+                                } else if !self.running
+                                    && let Some(jog) = self.continuous_jog.as_mut()
+                                {
+                                    jog.on_error();
+                                } else if self.char_counting_active() {
+                                    let failed_line = self.streamer.acknowledge().flatten();
+                                    if self.running {
+                                        self.handle_stream_error(error, failed_line);
+                                        self.last_fault = fault;
+                                    }
+                                } else if self.running {
+                                    self.handle_program_failed(error);
+                                    self.last_fault = fault;
                                 }
                             }
                             GrblResponse::Alarm(code) => {
-                                self.handle_program_failed(format!("ALARM:{code}"));
+                                let fault = self.describe_fault(FaultKind::Alarm, code, &raw);
+                                if let Some(info) = fault {
+                                    self.log(format!("[ALARM] {} - {}", info.summary(), info.cause));
+                                }
+                                self.handle_program_failed(
+                                    fault.map(FaultInfo::summary).unwrap_or_else(|| format!("ALARM:{code}")),
+                                );
+                                self.last_fault = fault;
                             }
                             GrblResponse::GrblVersion(ver) => {
                                 self.log(format!("Grbl {ver}"));
@@ -807,126 +1109,249 @@ impl All4LaserApp {
                                 }
                             }
                             GrblResponse::Setting(id, val) => {
-                                if let Some(state) = &mut self.settings_state {
-                                    if state.is_open {
-                                        state.settings.insert(id, val);
-                                    }
+                                if let Some(restore) = self.settings_restore.as_mut() {
+                                    restore.on_setting(id, &val);
+                                }
+                                if let Some(state) = &mut self.settings_state
+                                    && state.is_open
+                                {
+                                    state.settings.insert(id, val);
                                 }
                             }
                             GrblResponse::Message(msg) => {
                                 self.log(format!("[MSG] {msg}"));
                             }
                         },
+                        ControllerResponse::Resend(line) => {
+                            if self.running
+                                && self.marlin_framing_active()
+                                && let Err(err) = self.marlin_framer.on_resend(line)
+                            {
+                                self.handle_program_failed(err);
+                            }
+                        }
+                        ControllerResponse::Busy => {
+                            // Long moves (G28, G4, M400) answer busy instead of ok; keep waiting.
+                            if self.running {
+                                self.grbl_state.status = MacStatus::Run;
+                            }
+                        }
                         ControllerResponse::Message => {}
                     }
                 }
                 SerialMsg::Connected(port) => {
                     self.grbl_state.status = MacStatus::Idle;
                     self.log(format!("Connected to {port}"));
-                    // Auto-detect firmware (F47): send $I for GRBL identification
-                    if let Some(conn) = self.connection.as_ref() {
-                        conn.send("$I");
+                    // Auto-detect firmware (F47): $I / M115 identify the build and its options
+                    if let Some(query) =
+                        FirmwareListener::query(self.machine_profile.controller_kind)
+                        && let Some(conn) = self.connection.as_ref()
+                    {
+                        conn.send(query);
+                        self.firmware_listener = Some(FirmwareListener::new());
+                    }
+                    self.wcs_query_pending =
+                        self.machine_profile.controller_kind == ControllerKind::Grbl;
+                    if let Some(retry) = self.reconnect.take() {
+                        self.log(format!(
+                            "Reconnected after {} attempt(s) (lost: {}).",
+                            retry.attempts, retry.reason
+                        ));
+                        self.reconcile_pending = true;
+                        if self.last_checkpoint.is_some() && !self.program_lines.is_empty() {
+                            self.log("The job was interrupted - choose where to resume.".to_string());
+                            self.open_resume_dialog();
+                        }
                     }
                 }
                 SerialMsg::Disconnected(reason) => {
-                    if self.running {
-                        self.handle_program_failed(format!("Disconnected: {reason}"));
-                    }
-                    self.connection = None;
-                    self.grbl_state = GrblState::default();
-                    self.running = false;
-                    self.is_dry_run = false;
-                    self.framing_active = false;
-                    self.log(format!("Disconnected: {reason}"));
+                    self.handle_link_lost(reason);
                 }
                 SerialMsg::Error(err) => {
                     self.log(format!("Serial error: {err}"));
                 }
+                SerialMsg::UploadProgress { sent, total } => {
+                    // The controller runs the file as it arrives, so the upload is the progress
+                    if self.running && self.binary_job_active && total > 0 {
+                        let len = self.runtime_program_len();
+                        self.program_index = (len * sent / total).min(len);
+                    }
+                }
             }
         }
     }
 
     fn send_next_program_line(&mut self) {
+        if self.char_counting_active() {
+            self.fill_stream_buffer();
+            return;
+        }
         while self.program_index < self.runtime_program_len() {
             let line_idx = self.program_index;
             self.program_index += 1;
 
-            let mut cmd = if !self.prepared_program_lines.is_empty() {
-                self.prepared_program_lines[line_idx].clone()
-            } else if let (Some(file), Some(center)) =
-                (&self.loaded_file, self.job_transform.center)
-            {
-                if let Some(parsed) = file.lines.get(line_idx) {
-                    // Standard transform (offset/rotate)
-                    let transformed = parsed.transform(
-                        egui::vec2(self.job_transform.offset_x, self.job_transform.offset_y),
-                        self.job_transform.rotation,
-                        center,
-                        1.0,
-                    );
+            let Some(cmd) = self.runtime_command_at(line_idx) else {
+                continue;
+            };
+            self.transmit_program_line(&cmd);
+            return;
+        }
+    }
 
-                    // Apply Rotary transformation if enabled
-                    if self.machine_profile.rotary_enabled {
-                        crate::gcode::transform::apply_rotary(
-                            &transformed,
-                            self.machine_profile.rotary_diameter_mm,
-                            self.machine_profile.rotary_axis,
-                        )
-                    } else {
-                        transformed
-                    }
+    /// Send out-of-band commands queued during a character-counting stream as they fit;
+    /// false while some still wait
+    fn send_waiting_commands(&mut self) -> bool {
+        if let Some(conn) = self.connection.as_ref() {
+            while let Some(line) = self.streamer.next_waiting() {
+                conn.send(&line);
+            }
+        }
+        !self.streamer.has_waiting()
+    }
+
+    /// Character-counting streaming: send as many lines as fit in GRBL's RX buffer
+    fn fill_stream_buffer(&mut self) {
+        if !self.send_waiting_commands() {
+            return;
+        }
+        while self.running && self.program_index < self.runtime_program_len() {
+            let line_idx = self.program_index;
+            let Some(cmd) = self.runtime_command_at(line_idx) else {
+                self.program_index += 1;
+                continue;
+            };
+            if !self.streamer.can_send(&cmd) {
+                break;
+            }
+            self.program_index += 1;
+            self.streamer.push(line_idx, &cmd);
+            if !self.transmit_program_line(&cmd) {
+                break;
+            }
+        }
+    }
+
+    fn marlin_framing_active(&self) -> bool {
+        self.machine_profile.controller_kind == ControllerKind::Marlin
+            && self.machine_profile.marlin_line_checksums
+            && !self.binary_job_active
+    }
+
+    fn char_counting_active(&self) -> bool {
+        self.machine_profile.controller_kind == ControllerKind::Grbl
+            && self.machine_profile.grbl_char_counting
+            && !self.binary_job_active
+    }
+
+    /// Program line as it goes on the wire (job transform, rotary and dry-run applied),
+    /// or None for blank and comment lines
+    fn runtime_command_at(&self, line_idx: usize) -> Option<String> {
+        let mut cmd = if !self.prepared_program_lines.is_empty() {
+            self.prepared_program_lines[line_idx].clone()
+        } else if let (Some(file), Some(center)) =
+            (&self.loaded_file, self.job_transform.center)
+        {
+            if let Some(line) = file.lines.get(line_idx) {
+                // Standard transform (offset/rotate)
+                let transformed = crate::gcode::parser::parse_line(line).transform(
+                    egui::vec2(self.job_transform.offset_x, self.job_transform.offset_y),
+                    self.job_transform.rotation,
+                    center,
+                    1.0,
+                );
+
+                // Apply Rotary transformation if enabled
+                if self.machine_profile.rotary_enabled {
+                    crate::gcode::transform::apply_rotary(
+                        &transformed,
+                        self.machine_profile.rotary_diameter_mm,
+                        self.machine_profile.rotary_axis,
+                    )
                 } else {
-                    self.program_lines[line_idx].clone()
+                    transformed
                 }
             } else {
                 self.program_lines[line_idx].clone()
-            };
-
-            // Dry Run: Replace M3/M4 with M5
-            if self.is_dry_run {
-                if cmd.contains("M3") || cmd.contains("M4") {
-                    cmd = cmd.replace("M3", "M5").replace("M4", "M5");
-                }
-                // Strip S parameter if present to be safe, though M5 ignores it
-                // Simple string replacement might be brittle, but sufficient for now.
             }
+        } else {
+            self.program_lines[line_idx].clone()
+        };
 
-            let trimmed = cmd.trim().to_string();
-            if trimmed.is_empty() || trimmed.starts_with(';') || trimmed.starts_with('(') {
-                continue;
-            }
-            self.log(format!(
-                "[TX:{}/{}] {}",
-                self.program_index,
-                self.runtime_program_len(),
-                trimmed
-            ));
-            if let Some(conn) = self.connection.as_ref() {
-                let lines = vec![trimmed.clone()];
-                let send_result = match create_driver(
-                    self.machine_profile.controller_kind,
-                    self.machine_profile.laser_driver_profile,
-                ) {
-                    Ok(driver) => {
-                        let mut sender = RuntimeProgramSender { conn };
-                        driver.send_program(&lines, &mut sender)
-                    }
-                    Err(_) => {
-                        conn.send(&trimmed);
-                        Ok(())
-                    }
-                };
+        // Dry Run: Replace M3/M4 with M5
+        if self.is_dry_run {
+            cmd = crate::gcode::transform::laser_off(&cmd);
+        }
 
-                if let Err(err) = send_result {
-                    self.handle_program_failed(format!("Driver send failed: {err}"));
-                    return;
+        let trimmed = cmd.trim().to_string();
+        if trimmed.is_empty() || trimmed.starts_with(';') || trimmed.starts_with('(') {
+            return None;
+        }
+        Some(trimmed)
+    }
+
+    /// Hand one program line to the driver; false if the job had to be failed
+    fn transmit_program_line(&mut self, trimmed: &str) -> bool {
+        self.log(format!(
+            "[TX:{}/{}] {}",
+            self.program_index,
+            self.runtime_program_len(),
+            trimmed
+        ));
+        let framing = self.marlin_framing_active();
+        if let Some(conn) = self.connection.as_ref() {
+            let lines = vec![trimmed.to_string()];
+            let send_result = match create_driver(
+                self.machine_profile.controller_kind,
+                self.machine_profile.laser_driver_profile,
+            ) {
+                Ok(driver) => {
+                    let mut sender = RuntimeProgramSender {
+                        conn,
+                        framer: framing.then_some(&mut self.marlin_framer),
+                    };
+                    driver.send_program(&lines, &mut sender)
                 }
+                Err(_) => {
+                    conn.send(trimmed);
+                    Ok(())
+                }
+            };
+
+            if let Err(err) = send_result {
+                self.handle_program_failed(format!("Driver send failed: {err}"));
+                return false;
             }
-            return;
         }
+        true
     }
 
     fn connect(&mut self) {
+        self.reconnect = None;
+        if let Some(result) = self.open_connection(true) {
+            self.finish_connect(result);
+        }
+    }
+
+    /// The link only talks to `kind` controllers: warn and ask before switching the saved
+    /// profile instead of changing it behind the user's back
+    fn link_needs_kind(&mut self, kind: ControllerKind) -> bool {
+        let current = self.machine_profile.controller_kind;
+        if current == kind {
+            return false;
+        }
+        self.log(format!(
+            "[CONNECT] This link needs a {} controller but the machine profile is set to {}.",
+            kind.label(),
+            current.label()
+        ));
+        self.controller_kind_prompt = Some(kind);
+        self.grbl_state.status = MacStatus::Disconnected;
+        true
+    }
+
+    /// Open the configured link; None when it failed validation (already reported) or
+    /// handed off to controller detection
+    fn open_connection(&mut self, allow_detection: bool) -> Option<Result<SerialConnection, String>> {
         self.grbl_state.status = MacStatus::Connecting;
 
         let result = match self.connection_mode {
@@ -936,10 +1361,15 @@ impl All4LaserApp {
                     None => {
                         self.show_error("No port selected".to_string());
                         self.grbl_state.status = MacStatus::Disconnected;
-                        return;
+                        return None;
                     }
                 };
+                self.port_identity = Some(PortIdentity::capture(&port, &watchdog::available_ports()));
                 let baud = ui::connection::get_baud(&self.baud_rates, self.selected_baud);
+                if allow_detection && self.machine_profile.auto_detect_controller {
+                    self.start_controller_detection(port, baud);
+                    return None;
+                }
                 self.log(format!("Connecting to {port} @ {baud}…"));
                 SerialConnection::connect(&port, baud, self.controller_backend.clone())
             }
@@ -950,17 +1380,106 @@ impl All4LaserApp {
                     Err(_) => {
                         self.show_error("Invalid network port (must be 1..65535).".to_string());
                         self.grbl_state.status = MacStatus::Disconnected;
-                        return;
+                        return None;
                     }
                 };
                 self.log(format!("Connecting to tcp://{host}:{port}…"));
                 SerialConnection::connect_tcp(&host, port, self.controller_backend.clone())
             }
+            ui::connection::ConnectionMode::Rfc2217 => {
+                let host = self.network_host.trim().to_string();
+                let port = match self.network_port.trim().parse::<u16>() {
+                    Ok(v) => v,
+                    Err(_) => {
+                        self.show_error("Invalid network port (must be 1..65535).".to_string());
+                        self.grbl_state.status = MacStatus::Disconnected;
+                        return None;
+                    }
+                };
+                let baud = ui::connection::get_baud(&self.baud_rates, self.selected_baud);
+                self.log(format!("Connecting to rfc2217://{host}:{port} @ {baud}…"));
+                SerialConnection::connect_rfc2217(&host, port, baud, self.controller_backend.clone())
+            }
+            ui::connection::ConnectionMode::FluidNc => {
+                let host = self.network_host.trim().to_string();
+                let port = match self.network_port.trim().parse::<u16>() {
+                    Ok(v) => v,
+                    Err(_) => {
+                        self.show_error("Invalid network port (must be 1..65535).".to_string());
+                        self.grbl_state.status = MacStatus::Disconnected;
+                        return None;
+                    }
+                };
+                if self.link_needs_kind(ControllerKind::Grbl) {
+                    return None;
+                }
+                let config = crate::serial::fluidnc::FluidNcConfig::for_http_port(
+                    port,
+                    self.machine_profile.fluidnc_store,
+                );
+                self.log(format!("Connecting to ws://{host}:{}…", config.ws_port));
+                SerialConnection::connect_fluidnc(&host, config, self.controller_backend.clone())
+            }
+            ui::connection::ConnectionMode::RuidaUdp => {
+                let host = self.network_host.trim().to_string();
+                let port = match self.network_port.trim().parse::<u16>() {
+                    Ok(v) => v,
+                    Err(_) => {
+                        self.show_error("Invalid network port (must be 1..65535).".to_string());
+                        self.grbl_state.status = MacStatus::Disconnected;
+                        return None;
+                    }
+                };
+                if self.link_needs_kind(ControllerKind::Ruida) {
+                    return None;
+                }
+                self.log(format!("Connecting to udp://{host}:{port}…"));
+                SerialConnection::connect_ruida_udp(
+                    &host,
+                    port,
+                    crate::ruida::udp::RuidaUdpConfig::default(),
+                )
+            }
+            ui::connection::ConnectionMode::Virtual => {
+                if self.link_needs_kind(ControllerKind::Grbl) {
+                    return None;
+                }
+                self.log("Starting virtual GRBL machine…".to_string());
+                SerialConnection::connect_virtual(
+                    crate::grbl::sim::VirtualGrblConfig::from_profile(&self.machine_profile),
+                    self.controller_backend.clone(),
+                )
+            }
+            ui::connection::ConnectionMode::Replay => {
+                let path = std::path::PathBuf::from(self.session_path.trim());
+                self.log(format!("Replaying session {}…", path.display()));
+                crate::serial::session::load_session(&path).and_then(|records| {
+                    SerialConnection::connect_replay(
+                        records,
+                        self.controller_backend.clone(),
+                        true,
+                    )
+                })
+            }
         };
 
+        Some(result)
+    }
+
+    fn finish_connect(&mut self, result: Result<SerialConnection, String>) {
         match result {
             Ok(conn) => {
+                if self.record_session
+                    && self.connection_mode != ui::connection::ConnectionMode::Replay
+                {
+                    let path = crate::serial::session::default_session_path();
+                    match conn.start_recording(&path) {
+                        Ok(()) => self.log(format!("Recording session to {}", path.display())),
+                        Err(e) => self.show_error(format!("Session recording failed: {e}")),
+                    }
+                }
                 self.connection = Some(conn);
+                self.link_watchdog = LinkWatchdog::default();
             }
             Err(e) => {
                 self.grbl_state.status = MacStatus::Disconnected;
@@ -969,43 +1488,248 @@ impl All4LaserApp {
         }
     }
 
-    fn test_network_target(&mut self) {
-        let host = self.network_host.trim().to_string();
-        if host.is_empty() {
-            self.show_error("Network host is empty.".to_string());
-            return;
+    /// The link dropped without the user disconnecting: clean up and, if enabled, start retrying
+    fn handle_link_lost(&mut self, reason: String) {
+        if self.running {
+            self.handle_program_failed(format!("Disconnected: {reason}"));
+        }
+        self.connection = None;
+        self.firmware_listener = None;
+        self.settings_restore = None;
+        self.reset_file_transfers();
+        self.continuous_jog = None;
+        if self.profile_reader.take().is_some() {
+            self.profile_sync.set_error("The connection closed before the controller answered.".to_string());
+        }
+        self.interlocks.reset();
+        self.wcs = WcsTable::default();
+        self.wcs_query_pending = false;
+        if let Some(state) = &mut self.settings_state {
+            state.busy = false;
+        }
+        self.grbl_state = GrblState::default();
+        self.running = false;
+        self.is_dry_run = false;
+        self.framing_active = false;
+        self.log(format!("Disconnected: {reason}"));
+
+        let retryable = matches!(
+            self.connection_mode,
+            ui::connection::ConnectionMode::Serial
+                | ui::connection::ConnectionMode::Network
+                | ui::connection::ConnectionMode::Rfc2217
+                | ui::connection::ConnectionMode::FluidNc
+        );
+        if self.machine_profile.auto_reconnect && retryable {
+            self.log("Link lost - reconnecting…".to_string());
+            self.grbl_state.status = MacStatus::Connecting;
+            self.reconnect = Some(Reconnector::new(reason, Instant::now()));
         }
+    }
 
-        let port = match self.network_port.trim().parse::<u16>() {
-            Ok(v) => v,
-            Err(_) => {
-                self.show_error("Invalid network port (must be 1..65535).".to_string());
-                return;
+    /// Watch the live link for stalls and vanished ports, and retry a lost one
+    fn supervise_link(&mut self) {
+        let now = Instant::now();
+        if self.connection.is_some() {
+            if let Some(reason) = self.link_failure(now) {
+                if let Some(conn) = self.connection.take() {
+                    conn.disconnect();
+                }
+                self.handle_link_lost(reason);
             }
+            return;
+        }
+        let Some(retry) = self.reconnect.as_mut() else {
+            return;
         };
-
-        let addr_text = format!("{host}:{port}");
-        let socket_addr = match addr_text.to_socket_addrs() {
-            Ok(mut iter) => match iter.next() {
-                Some(addr) => addr,
-                None => {
-                    self.show_error(format!("Could not resolve host: {addr_text}"));
+        if !retry.attempt_due(now) {
+            return;
+        }
+        let attempt = retry.attempts;
+        match self.reopen_link() {
+            Ok(conn) => {
+                self.log(format!("Reconnect attempt {attempt}: link is back."));
+                self.finish_connect(Ok(conn));
+            }
+            Err(err) => {
+                let Some(retry) = self.reconnect.as_mut() else {
                     return;
+                };
+                if retry.failed(now) {
+                    self.grbl_state.status = MacStatus::Connecting;
+                    self.log(format!(
+                        "Reconnect attempt {attempt}/{} failed: {err}",
+                        watchdog::MAX_ATTEMPTS
+                    ));
+                } else {
+                    self.reconnect = None;
+                    self.grbl_state.status = MacStatus::Disconnected;
+                    self.show_error(format!("Could not reconnect after {attempt} attempts: {err}"));
                 }
-            },
-            Err(e) => {
-                self.show_error(format!("Invalid host or port '{addr_text}': {e}"));
-                return;
             }
-        };
+        }
+    }
 
-        match TcpStream::connect_timeout(&socket_addr, Duration::from_millis(1200)) {
-            Ok(stream) => {
-                let _ = stream.shutdown(std::net::Shutdown::Both);
-                self.log(format!("Network test OK: tcp://{addr_text}"));
+    fn link_failure(&mut self, now: Instant) -> Option<String> {
+        let polled = self
+            .controller_backend
+            .realtime_byte(RealtimeCommand::StatusReport)
+            .is_some()
+            || self
+                .controller_backend
+                .realtime_line(RealtimeCommand::StatusReport)
+                .is_some();
+        let watched = matches!(
+            self.connection_mode,
+            ui::connection::ConnectionMode::Serial
+                | ui::connection::ConnectionMode::Network
+                | ui::connection::ConnectionMode::Rfc2217
+                | ui::connection::ConnectionMode::FluidNc
+        );
+        if watched
+            && polled
+            && !self.uploading_to_controller()
+            && let Some(quiet) = self.link_watchdog.stalled(now)
+        {
+            if self.machine_profile.auto_reconnect {
+                return Some(format!("no response for {} s", quiet.as_secs()));
             }
-            Err(e) => {
-                self.show_error(format!("Network test failed for tcp://{addr_text}: {e}"));
+            // Nothing would bring the link back, and a long dwell or homing cycle can be this quiet
+            if self.link_watchdog.report_stall() {
+                self.log(format!(
+                    "⚠ No response for {} s; staying connected (auto-reconnect is off).",
+                    quiet.as_secs()
+                ));
+            }
+        }
+        if self.connection_mode == ui::connection::ConnectionMode::Serial
+            && self.link_watchdog.port_check_due(now)
+            && let Some(identity) = &self.port_identity
+            && !port_present(identity)
+        {
+            return Some(format!("{} disappeared", identity.path));
+        }
+        None
+    }
+
+    /// An upload is in flight and the controller may stay silent until it completes
+    fn uploading_to_controller(&self) -> bool {
+        self.file_upload.is_some() || self.file_http_upload || (self.binary_job_active && !self.binary_job_seen_run)
+    }
+
+    /// Open the same link again without prompts; serial ports are found by USB serial number
+    fn reopen_link(&mut self) -> Result<SerialConnection, String> {
+        if self.connection_mode == ui::connection::ConnectionMode::Serial {
+            let identity = self.port_identity.clone().ok_or("no port recorded")?;
+            let path = identity
+                .locate(&watchdog::available_ports())
+                .or_else(|| port_present(&identity).then(|| identity.path.clone()))
+                .ok_or_else(|| format!("{} is not present", identity.path))?;
+            self.ports = connection::list_ports();
+            if !self.ports.contains(&path) {
+                self.ports.push(path.clone());
+            }
+            self.selected_port = self.ports.iter().position(|p| *p == path).unwrap_or(0);
+        }
+        self.open_connection(false)
+            .unwrap_or_else(|| Err("connection settings are incomplete".to_string()))
+    }
+
+    /// First status report after a reconnect: say what state the controller came back in
+    fn reconcile_after_reconnect(&mut self) {
+        let state = &self.grbl_state;
+        let advice = match state.status {
+            MacStatus::Alarm => " - it was reset; unlock or home before resuming",
+            MacStatus::Run | MacStatus::Hold | MacStatus::Jog => {
+                " - it is still executing buffered moves; let them finish or stop before resuming"
+            }
+            _ => "",
+        };
+        self.log(format!(
+            "[RECONNECT] Controller reports {} at X{:.2} Y{:.2}{advice}",
+            state.status, state.mpos.x, state.mpos.y
+        ));
+    }
+
+    fn handle_line_control_action(&mut self, action: &ui::connection::ConnectionAction) {
+        let Some(conn) = self.connection.as_ref() else {
+            return;
+        };
+        let baud = ui::connection::get_baud(&self.baud_rates, self.selected_baud);
+        if action.baud_changed {
+            conn.set_baud_rate(baud);
+        }
+        if action.reset_board {
+            conn.reset_board();
+        }
+        if action.baud_changed {
+            self.log(format!("Baud rate set to {baud}."));
+        }
+        if action.reset_board {
+            self.log("Resetting board (DTR/RTS pulse)…".to_string());
+        }
+    }
+
+    fn test_network_target(&mut self) {
+        let host = self.network_host.trim().to_string();
+        if host.is_empty() {
+            self.show_error("Network host is empty.".to_string());
+            return;
+        }
+
+        let port = match self.network_port.trim().parse::<u16>() {
+            Ok(v) => v,
+            Err(_) => {
+                self.show_error("Invalid network port (must be 1..65535).".to_string());
+                return;
+            }
+        };
+
+        let addr_text = format!("{host}:{port}");
+        let socket_addr = match addr_text.to_socket_addrs() {
+            Ok(mut iter) => match iter.next() {
+                Some(addr) => addr,
+                None => {
+                    self.show_error(format!("Could not resolve host: {addr_text}"));
+                    return;
+                }
+            },
+            Err(e) => {
+                self.show_error(format!("Invalid host or port '{addr_text}': {e}"));
+                return;
+            }
+        };
+
+        match TcpStream::connect_timeout(&socket_addr, Duration::from_millis(1200)) {
+            Ok(stream) => {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+                self.log(format!("Network test OK: tcp://{addr_text}"));
+            }
+            Err(e) => {
+                self.show_error(format!("Network test failed for tcp://{addr_text}: {e}"));
+            }
+        }
+    }
+
+    /// Hold on a tripped interlock and record every trip/clear in the event log
+    fn update_interlocks(&mut self, pins: crate::grbl::types::InputPins) {
+        for event in self.interlocks.update(&self.machine_profile, pins) {
+            match event {
+                InterlockEvent::Tripped(interlock) => {
+                    let moving = self.running || self.framing_active;
+                    if moving {
+                        self.send_realtime(RealtimeCommand::FeedHold);
+                    }
+                    self.log(format!(
+                        "[INTERLOCK] {} tripped (Pn:{}){}",
+                        interlock.label(),
+                        pins.active_letters(),
+                        if moving { " - feed hold sent" } else { "" }
+                    ));
+                }
+                InterlockEvent::Cleared(interlock) => {
+                    self.log(format!("[INTERLOCK] {} cleared", interlock.label()));
+                }
             }
         }
     }
@@ -1014,7 +1738,17 @@ impl All4LaserApp {
         if let Some(conn) = self.connection.take() {
             conn.disconnect();
         }
+        self.reconnect = None;
+        self.reconcile_pending = false;
         self.grbl_state = GrblState::default();
+        self.reset_file_transfers();
+        self.continuous_jog = None;
+        if self.profile_reader.take().is_some() {
+            self.profile_sync.set_error("The connection closed before the controller answered.".to_string());
+        }
+        self.interlocks.reset();
+        self.wcs = WcsTable::default();
+        self.wcs_query_pending = false;
         self.running = false;
         self.clear_runtime_program();
         self.is_dry_run = false;
@@ -1022,6 +1756,86 @@ impl All4LaserApp {
         self.log("Disconnected".to_string());
     }
 
+    /// Probe the port on a background thread; `poll_controller_detection` connects afterwards
+    fn start_controller_detection(&mut self, port: String, baud: u32) {
+        self.log(format!("Detecting controller on {port}…"));
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        self.detect_receiver = Some(rx);
+        std::thread::spawn(move || {
+            let result = crate::controller::detect::probe_serial_port(&port, baud);
+            let _ = tx.send((port, result));
+        });
+    }
+
+    fn poll_controller_detection(&mut self) {
+        let Some(rx) = &self.detect_receiver else {
+            return;
+        };
+        let (port, result) = match rx.try_recv() {
+            Ok(msg) => msg,
+            Err(crossbeam_channel::TryRecvError::Empty) => return,
+            Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                self.detect_receiver = None;
+                self.grbl_state.status = MacStatus::Disconnected;
+                return;
+            }
+        };
+        self.detect_receiver = None;
+
+        match result {
+            Ok(info) => {
+                self.log(format!("Detected {}", info.summary()));
+                self.apply_detected_firmware(info);
+                let baud = ui::connection::get_baud(&self.baud_rates, self.selected_baud);
+                self.log(format!("Connecting to {port} @ {baud}…"));
+                let result =
+                    SerialConnection::connect(&port, baud, self.controller_backend.clone());
+                self.finish_connect(result);
+            }
+            Err(e) => {
+                self.grbl_state.status = MacStatus::Disconnected;
+                self.show_error(format!("Controller detection failed: {e}"));
+            }
+        }
+    }
+
+    /// Switch backend, driver profile and baud rate to match the detected firmware
+    fn apply_detected_firmware(&mut self, info: crate::controller::detect::FirmwareInfo) {
+        let previous_kind = self.machine_profile.controller_kind;
+        self.machine_profile.controller_kind = info.family.controller_kind();
+        self.apply_controller_kind_change(previous_kind);
+        // A driver the user picked for this controller wins over the detected default
+        if self.machine_profile.laser_driver_profile == LaserDriverProfile::Auto {
+            self.machine_profile.laser_driver_profile = info.family.driver_profile();
+            self.queued_prepared_programs.clear();
+        }
+
+        if let Some(baud) = info.baud_rate {
+            match self.baud_rates.iter().position(|b| *b == baud) {
+                Some(idx) => self.selected_baud = idx,
+                None => {
+                    self.baud_rates.push(baud);
+                    self.selected_baud = self.baud_rates.len() - 1;
+                }
+            }
+        }
+        if let Some(rx_buffer) = info.rx_buffer_size {
+            self.machine_profile.grbl_rx_buffer_size = rx_buffer as usize;
+        }
+        self.machine_profile.detected_firmware = Some(info);
+        self.save_active_machine_profile();
+    }
+
+    fn browse_session_file(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Serial session", &["log", "txt"])
+            .add_filter("All files", &["*"])
+            .pick_file()
+        {
+            self.session_path = path.to_string_lossy().to_string();
+        }
+    }
+
     fn open_file(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
             .add_filter("GCode", &["nc", "gcode", "ngc", "gc"])
@@ -1251,7 +2065,7 @@ impl All4LaserApp {
                 // Default to GCode loading
                 match GCodeFile::load(path) {
                     Ok(file) => {
-                        let lines = file.lines.iter().map(|l| l.raw.clone()).collect();
+                        let lines = file.lines.clone();
                         self.set_loaded_file(file, lines);
                     }
                     Err(e) => {
@@ -1285,8 +2099,113 @@ impl All4LaserApp {
 
         self.loaded_file = Some(file);
         self.current_job_parts = None;
-        if let Some(file) = self.loaded_file.as_ref() {
-            self.estimation = crate::gcode::estimation::estimate(&file.lines);
+        self.refresh_estimation();
+    }
+
+    fn refresh_estimation_when_due(&mut self) {
+        if self.estimation_due.is_some_and(|due| Instant::now() >= due) {
+            self.refresh_estimation();
+        }
+    }
+
+    /// Re-plan the loaded program against the active profile's limits and measured corrections
+    fn refresh_estimation(&mut self) {
+        self.estimation_due = None;
+        self.calibration = crate::gcode::calibration::Calibration::fit(&self.machine_profile.job_timings);
+        let config = crate::gcode::planner::PlannerConfig::from_profile(&self.machine_profile);
+        if let Some(file) = self.loaded_file.as_mut() {
+            self.estimation = file.estimate(&config);
+            file.estimated_time = Duration::from_secs_f32(self.calibration.seconds(&self.estimation));
+        }
+    }
+
+    /// Plan the program about to run so its measured time can be held against the estimate
+    fn start_job_clock(&mut self) {
+        self.job_clock = None;
+        if self.is_dry_run {
+            return;
+        }
+        let lines = self.runtime_program_lines();
+        let blocks = crate::gcode::interpreter::interpret(lines);
+        let config = crate::gcode::planner::PlannerConfig::from_profile(&self.machine_profile);
+        let timed = crate::gcode::planner::plan(&blocks, &config);
+        let planned = crate::gcode::estimation::estimate_planned(lines, &blocks, &timed);
+        let line_kinds = crate::gcode::estimation::line_kinds(lines, &blocks);
+        let name = self
+            .loaded_file
+            .as_ref()
+            .map(|f| f.filename.clone())
+            .unwrap_or_else(|| "job".to_string());
+        let mut clock = crate::gcode::calibration::JobClock::start(
+            name,
+            self.calibration.seconds(&planned),
+            &planned,
+            line_kinds,
+            &timed,
+            Instant::now(),
+        );
+        clock.queue_job = self.active_queue_job.as_ref().map(|job| job.id);
+        self.job_clock = Some(clock);
+    }
+
+    /// Time the running job by kind of work; once the machine is idle after the last line,
+    /// keep the result for calibration
+    fn tick_job_clock(&mut self) {
+        let holding = matches!(self.grbl_state.status, MacStatus::Hold | MacStatus::Door);
+        let idle = self.grbl_state.status == MacStatus::Idle;
+        let overridden = self.grbl_state.override_feed != 100 || self.grbl_state.override_rapid != 100;
+        let line = self.acknowledged_line();
+        let Some(clock) = self.job_clock.as_mut() else {
+            return;
+        };
+        clock.tick(Instant::now(), line, holding);
+        clock.disturbed |= overridden;
+        if !(clock.draining && idle) {
+            return;
+        }
+        let Some(clock) = self.job_clock.take() else {
+            return;
+        };
+        let queue_job = clock.queue_job;
+        let finished_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let Some(timing) = clock.finish(finished_at) else {
+            return;
+        };
+        self.log(format!(
+            "[ESTIMATE] {}: estimated {:.0}s, took {:.0}s ({:+.1}%)",
+            timing.name,
+            timing.estimated_s,
+            timing.actual_s,
+            timing.error_pct()
+        ));
+        if let Some(id) = queue_job {
+            self.job_queue_state.attach_timing(id, timing.clone());
+        }
+        self.machine_profile.record_job_timing(timing);
+        self.save_active_machine_profile();
+    }
+
+    fn handle_estimate_report_action(&mut self, action: ui::estimate_report::EstimateReportAction) {
+        if action.export_csv {
+            let csv = crate::gcode::calibration::error_report_csv(&self.machine_profile.job_timings);
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("CSV Report", &["csv"])
+                .set_file_name("estimate_accuracy.csv")
+                .save_file()
+            {
+                match std::fs::write(&path, &csv) {
+                    Ok(()) => self.log(format!("Estimate report exported: {}", path.display())),
+                    Err(e) => self.show_error(format!("Report export failed: {e}")),
+                }
+            }
+        }
+        if action.clear {
+            self.machine_profile.job_timings.clear();
+            self.save_active_machine_profile();
+            self.log("[ESTIMATE] Calibration reset.".to_string());
         }
     }
 
@@ -1320,9 +2239,71 @@ impl All4LaserApp {
         if self.machine_profile.rotary_enabled {
             job.rotary_diameter_mm = Some(self.machine_profile.rotary_diameter_mm);
         }
+        job.wcs = self.job_wcs;
+        job.wcs_origin_mm = self.job_wcs.and_then(|system| self.wcs.origin_xy(system));
+        job.restore_wcs = self.wcs.active;
+        job.machine_pos_mm = self
+            .is_connected()
+            .then_some((self.grbl_state.mpos.x, self.grbl_state.mpos.y));
         job
     }
 
+    /// Preview offset of the pinned coordinate system's origin, if known
+    fn job_wcs_origin(&self) -> egui::Vec2 {
+        self.job_wcs
+            .and_then(|system| self.wcs.origin_xy(system))
+            .map(|(x, y)| egui::vec2(x, y))
+            .unwrap_or(egui::Vec2::ZERO)
+    }
+
+    fn query_work_offsets(&mut self) {
+        self.wcs_query_pending = false;
+        self.send_command("$#");
+        self.send_command("$G");
+    }
+
+    fn handle_wcs_action(&mut self, action: &ui::machine_state::MachineStateAction) {
+        let changes_controller = action.select_wcs.is_some()
+            || action.zero_wcs.is_some()
+            || action.apply_named.is_some();
+        if changes_controller && self.running {
+            self.show_error("Work offsets cannot be changed while a job is running.".into());
+            return;
+        }
+        if let Some(system) = action.select_wcs {
+            self.send_command(system.gcode());
+            self.wcs.active = Some(system);
+        }
+        if let Some(system) = action.zero_wcs {
+            self.send_command(&system.zero_here_command());
+            self.log(format!("{} origin set to the current position", system.gcode()));
+        }
+        if let Some(named) = action
+            .apply_named
+            .and_then(|i| self.machine_profile.named_offsets.get(i))
+            .cloned()
+        {
+            self.send_command(&named.system.set_offset_command(named.offset));
+            self.log(format!("Applied offset '{}' to {}", named.name, named.system.gcode()));
+        }
+        if let Some((name, system)) = action.save_named.clone()
+            && let Some(offset) = self.wcs.offset(system)
+        {
+            self.machine_profile.named_offsets.retain(|o| o.name != name);
+            self.machine_profile.named_offsets.push(NamedOffset { name, system, offset });
+            self.save_active_machine_profile();
+        }
+        if let Some(i) = action.delete_named
+            && i < self.machine_profile.named_offsets.len()
+        {
+            self.machine_profile.named_offsets.remove(i);
+            self.save_active_machine_profile();
+        }
+        if changes_controller || action.refresh_offsets {
+            self.query_work_offsets();
+        }
+    }
+
     fn preview_used_layer_indices(&self) -> Vec<usize> {
         let mut used = Vec::new();
 
@@ -2072,7 +3053,7 @@ impl All4LaserApp {
                 let power_pct = self.framing_power.clamp(1.0, 100.0);
                 let power_s = (power_pct * 10.0).clamp(1.0, 1000.0); // Convert % to S-value
 
-                let commands = vec![
+                let mut commands = vec![
                     // Laser OFF, travel to first corner
                     "M5".to_string(),
                     format!("G0 X{:.2} Y{:.2}", min_x, min_y),
@@ -2085,6 +3066,13 @@ impl All4LaserApp {
                     // Laser OFF after contour
                     "M5".to_string(),
                 ];
+                // Trace in the job's pinned coordinate system, then go back to the active one
+                if let Some(system) = self.job_wcs {
+                    commands.insert(0, system.gcode().to_string());
+                    if let Some(previous) = self.wcs.active.filter(|&previous| previous != system) {
+                        commands.push(previous.gcode().to_string());
+                    }
+                }
 
                 for cmd in commands {
                     if let Some(conn) = self.connection.as_ref() {
@@ -2178,15 +3166,24 @@ impl All4LaserApp {
     }
 
     fn start_runtime_program(&mut self, mut runtime_lines: Vec<String>, driver_name: &str) {
+        if let Err(reason) = self.interlocks.can_start(&self.machine_profile) {
+            self.log(format!("[INTERLOCK] Job start blocked: {reason}"));
+            self.handle_program_failed(reason);
+            return;
+        }
 
         self.program_index = 0;
         self.running = true;
         self.notify_job_done = false;
         self.framing_active = false;
+        self.streamer = CharCountStreamer::new(self.machine_profile.grbl_rx_buffer_size);
 
-        // Air assist ON
+        // Air assist ON as the first program line, so its `ok` is counted in order with the
+        // job's instead of being taken for line 1 (or for Marlin's M110)
+        self.program_lead_in = 0;
         if self.machine_profile.air_assist {
-            self.send_command("M8");
+            runtime_lines.insert(0, "M8".to_string());
+            self.program_lead_in = 1;
         }
 
         // Append return-to-origin if configured
@@ -2197,6 +3194,7 @@ impl All4LaserApp {
         }
 
         self.prepared_program_lines = Arc::new(runtime_lines);
+        self.start_job_clock();
         self.log(format!(
             "Using driver '{}' ({} line(s) prepared).",
             driver_name,
@@ -2208,15 +3206,102 @@ impl All4LaserApp {
         } else {
             "Starting program…".to_string()
         });
-        self.send_next_program_line();
+
+        if self
+            .connection
+            .as_ref()
+            .is_some_and(|conn| conn.accepts_binary_jobs())
+        {
+            self.upload_binary_program();
+        } else if self
+            .connection
+            .as_ref()
+            .is_some_and(|conn| conn.accepts_file_jobs())
+        {
+            self.upload_file_program();
+        } else {
+            if self.marlin_framing_active() {
+                // Line 1 follows once the controller acknowledges the reset
+                let reset = self.marlin_framer.reset();
+                self.log(format!("> {reset}"));
+                if let Some(conn) = self.connection.as_ref() {
+                    conn.send(&reset);
+                }
+            } else {
+                self.send_next_program_line();
+            }
+        }
+    }
+
+    /// Runtime program as uploaded in one piece, with the laser kept off for dry runs
+    fn upload_program_lines(&self) -> Vec<String> {
+        self.runtime_program_lines()
+            .iter()
+            .map(|line| {
+                if self.is_dry_run {
+                    crate::gcode::transform::laser_off(line)
+                } else {
+                    line.clone()
+                }
+            })
+            .collect()
+    }
+
+    /// Packet transports (Ruida UDP) take the whole job at once instead of line streaming
+    fn upload_binary_program(&mut self) {
+        let job = LaserJob::from_program_lines(&self.upload_program_lines(), "All4Laser job");
+        let driver = create_export_driver(ControllerKind::Ruida, LaserDriverProfile::RuidaNative);
+        let binary = match driver.prepare_binary(&job, &self.machine_profile) {
+            Ok(Some(binary)) => binary,
+            Ok(None) => {
+                self.handle_program_failed("RD encoding failed: driver has no binary format".to_string());
+                return;
+            }
+            Err(e) => {
+                self.handle_program_failed(format!("RD encoding failed: {e}"));
+                return;
+            }
+        };
+
+        // The driver writes a scrambled .rd file; the UDP link scrambles each packet itself
+        let bytes = crate::ruida::protocol::unswizzle(&binary.bytes, crate::ruida::protocol::DEFAULT_MAGIC);
+        self.log(format!("Uploading {} byte(s) to controller…", bytes.len()));
+        self.program_index = 0;
+        self.binary_job_active = true;
+        self.binary_job_seen_run = false;
+        if let Some(conn) = self.connection.as_ref() {
+            conn.send_binary(bytes);
+        }
+    }
+
+    /// FluidNC/ESP3D: store the program on the controller and run it there.
+    /// Progress comes from the `SD:` field of status reports.
+    fn upload_file_program(&mut self) {
+        let mut text = self.upload_program_lines().join("\n");
+        text.push('\n');
+        let name = crate::serial::fluidnc::JOB_FILE_NAME;
+        self.log(format!(
+            "Uploading {} byte(s) as /{name} to {}…",
+            text.len(),
+            self.machine_profile.fluidnc_store.label()
+        ));
+        self.program_index = 0;
+        self.binary_job_active = true;
+        self.binary_job_seen_run = false;
+        if let Some(conn) = self.connection.as_ref() {
+            conn.upload_file(name, text.into_bytes(), true);
+        }
     }
 
     fn prepare_lines_for_queue(
         &mut self,
         source_name: &str,
         lines: &[String],
+        wcs: Option<CoordSystem>,
     ) -> Option<(Vec<String>, String)> {
-        let job = self.build_laser_job("Queued All4Laser job", source_name, lines);
+        let mut job = self.build_laser_job("Queued All4Laser job", source_name, lines);
+        job.wcs = wcs;
+        job.wcs_origin_mm = wcs.and_then(|system| self.wcs.origin_xy(system));
 
         let prepared = match prepare_program(
             self.machine_profile.controller_kind,
@@ -2286,6 +3371,7 @@ impl All4LaserApp {
         let id = self
             .job_queue_state
             .enqueue_job(base_name.clone(), self.program_lines.clone());
+        self.job_queue_state.set_job_wcs(id, self.job_wcs);
         let queue_lines = self.program_lines.as_ref().clone();
         self.prepare_queue_cache_entry(id, &base_name, &queue_lines);
         self.log(format!("Queued job #{id}: {base_name}"));
@@ -2293,7 +3379,8 @@ impl All4LaserApp {
     }
 
     fn prepare_queue_cache_entry(&mut self, id: u64, name: &str, lines: &[String]) {
-        if let Some((prepared_lines, driver_name)) = self.prepare_lines_for_queue(name, lines) {
+        let wcs = self.job_queue_state.job_wcs(id);
+        if let Some((prepared_lines, driver_name)) = self.prepare_lines_for_queue(name, lines, wcs) {
             self.queued_prepared_programs
                 .insert(id, (Arc::new(prepared_lines), driver_name.clone()));
             self.log(format!(
@@ -2321,6 +3408,7 @@ impl All4LaserApp {
 
         let file = GCodeFile::from_lines(&job.name, &job.lines);
         self.set_loaded_file(file, job.lines.to_vec());
+        self.job_wcs = job.wcs;
         if !self.run_preflight("queue", true) {
             self.job_queue_state
                 .record_failure(job.clone(), "Preflight blocked launch".into());
@@ -2400,6 +3488,9 @@ impl All4LaserApp {
 
         self.log("Program complete.".to_string());
         self.notify_job_done = true;
+        if let Some(clock) = self.job_clock.as_mut() {
+            clock.draining = true;
+        }
         self.clear_runtime_program();
 
         if let Some(job) = self.active_queue_job.take() {
@@ -2410,7 +3501,382 @@ impl All4LaserApp {
         }
     }
 
+    fn describe_fault(&self, kind: FaultKind, code: i32, raw: &str) -> Option<&'static FaultInfo> {
+        let family = faults::family_for(
+            self.machine_profile.controller_kind,
+            self.machine_profile.detected_firmware.as_ref(),
+        );
+        faults::describe(family, kind, code, raw)
+    }
+
+    fn run_recovery(&mut self, command: RecoveryCommand) {
+        match command {
+            RecoveryCommand::Line(line) => self.send_command(line),
+            RecoveryCommand::Realtime(rt) => {
+                self.send_realtime_or_warn(rt, "Recovery");
+            }
+            RecoveryCommand::ResetBoard => {
+                if let Some(conn) = self.connection.as_ref() {
+                    conn.reset_board();
+                    self.log("Resetting board (DTR/RTS pulse)…".to_string());
+                }
+            }
+            RecoveryCommand::Reconnect => {
+                self.disconnect();
+                self.connect();
+            }
+        }
+    }
+
+    /// Storage commands for the connected firmware; a firmware report without storage rules it out
+    fn file_dialect(&self) -> Option<FileDialect> {
+        let firmware = self.machine_profile.detected_firmware.as_ref();
+        let dialect = self.controller_backend.file_dialect(firmware)?;
+        (firmware.is_none() || self.controller_capabilities().supports_sd_card).then_some(dialect)
+    }
+
+    fn open_file_browser(&mut self) {
+        let Some(dialect) = self.file_dialect() else {
+            self.show_error(
+                "This controller has no onboard storage, or its firmware has not been identified yet."
+                    .to_string(),
+            );
+            return;
+        };
+        self.file_browser.open(dialect);
+        self.refresh_controller_files(0);
+    }
+
+    /// List the controller's files; `pending_oks` covers commands sent just before
+    fn refresh_controller_files(&mut self, pending_oks: usize) {
+        let Some(dialect) = self.file_browser.dialect else {
+            return;
+        };
+        if self.file_listing.is_some() || !self.is_connected() {
+            return;
+        }
+        self.file_listing = Some(FileListing::new(dialect).after_commands(pending_oks));
+        self.file_browser.refreshing = true;
+        self.send_command(dialect.list_command());
+    }
+
+    fn feed_file_listing(&mut self, raw: &str) {
+        if raw.starts_with("[fluidnc] uploaded") && std::mem::take(&mut self.file_http_upload) {
+            self.file_browser.upload_progress = None;
+            self.refresh_controller_files(0);
+            return;
+        }
+        let Some(listing) = self.file_listing.as_mut() else {
+            return;
+        };
+        let Some(files) = listing.push(raw) else {
+            return;
+        };
+        self.file_listing = None;
+        self.file_browser.refreshing = false;
+        if let Some((name, expected)) = self.file_verify.take() {
+            match controller_files::verify_upload(&files, &name, expected) {
+                Ok(()) => self.file_browser.set_message(format!("Uploaded and verified {name}"), false),
+                Err(err) => {
+                    self.file_browser.set_message(err.clone(), true);
+                    self.log(format!("[FILES] {err}"));
+                }
+            }
+        }
+        self.file_browser.files = files;
+    }
+
+    fn upload_job_to_controller(&mut self) {
+        let Some(dialect) = self.file_browser.dialect else {
+            return;
+        };
+        let name = self.file_browser.upload_name.trim().to_string();
+        let job = self.build_laser_job("All4Laser job", "upload", self.program_lines.as_ref());
+        let lines = match prepare_program(self.machine_profile.controller_kind, &self.machine_profile, &job) {
+            Ok(prepared) => controller_files::upload_lines(&prepared.lines),
+            Err(err) => {
+                self.file_browser.set_message(format!("Program preparation failed: {err}"), true);
+                return;
+            }
+        };
+        let http = self.connection.as_ref().is_some_and(|c| c.accepts_file_jobs());
+        self.file_browser.message = None;
+        match dialect.upload_method(&name, http) {
+            UploadMethod::Lines { begin, end } => {
+                self.log(format!("Uploading {} line(s) to {name}…", lines.len()));
+                let mut upload = UploadSession::new(&name, lines, begin, end);
+                let step = upload.start();
+                self.file_upload = Some(upload);
+                self.handle_file_upload_step(step);
+            }
+            UploadMethod::Http => {
+                let mut text = lines.join("\n");
+                text.push('\n');
+                let size = text.len() as u64;
+                self.file_http_upload = true;
+                self.file_verify = Some((name.clone(), (size, size)));
+                self.file_browser.upload_progress = Some(None);
+                if let Some(conn) = self.connection.as_ref() {
+                    conn.upload_file(name.trim_start_matches('/'), text.into_bytes(), false);
+                }
+            }
+            UploadMethod::Unsupported(reason) => self.file_browser.set_message(reason, true),
+        }
+    }
+
+    fn handle_file_upload_step(&mut self, step: UploadStep) {
+        match step {
+            UploadStep::Send(command) => {
+                if let Some(upload) = self.file_upload.as_ref() {
+                    self.file_browser.upload_progress = Some(Some(upload.progress()));
+                }
+                if let Some(conn) = self.connection.as_ref() {
+                    conn.send(&command);
+                }
+            }
+            UploadStep::Finished => {
+                if let Some(upload) = self.file_upload.take() {
+                    self.log(format!("Upload of {} finished, verifying…", upload.name));
+                    self.file_verify = Some((upload.name.clone(), upload.expected_size()));
+                }
+                self.file_browser.upload_progress = None;
+                self.refresh_controller_files(0);
+            }
+        }
+    }
+
+    fn abort_file_upload(&mut self, error: String) {
+        if let Some(upload) = self.file_upload.take()
+            && let Some(close) = upload.abort_command()
+        {
+            self.send_command(&close);
+        }
+        self.file_http_upload = false;
+        self.file_verify = None;
+        self.file_browser.upload_progress = None;
+        self.file_browser.set_message(format!("Upload failed: {error}"), true);
+        self.log(format!("[FILES] Upload failed: {error}"));
+    }
+
+    /// Start, feed or stop the hold-to-jog move from this frame's key and button state
+    fn update_continuous_jog(&mut self) {
+        let held = self.jog_hold_key.or(self.jog_hold_button);
+        if let Some(jog) = self.continuous_jog.as_mut() {
+            // A jog that ended at a limit or on an error waits here until let go
+            if held == Some(jog.dir) {
+                return;
+            }
+            if let Some(stop) = jog.release()
+                && let Some(conn) = self.connection.as_ref()
+            {
+                match stop {
+                    JogStop::Byte(byte) => conn.send_byte(byte),
+                    JogStop::Line(line) => conn.send(line),
+                }
+            }
+            if !jog.is_finished() {
+                return;
+            }
+            self.continuous_jog = None;
+        }
+        let Some(dir) = held else {
+            return;
+        };
+        if !self.is_connected() || self.running {
+            return;
+        }
+        let firmware = self.machine_profile.detected_firmware.as_ref();
+        let Some(style) = self.controller_backend.continuous_jog(firmware) else {
+            self.log(format!(
+                "Continuous jog is not supported by {} backend; back to step jogging.",
+                self.machine_profile.controller_kind.label()
+            ));
+            self.jog_continuous = false;
+            return;
+        };
+
+        let profile = &self.machine_profile;
+        let position_reported = self
+            .controller_backend
+            .realtime_byte(RealtimeCommand::StatusReport)
+            .is_some();
+        let limits = if position_reported {
+            JogLimits::around(self.grbl_state.mpos, profile.workspace_x_mm, profile.workspace_y_mm)
+        } else {
+            JogLimits::unknown_position(profile.workspace_x_mm, profile.workspace_y_mm)
+        };
+        let reach = limits.reach(self.grbl_state.mpos, dir);
+        let segment = continuous_jog::segment_length(
+            self.jog_feed,
+            profile.accel_x.min(profile.accel_y),
+            profile.jog_segment_ms,
+        );
+        let (jog, lines) = ContinuousJog::start(dir, style, self.jog_feed, segment, reach, self.grbl_state.mpos);
+        if lines.is_empty() {
+            self.log("Jog limit reached in that direction.".to_string());
+        } else if let Some(conn) = self.connection.as_ref() {
+            for line in &lines {
+                conn.send(line);
+            }
+        }
+        self.continuous_jog = Some(jog);
+    }
+
+    /// Read the machine limits from the controller and compare them with the profile
+    fn start_profile_sync(&mut self) {
+        let source = match self.machine_profile.controller_kind {
+            ControllerKind::Grbl if self.controller_capabilities().supports_grbl_settings => SyncSource::Grbl,
+            ControllerKind::Marlin => SyncSource::Marlin,
+            _ => {
+                self.show_error("Reading machine limits needs GRBL ($$) or Marlin (M503) firmware.".to_string());
+                return;
+            }
+        };
+        if !self.is_connected() || self.running {
+            self.show_error("Connect and let the job finish before syncing from the controller.".to_string());
+            return;
+        }
+        self.profile_reader = Some(ProfileReader::new(source));
+        self.profile_sync = ui::profile_sync::ProfileSyncState::reading();
+        for command in source.commands() {
+            self.send_command(command);
+        }
+    }
+
+    fn feed_profile_reader(&mut self, raw: &str) {
+        let Some(reader) = self.profile_reader.as_mut() else {
+            return;
+        };
+        let Some(result) = reader.push(raw) else {
+            return;
+        };
+        self.profile_reader = None;
+        match result {
+            Ok(values) => {
+                let rows = profile_sync::compare(&self.machine_profile, &values);
+                let differing = rows.iter().filter(|r| r.differs()).count();
+                self.log(format!(
+                    "[PROFILE] Controller reports {} machine value(s); {differing} differ from the profile.",
+                    rows.len()
+                ));
+                self.profile_sync.set_rows(rows);
+            }
+            Err(err) => self.profile_sync.set_error(err),
+        }
+    }
+
+    fn handle_profile_sync(&mut self, ctx: &egui::Context) {
+        let can_read = self.is_connected() && !self.running && self.profile_reader.is_none();
+        ui::profile_sync::show(ctx, &mut self.profile_sync, can_read);
+        if std::mem::take(&mut self.profile_sync.reread_requested) {
+            self.start_profile_sync();
+            return;
+        }
+        if !std::mem::take(&mut self.profile_sync.apply_requested) {
+            return;
+        }
+        let chosen = self.profile_sync.chosen();
+        for &(field, value) in &chosen {
+            field.set(&mut self.machine_profile, value);
+        }
+        self.save_active_machine_profile();
+        let names: Vec<&str> = chosen.iter().map(|(field, _)| field.label()).collect();
+        self.log(format!("[PROFILE] Updated from the controller: {}", names.join(", ")));
+        self.profile_sync = ui::profile_sync::ProfileSyncState::default();
+    }
+
+    fn reset_file_transfers(&mut self) {
+        self.file_listing = None;
+        self.file_upload = None;
+        self.file_http_upload = false;
+        self.file_verify = None;
+        self.file_browser.refreshing = false;
+        self.file_browser.upload_progress = None;
+    }
+
+    fn handle_file_browser(&mut self, ctx: &egui::Context) {
+        let idle = self.is_connected() && !self.running;
+        let has_job = !self.program_lines.is_empty();
+        let action = ui::file_browser::show(ctx, &mut self.file_browser, idle, has_job);
+        let Some(dialect) = self.file_browser.dialect else {
+            return;
+        };
+        if action.refresh {
+            self.refresh_controller_files(0);
+        }
+        if action.upload {
+            self.upload_job_to_controller();
+        }
+        if let Some(name) = action.run {
+            self.log(format!("Running {name} from controller storage."));
+            for command in dialect.run_commands(&name) {
+                self.send_command(&command);
+            }
+            self.file_browser.set_message(format!("Started {name}"), false);
+        }
+        if let Some(name) = action.delete {
+            self.send_command(&dialect.delete_command(&name));
+            self.refresh_controller_files(1);
+        }
+    }
+
+    /// Acknowledged line counted in the full prepared job, even while a resume is running
+    fn checkpoint_line(&self) -> usize {
+        let line = self.acknowledged_line().saturating_sub(self.program_lead_in);
+        self.resumed_from.as_ref().map_or(line, |plan| plan.original_line(line))
+    }
+
+    /// Last runtime line the controller has acknowledged
+    fn acknowledged_line(&self) -> usize {
+        let in_flight = if self.char_counting_active() {
+            self.streamer.lines_in_flight()
+        } else {
+            1
+        };
+        self.program_index.saturating_sub(in_flight)
+    }
+
+    /// Re-prepare the loaded job and offer to continue it from the last checkpoint
+    fn open_resume_dialog(&mut self) {
+        if self.program_lines.is_empty() {
+            self.show_error("No file loaded".to_string());
+            return;
+        }
+        let job = self.build_laser_job("All4Laser job", "resume", self.program_lines.as_ref());
+        match prepare_program(self.machine_profile.controller_kind, &self.machine_profile, &job) {
+            Ok(prepared) => {
+                let checkpoint = self
+                    .last_checkpoint
+                    .unwrap_or(0)
+                    .min(prepared.lines.len().saturating_sub(1));
+                self.resume_state = ui::resume::ResumeDialogState::open(Arc::new(prepared.lines), checkpoint);
+            }
+            Err(err) => self.show_error(format!("Program preparation failed: {err}")),
+        }
+    }
+
+    fn handle_resume_dialog(&mut self, ctx: &egui::Context) {
+        let can_start = self.is_connected() && !self.running;
+        ui::resume::show(ctx, &mut self.resume_state, can_start);
+        if !std::mem::take(&mut self.resume_state.start_requested) {
+            return;
+        }
+        let Some(program) = self.resume_state.program() else {
+            return;
+        };
+        let line = self.resume_state.line;
+        let plan = self.resume_state.plan.take().and_then(Result::ok);
+        self.resume_state = ui::resume::ResumeDialogState::default();
+        self.last_checkpoint = None;
+        self.log(format!("Resuming job at line {}.", line + 1));
+        self.resumed_from = plan;
+        self.start_runtime_program(program, "resume");
+    }
+
     fn handle_program_failed(&mut self, reason: String) {
+        if self.running && self.program_index > 0 {
+            self.last_checkpoint = Some(self.checkpoint_line());
+        }
         let line_info = if self.program_index > 0 {
             format!(" (at line {}/{})", self.program_index, self.runtime_program_len())
         } else {
@@ -2418,6 +3884,7 @@ impl All4LaserApp {
         };
         self.show_error(format!("Job failed{line_info}: {reason}"));
         self.running = false;
+        self.job_clock = None;
         self.clear_runtime_program();
         self.is_dry_run = false;
         self.framing_active = false;
@@ -2430,8 +3897,27 @@ impl All4LaserApp {
         }
     }
 
+    /// A streamed line was rejected while later lines may already sit in GRBL's buffer:
+    /// hold and reset so nothing after the bad line executes, then fail the job.
+    fn handle_stream_error(&mut self, error: String, failed_line: Option<usize>) {
+        if !self.streamer.is_idle() {
+            self.send_realtime(RealtimeCommand::FeedHold);
+            self.send_realtime(RealtimeCommand::Reset);
+        }
+        let reason = match failed_line {
+            Some(idx) => format!(
+                "{error} on line {}: {}",
+                idx + 1,
+                self.runtime_command_at(idx).unwrap_or_default()
+            ),
+            None => error,
+        };
+        self.handle_program_failed(reason);
+    }
+
     fn handle_program_aborted(&mut self) {
         self.running = false;
+        self.job_clock = None;
         self.clear_runtime_program();
         self.is_dry_run = false;
         self.framing_active = false;
@@ -2458,7 +3944,13 @@ impl All4LaserApp {
     fn send_command(&mut self, cmd: &str) {
         self.log(format!("> {cmd}"));
         if let Some(conn) = self.connection.as_ref() {
-            conn.send(cmd);
+            if self.running && self.char_counting_active() {
+                // Streaming fills GRBL's RX buffer; wait for room like a program line would
+                self.streamer.queue_untracked(cmd);
+                self.send_waiting_commands();
+            } else {
+                conn.send(cmd);
+            }
         } else {
             self.log("Not connected".to_string());
         }
@@ -2478,6 +3970,12 @@ impl All4LaserApp {
 
     fn clear_runtime_program(&mut self) {
         self.prepared_program_lines = Arc::new(Vec::new());
+        self.resumed_from = None;
+        self.program_lead_in = 0;
+        self.streamer.reset();
+        self.marlin_framer.clear();
+        self.binary_job_active = false;
+        self.binary_job_seen_run = false;
     }
 
     fn sync_queued_prepared_program_cache(&mut self) {
@@ -2635,7 +4133,21 @@ impl All4LaserApp {
         let caps = self.controller_capabilities();
 
         ctx.input(|i| {
-            if caps.supports_jog {
+            if caps.supports_jog && self.jog_continuous {
+                let z_up = caps.supports_z_jog && i.key_down(egui::Key::PageUp);
+                let z_down = caps.supports_z_jog && i.key_down(egui::Key::PageDown);
+                self.jog_hold_key = continuous_jog::held_direction(
+                    i.key_down(egui::Key::ArrowUp),
+                    i.key_down(egui::Key::ArrowDown),
+                    i.key_down(egui::Key::ArrowLeft),
+                    i.key_down(egui::Key::ArrowRight),
+                )
+                .or(match (z_up, z_down) {
+                    (true, false) => Some(JogDirection::Zup),
+                    (false, true) => Some(JogDirection::Zdown),
+                    _ => None,
+                });
+            } else if caps.supports_jog {
                 if i.key_pressed(egui::Key::ArrowUp) {
                     jog_dir = Some(JogDirection::N);
                 }
@@ -2648,10 +4160,10 @@ impl All4LaserApp {
                 if i.key_pressed(egui::Key::ArrowRight) {
                     jog_dir = Some(JogDirection::E);
                 }
-                if i.key_pressed(egui::Key::PageUp) {
+                if caps.supports_z_jog && i.key_pressed(egui::Key::PageUp) {
                     jog_dir = Some(JogDirection::Zup);
                 }
-                if i.key_pressed(egui::Key::PageDown) {
+                if caps.supports_z_jog && i.key_pressed(egui::Key::PageDown) {
                     jog_dir = Some(JogDirection::Zdown);
                 }
             }
@@ -2839,6 +4351,7 @@ impl All4LaserApp {
         let prev_kind = self.machine_profile.controller_kind;
         self.machine_profile = self.profile_store.active().clone();
         self.profile_store.save();
+        self.refresh_estimation();
         if self.machine_profile.controller_kind != prev_kind {
             self.apply_controller_kind_change(prev_kind);
         }
@@ -2986,6 +4499,46 @@ impl All4LaserApp {
                 }
                 ui.end_row();
 
+                if self.connection_mode == ui::connection::ConnectionMode::FluidNc {
+                    ui.label("Upload jobs to:");
+                    let store = &mut self.machine_profile.fluidnc_store;
+                    egui::ComboBox::from_id_salt("fluidnc_store_combo")
+                        .selected_text(store.label())
+                        .show_ui(ui, |ui| {
+                            for option in [FileStore::Sd, FileStore::Flash] {
+                                if ui.selectable_value(store, option, option.label()).changed() {
+                                    profile_changed = true;
+                                }
+                            }
+                        });
+                    ui.end_row();
+                }
+
+                ui.label("");
+                if ui
+                    .checkbox(
+                        &mut self.machine_profile.auto_detect_controller,
+                        crate::i18n::tr("Auto-detect on connect"),
+                    )
+                    .on_hover_text(crate::i18n::tr(
+                        "Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting",
+                    ))
+                    .changed()
+                {
+                    profile_changed = true;
+                }
+                ui.end_row();
+
+                if let Some(fw) = &self.machine_profile.detected_firmware {
+                    ui.label(format!("{}:", crate::i18n::tr("Firmware")));
+                    ui.label(
+                        egui::RichText::new(fw.summary())
+                            .small()
+                            .color(theme::SUBTEXT),
+                    );
+                    ui.end_row();
+                }
+
                 ui.label(format!("{}:", crate::i18n::tr("Laser Driver")));
                 let previous_driver_profile = self.machine_profile.laser_driver_profile;
                 egui::ComboBox::from_id_salt("laser_driver_profile_combo")
@@ -2999,36 +4552,166 @@ impl All4LaserApp {
                             );
                         }
                     });
-                if self.machine_profile.laser_driver_profile != previous_driver_profile {
-                    profile_changed = true;
-                    self.queued_prepared_programs.clear();
+                if self.machine_profile.laser_driver_profile != previous_driver_profile {
+                    profile_changed = true;
+                    self.queued_prepared_programs.clear();
+                }
+                ui.end_row();
+
+                ui.label("");
+                ui.label(
+                    egui::RichText::new(self.machine_profile.laser_driver_profile.description())
+                        .small()
+                        .color(theme::SUBTEXT),
+                );
+                ui.end_row();
+
+                if effective_driver_profile(
+                    self.machine_profile.controller_kind,
+                    self.machine_profile.laser_driver_profile,
+                ) == LaserDriverProfile::K40Egv
+                {
+                    ui.label("Nano board:");
+                    let previous_board = self.machine_profile.lihuiyu_board;
+                    egui::ComboBox::from_id_salt("lihuiyu_board_combo")
+                        .selected_text(self.machine_profile.lihuiyu_board.label())
+                        .show_ui(ui, |ui| {
+                            for board in crate::lihuiyu::protocol::LihuiyuBoard::ALL {
+                                ui.selectable_value(
+                                    &mut self.machine_profile.lihuiyu_board,
+                                    board,
+                                    board.label(),
+                                );
+                            }
+                        });
+                    if self.machine_profile.lihuiyu_board != previous_board {
+                        profile_changed = true;
+                        self.queued_prepared_programs.clear();
+                    }
+                    ui.end_row();
+                }
+
+                if self.machine_profile.laser_driver_profile == LaserDriverProfile::Auto {
+                    let resolved_profile = effective_driver_profile(
+                        self.machine_profile.controller_kind,
+                        self.machine_profile.laser_driver_profile,
+                    );
+                    ui.label("");
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Resolved now: {}",
+                            resolved_profile.label()
+                        ))
+                        .small()
+                        .color(theme::SUBTEXT),
+                    );
+                    ui.end_row();
+                }
+
+                if self.machine_profile.controller_kind == ControllerKind::Grbl {
+                    ui.label("Streaming:");
+                    if ui
+                        .checkbox(
+                            &mut self.machine_profile.grbl_char_counting,
+                            "Character counting",
+                        )
+                        .on_hover_text(
+                            "Keep GRBL's RX buffer full instead of waiting for each ok. \
+                             Smoother rasters; needs the exact RX buffer size.",
+                        )
+                        .changed()
+                    {
+                        profile_changed = true;
+                    }
+                    ui.end_row();
+
+                    if self.machine_profile.grbl_char_counting {
+                        ui.label("RX buffer (bytes):");
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut self.machine_profile.grbl_rx_buffer_size)
+                                    .range(32..=16384),
+                            )
+                            .changed()
+                        {
+                            profile_changed = true;
+                        }
+                        ui.end_row();
+                    }
+                }
+
+                if self.machine_profile.controller_kind == ControllerKind::Marlin {
+                    ui.label("Streaming:");
+                    if ui
+                        .checkbox(
+                            &mut self.machine_profile.marlin_line_checksums,
+                            "Line numbers + checksums",
+                        )
+                        .on_hover_text(
+                            "Send job lines as N<n> …*<checksum> so corrupted lines are \
+                             detected and resent on Resend: requests.",
+                        )
+                        .changed()
+                    {
+                        profile_changed = true;
+                    }
+                    ui.end_row();
+                }
+
+                for (label, enabled, pin, hint) in [
+                    (
+                        "Lid interlock:",
+                        &mut self.machine_profile.interlock_lid_enabled,
+                        &mut self.machine_profile.interlock_lid_pin,
+                        "D (door)",
+                    ),
+                    (
+                        "Water interlock:",
+                        &mut self.machine_profile.interlock_water_enabled,
+                        &mut self.machine_profile.interlock_water_pin,
+                        "e.g. !A",
+                    ),
+                ] {
+                    ui.label(label);
+                    ui.horizontal(|ui| {
+                        if ui.checkbox(enabled, "").changed() {
+                            profile_changed = true;
+                        }
+                        if ui
+                            .add_enabled(
+                                *enabled,
+                                egui::TextEdit::singleline(pin).hint_text(hint).desired_width(60.0),
+                            )
+                            .on_hover_text(
+                                "Pn: input letter from the status report. Prefix with ! to trip \
+                                 when the input is not reported (e.g. a flow switch).",
+                            )
+                            .changed()
+                        {
+                            profile_changed = true;
+                        }
+                        if *enabled
+                            && !pin.trim().is_empty()
+                            && let Err(err) = crate::controller::interlock::InterlockPin::parse(pin)
+                        {
+                            ui.label(egui::RichText::new(format!("⚠ {err}")).small().color(theme::RED));
+                        }
+                    });
+                    ui.end_row();
                 }
-                ui.end_row();
-
-                ui.label("");
-                ui.label(
-                    egui::RichText::new(self.machine_profile.laser_driver_profile.description())
-                        .small()
-                        .color(theme::SUBTEXT),
-                );
-                ui.end_row();
 
-                if self.machine_profile.laser_driver_profile == LaserDriverProfile::Auto {
-                    let resolved_profile = effective_driver_profile(
-                        self.machine_profile.controller_kind,
-                        self.machine_profile.laser_driver_profile,
-                    );
-                    ui.label("");
-                    ui.label(
-                        egui::RichText::new(format!(
-                            "Resolved now: {}",
-                            resolved_profile.label()
-                        ))
-                        .small()
-                        .color(theme::SUBTEXT),
-                    );
-                    ui.end_row();
+                ui.label("Machine limits:");
+                if ui
+                    .add_enabled(
+                        self.is_connected() && !self.running,
+                        egui::Button::new("⟳ Sync from controller"),
+                    )
+                    .on_hover_text("Read work area, max rates, accelerations and steps/mm from the firmware")
+                    .clicked()
+                {
+                    self.start_profile_sync();
                 }
+                ui.end_row();
 
                 ui.label("Width (mm):");
                 if ui
@@ -3099,6 +4782,53 @@ impl All4LaserApp {
                     profile_changed = true;
                 }
                 ui.end_row();
+
+                ui.label("Junction Dev.:");
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut self.machine_profile.junction_deviation_mm)
+                            .speed(0.001)
+                            .range(0.0..=1.0)
+                            .suffix(" mm"),
+                    )
+                    .on_hover_text("How far corners may be rounded at speed (GRBL $11)")
+                    .changed()
+                {
+                    profile_changed = true;
+                }
+                ui.end_row();
+
+                ui.label("Max S:");
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut self.machine_profile.max_spindle_s)
+                            .speed(10.0)
+                            .range(1.0..=100_000.0),
+                    )
+                    .on_hover_text("S value for full laser power (GRBL $30)")
+                    .changed()
+                {
+                    profile_changed = true;
+                }
+                ui.end_row();
+
+                ui.label("Jog Segment:");
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut self.machine_profile.jog_segment_ms)
+                            .range(20..=1000)
+                            .speed(5.0)
+                            .suffix(" ms"),
+                    )
+                    .on_hover_text(
+                        "Hold-to-jog move length on firmware without jog cancel; \
+                         never shorter than the braking distance at the jog feed",
+                    )
+                    .changed()
+                {
+                    profile_changed = true;
+                }
+                ui.end_row();
             });
 
         ui.horizontal(|ui| {
@@ -3112,6 +4842,15 @@ impl All4LaserApp {
                 profile_changed = true;
             }
         });
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut self.machine_profile.auto_reconnect, "Reconnect if the link drops")
+                .on_hover_text("Retry a lost connection and offer to resume the interrupted job")
+                .changed()
+            {
+                profile_changed = true;
+            }
+        });
         ui.horizontal(|ui| {
             if ui
                 .checkbox(&mut self.machine_profile.air_assist, "Air Assist (M8/M9)")
@@ -3206,18 +4945,139 @@ impl All4LaserApp {
         });
 
         if profile_changed {
-            // Sync active profile back into the store and persist
-            if let Some(p) = self
-                .profile_store
-                .profiles
-                .get_mut(self.profile_store.active_index)
-            {
-                *p = self.machine_profile.clone();
+            self.save_active_machine_profile();
+        }
+    }
+
+    fn handle_settings_dialog(&mut self, ctx: &egui::Context) {
+        let Some(state) = &mut self.settings_state else {
+            return;
+        };
+        ui::settings_dialog::show(ctx, state);
+        if !state.is_open {
+            self.settings_state = None;
+            return;
+        }
+
+        let refresh = std::mem::take(&mut state.refresh_requested);
+        let write = state.write_requested.take();
+        let take_snapshot = std::mem::take(&mut state.take_snapshot);
+        let delete_snapshot = state.delete_snapshot.take();
+        let board = state.settings.clone();
+
+        if take_snapshot {
+            let firmware = self
+                .machine_profile
+                .detected_firmware
+                .as_ref()
+                .map(|fw| fw.summary())
+                .unwrap_or_default();
+            let snapshots = &mut self.machine_profile.grbl_settings_snapshots;
+            let label = format!("{} #{}", self.machine_profile.name, snapshots.len() + 1);
+            snapshots.push(crate::grbl::settings::GrblSettingsSnapshot::new(
+                label,
+                firmware,
+                board.clone(),
+            ));
+            self.save_active_machine_profile();
+            self.log(format!("Saved {} GRBL settings to the machine profile.", board.len()));
+        }
+        if let Some(index) = delete_snapshot
+            && index < self.machine_profile.grbl_settings_snapshots.len()
+        {
+            self.machine_profile.grbl_settings_snapshots.remove(index);
+            self.save_active_machine_profile();
+        }
+        if (take_snapshot || delete_snapshot.is_some())
+            && let Some(state) = &mut self.settings_state
+        {
+            state.snapshots = self.machine_profile.grbl_settings_snapshots.clone();
+        }
+
+        if (refresh || write.is_some()) && !self.controller_capabilities().supports_grbl_settings {
+            self.log("GRBL settings are not supported by this controller.".into());
+            return;
+        }
+        if self.running || self.settings_restore.is_some() {
+            if refresh || write.is_some() {
+                self.log("Settings are busy: wait for the job or the current write to finish.".into());
+            }
+            return;
+        }
+        if refresh {
+            if let Some(state) = &mut self.settings_state {
+                state.settings.clear();
+                state.edits.clear();
+            }
+            self.send_command("$$");
+        }
+        if let Some(target) = write {
+            let mut restore = crate::grbl::settings::SettingsRestore::new(&target, &board);
+            let step = restore.start();
+            self.settings_restore = Some(restore);
+            if let Some(state) = &mut self.settings_state {
+                state.busy = true;
+                state.settings.clear();
+                state.edits.clear();
+            }
+            self.handle_settings_restore_step(step);
+        }
+    }
+
+    fn handle_settings_restore_step(&mut self, step: crate::grbl::settings::RestoreStep) {
+        use crate::grbl::settings::RestoreStep;
+        let progress = self.settings_restore.as_ref().map(|r| r.progress());
+        match step {
+            RestoreStep::Send(line) => {
+                if let Some(state) = &mut self.settings_state
+                    && let Some((done, total)) = progress
+                {
+                    state.status = Some(format!("Writing settings {done}/{total}…"));
+                }
+                self.send_command(&line);
+            }
+            RestoreStep::Waiting => {}
+            RestoreStep::Finished(report) => {
+                self.settings_restore = None;
+                let summary = if report.is_clean() {
+                    format!("{} settings written and verified.", report.written)
+                } else {
+                    let mut parts = vec![format!("{} settings written", report.written)];
+                    for (id, code) in &report.rejected {
+                        parts.push(format!("${id} rejected (error:{code})"));
+                    }
+                    for d in &report.mismatched {
+                        parts.push(format!(
+                            "${} reads back {} instead of {}",
+                            d.id,
+                            d.right.as_deref().unwrap_or("nothing"),
+                            d.left.as_deref().unwrap_or("")
+                        ));
+                    }
+                    parts.join("; ")
+                };
+                self.log(format!("[SETTINGS] {summary}"));
+                if let Some(state) = &mut self.settings_state {
+                    state.busy = false;
+                    state.status = Some(summary);
+                }
             }
-            self.profile_store.save();
         }
     }
 
+    /// Sync the active profile back into the store and persist
+    fn save_active_machine_profile(&mut self) {
+        if let Some(p) = self
+            .profile_store
+            .profiles
+            .get_mut(self.profile_store.active_index)
+        {
+            *p = self.machine_profile.clone();
+        }
+        self.profile_store.save();
+        self.estimation_due = Some(Instant::now() + ESTIMATION_SETTLE);
+    }
+
     #[allow(dead_code)]
     fn handle_camera_ui_actions(&mut self, ui: &mut egui::Ui) {
         let prev_cam_enabled = self.camera_state.enabled;
@@ -3311,6 +5171,8 @@ impl All4LaserApp {
             )
             .default_open(true)
             .show(ui, |ui| {
+                let line_control = self.connection.as_ref().is_some_and(|c| c.supports_line_control());
+                let modem = self.connection.as_ref().and_then(|c| c.modem_state());
                 let conn_action = ui::connection::show(
                     ui,
                     &self.ports,
@@ -3320,7 +5182,11 @@ impl All4LaserApp {
                     &mut self.connection_mode,
                     &mut self.network_host,
                     &mut self.network_port,
+                    &mut self.session_path,
+                    &mut self.record_session,
                     connected,
+                    line_control,
+                    modem,
                 );
                 if conn_action.connect {
                     self.connect();
@@ -3335,6 +5201,10 @@ impl All4LaserApp {
                 if conn_action.test_network {
                     self.test_network_target();
                 }
+                if conn_action.browse_session {
+                    self.browse_session_file();
+                }
+                self.handle_line_control_action(&conn_action);
 
                 ui.add_space(4.0);
                 self.ui_machine_profile_editor(ui);
@@ -3350,8 +5220,15 @@ impl All4LaserApp {
             )
             .default_open(true)
             .show(ui, |ui| {
-                let ms_action =
-                    ui::machine_state::show(ui, &self.grbl_state, self.is_focus_on, connected);
+                let ms_action = ui::machine_state::show(
+                    ui,
+                    &self.grbl_state,
+                    &self.wcs,
+                    &self.machine_profile.named_offsets,
+                    self.is_focus_on,
+                    connected,
+                );
+                self.handle_wcs_action(&ms_action);
                 if ms_action.toggle_focus && connected {
                     self.is_focus_on = !self.is_focus_on;
                     if self.is_focus_on {
@@ -3379,12 +5256,15 @@ impl All4LaserApp {
                     ui,
                     &mut self.jog_step,
                     &mut self.jog_feed,
+                    &mut self.jog_continuous,
                     caps.supports_jog,
+                    caps.supports_jog && caps.supports_z_jog,
                     caps.supports_home,
                 );
                 if let Some(dir) = jog_action.direction {
                     self.jog(dir);
                 }
+                self.jog_hold_button = self.jog_hold_button.or(jog_action.held);
             });
 
             ui.add_space(4.0);
@@ -4231,7 +6111,7 @@ impl All4LaserApp {
                         .default_open(false)
                         .show(ui, |ui| {
                             ui.group(|ui| {
-                                let est_time_s = self.estimation.estimated_seconds;
+                                let est_time_s = self.calibration.seconds(&self.estimation);
                                 let h = (est_time_s / 3600.0) as u32;
                                 let m = ((est_time_s % 3600.0) / 60.0) as u32;
                                 let s = (est_time_s % 60.0) as u32;
@@ -4239,7 +6119,44 @@ impl All4LaserApp {
                                     RichText::new(format!("⏱ Est. Time: {:02}:{:02}:{:02}", h, m, s))
                                         .strong()
                                         .color(theme::GREEN),
-                                );
+                                )
+                                .on_hover_text(if self.calibration.is_calibrated() {
+                                    format!(
+                                        "Planner: {:.0}s, corrected from this machine's measured jobs",
+                                        self.estimation.estimated_seconds
+                                    )
+                                } else {
+                                    "Planner estimate; finished jobs will calibrate it".to_string()
+                                });
+                                // Sections carry the planner's time; spread the correction evenly
+                                let correction = if self.estimation.estimated_seconds > 0.0 {
+                                    est_time_s / self.estimation.estimated_seconds
+                                } else {
+                                    1.0
+                                };
+                                if self.estimation.sections.len() > 1 {
+                                    egui::CollapsingHeader::new(RichText::new("Per layer / pass").small())
+                                        .id_salt("estimate_sections")
+                                        .show(ui, |ui| {
+                                            egui::Grid::new("estimate_sections_grid").striped(true).show(ui, |ui| {
+                                                for section in &self.estimation.sections {
+                                                    ui.label(RichText::new(&section.layer).small());
+                                                    ui.label(RichText::new(format!("#{}", section.pass)).small());
+                                                    let secs = (section.seconds * correction).round() as u32;
+                                                    ui.label(
+                                                        RichText::new(format!(
+                                                            "{:02}:{:02}:{:02}",
+                                                            secs / 3600,
+                                                            secs % 3600 / 60,
+                                                            secs % 60
+                                                        ))
+                                                        .small(),
+                                                    );
+                                                    ui.end_row();
+                                                }
+                                            });
+                                        });
+                                }
 
                                 ui.add_space(4.0);
                                 ui.horizontal(|ui| {
@@ -4248,15 +6165,32 @@ impl All4LaserApp {
                                         .on_hover_text("Reorder segments to minimize travel distance")
                                         .clicked()
                                     {
-                                        if let Some(mut file) = self.loaded_file.clone() {
-                                            let optimized_lines = crate::gcode::optimizer::optimize(&file.lines);
-                                            let raw_lines: Vec<String> =
-                                                optimized_lines.iter().map(|l| l.to_gcode()).collect();
-                                            file.lines = optimized_lines;
-                                            self.set_loaded_file(file, raw_lines);
-                                            self.log("Path optimized.".to_string());
+                                        if let Some(file) = &self.loaded_file {
+                                            let raw_lines = &file.lines;
+                                            let options = crate::gcode::optimizer::OptimizeOptions {
+                                                time_budget: std::time::Duration::from_secs(2),
+                                                ..Default::default()
+                                            };
+                                            let optimized = crate::gcode::optimizer::optimize(raw_lines, &options);
+                                            let file = GCodeFile::from_lines(&file.filename, &optimized.lines);
+                                            self.set_loaded_file(file, optimized.lines);
+                                            self.log(format!("Path optimized: {}", optimized.stats.summary()));
                                         }
                                     }
+                                    if ui
+                                        .add_enabled(!self.running, egui::Button::new("⏯ Resume…"))
+                                        .on_hover_text("Continue the job from a checkpoint or a chosen line")
+                                        .clicked()
+                                    {
+                                        self.open_resume_dialog();
+                                    }
+                                    if ui
+                                        .add_enabled(self.is_connected(), egui::Button::new("💾 Files…"))
+                                        .on_hover_text("Files on the controller's SD card or flash")
+                                        .clicked()
+                                    {
+                                        self.open_file_browser();
+                                    }
                                     if ui.button("🔍 Preflight Check").clicked() {
                                         self.preflight_state.report = Some(self.build_preflight_report());
                                         self.preflight_state.is_open = true;
@@ -4284,6 +6218,24 @@ impl All4LaserApp {
                                             .suffix(" mm"),
                                     );
                                 });
+                                ui.horizontal(|ui| {
+                                    ui.label("Coordinate system:");
+                                    egui::ComboBox::from_id_salt("job_wcs_combo")
+                                        .selected_text(self.job_wcs.map(|s| s.gcode()).unwrap_or("Active"))
+                                        .show_ui(ui, |ui| {
+                                            ui.selectable_value(&mut self.job_wcs, None, "Active");
+                                            for system in CoordSystem::ALL {
+                                                ui.selectable_value(&mut self.job_wcs, Some(system), system.gcode());
+                                            }
+                                        })
+                                        .response
+                                        .on_hover_text("Pin the job to a work coordinate system; preview and bounds use its $# offset");
+                                    if let Some(system) = self.job_wcs
+                                        && self.wcs.offset(system).is_none()
+                                    {
+                                        ui.label(RichText::new("offset unknown").small().color(theme::PEACH));
+                                    }
+                                });
                                 ui.horizontal(|ui| {
                                     ui.label("Rotation:");
                                     ui.add(
@@ -4323,9 +6275,12 @@ impl All4LaserApp {
                         let ms_action = ui::machine_state::show(
                             ui,
                             &self.grbl_state,
+                            &self.wcs,
+                            &self.machine_profile.named_offsets,
                             self.is_focus_on,
                             connected,
                         );
+                        self.handle_wcs_action(&ms_action);
                         if ms_action.toggle_focus && connected {
                             self.is_focus_on = !self.is_focus_on;
                             if self.is_focus_on {
@@ -4343,12 +6298,15 @@ impl All4LaserApp {
                             ui,
                             &mut self.jog_step,
                             &mut self.jog_feed,
+                            &mut self.jog_continuous,
                             caps.supports_jog,
+                            caps.supports_jog && caps.supports_z_jog,
                             caps.supports_home,
                         );
                         if let Some(dir) = jog_action.direction {
                             self.jog(dir);
                         }
+                        self.jog_hold_button = self.jog_hold_button.or(jog_action.held);
 
                         ui.add_space(8.0);
                         // Z-Probe & Focus
@@ -4417,6 +6375,8 @@ impl All4LaserApp {
                     }
                     RightPanelTab::Laser => {
                         // Connection
+                        let line_control = self.connection.as_ref().is_some_and(|c| c.supports_line_control());
+                        let modem = self.connection.as_ref().and_then(|c| c.modem_state());
                         let conn_action = ui::connection::show(
                             ui,
                             &self.ports,
@@ -4426,7 +6386,11 @@ impl All4LaserApp {
                             &mut self.connection_mode,
                             &mut self.network_host,
                             &mut self.network_port,
+                            &mut self.session_path,
+                            &mut self.record_session,
                             connected,
+                            line_control,
+                            modem,
                         );
                         if conn_action.connect {
                             self.connect();
@@ -4440,6 +6404,10 @@ impl All4LaserApp {
                         if conn_action.test_network {
                             self.test_network_target();
                         }
+                        if conn_action.browse_session {
+                            self.browse_session_file();
+                        }
+                        self.handle_line_control_action(&conn_action);
 
                         ui.add_space(8.0);
                         ui.push_id("classic_profile_editor", |ui| {
@@ -4684,24 +6652,10 @@ impl All4LaserApp {
         }
 
         // === Handle Settings Modal ===
-        let mut settings_write_blocked = false;
-        if let Some(state) = &mut self.settings_state {
-            ui::settings_dialog::show(ui.ctx(), state);
-            if !state.is_open {
-                self.settings_state = None;
-            } else {
-                let supports_grbl_settings = self.controller_capabilities().supports_grbl_settings;
-                if supports_grbl_settings && self.connection.is_some() {
-                    settings_write_blocked = true;
-                }
-            }
-        }
-        if settings_write_blocked {
-            self.log(format!(
-                "Settings write is not supported by {} backend.",
-                self.machine_profile.controller_kind.label()
-            ));
-        }
+        self.handle_settings_dialog(ui.ctx());
+        self.handle_resume_dialog(ui.ctx());
+        self.handle_file_browser(ui.ctx());
+        self.handle_profile_sync(ui.ctx());
 
         // Preferences Dialog
         let prefs_applied = ui::preferences::show(ui.ctx(), &mut self.preferences_state, &mut self.settings);
@@ -4750,7 +6704,15 @@ impl All4LaserApp {
             self.send_realtime_or_warn(RealtimeCommand::FeedHold, "Feed hold");
         }
         if actions.resume {
-            self.send_realtime_or_warn(RealtimeCommand::CycleStart, "Cycle start");
+            match self.interlocks.can_resume(&self.machine_profile) {
+                Ok(()) => {
+                    self.send_realtime_or_warn(RealtimeCommand::CycleStart, "Cycle start");
+                }
+                Err(reason) => {
+                    self.log(format!("[INTERLOCK] Resume refused: {reason}"));
+                    self.show_error(format!("Cannot resume: {reason}"));
+                }
+            }
         }
         if actions.home {
             self.send_command("$H");
@@ -4805,6 +6767,9 @@ impl All4LaserApp {
         if actions.open_job_queue {
             self.job_queue_state.is_open = true;
         }
+        if actions.open_estimate_report {
+            self.estimate_report.is_open = true;
+        }
         if actions.open_test_fire {
             self.test_fire.is_open = true;
         }
@@ -4817,13 +6782,10 @@ impl All4LaserApp {
         if actions.new_clear_project {
             self.new_clear_project();
         }
-        if actions.open_settings {
-            if self.settings_state.is_none() {
-                let mut state = ui::settings_dialog::SettingsDialogState::default();
-                state.is_open = true;
-                state.pending_writes.push((-1, "$$".to_string()));
-                self.settings_state = Some(state);
-            }
+        if actions.open_settings && self.settings_state.is_none() {
+            self.settings_state = Some(ui::settings_dialog::SettingsDialogState::open(
+                self.machine_profile.grbl_settings_snapshots.clone(),
+            ));
         }
         if actions.open_preferences {
             self.preferences_state.is_open = true;
@@ -4856,6 +6818,9 @@ impl All4LaserApp {
         if actions.export_job_report {
             self.handle_export_job_report();
         }
+        if actions.export_machine_file {
+            self.handle_export_machine_file();
+        }
         if actions.save_job_template {
             self.handle_save_job_template();
         }
@@ -4920,6 +6885,52 @@ impl All4LaserApp {
         }
     }
 
+    fn handle_export_machine_file(&mut self) {
+        let source_name = self
+            .loaded_file
+            .as_ref()
+            .map(|f| f.filename.clone())
+            .unwrap_or_else(|| "job".to_string());
+        let job = self.build_laser_job("All4Laser job", &source_name, self.program_lines.as_ref());
+        let binary = match prepare_machine_file(
+            self.machine_profile.controller_kind,
+            &self.machine_profile,
+            &job,
+        ) {
+            Ok(Some(binary)) => binary,
+            Ok(None) => {
+                self.show_error(
+                    "The selected laser driver has no native file format; export GCode instead."
+                        .into(),
+                );
+                return;
+            }
+            Err(e) => {
+                self.show_error(format!("Machine file export failed: {e}"));
+                return;
+            }
+        };
+
+        let stem = std::path::Path::new(&source_name)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("job");
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Machine file", &[binary.file_extension])
+            .set_file_name(format!("{stem}.{}", binary.file_extension))
+            .save_file()
+        {
+            match std::fs::write(&path, &binary.bytes) {
+                Ok(()) => self.log(format!(
+                    "Machine file exported: {} ({} bytes)",
+                    path.display(),
+                    binary.bytes.len()
+                )),
+                Err(e) => self.show_error(format!("Machine file export failed: {e}")),
+            }
+        }
+    }
+
     fn handle_export_lbrn2(&mut self) {
         if self.drawing_state.shapes.is_empty() {
             self.show_error("No shapes to export.".into());
@@ -4975,8 +6986,12 @@ impl All4LaserApp {
                     self.job_transform.rotation = proj.rotation_deg;
                     if let Some(mp) = proj.machine_profile {
                         let previous_kind = self.machine_profile.controller_kind;
+                        // Older projects carry a stale copy of the timing history; keep the live one
+                        let job_timings = std::mem::take(&mut self.machine_profile.job_timings);
                         self.machine_profile = mp;
+                        self.machine_profile.job_timings = job_timings;
                         self.apply_controller_kind_change(previous_kind);
+                        self.refresh_estimation();
                     }
                     self.camera_state.enabled = proj.camera_enabled;
                     self.camera_state.opacity = proj.camera_opacity;
@@ -5037,7 +7052,7 @@ impl All4LaserApp {
                 offset_x: self.job_transform.offset_x,
                 offset_y: self.job_transform.offset_y,
                 rotation_deg: self.job_transform.rotation,
-                machine_profile: Some(self.machine_profile.clone()),
+                machine_profile: Some(self.project_machine_profile()),
                 camera_enabled: self.camera_state.enabled,
                 camera_opacity: self.camera_state.opacity,
                 camera_calibration: self.camera_state.calibration.clone(),
@@ -5049,6 +7064,7 @@ impl All4LaserApp {
                     .selected_preset_name()
                     .map(str::to_string),
                 checkpoint_line: None,
+                job_wcs: None,
                 project_notes: self.project_notes.clone(),
                 shapes: self.drawing_state.shapes.clone(),
                 layers: self.layers.clone(),
@@ -5099,9 +7115,12 @@ impl All4LaserApp {
     }
 
         fn update_preview(&mut self, ui: &mut egui::Ui) {
+            // While choosing a resume point, show only what is left to run
             let segments = self
-                .loaded_file
+                .resume_state
+                .preview
                 .as_ref()
+                .or(self.loaded_file.as_ref())
                 .map(|f| {
                     f.segments
                         .iter()
@@ -5116,7 +7135,8 @@ impl All4LaserApp {
                 })
                 .unwrap_or_default();
 
-            let offset = egui::vec2(self.job_transform.offset_x, self.job_transform.offset_y);
+            let offset = egui::vec2(self.job_transform.offset_x, self.job_transform.offset_y)
+                + self.job_wcs_origin();
 
             let preview_action = ui::preview_panel::show(
                 ui,
@@ -5623,8 +7643,20 @@ impl All4LaserApp {
             }
         }
 
+        let report_action = ui::estimate_report::show(
+            ui.ctx(),
+            &mut self.estimate_report,
+            &self.machine_profile.job_timings,
+            &self.calibration,
+        );
+        self.handle_estimate_report_action(report_action);
+
         // === Job Queue Window ===
         {
+            self.job_queue_state.set_estimator(
+                crate::gcode::planner::PlannerConfig::from_profile(&self.machine_profile),
+                self.calibration,
+            );
             let active_name = self.active_queue_job.as_ref().map(|job| job.name.as_str());
             let queue_action = ui::job_queue::show(
                 ui.ctx(),
@@ -5657,6 +7689,7 @@ impl All4LaserApp {
                 let id = self
                     .job_queue_state
                     .enqueue_job(entry.name.clone(), entry.lines.clone());
+                self.job_queue_state.set_job_wcs(id, entry.wcs);
                 self.prepare_queue_cache_entry(id, &entry.name, entry.lines.as_ref());
                 self.log(format!("Requeued from history as #{id}: {}", entry.name));
             }
@@ -5684,6 +7717,17 @@ impl All4LaserApp {
                     ));
                 }
             }
+            if let Some(id) = queue_action.wcs_changed {
+                let payload = self
+                    .job_queue_state
+                    .queue
+                    .iter()
+                    .find(|j| j.id == id)
+                    .map(|job| (job.name.clone(), job.lines.clone()));
+                if let Some((name, lines)) = payload {
+                    self.prepare_queue_cache_entry(id, &name, &lines);
+                }
+            }
             if queue_action.start_next {
                 self.try_start_next_queued_job();
             }
@@ -5843,6 +7887,10 @@ impl eframe::App for All4LaserApp {
     fn logic(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Poll serial
         self.poll_serial();
+        self.supervise_link();
+        self.poll_controller_detection();
+        self.tick_job_clock();
+        self.refresh_estimation_when_due();
         
         // Poll camera
         self.poll_camera_stream(ctx);
@@ -5862,9 +7910,11 @@ impl eframe::App for All4LaserApp {
         }
         
         // Handle keyboard shortcuts (only when no text input is focused)
+        self.jog_hold_key = None;
         if !ctx.egui_wants_keyboard_input() {
             self.handle_keyboard(ctx);
         }
+        self.update_continuous_jog();
         
         // Handle drag-and-drop
         self.handle_file_drop(ctx);
@@ -5899,6 +7949,8 @@ impl eframe::App for All4LaserApp {
     }
 
     fn ui(&mut self, ui: &mut egui::Ui, _frame: &mut eframe::Frame) {
+        // Jog panels report held buttons again while they are drawn
+        self.jog_hold_button = None;
         // === TOP: Menu bar (Industrial theme only) ===
         let mut menu_actions = ui::toolbar::ToolbarAction::default();
         if self.ui_theme == theme::UiTheme::Industrial {
@@ -6151,6 +8203,44 @@ impl eframe::App for All4LaserApp {
             }
         }
 
+        // Link needs a different controller kind than the profile
+        if let Some(kind) = self.controller_kind_prompt {
+            let current = self.machine_profile.controller_kind;
+            let mut switch = false;
+            let mut cancel = false;
+            egui::Window::new("⚠ Controller Type")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+                .show(ui.ctx(), |ui| {
+                    ui.label(format!(
+                        "This connection talks to a {} controller, but the machine profile is set to {}.",
+                        kind.label(),
+                        current.label()
+                    ));
+                    ui.label("Switch the machine profile and connect?");
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.button(format!("✅ Switch to {}", kind.label())).clicked() {
+                            switch = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancel = true;
+                        }
+                    });
+                });
+            if switch {
+                self.controller_kind_prompt = None;
+                self.machine_profile.controller_kind = kind;
+                self.apply_controller_kind_change(current);
+                self.save_active_machine_profile();
+                self.connect();
+            }
+            if cancel {
+                self.controller_kind_prompt = None;
+            }
+        }
+
         // Startup wizard (F43)
         {
             let mut wctx = ui::wizard::WizardContext {
@@ -6198,13 +8288,32 @@ impl eframe::App for All4LaserApp {
                 .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
                 .show(ui.ctx(), |ui| {
                     ui.label(egui::RichText::new(err).color(theme::RED));
+                    if let Some(fault) = self.last_fault {
+                        ui.add_space(4.0);
+                        ui.label(fault.cause);
+                        ui.label(egui::RichText::new(fault.recovery).color(theme::SUBTEXT));
+                    }
                     ui.add_space(8.0);
-                    if ui.button("OK").clicked() {
+                    let mut recovery = None;
+                    ui.horizontal(|ui| {
+                        if ui.button("OK").clicked() {
+                            self.last_error = None;
+                        }
+                        let connected = self.connection.is_some();
+                        for action in self.last_fault.map(|f| f.actions).unwrap_or_default() {
+                            if ui.add_enabled(connected, egui::Button::new(action.label())).clicked() {
+                                recovery = Some(action.command());
+                            }
+                        }
+                    });
+                    if let Some(command) = recovery {
+                        self.run_recovery(command);
                         self.last_error = None;
                     }
                 });
-            if !open {
+            if !open || self.last_error.is_none() {
                 self.last_error = None;
+                self.last_fault = None;
             }
         }
 
@@ -6268,36 +8377,6 @@ impl eframe::App for All4LaserApp {
             self.run_program_internal();
         }
 
-        // === Handle Settings Modal ===
-        let supports_grbl_settings = self.controller_capabilities().supports_grbl_settings;
-        let mut settings_write_blocked = false;
-        if let Some(state) = &mut self.settings_state {
-            ui::settings_dialog::show(ui.ctx(), state);
-            if !state.is_open {
-                self.settings_state = None;
-            } else {
-                // If there are pending writes, send them
-                if !state.pending_writes.is_empty() {
-                    if !supports_grbl_settings {
-                        settings_write_blocked = true;
-                        state.pending_writes.clear();
-                    } else {
-                        let writes = std::mem::take(&mut state.pending_writes);
-                        for (id, val) in writes {
-                            if id == -1 && val == "$$" {
-                                self.send_command("$$"); // Refresh
-                            } else {
-                                self.send_command(&format!("${}={}", id, val));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        if settings_write_blocked {
-            self.log("GRBL settings write not supported by this controller.".into());
-        }
-
         // === Update Notification ===
         let mut close_update = false;
         let mut log_message = None;
@@ -6346,6 +8425,11 @@ fn chrono_lite() -> String {
     format!("{h:02}:{m:02}:{s:02}")
 }
 
+/// The recorded port is listed, or at least its device node still exists
+fn port_present(identity: &PortIdentity) -> bool {
+    identity.locate(&watchdog::available_ports()).is_some() || std::path::Path::new(&identity.path).exists()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
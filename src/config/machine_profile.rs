@@ -3,7 +3,13 @@
 use serde::{Deserialize, Serialize};
 
 use crate::controller::ControllerKind;
+use crate::controller::detect::FirmwareInfo;
+use crate::gcode::calibration::{JobTiming, MAX_TIMINGS};
+use crate::grbl::settings::GrblSettingsSnapshot;
+use crate::grbl::wcs::NamedOffset;
 use crate::laser::driver::LaserDriverProfile;
+use crate::lihuiyu::protocol::LihuiyuBoard;
+use crate::serial::fluidnc::FileStore;
 
 /// Machine profile saved to disk (port, baud, workspace, kinematics)
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -15,6 +21,15 @@ pub struct MachineProfile {
     pub max_rate_y: f32,
     pub accel_x: f32,
     pub accel_y: f32,
+    /// How far a corner may cut inside the path at speed (GRBL `$11`)
+    #[serde(default = "default_junction_deviation")]
+    pub junction_deviation_mm: f32,
+    /// Travel time of each hold-to-jog segment on firmware without jog cancel
+    #[serde(default = "default_jog_segment_ms")]
+    pub jog_segment_ms: u32,
+    /// S value that means full laser power (GRBL `$30`)
+    #[serde(default = "default_max_spindle_s")]
+    pub max_spindle_s: f32,
     pub return_to_origin: bool,
     pub air_assist: bool,
     pub rotary_enabled: bool,
@@ -25,6 +40,31 @@ pub struct MachineProfile {
     pub controller_kind: ControllerKind,
     #[serde(default)]
     pub laser_driver_profile: LaserDriverProfile,
+    /// Board revision for the K40 EGV driver (speed-code table)
+    #[serde(default)]
+    pub lihuiyu_board: LihuiyuBoard,
+    /// Probe the serial port on connect and pick the controller and driver from the reply
+    #[serde(default)]
+    pub auto_detect_controller: bool,
+    /// Last firmware reported by the controller
+    #[serde(default)]
+    pub detected_firmware: Option<FirmwareInfo>,
+    /// `$$` backups taken from the controller, oldest first
+    #[serde(default)]
+    pub grbl_settings_snapshots: Vec<GrblSettingsSnapshot>,
+    /// Named work origins (jigs), written back with G10 L2
+    #[serde(default)]
+    pub named_offsets: Vec<NamedOffset>,
+
+    // GRBL streaming
+    /// Keep GRBL's RX buffer full (character counting) instead of waiting for each `ok`
+    #[serde(default)]
+    pub grbl_char_counting: bool,
+    #[serde(default = "default_grbl_rx_buffer")]
+    pub grbl_rx_buffer_size: usize,
+    /// Send Marlin job lines as `N<n> …*<checksum>` and honour `Resend:` requests (opt-in)
+    #[serde(default)]
+    pub marlin_line_checksums: bool,
 
     // Tube wear tracking (F97)
     #[serde(default)]
@@ -44,6 +84,10 @@ pub struct MachineProfile {
     #[serde(default)]
     pub total_jobs_completed: u32,
 
+    /// Estimated against measured run time of finished jobs, oldest first
+    #[serde(default)]
+    pub job_timings: Vec<JobTiming>,
+
     // Auto-focus Z probe (F19)
     #[serde(default)]
     pub autofocus_enabled: bool,
@@ -75,11 +119,22 @@ pub struct MachineProfile {
     pub interlock_lid_pin: String, // M-code or input pin to check
     #[serde(default)]
     pub interlock_water_pin: String,
+
+    /// Retry a link that drops unexpectedly and offer to resume the interrupted job (opt-in)
+    #[serde(default)]
+    pub auto_reconnect: bool,
+
+    /// Where FluidNC/ESP3D connections store uploaded jobs
+    #[serde(default)]
+    pub fluidnc_store: FileStore,
 }
 
 fn default_controller_kind() -> ControllerKind {
     ControllerKind::Grbl
 }
+fn default_grbl_rx_buffer() -> usize {
+    crate::grbl::streamer::DEFAULT_RX_BUFFER_SIZE
+}
 fn default_tube_life() -> f64 {
     2000.0
 }
@@ -101,6 +156,15 @@ fn default_probe_max_depth() -> f32 {
 fn default_focus_offset() -> f32 {
     0.0
 }
+fn default_junction_deviation() -> f32 {
+    0.01
+}
+fn default_jog_segment_ms() -> u32 {
+    100
+}
+fn default_max_spindle_s() -> f32 {
+    1000.0
+}
 
 impl Default for MachineProfile {
     fn default() -> Self {
@@ -112,6 +176,9 @@ impl Default for MachineProfile {
             max_rate_y: 3000.0,
             accel_x: 200.0,
             accel_y: 200.0,
+            junction_deviation_mm: default_junction_deviation(),
+            jog_segment_ms: default_jog_segment_ms(),
+            max_spindle_s: default_max_spindle_s(),
             return_to_origin: true,
             air_assist: false,
             rotary_enabled: false,
@@ -120,6 +187,14 @@ impl Default for MachineProfile {
             rotary_steps_per_deg: 1.0,
             controller_kind: default_controller_kind(),
             laser_driver_profile: LaserDriverProfile::default(),
+            lihuiyu_board: LihuiyuBoard::default(),
+            auto_detect_controller: false,
+            detected_firmware: None,
+            grbl_settings_snapshots: Vec::new(),
+            named_offsets: Vec::new(),
+            grbl_char_counting: false,
+            grbl_rx_buffer_size: default_grbl_rx_buffer(),
+            marlin_line_checksums: false,
             tube_hours_total: 0.0,
             tube_life_hours: default_tube_life(),
             maintenance_jobs_since_lens_clean: 0,
@@ -127,6 +202,7 @@ impl Default for MachineProfile {
             maintenance_jobs_since_belt_check: 0,
             belt_check_interval_jobs: default_belt_check_interval(),
             total_jobs_completed: 0,
+            job_timings: Vec::new(),
             autofocus_enabled: false,
             probe_feed_rate: default_probe_feed(),
             probe_max_depth_mm: default_probe_max_depth(),
@@ -140,6 +216,8 @@ impl Default for MachineProfile {
             interlock_water_enabled: false,
             interlock_lid_pin: String::new(),
             interlock_water_pin: String::new(),
+            auto_reconnect: false,
+            fluidnc_store: FileStore::default(),
         }
     }
 }
@@ -165,6 +243,13 @@ impl MachineProfile {
         self.maintenance_jobs_since_belt_check += 1;
     }
 
+    /// Keep a finished job's timing for estimate calibration
+    pub fn record_job_timing(&mut self, timing: JobTiming) {
+        self.job_timings.push(timing);
+        let excess = self.job_timings.len().saturating_sub(MAX_TIMINGS);
+        self.job_timings.drain(..excess);
+    }
+
     /// Reset lens cleaning counter (F27)
     pub fn reset_lens_clean(&mut self) {
         self.maintenance_jobs_since_lens_clean = 0;
@@ -1,5 +1,6 @@
 pub mod event_log;
 pub mod machine_profile;
+pub mod profile_sync;
 pub mod project;
 pub mod recent_files;
 pub mod settings;
@@ -0,0 +1,359 @@
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+use crate::config::machine_profile::MachineProfile;
+
+/// Profile values the controller also knows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProfileField {
+    WorkspaceX,
+    WorkspaceY,
+    MaxRateX,
+    MaxRateY,
+    AccelX,
+    AccelY,
+    JunctionDeviation,
+    StepsPerMmX,
+    StepsPerMmY,
+}
+
+impl ProfileField {
+    pub const ALL: [ProfileField; 9] = [
+        ProfileField::WorkspaceX,
+        ProfileField::WorkspaceY,
+        ProfileField::MaxRateX,
+        ProfileField::MaxRateY,
+        ProfileField::AccelX,
+        ProfileField::AccelY,
+        ProfileField::JunctionDeviation,
+        ProfileField::StepsPerMmX,
+        ProfileField::StepsPerMmY,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ProfileField::WorkspaceX => "Width",
+            ProfileField::WorkspaceY => "Height",
+            ProfileField::MaxRateX => "Max Rate X",
+            ProfileField::MaxRateY => "Max Rate Y",
+            ProfileField::AccelX => "Accel X",
+            ProfileField::AccelY => "Accel Y",
+            ProfileField::JunctionDeviation => "Junction Deviation",
+            ProfileField::StepsPerMmX => "Steps/mm X",
+            ProfileField::StepsPerMmY => "Steps/mm Y",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            ProfileField::WorkspaceX | ProfileField::WorkspaceY | ProfileField::JunctionDeviation => "mm",
+            ProfileField::MaxRateX | ProfileField::MaxRateY => "mm/min",
+            ProfileField::AccelX | ProfileField::AccelY => "mm/s²",
+            ProfileField::StepsPerMmX | ProfileField::StepsPerMmY => "steps/mm",
+        }
+    }
+
+    /// GRBL `$` setting holding this value
+    pub fn grbl_setting(self) -> i32 {
+        match self {
+            ProfileField::JunctionDeviation => 11,
+            ProfileField::StepsPerMmX => 100,
+            ProfileField::StepsPerMmY => 101,
+            ProfileField::MaxRateX => 110,
+            ProfileField::MaxRateY => 111,
+            ProfileField::AccelX => 120,
+            ProfileField::AccelY => 121,
+            ProfileField::WorkspaceX => 130,
+            ProfileField::WorkspaceY => 131,
+        }
+    }
+
+    pub fn get(self, profile: &MachineProfile) -> f32 {
+        match self {
+            ProfileField::WorkspaceX => profile.workspace_x_mm,
+            ProfileField::WorkspaceY => profile.workspace_y_mm,
+            ProfileField::MaxRateX => profile.max_rate_x,
+            ProfileField::MaxRateY => profile.max_rate_y,
+            ProfileField::AccelX => profile.accel_x,
+            ProfileField::AccelY => profile.accel_y,
+            ProfileField::JunctionDeviation => profile.junction_deviation_mm,
+            ProfileField::StepsPerMmX => profile.steps_per_mm_x,
+            ProfileField::StepsPerMmY => profile.steps_per_mm_y,
+        }
+    }
+
+    pub fn set(self, profile: &mut MachineProfile, value: f32) {
+        let slot = match self {
+            ProfileField::WorkspaceX => &mut profile.workspace_x_mm,
+            ProfileField::WorkspaceY => &mut profile.workspace_y_mm,
+            ProfileField::MaxRateX => &mut profile.max_rate_x,
+            ProfileField::MaxRateY => &mut profile.max_rate_y,
+            ProfileField::AccelX => &mut profile.accel_x,
+            ProfileField::AccelY => &mut profile.accel_y,
+            ProfileField::JunctionDeviation => &mut profile.junction_deviation_mm,
+            ProfileField::StepsPerMmX => &mut profile.steps_per_mm_x,
+            ProfileField::StepsPerMmY => &mut profile.steps_per_mm_y,
+        };
+        *slot = value;
+    }
+}
+
+/// Profile values from a GRBL `$$` dump
+pub fn from_grbl_settings(settings: &BTreeMap<i32, String>) -> BTreeMap<ProfileField, f32> {
+    ProfileField::ALL
+        .iter()
+        .filter_map(|&field| {
+            let value = settings.get(&field.grbl_setting())?.trim().parse::<f32>().ok()?;
+            (value > 0.0).then_some((field, value))
+        })
+        .collect()
+}
+
+/// Which command set reads the machine limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncSource {
+    /// `$$`
+    Grbl,
+    /// `M503` (steps, rates, accelerations, junction deviation) and `M211` (soft endstop box)
+    Marlin,
+}
+
+impl SyncSource {
+    pub fn commands(self) -> &'static [&'static str] {
+        match self {
+            SyncSource::Grbl => &["$$"],
+            SyncSource::Marlin => &["M503", "M211"],
+        }
+    }
+}
+
+/// Collects the controller's answers until every command has been acknowledged
+#[derive(Debug)]
+pub struct ProfileReader {
+    source: SyncSource,
+    grbl: BTreeMap<i32, String>,
+    values: BTreeMap<ProfileField, f32>,
+    pending_oks: usize,
+}
+
+impl ProfileReader {
+    pub fn new(source: SyncSource) -> Self {
+        Self {
+            source,
+            grbl: BTreeMap::new(),
+            values: BTreeMap::new(),
+            pending_oks: source.commands().len(),
+        }
+    }
+
+    pub fn source(&self) -> SyncSource {
+        self.source
+    }
+
+    /// Feed one line; returns the values once the last command is answered
+    pub fn push(&mut self, line: &str) -> Option<Result<BTreeMap<ProfileField, f32>, String>> {
+        let line = line.trim();
+        if line.eq_ignore_ascii_case("ok") {
+            self.pending_oks = self.pending_oks.saturating_sub(1);
+            return (self.pending_oks == 0).then(|| self.finish());
+        }
+        if line.to_ascii_lowercase().starts_with("error") {
+            return Some(Err(format!("Controller rejected the settings query ({line})")));
+        }
+        match self.source {
+            SyncSource::Grbl => {
+                if let Some((id, value)) = line.strip_prefix('$').and_then(|rest| rest.split_once('='))
+                    && let Ok(id) = id.trim().parse::<i32>()
+                {
+                    self.grbl.insert(id, value.to_string());
+                }
+            }
+            SyncSource::Marlin => parse_marlin_line(line, &mut self.values),
+        }
+        None
+    }
+
+    fn finish(&mut self) -> Result<BTreeMap<ProfileField, f32>, String> {
+        if self.source == SyncSource::Grbl {
+            self.values = from_grbl_settings(&self.grbl);
+        }
+        if self.values.is_empty() {
+            return Err("The controller did not report any machine limits".to_string());
+        }
+        Ok(std::mem::take(&mut self.values))
+    }
+}
+
+/// `X80.00`-style axis words after a Marlin command
+fn axis_words(text: &str) -> impl Iterator<Item = (char, f32)> + '_ {
+    text.split_whitespace().filter_map(|word| {
+        let axis = word.chars().next()?.to_ascii_uppercase();
+        let value = word[axis.len_utf8()..].parse::<f32>().ok()?;
+        Some((axis, value))
+    })
+}
+
+fn xy(text: &str) -> (Option<f32>, Option<f32>) {
+    let mut x = None;
+    let mut y = None;
+    for (axis, value) in axis_words(text) {
+        match axis {
+            'X' => x = Some(value),
+            'Y' => y = Some(value),
+            _ => {}
+        }
+    }
+    (x, y)
+}
+
+/// One line of `M503`/`M211` output; `echo:` prefixes and comment lines are fine
+fn parse_marlin_line(line: &str, values: &mut BTreeMap<ProfileField, f32>) {
+    let line = line.strip_prefix("echo:").unwrap_or(line).trim();
+    let mut set = |field: ProfileField, value: Option<f32>| {
+        if let Some(value) = value.filter(|v| *v > 0.0) {
+            values.insert(field, value);
+        }
+    };
+
+    // "Soft endstops: On  Min:  X0.00 Y0.00 Z0.00   Max:  X235.00 Y235.00 Z250.00"
+    if line.starts_with("Soft endstops")
+        && let Some((min_part, max_part)) = line
+            .split_once("Min:")
+            .and_then(|(_, rest)| rest.split_once("Max:"))
+    {
+        let (min_x, min_y) = xy(min_part);
+        let (max_x, max_y) = xy(max_part);
+        set(ProfileField::WorkspaceX, max_x.zip(min_x).map(|(max, min)| max - min));
+        set(ProfileField::WorkspaceY, max_y.zip(min_y).map(|(max, min)| max - min));
+        return;
+    }
+
+    let Some((command, rest)) = line.split_once(char::is_whitespace) else {
+        return;
+    };
+    let (x, y) = xy(rest);
+    match command.to_ascii_uppercase().as_str() {
+        "M92" => {
+            set(ProfileField::StepsPerMmX, x);
+            set(ProfileField::StepsPerMmY, y);
+        }
+        // Marlin reports feed rates per second
+        "M203" => {
+            set(ProfileField::MaxRateX, x.map(|v| v * 60.0));
+            set(ProfileField::MaxRateY, y.map(|v| v * 60.0));
+        }
+        "M201" => {
+            set(ProfileField::AccelX, x);
+            set(ProfileField::AccelY, y);
+        }
+        // "M205 B20000.00 S0.00 T0.00 J0.01"
+        "M205" => set(
+            ProfileField::JunctionDeviation,
+            axis_words(rest).find(|(word, _)| *word == 'J').map(|(_, value)| value),
+        ),
+        _ => {}
+    }
+}
+
+/// One profile value next to what the controller reported
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncRow {
+    pub field: ProfileField,
+    pub profile: f32,
+    pub controller: f32,
+}
+
+impl SyncRow {
+    /// More than rounding apart
+    pub fn differs(&self) -> bool {
+        (self.profile - self.controller).abs() > (self.controller.abs() * 0.005).max(0.001)
+    }
+}
+
+pub fn compare(profile: &MachineProfile, values: &BTreeMap<ProfileField, f32>) -> Vec<SyncRow> {
+    values
+        .iter()
+        .map(|(&field, &controller)| SyncRow {
+            field,
+            profile: field.get(profile),
+            controller,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(source: SyncSource, lines: &[&str]) -> Option<Result<BTreeMap<ProfileField, f32>, String>> {
+        let mut reader = ProfileReader::new(source);
+        lines.iter().find_map(|line| reader.push(line))
+    }
+
+    #[test]
+    fn reads_grbl_settings() {
+        let values = read(
+            SyncSource::Grbl,
+            &[
+                "$11=0.020",
+                "$100=80.000",
+                "$101=80.000",
+                "$110=6000.000",
+                "$111=5000.000",
+                "$120=500.000",
+                "$121=400.000",
+                "$130=410.000",
+                "$131=390.000",
+                "$132=0.000",
+                "ok",
+            ],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(values.len(), 9);
+        assert_eq!(values[&ProfileField::JunctionDeviation], 0.02);
+        assert_eq!(values[&ProfileField::MaxRateY], 5000.0);
+        assert_eq!(values[&ProfileField::WorkspaceX], 410.0);
+
+        let mut profile = MachineProfile::default();
+        let rows = compare(&profile, &values);
+        let differing: Vec<_> = rows.iter().filter(|r| r.differs()).map(|r| r.field).collect();
+        assert!(!differing.contains(&ProfileField::StepsPerMmX), "80 matches the default");
+        assert!(differing.contains(&ProfileField::WorkspaceY));
+        assert!(differing.contains(&ProfileField::JunctionDeviation));
+        for row in &rows {
+            row.field.set(&mut profile, row.controller);
+        }
+        assert!(compare(&profile, &values).iter().all(|r| !r.differs()));
+
+        assert!(read(SyncSource::Grbl, &["error:9"]).unwrap().is_err());
+        assert!(read(SyncSource::Grbl, &["ok"]).unwrap().is_err(), "nothing reported");
+    }
+
+    #[test]
+    fn reads_marlin_report() {
+        let values = read(
+            SyncSource::Marlin,
+            &[
+                "echo:; Steps per unit:",
+                "echo:  M92 X100.00 Y100.00 Z400.00 E93.00",
+                "echo:; Maximum feedrates (units/s):",
+                "echo:  M203 X300.00 Y250.00 Z5.00 E25.00",
+                "echo:  M201 X3000.00 Y2500.00 Z100.00 E10000.00",
+                "echo:  M205 B20000.00 S0.00 T0.00 J0.05",
+                "ok",
+                "echo:Soft endstops: On   Min:  X-5.00 Y0.00 Z0.00   Max:  X235.00 Y220.00 Z250.00",
+                "ok",
+            ],
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(values[&ProfileField::StepsPerMmX], 100.0);
+        assert_eq!(values[&ProfileField::MaxRateX], 18000.0);
+        assert_eq!(values[&ProfileField::AccelY], 2500.0);
+        assert_eq!(values[&ProfileField::JunctionDeviation], 0.05);
+        assert_eq!(values[&ProfileField::WorkspaceX], 240.0);
+        assert_eq!(values[&ProfileField::WorkspaceY], 220.0);
+    }
+}
@@ -49,6 +49,9 @@ pub struct ProjectFile {
     pub material_selected_preset: Option<String>,
     #[serde(default)]
     pub checkpoint_line: Option<usize>,
+    /// Coordinate system the checkpointed job was prepared for
+    #[serde(default)]
+    pub job_wcs: Option<crate::grbl::wcs::CoordSystem>,
     #[serde(default)]
     pub project_notes: String,
 
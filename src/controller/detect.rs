@@ -0,0 +1,661 @@
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::grbl::protocol;
+use crate::laser::driver::LaserDriverProfile;
+
+use super::ControllerKind;
+
+/// Baud rates tried after the user's selection, most common first
+pub const PROBE_BAUD_RATES: &[u32] = &[115200, 250000, 230400, 57600, 38400, 19200, 9600];
+
+/// Firmware families that can be told apart from their banners and info replies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FirmwareFamily {
+    Grbl,
+    GrblHal,
+    FluidNc,
+    Marlin,
+    Smoothieware,
+}
+
+impl FirmwareFamily {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Grbl => "GRBL",
+            Self::GrblHal => "grblHAL",
+            Self::FluidNc => "FluidNC",
+            Self::Marlin => "Marlin",
+            Self::Smoothieware => "Smoothieware",
+        }
+    }
+
+    /// grblHAL, FluidNC and Smoothieware (grbl_mode) all speak the GRBL protocol
+    pub fn controller_kind(self) -> ControllerKind {
+        match self {
+            Self::Marlin => ControllerKind::Marlin,
+            Self::Grbl | Self::GrblHal | Self::FluidNc | Self::Smoothieware => ControllerKind::Grbl,
+        }
+    }
+
+    pub fn driver_profile(self) -> LaserDriverProfile {
+        match self {
+            Self::Grbl | Self::GrblHal | Self::FluidNc => LaserDriverProfile::GrblDeviceSafe,
+            Self::Marlin => LaserDriverProfile::MarlinLineProtocol,
+            Self::Smoothieware => LaserDriverProfile::SmoothieGcode,
+        }
+    }
+}
+
+/// What the controller said about itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FirmwareInfo {
+    pub family: FirmwareFamily,
+    /// e.g. `1.1h`, `3.7.8`, `2.1.2`, `edge-3332442`
+    pub version: String,
+    /// GRBL `[OPT:]`/`[NEWOPT:]` codes or enabled Marlin `Cap:` names
+    #[serde(default)]
+    pub options: Vec<String>,
+    /// Planner blocks and RX buffer bytes from `[OPT:…,blocks,bytes]`
+    #[serde(default)]
+    pub planner_blocks: Option<u32>,
+    #[serde(default)]
+    pub rx_buffer_size: Option<u32>,
+    /// grblHAL reports its axis count after the buffer sizes
+    #[serde(default)]
+    pub axis_count: Option<u32>,
+    /// Baud rate the controller answered on (serial probes only)
+    #[serde(default)]
+    pub baud_rate: Option<u32>,
+}
+
+impl FirmwareInfo {
+    fn new(family: FirmwareFamily, version: String) -> Self {
+        Self {
+            family,
+            version,
+            options: Vec::new(),
+            planner_blocks: None,
+            rx_buffer_size: None,
+            axis_count: None,
+            baud_rate: None,
+        }
+    }
+
+    /// Leading `major.minor` of the version string: `1.1h.20190825` → (1, 1)
+    pub fn version_number(&self) -> Option<(u32, u32)> {
+        let mut parts = self.version.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor: String = parts
+            .next()?
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        Some((major, minor.parse().ok()?))
+    }
+
+    pub fn summary(&self) -> String {
+        let mut text = format!("{} {}", self.family.label(), self.version);
+        if let Some(baud) = self.baud_rate {
+            text.push_str(&format!(" @ {baud}"));
+        }
+        if !self.options.is_empty() {
+            text.push_str(&format!(" [{}]", self.options.join(",")));
+        }
+        text
+    }
+
+    pub fn has_option(&self, option: &str) -> bool {
+        self.options.iter().any(|o| o.eq_ignore_ascii_case(option))
+    }
+}
+
+/// Identify the firmware from everything it printed during the probe
+pub fn classify(lines: &[String]) -> Option<FirmwareInfo> {
+    let mut info: Option<FirmwareInfo> = None;
+    let mut grbl_version: Option<String> = None;
+    let mut opt_line: Option<String> = None;
+    let mut new_opts: Vec<String> = Vec::new();
+    let mut marlin_caps: Vec<String> = Vec::new();
+
+    for raw in lines {
+        let line = raw.trim();
+        let lower = line.to_ascii_lowercase();
+
+        // FluidNC: "Grbl 3.7 [FluidNC v3.7.8 (wifi) '$' for help]", "[VER:3.7 FluidNC v3.7.8:]"
+        if let Some(pos) = lower.find("fluidnc v") {
+            let version = first_token(&line[pos + "fluidnc v".len()..]);
+            info = Some(FirmwareInfo::new(FirmwareFamily::FluidNc, version));
+            continue;
+        }
+
+        // grblHAL: "GrblHAL 1.1f ['$' or '$HELP' for help]", "[FIRMWARE:grblHAL]"
+        if let Some(rest) = lower.strip_prefix("grblhal ") {
+            let version = first_token(&line[line.len() - rest.len()..]);
+            info = Some(FirmwareInfo::new(FirmwareFamily::GrblHal, version));
+            continue;
+        }
+        if lower.starts_with("[firmware:grblhal") {
+            if !matches!(&info, Some(i) if i.family == FirmwareFamily::GrblHal) {
+                let version = grbl_version.clone().unwrap_or_default();
+                info = Some(FirmwareInfo::new(FirmwareFamily::GrblHal, version));
+            }
+            continue;
+        }
+
+        // "[VER:1.1h.20190825:]" carries the full build string for every GRBL flavour
+        if let Some(rest) = line.strip_prefix("[VER:") {
+            let ver = rest.trim_end_matches(']').split(':').next().unwrap_or("");
+            let ver = first_token(ver);
+            if !ver.is_empty() {
+                grbl_version = Some(ver);
+            }
+            continue;
+        }
+        // GRBL 0.9 `$I`: "[0.9j.20160303:]"
+        if line.starts_with('[') && line[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            let ver = first_token(line[1..].trim_end_matches(']').split(':').next().unwrap_or(""));
+            if !ver.is_empty() {
+                grbl_version = Some(ver);
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("[OPT:") {
+            opt_line = Some(rest.trim_end_matches(']').to_string());
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("[NEWOPT:") {
+            new_opts.extend(
+                rest.trim_end_matches(']')
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string),
+            );
+            continue;
+        }
+
+        // Plain GRBL banner: "Grbl 1.1h ['$' for help]"
+        if let Some(rest) = lower.strip_prefix("grbl ") {
+            if info.is_none() {
+                let version = first_token(&line[line.len() - rest.len()..]);
+                info = Some(FirmwareInfo::new(FirmwareFamily::Grbl, version));
+            }
+            continue;
+        }
+
+        // Marlin M115: "FIRMWARE_NAME:Marlin 2.1.2 (Jun 11 2023 12:00:00) SOURCE_CODE_URL:…"
+        if let Some(pos) = line.find("FIRMWARE_NAME:") {
+            let name = &line[pos + "FIRMWARE_NAME:".len()..];
+            if name.to_ascii_lowercase().starts_with("marlin") {
+                let version = first_token(name["marlin".len()..].trim_start());
+                info = Some(FirmwareInfo::new(FirmwareFamily::Marlin, version));
+            }
+            continue;
+        }
+        if let Some(cap) = line.strip_prefix("Cap:") {
+            if let Some((name, value)) = cap.split_once(':')
+                && value.trim() == "1"
+            {
+                marlin_caps.push(name.trim().to_string());
+            }
+            continue;
+        }
+        // Marlin boot banner: "echo:Marlin 2.0.9.3"
+        if let Some(rest) = lower.strip_prefix("echo:marlin ") {
+            if info.is_none() {
+                let version = first_token(&line[line.len() - rest.len()..]);
+                info = Some(FirmwareInfo::new(FirmwareFamily::Marlin, version));
+            }
+            continue;
+        }
+
+        // Smoothie `version`: "Build version: edge-3332442, Build date: …, MCU: LPC1769, …"
+        if let Some(rest) = lower.strip_prefix("build version:") {
+            let version = first_token(&line[line.len() - rest.len()..]);
+            let version = version.trim_end_matches(',').to_string();
+            info = Some(FirmwareInfo::new(FirmwareFamily::Smoothieware, version));
+            continue;
+        }
+        if lower == "smoothie" && info.is_none() {
+            info = Some(FirmwareInfo::new(
+                FirmwareFamily::Smoothieware,
+                String::new(),
+            ));
+        }
+    }
+
+    let mut info = info.or_else(|| {
+        // `$I` reply without a banner (e.g. the reset was swallowed by a bootloader)
+        grbl_version
+            .clone()
+            .map(|v| FirmwareInfo::new(FirmwareFamily::Grbl, v))
+    })?;
+
+    match info.family {
+        FirmwareFamily::Grbl | FirmwareFamily::GrblHal | FirmwareFamily::FluidNc => {
+            if let Some(ver) = grbl_version
+                && info.family != FirmwareFamily::FluidNc
+            {
+                info.version = ver;
+            }
+            if let Some(opt) = opt_line {
+                let mut fields = opt.split(',').map(str::trim);
+                if let Some(codes) = fields.next() {
+                    info.options.extend(codes.chars().map(|c| c.to_string()));
+                }
+                info.planner_blocks = fields.next().and_then(|s| s.parse().ok());
+                info.rx_buffer_size = fields.next().and_then(|s| s.parse().ok());
+                if info.family == FirmwareFamily::GrblHal {
+                    info.axis_count = fields.next().and_then(|s| s.parse().ok());
+                }
+            }
+            info.options.extend(new_opts);
+        }
+        FirmwareFamily::Marlin => info.options = marlin_caps,
+        FirmwareFamily::Smoothieware => {}
+    }
+
+    Some(info)
+}
+
+fn first_token(text: &str) -> String {
+    text.split_whitespace()
+        .next()
+        .unwrap_or("")
+        .trim_end_matches([']', ':'])
+        .to_string()
+}
+
+/// How long to listen after each probe step
+#[derive(Debug, Clone, Copy)]
+pub struct ProbeTiming {
+    /// After opening the port: Arduino-style boards reboot on DTR
+    pub boot: Duration,
+    /// After each query
+    pub reply: Duration,
+}
+
+impl Default for ProbeTiming {
+    fn default() -> Self {
+        Self {
+            boot: Duration::from_millis(1500),
+            reply: Duration::from_millis(600),
+        }
+    }
+}
+
+/// Queries in the order they are sent; each is harmless on the other firmwares
+/// (GRBL answers `M115` with `error:20`, Marlin echoes "Unknown command" for `$I`).
+const PROBE_QUERIES: &[&str] = &["$I", "M115", "version"];
+
+/// Soft reset, then `$I`, `M115` and `version` until the replies identify the firmware
+pub fn probe_stream<S: Read + Write>(stream: &mut S, timing: ProbeTiming) -> Option<FirmwareInfo> {
+    let mut lines = read_lines_for(stream, timing.boot);
+
+    let _ = stream.write_all(&[protocol::CMD_RESET]);
+    let _ = stream.flush();
+    lines.extend(read_lines_for(stream, timing.reply));
+
+    for query in PROBE_QUERIES {
+        let _ = stream.write_all(format!("{query}\n").as_bytes());
+        let _ = stream.flush();
+        lines.extend(read_lines_for(stream, timing.reply));
+        if let Some(info) = classify(&lines)
+            && is_conclusive(&info, &lines)
+        {
+            return Some(info);
+        }
+    }
+    classify(&lines)
+}
+
+/// A banner alone is not enough to stop: the info reply carries the options.
+pub fn is_conclusive(info: &FirmwareInfo, lines: &[String]) -> bool {
+    match info.family {
+        FirmwareFamily::Grbl | FirmwareFamily::GrblHal | FirmwareFamily::FluidNc => {
+            lines.iter().any(|l| l.starts_with("[OPT:"))
+        }
+        FirmwareFamily::Marlin => lines.iter().any(|l| l.contains("FIRMWARE_NAME:")),
+        FirmwareFamily::Smoothieware => !info.version.is_empty(),
+    }
+}
+
+/// Options from a FluidNC `$CD` config dump: `SD`, `LASER` and `AXES:<letters>`
+pub fn parse_fluidnc_config(lines: &[String]) -> Vec<String> {
+    let mut options = Vec::new();
+    let mut axes = String::new();
+    let mut in_axes = false;
+    for line in lines {
+        let indent = line.len() - line.trim_start().len();
+        let key = line.trim().trim_end_matches(':').to_ascii_lowercase();
+        if indent == 0 {
+            in_axes = key == "axes";
+            if key == "sdcard" {
+                options.push("SD".to_string());
+            }
+        } else if in_axes
+            && indent <= 2
+            && key.len() == 1
+            && "xyzabc".contains(key.as_str())
+        {
+            axes.push_str(&key.to_ascii_uppercase());
+        }
+        // Spindle sections are named after their type, e.g. `Laser:` or `PWM:`
+        if key == "laser" && !options.iter().any(|o| o == "LASER") {
+            options.push("LASER".to_string());
+        }
+    }
+    if !axes.is_empty() {
+        options.push(format!("AXES:{axes}"));
+    }
+    options
+}
+
+/// Watches the replies to the identification query sent right after connecting
+#[derive(Debug, Clone)]
+pub struct FirmwareListener {
+    lines: Vec<String>,
+    config_lines: Vec<String>,
+    identified: Option<FirmwareInfo>,
+    deadline: Instant,
+}
+
+/// What the app should do after feeding a line to the listener
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListenerStep {
+    Waiting,
+    /// Send this follow-up query (FluidNC config dump)
+    Send(&'static str),
+    Identified(FirmwareInfo),
+    /// Replies ended without naming a known firmware
+    Unknown,
+}
+
+/// Give up on identification after this long
+pub const LISTEN_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl FirmwareListener {
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            config_lines: Vec::new(),
+            identified: None,
+            deadline: Instant::now() + LISTEN_TIMEOUT,
+        }
+    }
+
+    /// Identification query for the configured protocol
+    pub fn query(kind: ControllerKind) -> Option<&'static str> {
+        match kind {
+            ControllerKind::Grbl => Some("$I"),
+            ControllerKind::Marlin => Some("M115"),
+            ControllerKind::Ruida | ControllerKind::Trocen => None,
+        }
+    }
+
+    pub fn expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    pub fn push(&mut self, line: &str) -> ListenerStep {
+        let line = line.trim_end();
+        let lower = line.trim().to_ascii_lowercase();
+        let finished = lower == "ok" || lower.starts_with("error");
+
+        // Second phase: collecting the FluidNC config dump
+        if let Some(info) = &mut self.identified {
+            if !finished {
+                self.config_lines.push(line.to_string());
+                return ListenerStep::Waiting;
+            }
+            info.options.extend(parse_fluidnc_config(&self.config_lines));
+            return ListenerStep::Identified(info.clone());
+        }
+
+        if !finished {
+            self.lines.push(line.trim().to_string());
+            return ListenerStep::Waiting;
+        }
+        // The first `ok`/`error` closes the reply to the identification query
+        match classify(&self.lines) {
+            Some(info) if info.family == FirmwareFamily::FluidNc => {
+                self.identified = Some(info);
+                ListenerStep::Send("$CD")
+            }
+            Some(info) => ListenerStep::Identified(info),
+            None => ListenerStep::Unknown,
+        }
+    }
+}
+
+impl Default for FirmwareListener {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_lines_for<S: Read>(stream: &mut S, window: Duration) -> Vec<String> {
+    let deadline = Instant::now() + window;
+    let mut bytes = Vec::new();
+    let mut buf = [0u8; 256];
+    while Instant::now() < deadline {
+        match stream.read(&mut buf) {
+            Ok(0) => std::thread::sleep(Duration::from_millis(5)),
+            Ok(n) => bytes.extend_from_slice(&buf[..n]),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::TimedOut
+                        | std::io::ErrorKind::WouldBlock
+                        | std::io::ErrorKind::Interrupted
+                ) => {}
+            Err(_) => break,
+        }
+    }
+    String::from_utf8_lossy(&bytes)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// Try `preferred` first, then the common rates, until a firmware answers
+pub fn probe_serial_port(port_name: &str, preferred: u32) -> Result<FirmwareInfo, String> {
+    let mut bauds = vec![preferred];
+    bauds.extend(PROBE_BAUD_RATES.iter().copied().filter(|b| *b != preferred));
+
+    for baud in bauds {
+        let mut port = serialport::new(port_name, baud)
+            .timeout(Duration::from_millis(50))
+            .open()
+            .map_err(|e| format!("Failed to open {port_name}: {e}"))?;
+        if let Some(mut info) = probe_stream(&mut port, ProbeTiming::default()) {
+            info.baud_rate = Some(baud);
+            return Ok(info);
+        }
+    }
+    Err(format!("No known controller answered on {port_name}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grbl::sim::{VirtualGrbl, VirtualGrblConfig};
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn classifies_grbl_family_replies() {
+        let grbl = classify(&lines(&[
+            "Grbl 1.1h ['$' for help]",
+            "[VER:1.1h.20190825:]",
+            "[OPT:VNM,15,128]",
+            "ok",
+        ]))
+        .expect("grbl");
+        assert_eq!(grbl.family, FirmwareFamily::Grbl);
+        assert_eq!(grbl.version, "1.1h.20190825");
+        assert!(grbl.has_option("V") && grbl.has_option("M"));
+        assert_eq!(grbl.planner_blocks, Some(15));
+        assert_eq!(grbl.rx_buffer_size, Some(128));
+
+        let hal = classify(&lines(&[
+            "GrblHAL 1.1f ['$' or '$HELP' for help]",
+            "[VER:1.1f.20230610:]",
+            "[OPT:VNMSL,35,1024,3,0]",
+            "[NEWOPT:ENUMS,RT+,HOME,SD]",
+            "[FIRMWARE:grblHAL]",
+            "ok",
+        ]))
+        .expect("grblHAL");
+        assert_eq!(hal.family, FirmwareFamily::GrblHal);
+        assert_eq!(hal.version, "1.1f.20230610");
+        assert!(hal.has_option("SD"));
+        assert_eq!(hal.rx_buffer_size, Some(1024));
+        assert_eq!(
+            hal.family.driver_profile(),
+            LaserDriverProfile::GrblDeviceSafe
+        );
+
+        let fluid = classify(&lines(&[
+            "Grbl 3.7 [FluidNC v3.7.8 (wifi) '$' for help]",
+            "[VER:3.7 FluidNC v3.7.8:]",
+            "[OPT:PHS]",
+            "ok",
+        ]))
+        .expect("fluidnc");
+        assert_eq!(fluid.family, FirmwareFamily::FluidNc);
+        assert_eq!(fluid.version, "3.7.8");
+        assert_eq!(fluid.family.controller_kind(), ControllerKind::Grbl);
+    }
+
+    #[test]
+    fn classifies_marlin_and_smoothie() {
+        let marlin = classify(&lines(&[
+            "echo:Unknown command: \"$I\"",
+            "FIRMWARE_NAME:Marlin 2.1.2 (Jun 11 2023 12:00:00) SOURCE_CODE_URL:github.com/MarlinFirmware/Marlin PROTOCOL_VERSION:1.0 MACHINE_TYPE:Laser",
+            "Cap:SERIAL_XON_XOFF:0",
+            "Cap:EEPROM:1",
+            "Cap:AUTOREPORT_POS:1",
+            "ok",
+        ]))
+        .expect("marlin");
+        assert_eq!(marlin.family, FirmwareFamily::Marlin);
+        assert_eq!(marlin.version, "2.1.2");
+        assert_eq!(marlin.options, vec!["EEPROM", "AUTOREPORT_POS"]);
+        assert_eq!(marlin.family.controller_kind(), ControllerKind::Marlin);
+
+        let smoothie = classify(&lines(&[
+            "Smoothie",
+            "ok",
+            "Build version: edge-3332442, Build date: Apr 22 2015 15:52:55, MCU: LPC1769, System Clock: 120MHz",
+        ]))
+        .expect("smoothie");
+        assert_eq!(smoothie.family, FirmwareFamily::Smoothieware);
+        assert_eq!(smoothie.version, "edge-3332442");
+        assert_eq!(
+            smoothie.family.driver_profile(),
+            LaserDriverProfile::SmoothieGcode
+        );
+
+        assert!(classify(&lines(&["\u{fffd}\u{fffd}x", "ok"])).is_none());
+    }
+
+    #[test]
+    fn listener_follows_fluidnc_with_config_dump() {
+        let mut listener = FirmwareListener::new();
+        for line in ["[VER:3.7 FluidNC v3.7.8:]", "[OPT:PHS]", "[MSG: Machine: Laser]"] {
+            assert_eq!(listener.push(line), ListenerStep::Waiting);
+        }
+        assert_eq!(listener.push("ok"), ListenerStep::Send("$CD"));
+        for line in [
+            "name: Laser",
+            "axes:",
+            "  x:",
+            "    steps_per_mm: 80",
+            "  y:",
+            "    steps_per_mm: 80",
+            "sdcard:",
+            "  cs_pin: gpio.5",
+            "Laser:",
+            "  pwm_hz: 5000",
+        ] {
+            assert_eq!(listener.push(line), ListenerStep::Waiting);
+        }
+        let ListenerStep::Identified(info) = listener.push("ok") else {
+            panic!("expected identification");
+        };
+        assert_eq!(info.family, FirmwareFamily::FluidNc);
+        assert!(info.has_option("SD") && info.has_option("LASER"));
+        assert!(info.has_option("AXES:XY"));
+
+        let mut listener = FirmwareListener::new();
+        listener.push("echo:Unknown command: \"$I\"");
+        assert_eq!(listener.push("ok"), ListenerStep::Unknown);
+    }
+
+    /// Byte-stream view of the virtual machine, as a serial port would see it
+    struct SimPort {
+        grbl: VirtualGrbl,
+        pending: Vec<u8>,
+        written: Vec<String>,
+    }
+
+    impl Read for SimPort {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.grbl.advance(0.005);
+            for line in self.grbl.drain_output() {
+                self.pending.extend_from_slice(line.as_bytes());
+                self.pending.extend_from_slice(b"\r\n");
+            }
+            if self.pending.is_empty() {
+                std::thread::sleep(Duration::from_millis(1));
+                return Err(std::io::ErrorKind::TimedOut.into());
+            }
+            let n = buf.len().min(self.pending.len());
+            buf[..n].copy_from_slice(&self.pending[..n]);
+            self.pending.drain(..n);
+            Ok(n)
+        }
+    }
+
+    impl Write for SimPort {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.written
+                .push(String::from_utf8_lossy(buf).trim().to_string());
+            self.grbl.write(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn probe_identifies_virtual_grbl_and_stops_early() {
+        let mut port = SimPort {
+            grbl: VirtualGrbl::new(&VirtualGrblConfig::default()),
+            pending: Vec::new(),
+            written: Vec::new(),
+        };
+        let timing = ProbeTiming {
+            boot: Duration::from_millis(20),
+            reply: Duration::from_millis(40),
+        };
+        let info = probe_stream(&mut port, timing).expect("virtual GRBL answers");
+        assert_eq!(info.family, FirmwareFamily::Grbl);
+        assert_eq!(info.version, "1.1h.20190825");
+        assert_eq!(info.planner_blocks, Some(15));
+        // `$I` was enough; the Marlin and Smoothie queries were never sent.
+        assert!(port.written.iter().any(|w| w == "$I"));
+        assert!(!port.written.iter().any(|w| w == "M115" || w == "version"));
+    }
+}
@@ -0,0 +1,320 @@
+#![allow(dead_code)]
+
+use super::detect::{FirmwareFamily, FirmwareInfo};
+use super::{ControllerKind, RealtimeCommand};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultKind {
+    Error,
+    Alarm,
+}
+
+/// One-click recovery offered next to a fault
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    Unlock,
+    Home,
+    SoftReset,
+    CycleStart,
+    ShowSettings,
+    MarlinRestart,
+    MarlinHome,
+    /// Pulse DTR/RTS; the only way out of Marlin's kill()
+    ResetBoard,
+    Reconnect,
+}
+
+/// What a recovery action sends to the controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryCommand {
+    Line(&'static str),
+    Realtime(RealtimeCommand),
+    /// Handled by the link, not sent as text
+    ResetBoard,
+    Reconnect,
+}
+
+impl RecoveryAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Unlock => "Unlock ($X)",
+            Self::Home => "Home ($H)",
+            Self::SoftReset => "Soft reset",
+            Self::CycleStart => "Resume (~)",
+            Self::ShowSettings => "Show settings ($$)",
+            Self::MarlinRestart => "Restart (M999)",
+            Self::MarlinHome => "Home (G28)",
+            Self::ResetBoard => "Reset board",
+            Self::Reconnect => "Reconnect",
+        }
+    }
+
+    pub fn command(self) -> RecoveryCommand {
+        match self {
+            Self::Unlock => RecoveryCommand::Line("$X"),
+            Self::Home => RecoveryCommand::Line("$H"),
+            Self::SoftReset => RecoveryCommand::Realtime(RealtimeCommand::Reset),
+            Self::CycleStart => RecoveryCommand::Realtime(RealtimeCommand::CycleStart),
+            Self::ShowSettings => RecoveryCommand::Line("$$"),
+            Self::MarlinRestart => RecoveryCommand::Line("M999"),
+            Self::MarlinHome => RecoveryCommand::Line("G28 X Y"),
+            Self::ResetBoard => RecoveryCommand::ResetBoard,
+            Self::Reconnect => RecoveryCommand::Reconnect,
+        }
+    }
+}
+
+/// Catalogue entry for an error or alarm code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaultInfo {
+    pub kind: FaultKind,
+    /// Numeric code; -1 for Marlin's textual errors
+    pub code: i32,
+    pub title: &'static str,
+    pub cause: &'static str,
+    pub recovery: &'static str,
+    pub actions: &'static [RecoveryAction],
+}
+
+impl FaultInfo {
+    /// e.g. `ALARM:2 Soft limit`
+    pub fn summary(&self) -> String {
+        match (self.kind, self.code) {
+            (_, code) if code < 0 => self.title.to_string(),
+            (FaultKind::Error, code) => format!("error:{code} {}", self.title),
+            (FaultKind::Alarm, code) => format!("ALARM:{code} {}", self.title),
+        }
+    }
+}
+
+use RecoveryAction::*;
+
+const fn err(
+    code: i32,
+    title: &'static str,
+    cause: &'static str,
+    recovery: &'static str,
+    actions: &'static [RecoveryAction],
+) -> FaultInfo {
+    FaultInfo { kind: FaultKind::Error, code, title, cause, recovery, actions }
+}
+
+const fn alarm(
+    code: i32,
+    title: &'static str,
+    cause: &'static str,
+    recovery: &'static str,
+    actions: &'static [RecoveryAction],
+) -> FaultInfo {
+    FaultInfo { kind: FaultKind::Alarm, code, title, cause, recovery, actions }
+}
+
+const FIX_GCODE: &str = "Fix the offending line in the job or regenerate the G-code.";
+
+#[rustfmt::skip]
+static GRBL_ERRORS: &[FaultInfo] = &[
+    err(1, "Expected command letter", "A G-code word is missing its letter.", FIX_GCODE, &[]),
+    err(2, "Bad number format", "A value is missing or is not a valid number.", FIX_GCODE, &[]),
+    err(3, "Invalid statement", "The '$' system command is not recognised or supported.", "Check the command spelling for this firmware version.", &[]),
+    err(4, "Value < 0", "A negative value was given where a positive one is required.", FIX_GCODE, &[]),
+    err(5, "Setting disabled", "Homing was requested but is not enabled ($22).", "Enable homing with $22=1 or don't use $H.", &[ShowSettings]),
+    err(6, "Value < 3 usec", "Step pulse time ($0) must be at least 3 microseconds.", "Write a larger value to $0.", &[ShowSettings]),
+    err(7, "EEPROM read fail", "Settings could not be read; defaults were restored.", "Restore your settings from a backup.", &[ShowSettings]),
+    err(8, "Not idle", "A '$' command was sent while the machine was not idle.", "Wait for the job to finish or stop it first.", &[]),
+    err(9, "G-code lock", "G-code is locked out during an alarm or jog.", "Clear the alarm by unlocking or homing.", &[Unlock, Home]),
+    err(10, "Homing not enabled", "Soft limits ($20) require homing ($22) to be enabled.", "Enable homing before soft limits.", &[ShowSettings]),
+    err(11, "Line overflow", "The line is longer than the controller's line buffer.", "Shorten the line, e.g. reduce decimal places.", &[]),
+    err(12, "Step rate > 30kHz", "The max rate and steps/mm settings exceed the step rate limit.", "Lower $110-$112 or the steps/mm values.", &[ShowSettings]),
+    err(13, "Check door", "The safety door is open.", "Close the door, then resume.", &[CycleStart]),
+    err(14, "Line length exceeded", "Build info or startup line is too long for EEPROM.", "Use a shorter value.", &[]),
+    err(15, "Travel exceeded", "The jog target is outside the machine travel.", "Jog a shorter distance or re-home.", &[Home]),
+    err(16, "Invalid jog command", "The $J= line is malformed or contains a forbidden word.", "Send only G20/G21, G90/G91, G53, axis words and F.", &[]),
+    err(17, "Laser mode requires PWM", "Laser mode ($32) needs a PWM spindle output.", "Rebuild the firmware with variable spindle support.", &[]),
+    err(20, "Unsupported command", "The block contains an unsupported G-code command.", FIX_GCODE, &[]),
+    err(21, "Modal group violation", "More than one command from the same modal group is in the block.", FIX_GCODE, &[]),
+    err(22, "Undefined feed rate", "A feed move was sent before any F value.", "Set a feed rate (F) before G1/G2/G3 moves.", &[]),
+    err(23, "Integer value required", "The command in the block requires an integer value.", FIX_GCODE, &[]),
+    err(24, "Axis words conflict", "Two commands in the block both require axis words.", FIX_GCODE, &[]),
+    err(25, "Repeated word", "A G-code word is repeated in the block.", FIX_GCODE, &[]),
+    err(26, "No axis words", "The command requires axis words but none were given.", FIX_GCODE, &[]),
+    err(27, "Invalid line number", "The N line number is outside 1-9999999.", FIX_GCODE, &[]),
+    err(28, "Missing P or L value", "The command is missing a required P or L word.", FIX_GCODE, &[]),
+    err(29, "Unsupported coordinate system", "Only G54-G59 are supported.", FIX_GCODE, &[]),
+    err(30, "G53 needs G0/G1", "G53 requires G0 or G1 motion mode.", FIX_GCODE, &[]),
+    err(31, "Unused axis words", "Axis words were given while G80 motion cancel is active.", FIX_GCODE, &[]),
+    err(32, "Arc without axis words", "G2/G3 has no axis words in the selected plane.", FIX_GCODE, &[]),
+    err(33, "Invalid motion target", "The arc or probe target is impossible to reach.", FIX_GCODE, &[]),
+    err(34, "Arc radius error", "The radius arc geometry cannot be computed.", "Use an I/J arc or check the radius.", &[]),
+    err(35, "Arc offset missing", "G2/G3 in offset mode has no I/J/K word in the plane.", FIX_GCODE, &[]),
+    err(36, "Unused words", "The block contains words not used by any command.", FIX_GCODE, &[]),
+    err(37, "Tool offset axis", "G43.1 can only offset its configured axis.", FIX_GCODE, &[]),
+    err(38, "Invalid tool number", "The tool number exceeds the supported maximum.", FIX_GCODE, &[]),
+];
+
+#[rustfmt::skip]
+static GRBL_ALARMS: &[FaultInfo] = &[
+    alarm(1, "Hard limit", "A limit switch triggered during motion; position is lost.", "Check the switches and the job size, then home.", &[Unlock, Home]),
+    alarm(2, "Soft limit", "The motion target is outside the machine travel; position was kept.", "Check the job placement and work offset, then unlock.", &[Unlock, Home]),
+    alarm(3, "Abort during cycle", "Reset while moving; position may be lost.", "Re-home the machine.", &[Home, Unlock]),
+    alarm(4, "Probe fail", "The probe was not in the expected state before probing.", "Check the probe wiring and position.", &[Unlock]),
+    alarm(5, "Probe fail", "The probe did not make contact within the programmed travel.", "Move closer or increase the probe distance.", &[Unlock]),
+    alarm(6, "Homing fail", "The homing cycle was reset.", "Home again.", &[Home]),
+    alarm(7, "Homing fail", "The safety door opened during homing.", "Close the door and home again.", &[Home]),
+    alarm(8, "Homing fail", "Pull-off did not clear the limit switch.", "Increase pull-off ($27) or check the switch.", &[ShowSettings, Home]),
+    alarm(9, "Homing fail", "The limit switch was not found within the search distance.", "Check the switches, homing direction ($23) and max travel ($130-$132).", &[ShowSettings, Home]),
+];
+
+/// grblHAL codes on top of the GRBL 1.1 ones
+#[rustfmt::skip]
+static GRBL_HAL_ERRORS: &[FaultInfo] = &[
+    err(39, "Value out of range", "A setting or parameter value is out of range.", "Use a value inside the allowed range.", &[]),
+    err(40, "Setting not available", "The setting is not supported by this driver.", "Check the board's feature set.", &[]),
+    err(41, "Retract below drill depth", "The canned cycle retract position is below the drill depth.", FIX_GCODE, &[]),
+    err(46, "Illegal homing configuration", "The homing settings are inconsistent.", "Review the homing settings ($22-$27, $44-$46).", &[ShowSettings]),
+    err(60, "SD card mount failed", "The SD card could not be mounted.", "Reinsert the card and try again.", &[]),
+    err(61, "SD card read failed", "The file could not be opened or read.", "Check the file name and card.", &[]),
+    err(62, "SD directory listing failed", "The directory could not be opened.", "Check the card.", &[]),
+    err(63, "SD directory not found", "The directory does not exist.", "Check the path.", &[]),
+    err(64, "SD file empty", "The file is empty.", "Upload the file again.", &[]),
+];
+
+#[rustfmt::skip]
+static GRBL_HAL_ALARMS: &[FaultInfo] = &[
+    alarm(10, "E-stop", "The emergency stop input is asserted.", "Release the E-stop, then reset and unlock.", &[SoftReset, Unlock]),
+    alarm(11, "Homing required", "The controller requires homing after power up or reset.", "Home the machine.", &[Home]),
+    alarm(12, "Limit switch engaged", "A limit switch is active.", "Move off the switch, then unlock.", &[Unlock]),
+    alarm(13, "Probe protection", "The probe input triggered outside a probing cycle.", "Clear the probe, then unlock.", &[Unlock]),
+    alarm(14, "Spindle at speed timeout", "The spindle did not reach the programmed speed.", "Check the spindle or laser power supply.", &[Unlock]),
+    alarm(15, "Homing fail", "The second switch of an auto-squared axis was not found.", "Check the ganged axis switches.", &[Home]),
+    alarm(16, "Self test failed", "The power-on self test failed.", "Power cycle the controller.", &[SoftReset]),
+    alarm(17, "Motor fault", "A stepper driver reported a fault.", "Check the driver and motor wiring.", &[SoftReset]),
+    alarm(18, "Homing fail", "The homing configuration is invalid.", "Review the homing settings.", &[ShowSettings]),
+];
+
+/// FluidNC (Grbl_Esp32) codes on top of the GRBL 1.1 ones
+#[rustfmt::skip]
+static FLUIDNC_ERRORS: &[FaultInfo] = &[
+    err(60, "SD card mount failed", "The SD card could not be mounted.", "Reinsert the card and try again.", &[]),
+    err(61, "SD card read failed", "The file could not be opened or read.", "Check the file name and card.", &[]),
+    err(62, "SD directory listing failed", "The directory could not be opened.", "Check the card.", &[]),
+    err(63, "SD directory not found", "The directory does not exist.", "Check the path.", &[]),
+    err(64, "SD file empty", "The file is empty.", "Upload the file again.", &[]),
+    err(70, "Bluetooth failed to start", "The Bluetooth interface could not start.", "Check the radio configuration.", &[]),
+    err(80, "WiFi failed to start", "The WiFi interface could not start.", "Check the WiFi configuration.", &[]),
+    err(90, "Number out of range", "A setting value is out of range.", "Use a value inside the allowed range.", &[]),
+    err(91, "Invalid value", "A setting value is invalid.", "Check the value format.", &[]),
+    err(100, "Message failed", "The controller could not send a message.", "Retry the command.", &[]),
+    err(110, "NVS write failed", "The setting could not be stored.", "Retry or reset the controller.", &[SoftReset]),
+    err(111, "NVS stats failed", "Storage statistics could not be read.", "Retry the command.", &[]),
+    err(120, "Authentication failed", "The command requires authentication.", "Log in with the right credentials.", &[]),
+    err(150, "Another interface busy", "Another connection is running a job.", "Wait for it to finish.", &[]),
+    err(151, "Jog cancelled", "The jog was cancelled.", "Jog again.", &[]),
+    err(152, "Bad pin specification", "A pin in the config file is invalid.", "Fix the config file and restart.", &[SoftReset]),
+    err(153, "Bad runtime config setting", "A config item could not be changed at runtime.", "Edit the config file instead.", &[]),
+    err(154, "Configuration invalid", "The config file failed validation.", "Fix the config file and restart.", &[SoftReset]),
+    err(160, "Upload failed", "The file upload did not complete.", "Upload the file again.", &[]),
+    err(161, "Download failed", "The file download did not complete.", "Retry the download.", &[]),
+    err(162, "Read-only setting", "The setting cannot be changed.", "Leave the setting as is.", &[]),
+];
+
+/// Marlin reports errors as text; matched by lowercase substring
+#[rustfmt::skip]
+static MARLIN_ERRORS: &[(&str, FaultInfo)] = &[
+    ("printer halted", err(-1, "Printer halted", "Marlin called kill(), e.g. after an E-stop or a safety fault.", "Fix the cause, then reset the board (M999 does not clear a kill).", &[ResetBoard, Reconnect])),
+    ("printer stopped due to errors", err(-1, "Printer stopped", "An earlier error stopped the firmware.", "Fix the error, then restart with M999.", &[MarlinRestart])),
+    ("thermal runaway", err(-1, "Thermal runaway", "A heater is not behaving as expected.", "Check the heater and thermistor wiring.", &[MarlinRestart])),
+    ("mintemp", err(-1, "MINTEMP triggered", "A thermistor reads below the minimum temperature.", "Check the thermistor wiring.", &[MarlinRestart])),
+    ("maxtemp", err(-1, "MAXTEMP triggered", "A thermistor reads above the maximum temperature.", "Check the heater and thermistor.", &[MarlinRestart])),
+    ("homing failed", err(-1, "Homing failed", "An endstop was not reached while homing.", "Check the endstops, then home again.", &[MarlinHome])),
+    ("probing failed", err(-1, "Probing failed", "The probe did not trigger.", "Check the probe, then retry.", &[])),
+    ("move out of range", err(-1, "Move out of range", "The target is outside the software endstops.", "Check the job placement.", &[])),
+    ("unknown command", err(-1, "Unknown command", "The command is not supported by this Marlin build.", "Enable the feature in Configuration.h or remove the command.", &[])),
+    ("open failed", err(-1, "SD open failed", "The file could not be opened on the SD card.", "Check the file name (8.3) and card.", &[])),
+    ("no media", err(-1, "No SD card", "No SD card is inserted.", "Insert the card.", &[])),
+];
+
+/// Family to use for lookups: the detected firmware if it matches the configured kind
+pub fn family_for(kind: ControllerKind, detected: Option<&FirmwareInfo>) -> FirmwareFamily {
+    match detected {
+        Some(info) if info.family.controller_kind() == kind => info.family,
+        _ if kind == ControllerKind::Marlin => FirmwareFamily::Marlin,
+        _ => FirmwareFamily::Grbl,
+    }
+}
+
+/// Catalogue entry for a numeric error or alarm code
+pub fn lookup(family: FirmwareFamily, kind: FaultKind, code: i32) -> Option<&'static FaultInfo> {
+    let extended: &[FaultInfo] = match (family, kind) {
+        (FirmwareFamily::GrblHal, FaultKind::Error) => GRBL_HAL_ERRORS,
+        (FirmwareFamily::GrblHal, FaultKind::Alarm) => GRBL_HAL_ALARMS,
+        (FirmwareFamily::FluidNc, FaultKind::Error) => FLUIDNC_ERRORS,
+        _ => &[],
+    };
+    let base = match kind {
+        FaultKind::Error => GRBL_ERRORS,
+        FaultKind::Alarm => GRBL_ALARMS,
+    };
+    extended.iter().chain(base).find(|info| info.code == code)
+}
+
+/// Catalogue entry for a Marlin error line such as `Error:Printer halted. kill() called!`
+pub fn lookup_text(line: &str) -> Option<&'static FaultInfo> {
+    let lower = line.to_ascii_lowercase();
+    MARLIN_ERRORS
+        .iter()
+        .find(|(pattern, _)| lower.contains(pattern))
+        .map(|(_, info)| info)
+}
+
+/// Catalogue entry for a controller error/alarm, using the raw line for textual errors
+pub fn describe(family: FirmwareFamily, kind: FaultKind, code: i32, raw: &str) -> Option<&'static FaultInfo> {
+    if family == FirmwareFamily::Marlin || code < 0 {
+        return lookup_text(raw).or_else(|| lookup(family, kind, code));
+    }
+    lookup(family, kind, code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_resolve_per_family() {
+        let soft = lookup(FirmwareFamily::Grbl, FaultKind::Alarm, 2).unwrap();
+        assert_eq!(soft.summary(), "ALARM:2 Soft limit");
+        assert!(soft.actions.contains(&Unlock));
+
+        // grblHAL extends both tables; plain GRBL does not know its codes
+        assert!(lookup(FirmwareFamily::Grbl, FaultKind::Alarm, 11).is_none());
+        assert_eq!(lookup(FirmwareFamily::GrblHal, FaultKind::Alarm, 11).unwrap().actions, &[Home]);
+        assert_eq!(lookup(FirmwareFamily::GrblHal, FaultKind::Error, 22).unwrap().title, "Undefined feed rate");
+        assert_eq!(lookup(FirmwareFamily::FluidNc, FaultKind::Error, 152).unwrap().code, 152);
+        assert!(lookup(FirmwareFamily::Grbl, FaultKind::Error, 999).is_none());
+    }
+
+    #[test]
+    fn marlin_text_errors() {
+        let info = describe(FirmwareFamily::Marlin, FaultKind::Error, -1, "Error:Printer halted. kill() called!").unwrap();
+        assert_eq!(info.title, "Printer halted");
+        assert_eq!(info.actions[0].command(), RecoveryCommand::ResetBoard);
+        let stopped = lookup_text("Error:Printer stopped due to errors. Fix the error and use M999 to restart.").unwrap();
+        assert_eq!(stopped.actions[0].command(), RecoveryCommand::Line("M999"));
+        assert!(lookup_text("Error:something new").is_none());
+    }
+
+    #[test]
+    fn family_follows_detection() {
+        let info = FirmwareInfo {
+            family: FirmwareFamily::GrblHal,
+            version: "1.1f".to_string(),
+            options: Vec::new(),
+            planner_blocks: None,
+            rx_buffer_size: None,
+            axis_count: None,
+            baud_rate: None,
+        };
+        assert_eq!(family_for(ControllerKind::Grbl, Some(&info)), FirmwareFamily::GrblHal);
+        assert_eq!(family_for(ControllerKind::Marlin, Some(&info)), FirmwareFamily::Marlin);
+        assert_eq!(family_for(ControllerKind::Grbl, None), FirmwareFamily::Grbl);
+    }
+}
@@ -0,0 +1,399 @@
+#![allow(dead_code)]
+
+use std::time::{Duration, Instant};
+
+use super::detect::{FirmwareFamily, FirmwareInfo};
+
+/// Smoothie's `ls` has no terminating `ok`; the listing ends once replies stop
+pub const LIST_QUIET_TIME: Duration = Duration::from_millis(1500);
+
+/// How a firmware exposes its onboard storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileDialect {
+    /// `$F`, `$F=<file>`, `$FD=<file>`
+    GrblHal,
+    /// `$SD/List`, `$SD/Run=`, `$SD/Delete=`
+    FluidNc,
+    /// `M20`, `M23`+`M24`, `M30`, `M28`/`M29`
+    Marlin,
+    /// `ls`, `play`, `rm`, `M28`/`M29`
+    Smoothie,
+}
+
+/// How a file gets onto the controller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadMethod {
+    /// Stream the lines between a begin and an end command, one `ok` per line
+    Lines { begin: String, end: String },
+    /// Multipart upload through the controller's web server
+    Http,
+    Unsupported(&'static str),
+}
+
+impl FileDialect {
+    pub fn for_firmware(family: FirmwareFamily) -> Option<Self> {
+        match family {
+            FirmwareFamily::GrblHal => Some(Self::GrblHal),
+            FirmwareFamily::FluidNc => Some(Self::FluidNc),
+            FirmwareFamily::Marlin => Some(Self::Marlin),
+            FirmwareFamily::Smoothieware => Some(Self::Smoothie),
+            FirmwareFamily::Grbl => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::GrblHal => "grblHAL",
+            Self::FluidNc => "FluidNC",
+            Self::Marlin => "Marlin",
+            Self::Smoothie => "Smoothieware",
+        }
+    }
+
+    pub fn list_command(self) -> &'static str {
+        match self {
+            Self::GrblHal => "$F",
+            Self::FluidNc => "$SD/List",
+            Self::Marlin => "M20",
+            Self::Smoothie => "ls -s /sd",
+        }
+    }
+
+    /// Commands that start printing/burning a stored file
+    pub fn run_commands(self, name: &str) -> Vec<String> {
+        let path = absolute(name);
+        match self {
+            Self::GrblHal => vec![format!("$F={path}")],
+            Self::FluidNc => vec![format!("$SD/Run={path}")],
+            Self::Marlin => vec![format!("M23 {}", name.trim_start_matches('/')), "M24".to_string()],
+            Self::Smoothie => vec![format!("play /sd{path}")],
+        }
+    }
+
+    pub fn delete_command(self, name: &str) -> String {
+        let path = absolute(name);
+        match self {
+            Self::GrblHal => format!("$FD={path}"),
+            Self::FluidNc => format!("$SD/Delete={path}"),
+            Self::Marlin => format!("M30 {}", name.trim_start_matches('/')),
+            Self::Smoothie => format!("rm /sd{path}"),
+        }
+    }
+
+    /// `http` is true when the connection can take HTTP uploads (FluidNC WebSocket)
+    pub fn upload_method(self, name: &str, http: bool) -> UploadMethod {
+        let name = name.trim_start_matches('/');
+        match self {
+            Self::Marlin | Self::Smoothie => UploadMethod::Lines {
+                begin: format!("M28 {name}"),
+                end: format!("M29 {name}"),
+            },
+            Self::FluidNc if http => UploadMethod::Http,
+            Self::FluidNc => {
+                UploadMethod::Unsupported("Upload to FluidNC needs the FluidNC (WebSocket) connection")
+            }
+            Self::GrblHal => UploadMethod::Unsupported("grblHAL only accepts uploads over YModem or its web server"),
+        }
+    }
+
+    /// One entry of a listing reply, or None for anything else
+    pub fn parse_entry(self, line: &str) -> Option<StoredFile> {
+        let line = line.trim();
+        match self {
+            Self::GrblHal | Self::FluidNc => {
+                let inner = line.strip_prefix("[FILE:")?.strip_suffix(']')?;
+                let mut parts = inner.split('|');
+                let mut name = parts.next()?.trim();
+                if self == Self::FluidNc {
+                    name = name.strip_prefix("/sd").unwrap_or(name);
+                }
+                let size = parts
+                    .find_map(|p| p.strip_prefix("SIZE:"))
+                    .and_then(|s| s.trim().parse().ok());
+                Some(StoredFile { name: absolute(name), size })
+            }
+            Self::Marlin | Self::Smoothie => {
+                if line.is_empty() || line.starts_with('<') || line.eq_ignore_ascii_case("ok") {
+                    return None;
+                }
+                // `NAME.GCO 1200 [long name]`; directories end in a slash
+                let mut words = line.split_whitespace();
+                let name = words.next()?;
+                if name.ends_with('/') || name.contains(':') {
+                    return None;
+                }
+                let size = words.next().and_then(|s| s.parse().ok());
+                Some(StoredFile { name: absolute(name), size })
+            }
+        }
+    }
+}
+
+fn absolute(name: &str) -> String {
+    format!("/{}", name.trim().trim_start_matches('/'))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredFile {
+    /// Path from the storage root, with a leading `/`
+    pub name: String,
+    pub size: Option<u64>,
+}
+
+/// Collects a listing from the replies that follow the list command
+#[derive(Debug, Clone)]
+pub struct FileListing {
+    dialect: FileDialect,
+    files: Vec<StoredFile>,
+    /// Marlin wraps entries in Begin/End markers
+    in_block: bool,
+    /// `ok`s still due for commands sent before the list command
+    pending_oks: usize,
+    last_line: Instant,
+}
+
+impl FileListing {
+    pub fn new(dialect: FileDialect) -> Self {
+        Self {
+            dialect,
+            files: Vec::new(),
+            in_block: dialect != FileDialect::Marlin,
+            pending_oks: 0,
+            last_line: Instant::now(),
+        }
+    }
+
+    /// Listing requested right after `count` other commands whose `ok`s are still to come
+    pub fn after_commands(mut self, count: usize) -> Self {
+        self.pending_oks = count;
+        self
+    }
+
+    /// Feed a reply line; returns the files once the listing is complete
+    pub fn push(&mut self, line: &str) -> Option<Vec<StoredFile>> {
+        let line = line.trim();
+        if line.starts_with('<') {
+            // Status reports interleave with everything; only Smoothie ends on silence
+            return self.expired().then(|| self.take());
+        }
+        self.last_line = Instant::now();
+        match self.dialect {
+            FileDialect::Marlin => {
+                if line.eq_ignore_ascii_case("Begin file list") {
+                    self.in_block = true;
+                } else if line.eq_ignore_ascii_case("End file list") {
+                    return Some(self.take());
+                } else if self.in_block {
+                    self.files.extend(self.dialect.parse_entry(line));
+                }
+            }
+            _ if line.eq_ignore_ascii_case("ok") && self.pending_oks > 0 => self.pending_oks -= 1,
+            _ if line.eq_ignore_ascii_case("ok") => return Some(self.take()),
+            _ => self.files.extend(self.dialect.parse_entry(line)),
+        }
+        None
+    }
+
+    pub fn expired(&self) -> bool {
+        self.dialect == FileDialect::Smoothie && self.last_line.elapsed() >= LIST_QUIET_TIME
+    }
+
+    fn take(&mut self) -> Vec<StoredFile> {
+        let mut files = std::mem::take(&mut self.files);
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+        files.dedup_by(|a, b| a.name == b.name);
+        files
+    }
+}
+
+/// Program text as it will be stored: comments and blank lines dropped
+pub fn upload_lines(lines: &[String]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| line.split(';').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Line-by-line upload (`M28 … M29`), advanced by the controller's `ok`s
+#[derive(Debug, Clone)]
+pub struct UploadSession {
+    pub name: String,
+    lines: Vec<String>,
+    begin: String,
+    end: String,
+    /// Commands sent so far: begin, then each line, then end
+    sent: usize,
+    acked: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadStep {
+    Send(String),
+    /// Everything is written and the file closed; verify with a fresh listing
+    Finished,
+}
+
+impl UploadSession {
+    pub fn new(name: &str, lines: Vec<String>, begin: String, end: String) -> Self {
+        Self {
+            name: absolute(name),
+            lines,
+            begin,
+            end,
+            sent: 0,
+            acked: 0,
+        }
+    }
+
+    fn command(&self, idx: usize) -> Option<String> {
+        match idx {
+            0 => Some(self.begin.clone()),
+            i if i <= self.lines.len() => Some(self.lines[i - 1].clone()),
+            i if i == self.lines.len() + 1 => Some(self.end.clone()),
+            _ => None,
+        }
+    }
+
+    /// First command to send
+    pub fn start(&mut self) -> UploadStep {
+        self.sent = 1;
+        UploadStep::Send(self.begin.clone())
+    }
+
+    /// The controller acknowledged the previous command
+    pub fn on_ok(&mut self) -> UploadStep {
+        self.acked += 1;
+        match self.command(self.sent) {
+            Some(command) => {
+                self.sent += 1;
+                UploadStep::Send(command)
+            }
+            None => UploadStep::Finished,
+        }
+    }
+
+    /// Command to close the file after an error, if it was opened
+    pub fn abort_command(&self) -> Option<String> {
+        (self.sent > 0).then(|| self.end.clone())
+    }
+
+    /// 0..=1 by acknowledged lines
+    pub fn progress(&self) -> f32 {
+        let total = self.lines.len() + 2;
+        self.acked as f32 / total as f32
+    }
+
+    /// Bytes the stored file should hold, for LF or CRLF line endings
+    pub fn expected_size(&self) -> (u64, u64) {
+        let text: u64 = self.lines.iter().map(|l| l.len() as u64).sum();
+        let count = self.lines.len() as u64;
+        (text + count, text + 2 * count)
+    }
+}
+
+/// Check a fresh listing for the uploaded file and, when sizes are reported, its size
+pub fn verify_upload(files: &[StoredFile], name: &str, expected: (u64, u64)) -> Result<(), String> {
+    let name = absolute(name);
+    // Marlin reports 8.3 names in upper case
+    let file = files
+        .iter()
+        .find(|f| f.name.eq_ignore_ascii_case(&name))
+        .ok_or_else(|| format!("{name} is not on the controller after upload"))?;
+    match file.size {
+        Some(size) if size < expected.0 || size > expected.1 => Err(format!(
+            "{name} is {size} bytes on the controller, expected {}",
+            if expected.0 == expected.1 {
+                expected.0.to_string()
+            } else {
+                format!("{}-{}", expected.0, expected.1)
+            }
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Dialect for the firmware identified on this connection
+pub fn dialect_for(info: Option<&FirmwareInfo>) -> Option<FileDialect> {
+    FileDialect::for_firmware(info?.family)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing(dialect: FileDialect, replies: &[&str]) -> Option<Vec<StoredFile>> {
+        let mut listing = FileListing::new(dialect);
+        replies.iter().find_map(|line| listing.push(line))
+    }
+
+    #[test]
+    fn parses_listings_per_dialect() {
+        let hal = listing(
+            FileDialect::GrblHal,
+            &["[FILE:/jobs/logo.nc|SIZE:2048]", "<Idle|MPos:0,0,0>", "[DIR:/jobs]", "ok"],
+        )
+        .unwrap();
+        assert_eq!(hal, vec![StoredFile { name: "/jobs/logo.nc".into(), size: Some(2048) }]);
+
+        let fluid = listing(FileDialect::FluidNc, &["[FILE:/sd/all4laser.gcode|SIZE:18]", "ok"]).unwrap();
+        assert_eq!(fluid[0].name, "/all4laser.gcode");
+
+        // The `ok` of a delete sent just before the listing must not end it
+        let mut after_delete = FileListing::new(FileDialect::GrblHal).after_commands(1);
+        assert_eq!(after_delete.push("ok"), None);
+        assert_eq!(after_delete.push("[FILE:/a.nc|SIZE:1]"), None);
+        assert_eq!(after_delete.push("ok").map(|f| f.len()), Some(1));
+
+        let marlin = listing(
+            FileDialect::Marlin,
+            &["echo:SD card ok", "Begin file list", "JOB.GCO 1200", "OLD/", "End file list", "ok"],
+        )
+        .unwrap();
+        assert_eq!(marlin, vec![StoredFile { name: "/JOB.GCO".into(), size: Some(1200) }]);
+
+        assert_eq!(
+            FileDialect::Smoothie.parse_entry("cut.g 512"),
+            Some(StoredFile { name: "/cut.g".into(), size: Some(512) })
+        );
+        assert_eq!(FileDialect::Smoothie.parse_entry("config/"), None);
+    }
+
+    #[test]
+    fn maps_run_and_delete_commands() {
+        assert_eq!(FileDialect::GrblHal.run_commands("logo.nc"), vec!["$F=/logo.nc"]);
+        assert_eq!(FileDialect::Marlin.run_commands("/JOB.GCO"), vec!["M23 JOB.GCO", "M24"]);
+        assert_eq!(FileDialect::Smoothie.run_commands("/cut.g"), vec!["play /sd/cut.g"]);
+        assert_eq!(FileDialect::FluidNc.delete_command("/a.nc"), "$SD/Delete=/a.nc");
+        assert_eq!(FileDialect::GrblHal.delete_command("a.nc"), "$FD=/a.nc");
+        assert_eq!(FileDialect::FluidNc.upload_method("a.nc", true), UploadMethod::Http);
+        assert!(matches!(FileDialect::GrblHal.upload_method("a.nc", false), UploadMethod::Unsupported(_)));
+    }
+
+    #[test]
+    fn upload_session_streams_and_verifies() {
+        let lines = upload_lines(&["G0 X0 ; home".to_string(), String::new(), "G1 X10 F600".to_string()]);
+        let UploadMethod::Lines { begin, end } = FileDialect::Marlin.upload_method("job.gco", false) else {
+            panic!("marlin uploads line by line");
+        };
+        let mut upload = UploadSession::new("job.gco", lines, begin, end);
+
+        let UploadStep::Send(first) = upload.start() else {
+            panic!("upload starts with the open command");
+        };
+        let mut sent = vec![first];
+        while let UploadStep::Send(cmd) = upload.on_ok() {
+            sent.push(cmd);
+        }
+        assert_eq!(sent, vec!["M28 job.gco", "G0 X0", "G1 X10 F600", "M29 job.gco"]);
+        assert_eq!(upload.progress(), 1.0);
+
+        // "G0 X0" + "G1 X10 F600" = 16 chars, 2 line endings
+        assert_eq!(upload.expected_size(), (18, 20));
+        let stored = |size| vec![StoredFile { name: "/JOB.GCO".into(), size: Some(size) }];
+        assert!(verify_upload(&stored(20), "job.gco", upload.expected_size()).is_ok());
+        assert!(verify_upload(&stored(9), "job.gco", upload.expected_size()).is_err());
+        assert!(verify_upload(&[], "job.gco", upload.expected_size()).is_err());
+    }
+}
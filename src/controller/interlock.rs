@@ -0,0 +1,213 @@
+#![allow(dead_code)]
+
+use crate::config::machine_profile::MachineProfile;
+use crate::grbl::types::InputPins;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interlock {
+    Lid,
+    Water,
+}
+
+impl Interlock {
+    pub const ALL: [Interlock; 2] = [Interlock::Lid, Interlock::Water];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Lid => "Lid",
+            Self::Water => "Water flow",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Lid => 0,
+            Self::Water => 1,
+        }
+    }
+
+    fn enabled(self, profile: &MachineProfile) -> bool {
+        match self {
+            Self::Lid => profile.interlock_lid_enabled,
+            Self::Water => profile.interlock_water_enabled,
+        }
+    }
+
+    fn pin_text(self, profile: &MachineProfile) -> &str {
+        match self {
+            Self::Lid => &profile.interlock_lid_pin,
+            Self::Water => &profile.interlock_water_pin,
+        }
+    }
+
+    /// Configured pin, falling back to the safety door input for the lid
+    pub fn pin(self, profile: &MachineProfile) -> Result<InterlockPin, String> {
+        let text = self.pin_text(profile).trim();
+        if text.is_empty() && self == Self::Lid {
+            return Ok(InterlockPin { letter: 'D', trip_when_active: true });
+        }
+        InterlockPin::parse(text).map_err(|e| format!("{} interlock: {e}", self.label()))
+    }
+}
+
+/// A `Pn:` letter; `!P` trips when the input is *not* reported (e.g. a flow switch)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterlockPin {
+    pub letter: char,
+    pub trip_when_active: bool,
+}
+
+impl InterlockPin {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (inverted, rest) = match text.strip_prefix('!') {
+            Some(rest) => (true, rest.trim()),
+            None => (false, text),
+        };
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => Ok(Self {
+                letter: c.to_ascii_uppercase(),
+                trip_when_active: !inverted,
+            }),
+            (None, _) => Err("no input pin configured".to_string()),
+            _ => Err(format!("'{text}' is not a Pn: input letter")),
+        }
+    }
+
+    pub fn is_tripped(&self, pins: InputPins) -> bool {
+        pins.is_active(self.letter) == self.trip_when_active
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterlockEvent {
+    Tripped(Interlock),
+    Cleared(Interlock),
+}
+
+/// Tracks lid/water interlocks from the input pins in status reports
+#[derive(Debug, Clone, Default)]
+pub struct InterlockMonitor {
+    reported: bool,
+    tripped: [bool; 2],
+}
+
+impl InterlockMonitor {
+    pub fn any_enabled(profile: &MachineProfile) -> bool {
+        Interlock::ALL.iter().any(|i| i.enabled(profile))
+    }
+
+    /// Forget pin state, e.g. after a disconnect
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn is_tripped(&self, interlock: Interlock) -> bool {
+        self.tripped[interlock.index()]
+    }
+
+    /// Feed the pins from a status report; returns the interlocks that changed state
+    pub fn update(&mut self, profile: &MachineProfile, pins: InputPins) -> Vec<InterlockEvent> {
+        self.reported = true;
+        let mut events = Vec::new();
+        for interlock in Interlock::ALL {
+            let tripped = interlock.enabled(profile)
+                && interlock.pin(profile).is_ok_and(|pin| pin.is_tripped(pins));
+            let was = &mut self.tripped[interlock.index()];
+            if tripped != *was {
+                *was = tripped;
+                events.push(if tripped {
+                    InterlockEvent::Tripped(interlock)
+                } else {
+                    InterlockEvent::Cleared(interlock)
+                });
+            }
+        }
+        events
+    }
+
+    /// Whether a job may start: every enabled interlock is configured, reported and clear
+    pub fn can_start(&self, profile: &MachineProfile) -> Result<(), String> {
+        let enabled: Vec<Interlock> =
+            Interlock::ALL.into_iter().filter(|i| i.enabled(profile)).collect();
+        if enabled.is_empty() {
+            return Ok(());
+        }
+        for interlock in &enabled {
+            interlock.pin(profile)?;
+        }
+        if !self.reported {
+            return Err("No status report received yet; interlock inputs are unknown".to_string());
+        }
+        match enabled.iter().find(|i| self.is_tripped(**i)) {
+            Some(interlock) => Err(format!("{} interlock is tripped", interlock.label())),
+            None => Ok(()),
+        }
+    }
+
+    /// Resuming from a hold needs the same conditions as starting
+    pub fn can_resume(&self, profile: &MachineProfile) -> Result<(), String> {
+        self.can_start(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(lid: bool, water: Option<&str>) -> MachineProfile {
+        MachineProfile {
+            interlock_lid_enabled: lid,
+            interlock_water_enabled: water.is_some(),
+            interlock_water_pin: water.unwrap_or_default().to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pin_spec_parsing() {
+        assert_eq!(
+            InterlockPin::parse("!p"),
+            Ok(InterlockPin { letter: 'P', trip_when_active: false })
+        );
+        assert!(InterlockPin::parse("").is_err());
+        assert!(InterlockPin::parse("M62").is_err());
+        assert_eq!(Interlock::Lid.pin(&profile(true, None)).unwrap().letter, 'D');
+    }
+
+    #[test]
+    fn trips_and_clears_on_pin_changes() {
+        let profile = profile(true, Some("!A"));
+        let mut monitor = InterlockMonitor::default();
+        assert!(monitor.can_start(&profile).is_err(), "unknown before first report");
+
+        // Flow switch closed, lid shut
+        assert!(monitor.update(&profile, InputPins::parse("A")).is_empty());
+        assert!(monitor.can_start(&profile).is_ok());
+
+        // Lid opens and flow stops together
+        let events = monitor.update(&profile, InputPins::parse("D"));
+        assert_eq!(
+            events,
+            vec![
+                InterlockEvent::Tripped(Interlock::Lid),
+                InterlockEvent::Tripped(Interlock::Water)
+            ]
+        );
+        assert!(monitor.can_resume(&profile).is_err());
+
+        let events = monitor.update(&profile, InputPins::parse("A"));
+        assert_eq!(events.len(), 2);
+        assert!(monitor.can_resume(&profile).is_ok());
+    }
+
+    #[test]
+    fn disabled_interlocks_never_block() {
+        let profile = profile(false, None);
+        let mut monitor = InterlockMonitor::default();
+        assert!(monitor.can_start(&profile).is_ok());
+        assert!(monitor.update(&profile, InputPins::parse("D")).is_empty());
+        assert!(monitor.can_start(&profile).is_ok());
+    }
+}
@@ -0,0 +1,421 @@
+#![allow(dead_code)]
+
+use super::direction_delta;
+use crate::grbl::protocol;
+use crate::grbl::types::{GPoint, JogDirection};
+
+/// Segments kept queued on firmware without jog cancel; each one is overrun on release
+const SEGMENT_QUEUE_DEPTH: usize = 2;
+/// Marlin's quickstop: drops every planned move (acted on at once with the emergency parser)
+const QUICKSTOP: &str = "M410";
+/// Shorter moves are rounded away by the one-decimal jog commands
+const MIN_JOG_MM: f32 = 0.1;
+
+/// How a held jog is stopped on this firmware
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JogStyle {
+    /// `$J=` moves that the jog-cancel byte stops mid-move (GRBL 1.1+, grblHAL, FluidNC)
+    Cancellable,
+    /// Short plain G-code moves fed as the reported position catches up (Smoothieware, GRBL 0.9).
+    /// Their `ok` means planned, not done, so counting `ok`s would let the planner fill up.
+    Stepped,
+    /// Short moves fed on `ok`, with the planner flushed by `M410` on release (Marlin, which
+    /// reports no position while moving)
+    Quickstop,
+}
+
+/// What stops a released jog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JogStop {
+    /// Real-time byte
+    Byte(u8),
+    /// Line that answers with an `ok` of its own
+    Line(&'static str),
+}
+
+/// XY box the head may jog inside, in machine coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JogLimits {
+    pub min: (f32, f32),
+    pub max: (f32, f32),
+}
+
+impl JogLimits {
+    /// Bed-sized box holding `pos`; GRBL homed to the far corner works in negative machine space
+    pub fn around(pos: GPoint, width: f32, height: f32) -> Self {
+        let span = |p: f32, size: f32| if p < -0.01 { (-size, 0.0) } else { (0.0, size) };
+        let (min_x, max_x) = span(pos.x, width);
+        let (min_y, max_y) = span(pos.y, height);
+        Self {
+            min: (min_x, min_y),
+            max: (max_x, max_y),
+        }
+    }
+
+    /// Travel budget of one bed size in every direction, for firmware that doesn't report its position
+    pub fn unknown_position(width: f32, height: f32) -> Self {
+        Self {
+            min: (-width, -height),
+            max: (width, height),
+        }
+    }
+
+    /// Per-axis distance `dir` can travel from `pos` before leaving the box; None for Z and home
+    pub fn reach(&self, pos: GPoint, dir: JogDirection) -> Option<f32> {
+        let (dx, dy) = direction_delta(dir);
+        if dx == 0.0 && dy == 0.0 {
+            return None;
+        }
+        let room = |d: f32, p: f32, min: f32, max: f32| {
+            if d > 0.0 {
+                max - p
+            } else if d < 0.0 {
+                p - min
+            } else {
+                f32::INFINITY
+            }
+        };
+        let reach = room(dx, pos.x, self.min.0, self.max.0).min(room(dy, pos.y, self.min.1, self.max.1));
+        // Round down so the one-decimal command never lands past the limit
+        Some((reach.max(0.0) * 10.0).floor() / 10.0)
+    }
+}
+
+/// Length of one held-jog segment: `segment_ms` of travel at `feed`, but never shorter than the
+/// braking distance, or the planner would stop between segments and the head would stutter
+pub fn segment_length(feed_mm_min: f32, accel_mm_s2: f32, segment_ms: u32) -> f32 {
+    let speed = feed_mm_min / 60.0;
+    let cruise = speed * segment_ms as f32 / 1000.0;
+    let braking = if accel_mm_s2 > 0.0 {
+        speed * speed / (2.0 * accel_mm_s2)
+    } else {
+        0.0
+    };
+    cruise.max(braking).max(MIN_JOG_MM)
+}
+
+/// Diagonal for the arrow keys currently held; opposite keys cancel out
+pub fn held_direction(up: bool, down: bool, left: bool, right: bool) -> Option<JogDirection> {
+    let dy = up as i8 - down as i8;
+    let dx = right as i8 - left as i8;
+    match (dx, dy) {
+        (0, 1) => Some(JogDirection::N),
+        (0, -1) => Some(JogDirection::S),
+        (1, 0) => Some(JogDirection::E),
+        (-1, 0) => Some(JogDirection::W),
+        (1, 1) => Some(JogDirection::NE),
+        (-1, 1) => Some(JogDirection::NW),
+        (1, -1) => Some(JogDirection::SE),
+        (-1, -1) => Some(JogDirection::SW),
+        _ => None,
+    }
+}
+
+/// A jog that runs for as long as a key or button is held
+#[derive(Debug)]
+pub struct ContinuousJog {
+    pub dir: JogDirection,
+    style: JogStyle,
+    feed: f32,
+    segment: f32,
+    /// Travel left before the limit; None when the axis has no known bound (Z)
+    remaining: Option<f32>,
+    /// One move to the limit instead of segments
+    single_move: bool,
+    /// Machine position when the jog started
+    origin: GPoint,
+    /// Distance sent so far along the jog
+    commanded: f32,
+    /// Distance covered by the last reported position
+    travelled: f32,
+    /// `ok`s still due for lines already sent
+    outstanding: usize,
+    held: bool,
+}
+
+impl ContinuousJog {
+    /// Jog from `origin` toward `dir` until released. A cancellable XY jog is one move to the
+    /// limit (`reach`); everything else is fed in `segment`-long moves. Returns the lines to send first.
+    pub fn start(
+        dir: JogDirection,
+        style: JogStyle,
+        feed: f32,
+        segment: f32,
+        reach: Option<f32>,
+        origin: GPoint,
+    ) -> (Self, Vec<String>) {
+        let mut jog = Self {
+            dir,
+            style,
+            feed,
+            segment: segment.max(MIN_JOG_MM),
+            remaining: reach,
+            single_move: style == JogStyle::Cancellable && reach.is_some(),
+            origin,
+            commanded: 0.0,
+            travelled: 0.0,
+            outstanding: 0,
+            held: true,
+        };
+        let lines = if jog.single_move {
+            jog.next_move(reach.unwrap_or_default()).unwrap_or_default()
+        } else {
+            jog.top_up()
+        };
+        if lines.is_empty() {
+            jog.held = false;
+        }
+        (jog, lines)
+    }
+
+    fn lines_per_move(&self) -> usize {
+        match self.style {
+            JogStyle::Cancellable => 1,
+            JogStyle::Stepped | JogStyle::Quickstop => 3,
+        }
+    }
+
+    /// Segments not yet run: ahead of the reported position when `ok`s only mean planned,
+    /// otherwise the moves still waiting for their `ok`
+    fn segments_queued(&self) -> usize {
+        match self.style {
+            JogStyle::Stepped => {
+                let ahead = (self.commanded - self.travelled).max(0.0);
+                (ahead / self.segment - 1e-3).ceil() as usize
+            }
+            JogStyle::Cancellable | JogStyle::Quickstop => self.outstanding.div_ceil(self.lines_per_move()),
+        }
+    }
+
+    /// Segments that bring the queue back to [`SEGMENT_QUEUE_DEPTH`]
+    fn top_up(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        while self.segments_queued() < SEGMENT_QUEUE_DEPTH
+            && let Some(segment) = self.next_move(self.segment)
+        {
+            lines.extend(segment);
+        }
+        lines
+    }
+
+    /// Lines for a move of up to `distance` per axis, or None once the limit is reached
+    fn next_move(&mut self, distance: f32) -> Option<Vec<String>> {
+        let distance = self.remaining.map_or(distance, |left| distance.min(left));
+        if distance < MIN_JOG_MM {
+            return None;
+        }
+        if let Some(left) = self.remaining.as_mut() {
+            *left -= distance;
+        }
+        self.commanded += distance;
+        let lines = match self.style {
+            JogStyle::Cancellable => vec![protocol::jog_command(self.dir, distance, self.feed)],
+            JogStyle::Stepped | JogStyle::Quickstop => {
+                protocol::jog_command_legacy(self.dir, distance, self.feed)
+            }
+        };
+        self.outstanding += lines.len();
+        Some(lines)
+    }
+
+    /// Account for an `ok`; returns the next segment to keep the queue topped up
+    pub fn on_ok(&mut self) -> Vec<String> {
+        self.outstanding = self.outstanding.saturating_sub(1);
+        if !self.held || self.single_move || self.style == JogStyle::Stepped {
+            return Vec::new();
+        }
+        self.top_up()
+    }
+
+    /// Account for a reported machine position; a stepped jog refills from here
+    pub fn on_position(&mut self, pos: GPoint) -> Vec<String> {
+        if self.style != JogStyle::Stepped {
+            return Vec::new();
+        }
+        let (dx, dy) = direction_delta(self.dir);
+        self.travelled = if dx != 0.0 {
+            (pos.x - self.origin.x) * dx
+        } else if dy != 0.0 {
+            (pos.y - self.origin.y) * dy
+        } else if self.dir == JogDirection::Zdown {
+            self.origin.z - pos.z
+        } else {
+            pos.z - self.origin.z
+        };
+        if !self.held {
+            return Vec::new();
+        }
+        self.top_up()
+    }
+
+    /// A rejected move (soft limit, alarm) ends the jog
+    pub fn on_error(&mut self) {
+        self.outstanding = self.outstanding.saturating_sub(1);
+        self.held = false;
+    }
+
+    /// Key or button let go; returns what stops the head early, if the firmware has something.
+    /// A stepped jog runs out its last queued segments.
+    pub fn release(&mut self) -> Option<JogStop> {
+        if !std::mem::take(&mut self.held) {
+            return None;
+        }
+        match self.style {
+            JogStyle::Cancellable => Some(JogStop::Byte(protocol::CMD_JOG_CANCEL)),
+            JogStyle::Stepped => None,
+            JogStyle::Quickstop => {
+                self.outstanding += 1;
+                Some(JogStop::Line(QUICKSTOP))
+            }
+        }
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.held
+    }
+
+    /// Released and every line sent has been answered
+    pub fn is_finished(&self) -> bool {
+        !self.held && self.outstanding == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grbl::sim::{VirtualGrbl, VirtualGrblConfig};
+
+    #[test]
+    fn limits_and_segment_length() {
+        let limits = JogLimits::around(GPoint::new(100.0, 50.0, 0.0), 400.0, 300.0);
+        assert_eq!(limits.reach(GPoint::new(100.0, 50.0, 0.0), JogDirection::E), Some(300.0));
+        // A diagonal stops where its first axis runs out
+        assert_eq!(limits.reach(GPoint::new(100.0, 50.0, 0.0), JogDirection::SW), Some(50.0));
+        assert_eq!(limits.reach(GPoint::new(100.0, 50.0, 0.0), JogDirection::Zup), None);
+
+        let homed_far = JogLimits::around(GPoint::new(-10.0, -290.0, 0.0), 400.0, 300.0);
+        assert_eq!(homed_far.reach(GPoint::new(-10.0, -290.0, 0.0), JogDirection::NE), Some(10.0));
+
+        // 3000 mm/min = 50 mm/s: 100 ms cruise is 5 mm, braking at 200 mm/s² needs 6.25 mm
+        assert_eq!(segment_length(3000.0, 200.0, 100), 6.25);
+        assert_eq!(segment_length(3000.0, 2000.0, 100), 5.0);
+
+        assert_eq!(held_direction(true, false, false, true), Some(JogDirection::NE));
+        assert_eq!(held_direction(true, true, true, false), Some(JogDirection::W));
+        assert_eq!(held_direction(false, false, true, true), None);
+    }
+
+    #[test]
+    fn cancellable_jog_stops_on_release() {
+        let mut grbl = VirtualGrbl::new(&VirtualGrblConfig::default());
+        grbl.drain_output();
+
+        let (mut jog, lines) =
+            ContinuousJog::start(JogDirection::NE, JogStyle::Cancellable, 1200.0, 5.0, Some(150.0), GPoint::zero());
+        assert_eq!(lines, vec!["$J=G91X150.0Y150.0F1200"]);
+        for line in &lines {
+            grbl.write(format!("{line}\n").as_bytes());
+        }
+        assert_eq!(grbl.drain_output(), vec!["ok"]);
+        assert!(jog.on_ok().is_empty());
+
+        grbl.advance(0.5);
+        let Some(JogStop::Byte(byte)) = jog.release() else {
+            panic!("GRBL stops with the jog-cancel byte");
+        };
+        grbl.write(&[byte]);
+        grbl.run_until_idle(2.0);
+        let [x, y, _] = grbl.machine_position();
+        assert!(x > 0.0 && x < 20.0 && (x - y).abs() < 1e-3, "stopped at {x},{y}");
+        assert!(jog.is_finished());
+    }
+
+    #[test]
+    fn quickstop_jog_keeps_two_segments_queued() {
+        let (mut jog, lines) =
+            ContinuousJog::start(JogDirection::W, JogStyle::Quickstop, 600.0, 2.0, Some(5.0), GPoint::zero());
+        assert_eq!(lines, vec!["G91", "G1X-2.0F600", "G90", "G91", "G1X-2.0F600", "G90"]);
+
+        // The next segment goes out once a whole segment has been acknowledged
+        assert!(jog.on_ok().is_empty());
+        assert!(jog.on_ok().is_empty());
+        assert_eq!(jog.on_ok(), vec!["G91", "G1X-1.0F600", "G90"]);
+        for _ in 0..3 {
+            assert!(jog.on_ok().is_empty(), "limit reached");
+        }
+
+        // Release flushes the planner; the quickstop has an ok of its own
+        assert_eq!(jog.release(), Some(JogStop::Line("M410")));
+        assert!(!jog.is_finished());
+        for _ in 0..4 {
+            jog.on_ok();
+        }
+        assert!(jog.is_finished());
+
+        // Z has no known bound and keeps going while held
+        let (mut z, lines) =
+            ContinuousJog::start(JogDirection::Zup, JogStyle::Cancellable, 300.0, 1.0, None, GPoint::zero());
+        assert_eq!(lines, vec!["$J=G91Z1.0F300", "$J=G91Z1.0F300"]);
+        assert_eq!(z.on_ok(), vec!["$J=G91Z1.0F300"]);
+        assert_eq!(z.release(), Some(JogStop::Byte(protocol::CMD_JOG_CANCEL)));
+
+        // Already at the limit: nothing to send
+        let (at_limit, lines) =
+            ContinuousJog::start(JogDirection::E, JogStyle::Cancellable, 600.0, 2.0, Some(0.0), GPoint::zero());
+        assert!(lines.is_empty() && at_limit.is_finished());
+    }
+
+    #[test]
+    fn stepped_jog_on_ok_when_planned_firmware_stops_within_the_queue() {
+        let mut grbl = VirtualGrbl::new(&VirtualGrblConfig {
+            legacy_0_9: true,
+            ..Default::default()
+        });
+        grbl.drain_output();
+        grbl.write(b"$J=G91X1F600\n");
+        assert_eq!(grbl.drain_output(), vec!["error:20"], "0.9 has no $J=");
+
+        let segment = 2.0;
+        let (mut jog, lines) =
+            ContinuousJog::start(JogDirection::E, JogStyle::Stepped, 1200.0, segment, Some(150.0), GPoint::zero());
+        let send = |grbl: &mut VirtualGrbl, lines: Vec<String>| {
+            for line in lines {
+                grbl.write(format!("{line}\n").as_bytes());
+            }
+        };
+        send(&mut grbl, lines);
+
+        // Held for a second: answer every ok and report the position four times a second
+        let mut max_planned = 0;
+        for tick in 0..20 {
+            for reply in grbl.drain_output() {
+                assert_eq!(reply, "ok");
+                let lines = jog.on_ok();
+                send(&mut grbl, lines);
+            }
+            max_planned = max_planned.max(grbl.planner_len());
+            if tick % 5 == 0 {
+                let [x, y, z] = grbl.machine_position();
+                let lines = jog.on_position(GPoint::new(x, y, z));
+                send(&mut grbl, lines);
+            }
+            grbl.advance(0.05);
+        }
+        assert!(max_planned <= SEGMENT_QUEUE_DEPTH, "planner held {max_planned} segments");
+
+        let [released_at, _, _] = grbl.machine_position();
+        assert!(released_at > 2.0, "the head moved while held ({released_at})");
+        assert_eq!(jog.release(), None);
+        grbl.run_until_idle(5.0);
+        for _ in grbl.drain_output() {
+            jog.on_ok();
+        }
+        let [stopped_at, _, _] = grbl.machine_position();
+        let overrun = stopped_at - released_at;
+        assert!(
+            overrun <= SEGMENT_QUEUE_DEPTH as f32 * segment + 1e-3,
+            "overran {overrun} mm past the release point"
+        );
+        assert!(jog.is_finished());
+    }
+}
@@ -0,0 +1,266 @@
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+
+use crate::grbl::types::GrblResponse;
+
+use super::ControllerResponse;
+
+/// Framed lines kept for `Resend:` requests
+pub const DEFAULT_RESEND_WINDOW: usize = 64;
+
+/// XOR of every byte before the `*`, as Marlin computes it
+pub fn checksum(text: &str) -> u8 {
+    text.bytes().fold(0, |acc, b| acc ^ b)
+}
+
+/// `N<n> <cmd>*<checksum>`
+pub fn frame_line(line_number: u32, command: &str) -> String {
+    let body = format!("N{line_number} {}", command.trim());
+    let cs = checksum(&body);
+    format!("{body}*{cs}")
+}
+
+/// Parse Marlin-specific replies; None falls through to the generic line protocol parser
+pub fn parse_response(line: &str) -> Option<ControllerResponse> {
+    let trimmed = line.trim();
+    let lower = trimmed.to_ascii_lowercase();
+
+    // "Resend: 12", "Resend:12", "rs 12", "rs N12"
+    let resend = lower
+        .strip_prefix("resend:")
+        .or_else(|| lower.strip_prefix("rs "))
+        .map(|rest| rest.trim().trim_start_matches('n'));
+    if let Some(rest) = resend {
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        return digits.parse().ok().map(ControllerResponse::Resend);
+    }
+
+    // "echo:busy: processing", "busy: paused for user"
+    if lower.trim_start_matches("echo:").starts_with("busy:") {
+        return Some(ControllerResponse::Busy);
+    }
+
+    // ADVANCED_OK: "ok N12 P15 B3"
+    if lower == "ok" || lower.starts_with("ok ") {
+        return Some(ControllerResponse::Grbl(GrblResponse::Ok));
+    }
+
+    // Transmission errors are always followed by Resend + ok; they are not job failures.
+    if let Some(err) = lower.strip_prefix("error:") {
+        let err = err.trim();
+        if err.starts_with("checksum mismatch")
+            || err.starts_with("no checksum")
+            || err.starts_with("no line number")
+            || err.starts_with("line number is not last line number")
+        {
+            return Some(ControllerResponse::Message);
+        }
+        return Some(ControllerResponse::Grbl(GrblResponse::Error(-1)));
+    }
+
+    None
+}
+
+/// Numbers and checksums outgoing lines and replays them on `Resend:` requests
+#[derive(Debug, Clone)]
+pub struct MarlinFramer {
+    next_line_number: u32,
+    window: usize,
+    /// (line number, framed text), oldest first
+    history: VecDeque<(u32, String)>,
+    /// Next line to replay after a `Resend:`; None when streaming new lines
+    resend_cursor: Option<u32>,
+    /// An `M110` went out and its `ok` has not come back yet
+    reset_pending: bool,
+}
+
+impl Default for MarlinFramer {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESEND_WINDOW)
+    }
+}
+
+impl MarlinFramer {
+    pub fn new(window: usize) -> Self {
+        Self {
+            next_line_number: 1,
+            window: window.max(1),
+            history: VecDeque::new(),
+            resend_cursor: None,
+            reset_pending: false,
+        }
+    }
+
+    /// `M110` line that resets the controller's counter; numbering restarts at 1.
+    /// Line 1 must wait until [`Self::acknowledge_reset`] has taken the reset's `ok`.
+    pub fn reset(&mut self) -> String {
+        self.clear();
+        self.reset_pending = true;
+        frame_line(0, "M110 N0")
+    }
+
+    /// Forget the numbering state without sending anything
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.resend_cursor = None;
+        self.next_line_number = 1;
+        self.reset_pending = false;
+    }
+
+    /// Take an `ok` for the pending `M110`; false when the `ok` belongs to a numbered line
+    pub fn acknowledge_reset(&mut self) -> bool {
+        std::mem::take(&mut self.reset_pending)
+    }
+
+    pub fn is_reset_pending(&self) -> bool {
+        self.reset_pending
+    }
+
+    pub fn frame(&mut self, command: &str) -> String {
+        let n = self.next_line_number;
+        let framed = frame_line(n, command);
+        self.next_line_number += 1;
+        self.history.push_back((n, framed.clone()));
+        while self.history.len() > self.window {
+            self.history.pop_front();
+        }
+        framed
+    }
+
+    /// Rewind to `line_number`; fails when the line already left the retransmit window
+    pub fn on_resend(&mut self, line_number: u32) -> Result<(), String> {
+        if line_number >= self.next_line_number {
+            // Marlin asks for the line after the last one it accepted: nothing was lost.
+            return Ok(());
+        }
+        if !self.history.iter().any(|(n, _)| *n == line_number) {
+            return Err(format!(
+                "Marlin requested line {line_number}, which is outside the {}-line resend window",
+                self.window
+            ));
+        }
+        self.resend_cursor = Some(line_number);
+        Ok(())
+    }
+
+    pub fn is_resending(&self) -> bool {
+        self.resend_cursor.is_some()
+    }
+
+    /// Next line to replay, if a resend is in progress
+    pub fn next_resend(&mut self) -> Option<String> {
+        let n = self.resend_cursor?;
+        let framed = self
+            .history
+            .iter()
+            .find(|(line, _)| *line == n)
+            .map(|(_, text)| text.clone());
+        self.resend_cursor = (n + 1 < self.next_line_number).then_some(n + 1);
+        if framed.is_none() {
+            self.resend_cursor = None;
+        }
+        framed
+    }
+
+    pub fn next_line_number(&self) -> u32 {
+        self.next_line_number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_match_marlin_checksums() {
+        assert_eq!(frame_line(0, "M110 N0"), "N0 M110 N0*125");
+        assert_eq!(frame_line(1, "G28"), "N1 G28*18");
+        assert_eq!(
+            frame_line(12, " G1 X10 Y20 F1200 "),
+            "N12 G1 X10 Y20 F1200*124"
+        );
+    }
+
+    #[test]
+    fn parses_resend_busy_and_transmission_errors() {
+        assert!(matches!(
+            parse_response("Resend: 12"),
+            Some(ControllerResponse::Resend(12))
+        ));
+        assert!(matches!(
+            parse_response("rs N7"),
+            Some(ControllerResponse::Resend(7))
+        ));
+        assert!(matches!(
+            parse_response("rs 7"),
+            Some(ControllerResponse::Resend(7))
+        ));
+        assert!(matches!(
+            parse_response("echo:busy: processing"),
+            Some(ControllerResponse::Busy)
+        ));
+        assert!(matches!(
+            parse_response("ok N12 P15 B3"),
+            Some(ControllerResponse::Grbl(GrblResponse::Ok))
+        ));
+        assert!(matches!(
+            parse_response("Error:checksum mismatch, Last Line: 11"),
+            Some(ControllerResponse::Message)
+        ));
+        assert!(matches!(
+            parse_response("Error:Printer halted. kill() called!"),
+            Some(ControllerResponse::Grbl(GrblResponse::Error(_)))
+        ));
+        assert!(parse_response("echo:Unknown command").is_none());
+    }
+
+    #[test]
+    fn resend_replays_from_requested_line_then_resumes() {
+        let mut framer = MarlinFramer::default();
+        assert_eq!(framer.reset(), "N0 M110 N0*125");
+        let sent: Vec<String> = ["G1 X1", "G1 X2", "G1 X3"]
+            .iter()
+            .map(|cmd| framer.frame(cmd))
+            .collect();
+
+        framer.on_resend(2).expect("in window");
+        assert_eq!(framer.next_resend().as_ref(), Some(&sent[1]));
+        // Duplicate Resend for the same line while replaying is harmless.
+        framer.on_resend(3).expect("in window");
+        assert_eq!(framer.next_resend().as_ref(), Some(&sent[2]));
+        assert_eq!(framer.next_resend(), None);
+        assert!(!framer.is_resending());
+        assert!(framer.frame("G1 X4").starts_with("N4 "));
+    }
+
+    #[test]
+    fn reset_ok_is_consumed_once() {
+        let mut framer = MarlinFramer::default();
+        framer.reset();
+        assert!(framer.is_reset_pending());
+        assert!(framer.acknowledge_reset());
+        // The next ok belongs to line 1
+        framer.frame("G1 X1");
+        assert!(!framer.acknowledge_reset());
+        framer.reset();
+        framer.clear();
+        assert!(!framer.acknowledge_reset());
+    }
+
+    #[test]
+    fn resend_outside_window_fails() {
+        let mut framer = MarlinFramer::new(2);
+        framer.reset();
+        for cmd in ["G1 X1", "G1 X2", "G1 X3"] {
+            framer.frame(cmd);
+        }
+        assert!(framer.on_resend(1).is_err());
+        assert!(framer.on_resend(2).is_ok());
+        // Asking for the next unsent line means nothing was lost.
+        let mut fresh = MarlinFramer::default();
+        fresh.frame("G1 X1");
+        assert!(fresh.on_resend(2).is_ok());
+        assert!(!fresh.is_resending());
+    }
+}
@@ -1,7 +1,18 @@
 #![allow(dead_code)]
 
+pub mod detect;
+pub mod faults;
+pub mod files;
+pub mod interlock;
+pub mod jog;
+pub mod marlin;
+
 use std::sync::Arc;
 
+use detect::{FirmwareFamily, FirmwareInfo};
+use files::FileDialect;
+use jog::JogStyle;
+
 use crate::grbl::types::{GPoint, GrblResponse, GrblState, JogDirection, MacStatus};
 use crate::grbl::{parser, protocol};
 
@@ -63,6 +74,55 @@ mod tests {
         assert!(!caps.supports_grbl_settings);
     }
 
+    #[test]
+    fn continuous_jog_style_follows_firmware() {
+        let grbl = create_backend(ControllerKind::Grbl);
+        let style = |banner: &str| {
+            let info = detect::classify(&[banner.to_string()]).expect("known firmware");
+            grbl.continuous_jog(Some(&info))
+        };
+        assert_eq!(grbl.continuous_jog(None), Some(JogStyle::Cancellable));
+        assert_eq!(style("Grbl 1.1h ['$' for help]"), Some(JogStyle::Cancellable));
+        assert_eq!(style("Grbl 0.9j ['$' for help]"), Some(JogStyle::Stepped));
+        assert_eq!(
+            style("Build version: edge-3332442, Build date: Jan 1 2021, MCU: LPC1769, System Clock: 100MHz"),
+            Some(JogStyle::Stepped)
+        );
+        assert_eq!(create_backend(ControllerKind::Marlin).continuous_jog(None), Some(JogStyle::Quickstop));
+        assert_eq!(create_backend(ControllerKind::Ruida).continuous_jog(None), None);
+    }
+
+    #[test]
+    fn firmware_narrows_capabilities() {
+        let grbl = create_backend(ControllerKind::Grbl).capabilities();
+        let lines = |text: &[&str]| text.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let legacy = detect::classify(&lines(&["Grbl 0.9j ['$' for help]", "[0.9j.20160303:]"]))
+            .expect("grbl 0.9");
+        let caps = grbl.for_firmware(&legacy);
+        assert!(!caps.supports_feed_override && !caps.supports_jog);
+        assert!(caps.supports_hold_resume && caps.supports_grbl_settings);
+
+        let hal = detect::classify(&lines(&[
+            "GrblHAL 1.1f ['$' or '$HELP' for help]",
+            "[OPT:NMSL,35,1024,2,0]",
+            "[NEWOPT:ENUMS,SD]",
+        ]))
+        .expect("grblHAL");
+        let caps = grbl.for_firmware(&hal);
+        assert!(caps.supports_feed_override && caps.supports_jog);
+        assert!(!caps.supports_spindle_override, "no variable spindle");
+        assert!(!caps.supports_z_jog, "two-axis build");
+        assert!(caps.supports_sd_card);
+
+        let marlin = detect::classify(&lines(&["FIRMWARE_NAME:Marlin 2.1.2", "Cap:SDCARD:1"]))
+            .expect("marlin");
+        let caps = create_backend(ControllerKind::Marlin)
+            .capabilities()
+            .for_firmware(&marlin);
+        assert!(caps.supports_sd_card && !caps.supports_feed_override);
+    }
+
     #[test]
     fn line_backend_maps_realtime_lines() {
         let backend = create_backend(ControllerKind::Trocen);
@@ -114,6 +174,28 @@ mod tests {
             other => panic!("Unexpected error parse result: {other:?}"),
         }
     }
+
+    #[test]
+    fn marlin_backend_parses_resend_and_busy() {
+        let backend = create_backend(ControllerKind::Marlin);
+        assert!(matches!(
+            backend.parse_response("Resend: 42"),
+            ControllerResponse::Resend(42)
+        ));
+        assert!(matches!(
+            backend.parse_response("echo:busy: processing"),
+            ControllerResponse::Busy
+        ));
+        assert!(matches!(
+            backend.parse_response("Error:checksum mismatch, Last Line: 41"),
+            ControllerResponse::Message
+        ));
+        // Other line-protocol backends keep treating these as plain messages.
+        assert!(matches!(
+            create_backend(ControllerKind::Trocen).parse_response("Resend: 42"),
+            ControllerResponse::Message
+        ));
+    }
 }
 
 impl Default for ControllerKind {
@@ -166,11 +248,63 @@ pub struct ControllerCapabilities {
     pub supports_home: bool,
     pub supports_unlock: bool,
     pub supports_grbl_settings: bool,
+    pub supports_z_jog: bool,
+    /// Controller-side file storage (SD card or flash)
+    pub supports_sd_card: bool,
+}
+
+impl ControllerCapabilities {
+    /// Narrow the protocol's static capability set to what the connected firmware reported
+    pub fn for_firmware(mut self, info: &FirmwareInfo) -> Self {
+        match info.family {
+            FirmwareFamily::Grbl | FirmwareFamily::GrblHal => {
+                // Real-time overrides and `$J=` jogging arrived with GRBL 1.1
+                if info.family == FirmwareFamily::Grbl
+                    && info.version_number().is_some_and(|v| v < (1, 1))
+                {
+                    self.supports_feed_override = false;
+                    self.supports_rapid_override = false;
+                    self.supports_spindle_override = false;
+                    self.supports_jog = false;
+                }
+                // Without a variable spindle there is no laser power to override
+                if !info.options.is_empty() && !info.has_option("V") {
+                    self.supports_spindle_override = false;
+                }
+                if let Some(axes) = info.axis_count {
+                    self.supports_z_jog = axes >= 3;
+                }
+                self.supports_sd_card = info.has_option("SD");
+            }
+            FirmwareFamily::FluidNc => {
+                if let Some(axes) = info.options.iter().find_map(|o| o.strip_prefix("AXES:")) {
+                    self.supports_z_jog = axes.contains('Z');
+                }
+                self.supports_sd_card = info.has_option("SD");
+            }
+            FirmwareFamily::Smoothieware => {
+                // grbl_mode accepts GRBL commands but not the override bytes or `$$`
+                self.supports_feed_override = false;
+                self.supports_rapid_override = false;
+                self.supports_spindle_override = false;
+                self.supports_grbl_settings = false;
+                self.supports_sd_card = true;
+            }
+            FirmwareFamily::Marlin => {
+                self.supports_sd_card = info.has_option("SDCARD");
+            }
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum ControllerResponse {
     Grbl(GrblResponse),
+    /// Marlin asked for a line to be sent again (`Resend: N`)
+    Resend(u32),
+    /// Marlin is still executing a long command (`busy: processing`)
+    Busy,
     Message,
 }
 
@@ -180,6 +314,10 @@ pub trait ControllerBackend: Send + Sync {
     fn realtime_byte(&self, command: RealtimeCommand) -> Option<u8>;
     fn realtime_line(&self, command: RealtimeCommand) -> Option<&'static str>;
     fn jog_command(&self, dir: JogDirection, step: f32, speed: f32) -> Option<String>;
+    /// Onboard file commands for the identified firmware, if it has storage
+    fn file_dialect(&self, firmware: Option<&FirmwareInfo>) -> Option<FileDialect>;
+    /// How hold-to-jog is fed and stopped, if the protocol can jog continuously
+    fn continuous_jog(&self, firmware: Option<&FirmwareInfo>) -> Option<JogStyle>;
 }
 
 #[derive(Default)]
@@ -198,6 +336,8 @@ impl ControllerBackend for GrblBackend {
             supports_home: true,
             supports_unlock: true,
             supports_grbl_settings: true,
+            supports_z_jog: true,
+            supports_sd_card: false,
         }
     }
 
@@ -235,6 +375,21 @@ impl ControllerBackend for GrblBackend {
     fn jog_command(&self, dir: JogDirection, step: f32, speed: f32) -> Option<String> {
         Some(protocol::jog_command(dir, step, speed))
     }
+
+    fn file_dialect(&self, firmware: Option<&FirmwareInfo>) -> Option<FileDialect> {
+        // Plain GRBL has no storage; the GRBL-speaking ports each have their own commands
+        files::dialect_for(firmware).filter(|d| *d != FileDialect::Marlin)
+    }
+
+    fn continuous_jog(&self, firmware: Option<&FirmwareInfo>) -> Option<JogStyle> {
+        // Smoothieware's grbl_mode and GRBL before 1.1 have no jog cancel
+        let cancellable = firmware.is_none_or(|info| match info.family {
+            FirmwareFamily::Smoothieware => false,
+            FirmwareFamily::Grbl => info.version_number().is_none_or(|v| v >= (1, 1)),
+            _ => true,
+        });
+        Some(if cancellable { JogStyle::Cancellable } else { JogStyle::Stepped })
+    }
 }
 
 struct LineProtocolBackend {
@@ -255,10 +410,17 @@ impl ControllerBackend for LineProtocolBackend {
             supports_home: false,
             supports_unlock: false,
             supports_grbl_settings: false,
+            supports_z_jog: true,
+            supports_sd_card: false,
         }
     }
 
     fn parse_response(&self, line: &str) -> ControllerResponse {
+        if self.kind == ControllerKind::Marlin
+            && let Some(response) = marlin::parse_response(line)
+        {
+            return response;
+        }
         if let Some(response) = parse_line_protocol_response(line) {
             return ControllerResponse::Grbl(response);
         }
@@ -300,6 +462,14 @@ impl ControllerBackend for LineProtocolBackend {
             ControllerKind::Grbl => None,
         }
     }
+
+    fn file_dialect(&self, _firmware: Option<&FirmwareInfo>) -> Option<FileDialect> {
+        (self.kind == ControllerKind::Marlin).then_some(FileDialect::Marlin)
+    }
+
+    fn continuous_jog(&self, _firmware: Option<&FirmwareInfo>) -> Option<JogStyle> {
+        (self.kind == ControllerKind::Marlin).then_some(JogStyle::Quickstop)
+    }
 }
 
 pub fn create_backend(kind: ControllerKind) -> Arc<dyn ControllerBackend> {
@@ -0,0 +1,443 @@
+#![allow(dead_code)]
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use super::estimation::{EstimationResult, KindEstimate, WorkKind};
+use super::planner::PlannedBlock;
+
+/// Finished jobs kept per machine
+pub const MAX_TIMINGS: usize = 200;
+/// Most recent jobs a fit looks at, so a re-tuned machine catches up
+const FIT_WINDOW: usize = 30;
+/// Runs shorter than this are mostly start-up noise
+const MIN_SAMPLE_SECONDS: f32 = 5.0;
+const SCALE_RANGE: (f32, f32) = (0.5, 3.0);
+const MAX_SEGMENT_OVERHEAD_S: f32 = 0.1;
+
+/// Planned and measured time of one kind of work in a job
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KindTiming {
+    pub kind: WorkKind,
+    /// Straight from the planner, before any correction
+    pub planned_s: f32,
+    pub segments: u32,
+    pub actual_s: f32,
+}
+
+/// A job that ran to the end: what was quoted and what the clock said
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobTiming {
+    /// Unix seconds
+    pub finished_at: u64,
+    pub name: String,
+    /// As shown before the run, corrections included
+    pub estimated_s: f32,
+    pub actual_s: f32,
+    pub kinds: Vec<KindTiming>,
+}
+
+impl JobTiming {
+    /// How far off the quote was, relative to the actual time; positive when it was too long
+    pub fn error_pct(&self) -> f32 {
+        if self.actual_s <= 0.0 {
+            return 0.0;
+        }
+        (self.estimated_s - self.actual_s) / self.actual_s * 100.0
+    }
+}
+
+/// Correction for one kind of work: actual ≈ `scale` × planned + `per_segment_s` × segments.
+/// `scale` soaks up acceleration and rate differences, `per_segment_s` the controller's
+/// per-block overhead that short raster moves pay for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KindFit {
+    pub scale: f32,
+    pub per_segment_s: f32,
+    /// Jobs the fit is based on
+    pub samples: usize,
+}
+
+impl Default for KindFit {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            per_segment_s: 0.0,
+            samples: 0,
+        }
+    }
+}
+
+impl KindFit {
+    /// Least squares over `(planned, segments, actual)`, pulled towards no correction by the
+    /// weight of one average job so a single odd run can't swing it far
+    fn from_samples(samples: &[(f32, f32, f32)]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let n = samples.len() as f32;
+        let (mut pp, mut pn, mut nn, mut pt, mut nt) = (0.0f32, 0.0f32, 0.0f32, 0.0f32, 0.0f32);
+        for &(p, s, t) in samples {
+            pp += p * p;
+            pn += p * s;
+            nn += s * s;
+            pt += p * t;
+            nt += s * t;
+        }
+        let prior_p = pp / n;
+        let prior_n = nn / n;
+        let (a11, a22) = (pp + prior_p, nn + prior_n);
+        let b1 = pt + prior_p;
+        let det = a11 * a22 - pn * pn;
+
+        let mut scale = b1 / a11;
+        let mut per_segment_s = 0.0;
+        if det > f32::EPSILON * a11 * a22 {
+            let overhead = (a11 * nt - pn * b1) / det;
+            if overhead > 0.0 {
+                scale = (b1 * a22 - pn * nt) / det;
+                per_segment_s = overhead.min(MAX_SEGMENT_OVERHEAD_S);
+            }
+        }
+        Self {
+            scale: scale.clamp(SCALE_RANGE.0, SCALE_RANGE.1),
+            per_segment_s,
+            samples: samples.len(),
+        }
+    }
+
+    pub fn apply(&self, planned_s: f32, segments: u32) -> f32 {
+        self.scale * planned_s + self.per_segment_s * segments as f32
+    }
+}
+
+/// Corrections learned from a machine's finished jobs
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Calibration {
+    fits: [KindFit; 3],
+}
+
+impl Calibration {
+    /// Fit each kind of work on its most recent measurements
+    pub fn fit(timings: &[JobTiming]) -> Self {
+        let mut calibration = Self::default();
+        for kind in WorkKind::ALL {
+            let samples: Vec<(f32, f32, f32)> = timings
+                .iter()
+                .rev()
+                .filter_map(|job| job.kinds.iter().find(|k| k.kind == kind))
+                .filter(|k| k.planned_s > 0.0 && k.actual_s >= MIN_SAMPLE_SECONDS)
+                .take(FIT_WINDOW)
+                .map(|k| (k.planned_s, k.segments as f32, k.actual_s))
+                .collect();
+            calibration.fits[kind.index()] = KindFit::from_samples(&samples);
+        }
+        calibration
+    }
+
+    pub fn get(&self, kind: WorkKind) -> KindFit {
+        self.fits[kind.index()]
+    }
+
+    /// True once any measured job shapes the corrections
+    pub fn is_calibrated(&self) -> bool {
+        self.fits.iter().any(|fit| fit.samples > 0)
+    }
+
+    pub fn kind_seconds(&self, kind: &KindEstimate) -> f32 {
+        self.get(kind.kind).apply(kind.seconds, kind.segments)
+    }
+
+    /// Corrected run time of a planned program
+    pub fn seconds(&self, result: &EstimationResult) -> f32 {
+        if result.kinds.is_empty() {
+            return result.estimated_seconds;
+        }
+        result.kinds.iter().map(|kind| self.kind_seconds(kind)).sum()
+    }
+}
+
+/// Wall-clock time of a running job, split by the kind of work of the line in progress.
+/// That line is where the planned timeline says the machine is; the controller acknowledges
+/// lines well before it runs them, so acknowledgements only cap how far the timeline can get.
+/// Feed holds don't count.
+#[derive(Debug)]
+pub struct JobClock {
+    pub name: String,
+    pub estimated_s: f32,
+    planned: Vec<KindEstimate>,
+    line_kinds: Vec<WorkKind>,
+    /// Planned time at which each block ends, with its line
+    timeline: Vec<(f32, usize)>,
+    /// Time spent running, holds excluded
+    run_s: f32,
+    actual: [f32; 3],
+    last: Instant,
+    /// Feed or rapid overrides were in play, so the run can't judge the estimate
+    pub disturbed: bool,
+    /// Every line is sent; the machine is still working through its planner
+    pub draining: bool,
+    /// Queue job the run belongs to
+    pub queue_job: Option<u64>,
+}
+
+impl JobClock {
+    pub fn start(
+        name: String,
+        estimated_s: f32,
+        planned: &EstimationResult,
+        line_kinds: Vec<WorkKind>,
+        blocks: &[PlannedBlock],
+        now: Instant,
+    ) -> Self {
+        let timeline = blocks
+            .iter()
+            .scan(0.0, |end, block| {
+                *end += block.seconds;
+                Some((*end, block.line))
+            })
+            .collect();
+        Self {
+            name,
+            estimated_s,
+            planned: planned.kinds.clone(),
+            line_kinds,
+            timeline,
+            run_s: 0.0,
+            actual: [0.0; 3],
+            last: now,
+            disturbed: false,
+            draining: false,
+            queue_job: None,
+        }
+    }
+
+    /// Book the time since the last tick to the line the machine is working on;
+    /// `acknowledged` is the last line the controller has accepted
+    pub fn tick(&mut self, now: Instant, acknowledged: usize, holding: bool) {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f32();
+        self.last = now;
+        if holding {
+            return;
+        }
+        let line = self.planned_line(self.run_s).min(acknowledged);
+        self.run_s += elapsed;
+        let kind = self
+            .line_kinds
+            .get(line)
+            .or(self.line_kinds.last())
+            .copied()
+            .unwrap_or(WorkKind::Vector);
+        self.actual[kind.index()] += elapsed;
+    }
+
+    /// Line the plan has the machine on `seconds` into the run; the last one once it is over
+    fn planned_line(&self, seconds: f32) -> usize {
+        let i = self.timeline.partition_point(|&(end, _)| end <= seconds);
+        self.timeline
+            .get(i)
+            .or(self.timeline.last())
+            .map_or(usize::MAX, |&(_, line)| line)
+    }
+
+    pub fn actual_seconds(&self) -> f32 {
+        self.actual.iter().sum()
+    }
+
+    /// The finished run's record; None when it can't say anything about the estimate
+    pub fn finish(self, finished_at: u64) -> Option<JobTiming> {
+        let actual_s = self.actual_seconds();
+        if self.disturbed || actual_s <= 0.0 || self.planned.is_empty() {
+            return None;
+        }
+        let kinds = self
+            .planned
+            .iter()
+            .map(|planned| KindTiming {
+                kind: planned.kind,
+                planned_s: planned.seconds,
+                segments: planned.segments,
+                actual_s: self.actual[planned.kind.index()],
+            })
+            .collect();
+        Some(JobTiming {
+            finished_at,
+            name: self.name,
+            estimated_s: self.estimated_s,
+            actual_s,
+            kinds,
+        })
+    }
+}
+
+/// Estimation error of every recorded job, oldest first
+pub fn error_report_csv(timings: &[JobTiming]) -> String {
+    let mut csv = String::from("Date,Job,Estimated (s),Actual (s),Error (%)");
+    for kind in WorkKind::ALL {
+        csv += &format!(",{0} planned (s),{0} actual (s)", kind.label());
+    }
+    csv += "\n";
+    for job in timings {
+        csv += &format!(
+            "{},{},{:.1},{:.1},{:.1}",
+            super::generator::date_from_unix(job.finished_at),
+            job.name.replace(',', " "),
+            job.estimated_s,
+            job.actual_s,
+            job.error_pct()
+        );
+        for kind in WorkKind::ALL {
+            match job.kinds.iter().find(|k| k.kind == kind) {
+                Some(k) => csv += &format!(",{:.1},{:.1}", k.planned_s, k.actual_s),
+                None => csv += ",,",
+            }
+        }
+        csv += "\n";
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn job(kind: WorkKind, planned_s: f32, segments: u32, actual_s: f32) -> JobTiming {
+        JobTiming {
+            finished_at: 0,
+            name: "job".to_string(),
+            estimated_s: planned_s,
+            actual_s,
+            kinds: vec![KindTiming {
+                kind,
+                planned_s,
+                segments,
+                actual_s,
+            }],
+        }
+    }
+
+    #[test]
+    fn fits_scale_and_segment_overhead() {
+        assert!(!Calibration::fit(&[]).is_calibrated());
+
+        // Raster runs 10% slow plus 4 ms per block; vector is spot on
+        let mut timings: Vec<JobTiming> = (1..=12)
+            .map(|i| {
+                let planned = 60.0 * i as f32;
+                let segments = 2000 * (i % 4 + 1);
+                job(
+                    WorkKind::Raster,
+                    planned,
+                    segments,
+                    1.1 * planned + 0.004 * segments as f32,
+                )
+            })
+            .collect();
+        timings.extend((1..=5).map(|i| job(WorkKind::Vector, 100.0 * i as f32, 50, 100.0 * i as f32)));
+        let calibration = Calibration::fit(&timings);
+
+        let raster = calibration.get(WorkKind::Raster);
+        assert_eq!(raster.samples, 12);
+        assert!((raster.scale - 1.1).abs() < 0.05, "{raster:?}");
+        assert!((raster.per_segment_s - 0.004).abs() < 0.001, "{raster:?}");
+        let vector = calibration.get(WorkKind::Vector);
+        assert!(
+            (vector.scale - 1.0).abs() < 0.01 && vector.per_segment_s < 0.001,
+            "{vector:?}"
+        );
+        assert_eq!(calibration.get(WorkKind::Fill), KindFit::default());
+
+        // One measurement moves the estimate part of the way
+        let single = Calibration::fit(&[job(WorkKind::Fill, 100.0, 0, 150.0)]).get(WorkKind::Fill);
+        assert!(single.scale > 1.1 && single.scale < 1.5, "{single:?}");
+
+        let result = EstimationResult {
+            estimated_seconds: 160.0,
+            kinds: vec![
+                KindEstimate {
+                    kind: WorkKind::Vector,
+                    seconds: 100.0,
+                    segments: 50,
+                },
+                KindEstimate {
+                    kind: WorkKind::Raster,
+                    seconds: 60.0,
+                    segments: 1000,
+                },
+            ],
+            ..Default::default()
+        };
+        let corrected = calibration.seconds(&result);
+        assert!((corrected - (100.0 + 66.0 + 4.0)).abs() < 3.0, "{corrected}");
+    }
+
+    #[test]
+    fn clock_books_time_by_kind_and_skips_holds() {
+        let planned = EstimationResult {
+            estimated_seconds: 30.0,
+            kinds: vec![
+                KindEstimate {
+                    kind: WorkKind::Vector,
+                    seconds: 10.0,
+                    segments: 3,
+                },
+                KindEstimate {
+                    kind: WorkKind::Fill,
+                    seconds: 20.0,
+                    segments: 40,
+                },
+            ],
+            ..Default::default()
+        };
+        let kinds = vec![WorkKind::Vector, WorkKind::Vector, WorkKind::Fill];
+        let blocks = [
+            PlannedBlock {
+                line: 1,
+                seconds: 10.0,
+                ..Default::default()
+            },
+            PlannedBlock {
+                line: 2,
+                seconds: 20.0,
+                ..Default::default()
+            },
+        ];
+        let start = Instant::now();
+        let mut clock = JobClock::start("box".to_string(), 33.0, &planned, kinds.clone(), &blocks, start);
+        // The whole job sits in the controller's buffer from the start; the vector move still runs first
+        for s in 1..=12 {
+            clock.tick(start + Duration::from_secs(s), 2, false);
+        }
+        clock.tick(start + Duration::from_secs(20), 2, true);
+        for s in 21..=41 {
+            clock.tick(start + Duration::from_secs(s), 2, false);
+        }
+        let timing = clock.finish(1_700_000_000).expect("clean run");
+        assert_eq!(timing.actual_s, 33.0);
+        assert_eq!(timing.kinds[0].actual_s, 10.0);
+        assert_eq!(timing.kinds[1].actual_s, 23.0);
+        assert!((timing.error_pct() - 0.0).abs() < 1e-3);
+
+        // Lines the controller hasn't taken yet can't be running
+        let mut starved = JobClock::start("box".to_string(), 33.0, &planned, kinds, &blocks, start);
+        for s in 1..=15 {
+            starved.tick(start + Duration::from_secs(s), 1, false);
+        }
+        assert_eq!(starved.actual[WorkKind::Vector.index()], 15.0);
+
+        let csv = error_report_csv(&[timing]);
+        assert!(
+            csv.lines()
+                .nth(1)
+                .unwrap()
+                .starts_with("2023-11-14,box,33.0,33.0,0.0,10.0,10.0,20.0,23.0,,")
+        );
+
+        let mut disturbed = JobClock::start("box".to_string(), 33.0, &planned, Vec::new(), &[], start);
+        disturbed.tick(start + Duration::from_secs(5), 0, false);
+        disturbed.disturbed = true;
+        assert!(disturbed.finish(0).is_none());
+    }
+}
@@ -1,6 +1,8 @@
 #![allow(dead_code)]
 
-use crate::gcode::types::{GCodeLine, ModalState};
+use crate::gcode::interpreter::{Block, BlockKind};
+use crate::gcode::planner::{self, PlannedBlock, PlannerConfig};
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 #[derive(Debug, Clone, Default)]
@@ -8,6 +10,20 @@ pub struct EstimationResult {
     pub total_travel_mm: f32,
     pub total_burn_mm: f32,
     pub estimated_seconds: f32,
+    /// In program order; empty sections are left out
+    pub sections: Vec<SectionEstimate>,
+    /// Only the kinds the program does
+    pub kinds: Vec<KindEstimate>,
+}
+
+/// Time and distances of one pass of one layer
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SectionEstimate {
+    pub layer: String,
+    pub pass: u32,
+    pub seconds: f32,
+    pub burn_mm: f32,
+    pub travel_mm: f32,
 }
 
 impl EstimationResult {
@@ -16,6 +32,74 @@ impl EstimationResult {
     }
 }
 
+/// Kind of work, each of which runs at its own pace on a real machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WorkKind {
+    Vector,
+    Fill,
+    Raster,
+}
+
+impl WorkKind {
+    pub const ALL: [WorkKind; 3] = [WorkKind::Vector, WorkKind::Fill, WorkKind::Raster];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WorkKind::Vector => "Vector",
+            WorkKind::Fill => "Fill",
+            WorkKind::Raster => "Raster",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Planned time of one kind of work
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KindEstimate {
+    pub kind: WorkKind,
+    pub seconds: f32,
+    /// Motion blocks, each of which costs the controller some fixed time
+    pub segments: u32,
+}
+
+/// Kind of work every line does. The generator's comments say so (`; Fill Scan`,
+/// `; Shape N: RasterImage`); without them, burn moves that set their own power are
+/// raster rows and travel belongs to whatever burned last.
+pub fn line_kinds<S: AsRef<str>>(lines: &[S], blocks: &[Block]) -> Vec<WorkKind> {
+    let mut burned: Vec<Option<WorkKind>> = vec![None; lines.len()];
+    let mut power_line = None;
+    for block in blocks {
+        if let BlockKind::Power(_) = block.kind {
+            power_line = Some(block.line);
+        } else if !block.is_rapid()
+            && block.motion().is_some_and(|m| m.burns())
+            && let Some(slot) = burned.get_mut(block.line)
+        {
+            *slot = Some(if power_line == Some(block.line) { WorkKind::Raster } else { WorkKind::Vector });
+        }
+    }
+
+    let mut marked = None;
+    let mut last_burn = WorkKind::Vector;
+    let mut kinds = Vec::with_capacity(lines.len());
+    for (line, burn) in lines.iter().zip(burned) {
+        let line = line.as_ref().trim();
+        if line.starts_with("; Fill Scan") {
+            marked = Some(WorkKind::Fill);
+        } else if line.starts_with("; Shape ") {
+            marked = Some(if line.contains("RasterImage") { WorkKind::Raster } else { WorkKind::Vector });
+        } else if line.starts_with("; Layer ") || line.contains(";LAYER:") {
+            marked = None;
+        }
+        last_burn = burn.unwrap_or(last_burn);
+        kinds.push(marked.unwrap_or(last_burn));
+    }
+    kinds
+}
+
 /// Generate a CSV job report (F15)
 pub fn generate_job_report_csv(
     filename: &str,
@@ -33,6 +117,15 @@ pub fn generate_job_report_csv(
     csv += &format!("Travel Distance (mm),{:.1}\n", result.total_travel_mm);
     csv += &format!("Burn Distance (mm),{:.1}\n", result.total_burn_mm);
     csv += &format!("Estimated Time (s),{:.1}\n", result.estimated_seconds);
+    if result.sections.len() > 1 {
+        csv += "\nSection,Pass,Time (s),Burn (mm),Travel (mm)\n";
+        for section in &result.sections {
+            csv += &format!(
+                "{},{},{:.1},{:.1},{:.1}\n",
+                section.layer, section.pass, section.seconds, section.burn_mm, section.travel_mm
+            );
+        }
+    }
     csv += "\nLayer,Speed,Power,Passes,Mode\n";
     for layer in layers {
         if !layer.visible {
@@ -54,81 +147,84 @@ fn chrono_now() -> String {
     format!("{now}")
 }
 
-pub fn estimate(lines: &[GCodeLine]) -> EstimationResult {
-    let mut result = EstimationResult::default();
-    let mut state = ModalState::default();
-
+/// Section every line belongs to, from the generator's `; Layer C..`/`; Pass N` comments and
+/// `;LAYER:` markers from other programs; lines before the first marker are "Program"
+pub fn line_sections<S: AsRef<str>>(lines: &[S]) -> (Vec<usize>, Vec<SectionEstimate>) {
+    let mut sections = vec![SectionEstimate {
+        layer: "Program".to_string(),
+        pass: 1,
+        ..Default::default()
+    }];
+    let mut layer = sections[0].layer.clone();
+    let mut current = 0;
+    let mut of_line = Vec::with_capacity(lines.len());
     for line in lines {
-        // Track modal state
-        if let Some(g) = line.g_code {
-            if matches!(g, 0 | 1 | 2 | 3) {
-                state.current_g = g;
-            }
-            if g == 90 {
-                state.absolute = true;
-            }
-            if g == 91 {
-                state.absolute = false;
-            }
+        let line = line.as_ref().trim();
+        let mut pass = None;
+        if let Some(label) = line.strip_prefix("; Layer ") {
+            layer = label.split(" — ").next().unwrap_or(label).trim().to_string();
+            pass = Some(1);
+        } else if let Some((_, name)) = line.split_once(";LAYER:") {
+            layer = name.trim().to_string();
+            pass = Some(1);
+        } else if let Some(n) = line.strip_prefix("; Pass ").and_then(|n| n.trim().parse::<u32>().ok()) {
+            pass = Some(n);
         }
-        if let Some(f) = line.f {
-            state.f = f;
-        }
-        if let Some(s) = line.s {
-            state.s = s;
-        }
-        if let Some(m) = line.m_code {
-            if matches!(m, 3 | 4) {
-                state.laser_on = true;
-            }
-            if m == 5 {
-                state.laser_on = false;
-            }
-        }
-
-        // Calculate move
-        let has_xyz = line.x.is_some() || line.y.is_some() || line.z.is_some();
-        if has_xyz && matches!(state.current_g, 0 | 1 | 2 | 3) {
-            let nx = line.x.unwrap_or(state.x);
-            let ny = line.y.unwrap_or(state.y);
-
-            let dist = if state.absolute {
-                ((nx - state.x).powi(2) + (ny - state.y).powi(2)).sqrt()
-            } else {
-                (nx.powi(2) + ny.powi(2)).sqrt()
+        if let Some(pass) = pass {
+            current = match sections.iter().position(|s| s.layer == layer && s.pass == pass) {
+                Some(idx) => idx,
+                None => {
+                    sections.push(SectionEstimate {
+                        layer: layer.clone(),
+                        pass,
+                        ..Default::default()
+                    });
+                    sections.len() - 1
+                }
             };
+        }
+        of_line.push(current);
+    }
+    (of_line, sections)
+}
 
-            if dist > 0.0 {
-                let is_burn = state.laser_on && state.current_g > 0;
-                if is_burn {
-                    result.total_burn_mm += dist;
-                } else {
-                    result.total_travel_mm += dist;
-                }
+/// Distances and planned machine time, overall and per layer pass
+pub fn estimate<S: AsRef<str>>(lines: &[S], blocks: &[Block], config: &PlannerConfig) -> EstimationResult {
+    estimate_planned(lines, blocks, &planner::plan(blocks, config))
+}
 
-                // Time est: dist / feed_rate (mm/min) * 60
-                // For G0, we assume a "rapid" rate if state.f is 0, let's guess 3000
-                let feed = if state.current_g == 0 {
-                    if state.f > 0.0 { state.f } else { 3000.0 }
-                } else {
-                    state.f
-                };
+/// [`estimate`] for blocks the planner has already timed
+pub fn estimate_planned<S: AsRef<str>>(lines: &[S], blocks: &[Block], planned: &[PlannedBlock]) -> EstimationResult {
+    let mut result = EstimationResult::default();
+    let (of_line, mut sections) = line_sections(lines);
+    let line_kind = line_kinds(lines, blocks);
+    let mut kinds = WorkKind::ALL.map(|kind| KindEstimate {
+        kind,
+        seconds: 0.0,
+        segments: 0,
+    });
 
-                if feed > 0.0 {
-                    result.estimated_seconds += (dist / feed) * 60.0;
-                }
-            }
-
-            if state.absolute {
-                state.x = nx;
-                state.y = ny;
-            } else {
-                state.x += nx;
-                state.y += ny;
-            }
+    for (block, planned) in blocks.iter().zip(planned) {
+        let kind = &mut kinds[line_kind.get(block.line).copied().unwrap_or(WorkKind::Vector).index()];
+        kind.seconds += planned.seconds;
+        if block.motion().is_some() {
+            kind.segments += 1;
+        }
+        let section = &mut sections[of_line.get(block.line).copied().unwrap_or(0)];
+        section.seconds += planned.seconds;
+        result.estimated_seconds += planned.seconds;
+        if planned.burn {
+            section.burn_mm += planned.length;
+            result.total_burn_mm += planned.length;
+        } else {
+            section.travel_mm += planned.length;
+            result.total_travel_mm += planned.length;
         }
     }
 
+    sections.retain(|s| s.seconds > 0.0);
+    result.sections = sections;
+    result.kinds = kinds.into_iter().filter(|k| k.seconds > 0.0).collect();
     result
 }
 
@@ -148,4 +244,71 @@ mod tests {
         let result_zero = EstimationResult::default();
         assert_eq!(result_zero.duration(), Duration::from_secs(0));
     }
+
+    #[test]
+    fn arcs_are_measured_along_the_curve() {
+        let lines = ["G0 X10 Y0", "M3 S500", "G2 X10 Y0 I-10 J0 F600", "M5"];
+        let blocks = crate::gcode::interpreter::interpret(&lines);
+        let result = estimate(&lines, &blocks, &PlannerConfig::default());
+        let circumference = std::f32::consts::TAU * 10.0;
+        assert!((result.total_burn_mm - circumference).abs() < 1e-2);
+        // 10 mm/s around the circle; the chords are short enough to barely slow it down
+        let circle = result.estimated_seconds - (result.total_travel_mm / 50.0 + 0.25);
+        assert!((circle - circumference / 10.0 - 0.05).abs() < 0.05, "{}", result.estimated_seconds);
+    }
+
+    #[test]
+    fn splits_time_by_layer_and_pass() {
+        let lines = [
+            "G21",
+            "; Layer C00 (Cut) — Speed:600 Power:1000 Passes:2 Mode:Line",
+            "; Pass 1",
+            "M3 S1000",
+            "G1 X10 F600",
+            "M5",
+            "; Pass 2",
+            "G0 X0",
+            "M3 S1000",
+            "G1 X10",
+            "M5",
+            ";LAYER:Engrave",
+            "M3 S200",
+            "G1 X20 F1200",
+            "M5",
+        ];
+        let blocks = crate::gcode::interpreter::interpret(&lines);
+        let result = estimate(&lines, &blocks, &PlannerConfig::default());
+        let labels: Vec<(&str, u32)> = result.sections.iter().map(|s| (s.layer.as_str(), s.pass)).collect();
+        assert_eq!(labels, [("C00 (Cut)", 1), ("C00 (Cut)", 2), ("Engrave", 1)]);
+        assert_eq!(result.sections[0].burn_mm, 10.0);
+        assert_eq!(result.sections[1].travel_mm, 10.0);
+        let sum: f32 = result.sections.iter().map(|s| s.seconds).sum();
+        assert!((sum - result.estimated_seconds).abs() < 1e-4);
+        assert!(result.sections[2].seconds < result.sections[0].seconds, "twice as fast");
+    }
+
+    #[test]
+    fn classifies_work_by_comments_then_content() {
+        let lines = [
+            "; Fill Scan (Layer C00, angle 0.0°)",
+            "M3 S300",
+            "G1 X10 F3000",
+            "; Shape 1: Rectangle [Layer C01]",
+            "G1 Y10",
+            ";LAYER:Photo",
+            "G0 X0 Y20",
+            "G1 X1 S100 F6000",
+            "G1 X2 S200",
+            "G0 X0 Y21",
+        ];
+        let blocks = crate::gcode::interpreter::interpret(&lines);
+        use WorkKind::*;
+        assert_eq!(
+            line_kinds(&lines, &blocks),
+            [Fill, Fill, Fill, Vector, Vector, Vector, Vector, Raster, Raster, Raster]
+        );
+        let result = estimate(&lines, &blocks, &PlannerConfig::default());
+        let kinds: Vec<(WorkKind, u32)> = result.kinds.iter().map(|k| (k.kind, k.segments)).collect();
+        assert_eq!(kinds, [(Vector, 2), (Fill, 1), (Raster, 3)]);
+    }
 }
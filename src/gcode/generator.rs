@@ -4,7 +4,11 @@ fn chrono_lite_date() -> String {
     let elapsed = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default();
-    let secs = elapsed.as_secs();
+    date_from_unix(elapsed.as_secs())
+}
+
+/// `YYYY-MM-DD` (UTC) of a Unix timestamp
+pub fn date_from_unix(secs: u64) -> String {
     let days = secs / 86400;
     let mut y = 1970i32;
     let mut rem = days;
@@ -0,0 +1,650 @@
+#![allow(dead_code)]
+
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use super::types::{ModalState, Plane};
+
+pub(crate) const MM_PER_INCH: f32 = 25.4;
+/// Largest chord error when an arc is drawn or sent as straight segments
+pub const ARC_TOLERANCE_MM: f32 = 0.02;
+const MAX_ARC_SEGMENTS: usize = 2000;
+/// GRBL's ARC_ANGULAR_TRAVEL_EPSILON: a sweep this close to zero is a full circle
+const ANGULAR_EPSILON: f32 = 5e-7;
+
+/// Letter/value words of a line with `;` and `(...)` comments removed
+pub fn words(line: &str) -> Vec<(char, f32)> {
+    let mut code = String::with_capacity(line.len());
+    let mut in_paren = false;
+    for c in line.chars() {
+        match c {
+            ';' if !in_paren => break,
+            '(' => in_paren = true,
+            ')' => in_paren = false,
+            _ if !in_paren => code.push(c.to_ascii_uppercase()),
+            _ => {}
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut chars = code.chars().peekable();
+    while let Some(c) = chars.next() {
+        if !c.is_ascii_alphabetic() {
+            continue;
+        }
+        let mut number = String::new();
+        while let Some(&d) = chars.peek() {
+            if d.is_ascii_digit() || matches!(d, '.' | '-' | '+') {
+                number.push(d);
+                chars.next();
+            } else if d == ' ' && number.is_empty() {
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        if let Ok(value) = number.parse() {
+            out.push((c, value));
+        }
+    }
+    out
+}
+
+/// Tool position in millimetres (A in degrees)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Position {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub a: f32,
+}
+
+impl Position {
+    /// The two coordinates spanning `plane`, in arc order
+    pub fn in_plane(&self, plane: Plane) -> (f32, f32) {
+        match plane {
+            Plane::XY => (self.x, self.y),
+            Plane::ZX => (self.z, self.x),
+            Plane::YZ => (self.y, self.z),
+        }
+    }
+
+    /// The coordinate along the plane's normal, which a helical arc moves linearly
+    pub fn normal_to(&self, plane: Plane) -> f32 {
+        match plane {
+            Plane::XY => self.z,
+            Plane::ZX => self.y,
+            Plane::YZ => self.x,
+        }
+    }
+
+    pub fn set_in_plane(&mut self, plane: Plane, u: f32, v: f32) {
+        match plane {
+            Plane::XY => (self.x, self.y) = (u, v),
+            Plane::ZX => (self.z, self.x) = (u, v),
+            Plane::YZ => (self.y, self.z) = (u, v),
+        }
+    }
+}
+
+/// One move with the modal values it runs under
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motion {
+    pub from: Position,
+    pub to: Position,
+    /// mm/min
+    pub feed: f32,
+    pub power: f32,
+    /// M3/M4 in effect
+    pub laser: bool,
+}
+
+impl Motion {
+    pub fn xy_length(&self) -> f32 {
+        (self.to.x - self.from.x).hypot(self.to.y - self.from.y)
+    }
+
+    /// False for moves that only change Z or A
+    pub fn is_xy(&self) -> bool {
+        self.from.x != self.to.x
+            || self.from.y != self.to.y
+            || (self.from.z == self.to.z && self.from.a == self.to.a)
+    }
+
+    /// Laser armed with power above zero. The motion does not know it is a rapid,
+    /// so callers rule out G0 ([`Block::is_rapid`]) themselves
+    pub fn burns(&self) -> bool {
+        self.laser && self.power > 0.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockKind {
+    /// G0
+    Rapid(Motion),
+    /// G1
+    Linear(Motion),
+    /// G2 (clockwise) / G3 in `plane`, around `center`
+    Arc {
+        motion: Motion,
+        center: Position,
+        clockwise: bool,
+        plane: Plane,
+    },
+    /// G4, in seconds
+    Dwell(f32),
+    /// M3, or M4 when `dynamic`
+    LaserOn { dynamic: bool },
+    /// M5
+    LaserOff,
+    /// S word
+    Power(f32),
+    /// M7/M8 on, M9 off
+    Air(bool),
+}
+
+/// What one program line does, in execution order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Block {
+    /// Index of the source line
+    pub line: usize,
+    /// `N` word, if the line had one
+    pub number: Option<u32>,
+    pub kind: BlockKind,
+}
+
+impl Block {
+    pub fn motion(&self) -> Option<&Motion> {
+        match &self.kind {
+            BlockKind::Rapid(motion) | BlockKind::Linear(motion) | BlockKind::Arc { motion, .. } => Some(motion),
+            _ => None,
+        }
+    }
+
+    pub fn is_rapid(&self) -> bool {
+        matches!(self.kind, BlockKind::Rapid(_))
+    }
+
+    pub fn arc(&self) -> Option<ArcPath> {
+        match &self.kind {
+            BlockKind::Arc {
+                motion,
+                center,
+                clockwise,
+                plane,
+            } => Some(ArcPath::new(motion, *center, *clockwise, *plane)),
+            _ => None,
+        }
+    }
+
+    /// Distance the tool travels, along the arc for G2/G3
+    pub fn length(&self) -> f32 {
+        if let Some(arc) = self.arc() {
+            return arc.length();
+        }
+        self.motion().map_or(0.0, |m| {
+            let (dx, dy, dz) = (m.to.x - m.from.x, m.to.y - m.from.y, m.to.z - m.from.z);
+            (dx * dx + dy * dy + dz * dz).sqrt()
+        })
+    }
+
+    /// Points the tool passes after `from`, ending at `to`; arcs are split within [`ARC_TOLERANCE_MM`]
+    pub fn waypoints(&self) -> Vec<Position> {
+        if let Some(arc) = self.arc() {
+            return arc.points(ARC_TOLERANCE_MM);
+        }
+        self.motion().map(|m| vec![m.to]).unwrap_or_default()
+    }
+
+    /// XY box swept by the move (min_x, min_y, max_x, max_y), arcs included
+    pub fn xy_extents(&self) -> Option<(f32, f32, f32, f32)> {
+        if let Some(arc) = self.arc() {
+            return Some(arc.xy_extents());
+        }
+        let m = self.motion()?;
+        Some((
+            m.from.x.min(m.to.x),
+            m.from.y.min(m.to.y),
+            m.from.x.max(m.to.x),
+            m.from.y.max(m.to.y),
+        ))
+    }
+}
+
+/// An arc move resolved to its radius and swept angle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArcPath {
+    pub from: Position,
+    pub to: Position,
+    pub center: Position,
+    pub plane: Plane,
+    pub radius: f32,
+    /// Angle of `from` around the centre, within the plane
+    pub start_angle: f32,
+    /// Signed travel, counter-clockwise positive; a full turn when the ends meet
+    pub sweep: f32,
+}
+
+impl ArcPath {
+    pub fn new(motion: &Motion, center: Position, clockwise: bool, plane: Plane) -> Self {
+        let (cu, cv) = center.in_plane(plane);
+        let (fu, fv) = motion.from.in_plane(plane);
+        let (tu, tv) = motion.to.in_plane(plane);
+        let (ru, rv) = (fu - cu, fv - cv);
+        let (eu, ev) = (tu - cu, tv - cv);
+        // Same construction as GRBL's mc_arc, so full circles come out the same way
+        let mut sweep = (ru * ev - rv * eu).atan2(ru * eu + rv * ev);
+        if clockwise {
+            if sweep >= -ANGULAR_EPSILON {
+                sweep -= TAU;
+            }
+        } else if sweep <= ANGULAR_EPSILON {
+            sweep += TAU;
+        }
+        Self {
+            from: motion.from,
+            to: motion.to,
+            center,
+            plane,
+            radius: ru.hypot(rv),
+            start_angle: rv.atan2(ru),
+            sweep,
+        }
+    }
+
+    /// Travel along the axis normal to the plane (helical arcs)
+    pub fn helix(&self) -> f32 {
+        self.to.normal_to(self.plane) - self.from.normal_to(self.plane)
+    }
+
+    pub fn length(&self) -> f32 {
+        (self.radius * self.sweep).hypot(self.helix())
+    }
+
+    /// Position a fraction `t` of the way along the arc
+    pub fn point_at(&self, t: f32) -> Position {
+        let angle = self.start_angle + self.sweep * t;
+        let (cu, cv) = self.center.in_plane(self.plane);
+        let mut p = Position {
+            x: self.from.x + (self.to.x - self.from.x) * t,
+            y: self.from.y + (self.to.y - self.from.y) * t,
+            z: self.from.z + (self.to.z - self.from.z) * t,
+            a: self.from.a + (self.to.a - self.from.a) * t,
+        };
+        p.set_in_plane(self.plane, cu + self.radius * angle.cos(), cv + self.radius * angle.sin());
+        p
+    }
+
+    /// Points after `from` with no chord straying more than `tolerance` from the arc;
+    /// the last one is exactly `to`
+    pub fn points(&self, tolerance: f32) -> Vec<Position> {
+        let step = if self.radius > tolerance {
+            2.0 * (1.0 - tolerance / self.radius).acos()
+        } else {
+            TAU
+        };
+        let count = ((self.sweep.abs() / step).ceil() as usize).clamp(1, MAX_ARC_SEGMENTS);
+        let mut points: Vec<Position> = (1..count).map(|i| self.point_at(i as f32 / count as f32)).collect();
+        points.push(self.to);
+        points
+    }
+
+    /// XY box of the arc: its ends plus every axis crossing inside the sweep
+    pub fn xy_extents(&self) -> (f32, f32, f32, f32) {
+        let mut min = (self.from.x.min(self.to.x), self.from.y.min(self.to.y));
+        let mut max = (self.from.x.max(self.to.x), self.from.y.max(self.to.y));
+        let (lo, hi) = if self.sweep >= 0.0 {
+            (self.start_angle, self.start_angle + self.sweep)
+        } else {
+            (self.start_angle + self.sweep, self.start_angle)
+        };
+        let mut quarter = (lo / FRAC_PI_2).ceil();
+        while quarter * FRAC_PI_2 <= hi {
+            let p = self.point_at((quarter * FRAC_PI_2 - self.start_angle) / self.sweep);
+            min = (min.0.min(p.x), min.1.min(p.y));
+            max = (max.0.max(p.x), max.1.max(p.y));
+            quarter += 1.0;
+        }
+        (min.0, min.1, max.0, max.1)
+    }
+}
+
+/// Runs G-code lines through the modal state, the way the controller would see them
+#[derive(Debug, Clone, Default)]
+pub struct Interpreter {
+    pub state: ModalState,
+    line: usize,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            x: self.state.x,
+            y: self.state.y,
+            z: self.state.z,
+            a: self.state.a,
+        }
+    }
+
+    fn length(&self, value: f32) -> f32 {
+        if self.state.inches { value * MM_PER_INCH } else { value }
+    }
+
+    /// Interpret the next line; blocks come out in the order the controller executes them
+    pub fn push(&mut self, raw: &str) -> Vec<Block> {
+        let line = self.line;
+        self.line += 1;
+        let trimmed = raw.trim_start();
+        // `$` system commands and realtime bytes are not program lines
+        if trimmed.starts_with('$') || trimmed.starts_with('?') {
+            return Vec::new();
+        }
+        let words = words(raw);
+        let number = words.iter().find(|(c, _)| *c == 'N').map(|&(_, v)| v as u32);
+        let mut kinds = Vec::new();
+
+        let mut motion = None;
+        let mut dwell = false;
+        // G10/G28/G30/G53/G92 take axis words that are not a move in work space
+        let mut non_modal_axes = false;
+        let mut set_position = false;
+        let mut laser = None;
+        let mut air = None;
+        for &(letter, value) in &words {
+            match letter {
+                'G' => match (value * 10.0).round() as i32 {
+                    code @ (0 | 10 | 20 | 30) => motion = Some(code / 10),
+                    40 => dwell = true,
+                    170 => self.state.plane = Plane::XY,
+                    180 => self.state.plane = Plane::ZX,
+                    190 => self.state.plane = Plane::YZ,
+                    200 => self.state.inches = true,
+                    210 => self.state.inches = false,
+                    900 => self.state.absolute = true,
+                    910 => self.state.absolute = false,
+                    901 => self.state.arc_absolute = true,
+                    911 => self.state.arc_absolute = false,
+                    920 => set_position = true,
+                    100 | 280 | 300 | 530 => non_modal_axes = true,
+                    _ => {}
+                },
+                'M' => match value as i32 {
+                    3 => laser = Some(Some(false)),
+                    4 => laser = Some(Some(true)),
+                    5 => laser = Some(None),
+                    7 | 8 => air = Some(true),
+                    9 => air = Some(false),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        let word = |letter: char| words.iter().rev().find(|(c, _)| *c == letter).map(|&(_, v)| v);
+
+        if let Some(f) = word('F') {
+            self.state.f = self.length(f);
+        }
+        // On a dwell line S is the time (Marlin), not power
+        if let Some(s) = word('S').filter(|_| !dwell) {
+            self.state.s = s;
+            kinds.push(BlockKind::Power(s));
+        }
+        match laser {
+            Some(Some(dynamic)) => {
+                self.state.laser_on = true;
+                kinds.push(BlockKind::LaserOn { dynamic });
+            }
+            Some(None) => {
+                self.state.laser_on = false;
+                kinds.push(BlockKind::LaserOff);
+            }
+            None => {}
+        }
+        if let Some(on) = air {
+            kinds.push(BlockKind::Air(on));
+        }
+        if dwell {
+            kinds.push(BlockKind::Dwell(word('P').or(word('S')).unwrap_or(0.0).max(0.0)));
+        }
+        if let Some(g) = motion {
+            self.state.current_g = g;
+        }
+
+        let axes = ['X', 'Y', 'Z', 'A'].map(word);
+        if set_position {
+            let [x, y, z, a] = axes;
+            self.state.x = x.map_or(self.state.x, |v| self.length(v));
+            self.state.y = y.map_or(self.state.y, |v| self.length(v));
+            self.state.z = z.map_or(self.state.z, |v| self.length(v));
+            self.state.a = a.unwrap_or(self.state.a);
+        } else if !non_modal_axes
+            && axes.iter().any(Option::is_some)
+            && let Some(kind) = self.motion(axes, &word)
+        {
+            kinds.push(kind);
+        }
+
+        kinds
+            .into_iter()
+            .map(|kind| Block { line, number, kind })
+            .collect()
+    }
+
+    fn motion(&mut self, axes: [Option<f32>; 4], word: &dyn Fn(char) -> Option<f32>) -> Option<BlockKind> {
+        let from = self.position();
+        let target = |current: f32, value: Option<f32>, scale: &dyn Fn(f32) -> f32| match value {
+            Some(v) if self.state.absolute => scale(v),
+            Some(v) => current + scale(v),
+            None => current,
+        };
+        let length = |v: f32| self.length(v);
+        let to = Position {
+            x: target(from.x, axes[0], &length),
+            y: target(from.y, axes[1], &length),
+            z: target(from.z, axes[2], &length),
+            a: target(from.a, axes[3], &|v| v),
+        };
+        let motion = Motion {
+            from,
+            to,
+            feed: self.state.f,
+            power: self.state.s,
+            laser: self.state.laser_on,
+        };
+
+        let kind = match self.state.current_g {
+            0 => BlockKind::Rapid(motion),
+            1 => BlockKind::Linear(motion),
+            g @ (2 | 3) => {
+                let clockwise = g == 2;
+                let plane = self.state.plane;
+                let center = self.arc_center(from, to, clockwise, word)?;
+                BlockKind::Arc {
+                    motion,
+                    center,
+                    clockwise,
+                    plane,
+                }
+            }
+            _ => return None,
+        };
+        self.state.x = to.x;
+        self.state.y = to.y;
+        self.state.z = to.z;
+        self.state.a = to.a;
+        Some(kind)
+    }
+
+    /// Arc centre from I/J/K offsets or an R radius; None when the line gives neither
+    fn arc_center(
+        &self,
+        from: Position,
+        to: Position,
+        clockwise: bool,
+        word: &dyn Fn(char) -> Option<f32>,
+    ) -> Option<Position> {
+        let plane = self.state.plane;
+        let (offset_first, offset_second) = plane.offset_words();
+        let (from_u, from_v) = from.in_plane(plane);
+        let (to_u, to_v) = to.in_plane(plane);
+
+        let (center_u, center_v) = if let Some(r) = word('R') {
+            // GRBL's radius-format construction; a negative R picks the long way round
+            let r = self.length(r);
+            let (du, dv) = (to_u - from_u, to_v - from_v);
+            let chord = du.hypot(dv);
+            if chord == 0.0 {
+                return None;
+            }
+            let mut h = -(4.0 * r * r - du * du - dv * dv).max(0.0).sqrt() / chord;
+            if !clockwise {
+                h = -h;
+            }
+            if r < 0.0 {
+                h = -h;
+            }
+            (from_u + 0.5 * (du - dv * h), from_v + 0.5 * (dv + du * h))
+        } else {
+            let (i, j) = (word(offset_first), word(offset_second));
+            if i.is_none() && j.is_none() {
+                return None;
+            }
+            let (i, j) = (self.length(i.unwrap_or(0.0)), self.length(j.unwrap_or(0.0)));
+            if self.state.arc_absolute {
+                (i, j)
+            } else {
+                (from_u + i, from_v + j)
+            }
+        };
+
+        let mut center = from;
+        center.set_in_plane(plane, center_u, center_v);
+        Some(center)
+    }
+}
+
+/// Interpret a whole program
+pub fn interpret<S: AsRef<str>>(lines: &[S]) -> Vec<Block> {
+    let mut interpreter = Interpreter::new();
+    lines
+        .iter()
+        .flat_map(|line| interpreter.push(line.as_ref()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn motions(lines: &[&str]) -> Vec<BlockKind> {
+        interpret(lines)
+            .into_iter()
+            .filter(|b| b.motion().is_some())
+            .map(|b| b.kind)
+            .collect()
+    }
+
+    #[test]
+    fn carries_modal_state_across_lines() {
+        let blocks = interpret(&[
+            "N10 G21 G90 G0 X10 Y10 (move to start)",
+            "M3 S500",
+            "G1 X20 F600 ; first cut",
+            "Y20",
+            "G91 X-5 Y-5",
+            "G4 P0.5",
+            "M5",
+            "$H",
+        ]);
+        assert_eq!(blocks[0].number, Some(10));
+        let kinds: Vec<_> = blocks.iter().map(|b| (b.line, b.kind)).collect();
+        assert!(matches!(kinds[1], (1, BlockKind::Power(s)) if s == 500.0));
+        assert!(matches!(kinds[2], (1, BlockKind::LaserOn { dynamic: false })));
+
+        let Some(&(3, BlockKind::Linear(second))) = kinds.get(4) else {
+            panic!("modal G1 without a G word: {kinds:?}");
+        };
+        assert_eq!((second.from.x, second.to.y, second.feed), (20.0, 20.0, 600.0));
+        assert!(second.burns());
+        let Some(&(4, BlockKind::Linear(relative))) = kinds.get(5) else {
+            panic!("relative move: {kinds:?}");
+        };
+        assert_eq!((relative.to.x, relative.to.y), (15.0, 15.0));
+        assert!(matches!(kinds[6], (5, BlockKind::Dwell(s)) if s == 0.5));
+        assert!(matches!(kinds[7], (6, BlockKind::LaserOff)));
+        assert_eq!(kinds.len(), 8, "$H is not a move");
+
+        // Inches, A axis, and G92 re-zeroing without moving
+        let kinds = motions(&["G20 G1 X1 A90 F10", "G92 X0", "G21 G1 X5"]);
+        let BlockKind::Linear(inch) = kinds[0] else { panic!() };
+        assert_eq!((inch.to.x, inch.to.a, inch.feed), (25.4, 90.0, 254.0));
+        let BlockKind::Linear(after) = kinds[1] else { panic!() };
+        assert_eq!((after.from.x, after.to.x), (0.0, 5.0));
+    }
+
+    #[test]
+    fn arcs_in_offset_and_radius_form() {
+        // Quarter circle from (10,0) to (0,10) around the origin, written both ways
+        let kinds = motions(&["G0 X10 Y0", "G3 X0 Y10 I-10 J0", "G0 X10 Y0", "G3 X0 Y10 R10"]);
+        for kind in [kinds[1], kinds[3]] {
+            let BlockKind::Arc { center, clockwise, plane, .. } = kind else {
+                panic!("{kind:?}");
+            };
+            assert!(!clockwise && plane == Plane::XY);
+            assert!(center.x.abs() < 1e-4 && center.y.abs() < 1e-4, "{center:?}");
+        }
+
+        // Clockwise over the same chord bends the other way; a negative R takes the long way round
+        let kinds = motions(&["G0 X10 Y0", "G2 X0 Y10 R10", "G0 X10 Y0", "G2 X0 Y10 R-10"]);
+        let BlockKind::Arc { center, .. } = kinds[1] else { panic!() };
+        assert!((center.x - 10.0).abs() < 1e-4 && (center.y - 10.0).abs() < 1e-4, "{center:?}");
+        let BlockKind::Arc { center, .. } = kinds[3] else { panic!() };
+        assert!(center.x.abs() < 1e-4 && center.y.abs() < 1e-4, "{center:?}");
+
+        // G18: offsets are K (Z) and I (X)
+        let kinds = motions(&["G18 G0 X0 Z0", "G2 X10 Z0 I5 K0"]);
+        let BlockKind::Arc { center, plane, .. } = kinds[1] else { panic!() };
+        assert_eq!((plane, center.x, center.z), (Plane::ZX, 5.0, 0.0));
+    }
+
+    fn arc(lines: &[&str]) -> (Block, ArcPath) {
+        let block = *interpret(lines).last().unwrap();
+        (block, block.arc().expect("arc"))
+    }
+
+    #[test]
+    fn arc_length_extents_and_points() {
+        // Quarter circle through the +Y crossing: the box reaches Y10 even though neither end does
+        let (block, quarter) = arc(&["G0 X7.0710678 Y7.0710678", "G3 X-7.0710678 Y7.0710678 I-7.0710678 J-7.0710678"]);
+        assert!((quarter.sweep - FRAC_PI_2).abs() < 1e-4);
+        assert!((block.length() - 10.0 * FRAC_PI_2).abs() < 1e-3);
+        let (_, _, _, max_y) = block.xy_extents().unwrap();
+        assert!((max_y - 10.0).abs() < 1e-3, "{max_y}");
+
+        // Same ends, no offsets between them: a full circle, clockwise
+        let (block, circle) = arc(&["G0 X10 Y0", "G2 X10 Y0 I-10 J0 F600"]);
+        assert!((circle.sweep + TAU).abs() < 1e-4);
+        assert!((block.length() - TAU * 10.0).abs() < 1e-2);
+        let (min_x, min_y, max_x, max_y) = block.xy_extents().unwrap();
+        assert!((min_x + 10.0).abs() < 1e-3 && (min_y + 10.0).abs() < 1e-3);
+        assert!((max_x - 10.0).abs() < 1e-3 && (max_y - 10.0).abs() < 1e-3);
+        let points = block.waypoints();
+        assert!(points.len() >= 40 && points.last() == Some(&circle.to));
+        for p in &points {
+            assert!((p.x.hypot(p.y) - 10.0).abs() < 1e-3);
+        }
+        // Chords stay within tolerance of the circle
+        let step = (points[0].x - points[1].x).hypot(points[0].y - points[1].y);
+        assert!(10.0 - (10.0f32.powi(2) - (step / 2.0).powi(2)).sqrt() <= ARC_TOLERANCE_MM + 1e-4);
+
+        // Helix: one turn while rising 5 mm
+        let (block, helix) = arc(&["G0 X10 Y0 Z0", "G3 X10 Y0 Z5 I-10 J0"]);
+        assert_eq!(helix.helix(), 5.0);
+        assert!((block.length() - (TAU * 10.0).hypot(5.0)).abs() < 1e-2);
+        let halfway = helix.point_at(0.5);
+        assert!((halfway.x + 10.0).abs() < 1e-3 && (halfway.z - 2.5).abs() < 1e-4);
+    }
+}
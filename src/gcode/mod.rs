@@ -1,11 +1,15 @@
+pub mod calibration;
 pub mod estimation;
 pub mod file;
 pub mod fill;
 pub mod generator;
+pub mod interpreter;
 pub mod lbrn_import;
 pub mod optimizer;
 pub mod parser;
 pub mod path_utils;
+pub mod planner;
+pub mod resume;
 pub mod transform;
 pub mod types;
 pub mod xcs_import;
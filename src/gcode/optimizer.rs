@@ -1,150 +1,1260 @@
-use crate::gcode::types::GCodeLine;
+use std::time::{Duration, Instant};
+
+use crate::gcode::interpreter::{Block, BlockKind, Interpreter, interpret, words};
+use crate::gcode::types::{ModalState, Plane};
+
+/// A path ending this close to its start is a closed loop (mm)
+const CLOSE_TOLERANCE_MM: f32 = 0.01;
+/// Travel changes smaller than this are not improvements (mm)
+const EPSILON_MM: f32 = 1e-3;
+/// Or-opt re-inserts a chain next to this many of its nearest paths
+const NEIGHBOURS: usize = 10;
+/// Longest run of paths Or-opt moves at once
+const MAX_CHAIN: usize = 3;
+
+type Point = (f32, f32);
+
+fn dist(a: Point, b: Point) -> f32 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimizeOptions {
+    /// Time allowed for the whole search. A group whose nearest-neighbour order isn't done in time
+    /// keeps its original order; 2-opt/Or-opt stop where they are.
+    pub time_budget: Duration,
+    /// Cut open paths from whichever end is nearer
+    pub allow_reverse: bool,
+    /// Start closed loops at the vertex nearest the head
+    pub rotate_loops: bool,
+}
+
+impl Default for OptimizeOptions {
+    fn default() -> Self {
+        Self {
+            time_budget: Duration::from_millis(500),
+            allow_reverse: true,
+            rotate_loops: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OptimizeStats {
+    /// Paths that could be reordered
+    pub paths: usize,
+    pub reversed: usize,
+    /// Closed loops started at another vertex
+    pub rotated: usize,
+    pub travel_before_mm: f32,
+    pub travel_after_mm: f32,
+    pub elapsed: Duration,
+    /// The time budget ran out before no move improved the order
+    pub budget_exhausted: bool,
+}
+
+impl OptimizeStats {
+    /// Change in travel, negative when it got shorter
+    pub fn travel_change_pct(&self) -> f32 {
+        if self.travel_before_mm > 0.0 {
+            (self.travel_after_mm - self.travel_before_mm) / self.travel_before_mm * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} paths, travel {:.0} → {:.0} mm ({:+.1}%), {} reversed, {} loops rotated, {:.2} s{}",
+            self.paths,
+            self.travel_before_mm,
+            self.travel_after_mm,
+            self.travel_change_pct(),
+            self.reversed,
+            self.rotated,
+            self.elapsed.as_secs_f32(),
+            if self.budget_exhausted {
+                " (time budget reached)"
+            } else {
+                ""
+            }
+        )
+    }
+}
 
-/// A group of GCode lines that form a continuous burn (M3 on -> moves -> M5 off)
 #[derive(Debug, Clone)]
-pub struct BurnPath<'a> {
-    pub start_x: f32,
-    pub start_y: f32,
-    pub end_x: f32,
-    pub end_y: f32,
-    pub min_x: f32,
-    pub min_y: f32,
-    pub max_x: f32,
-    pub max_y: f32,
-    pub lines: Vec<&'a GCodeLine>,
-    pub nesting_level: usize,
-}
-
-pub fn optimize(lines: &[GCodeLine]) -> Vec<GCodeLine> {
-    if lines.is_empty() {
-        return Vec::new();
-    }
-
-    let mut optimized = Vec::new();
-    let mut header = Vec::new();
-    let mut footer = Vec::new();
-    let mut burn_paths = Vec::new();
-
-    // 1. Group lines into burn paths and isolate header/footer
-    let mut current_path: Option<BurnPath> = None;
-    let mut cur_x = 0.0;
-    let mut cur_y = 0.0;
-
-    // We assume absolute mode for simplicity of distance calculation
-    for line in lines {
-        if let Some(m) = line.m_code {
-            if matches!(m, 3 | 4) {
-                current_path = Some(BurnPath {
-                    start_x: cur_x,
-                    start_y: cur_y,
-                    end_x: cur_x,
-                    end_y: cur_y,
-                    min_x: cur_x,
-                    min_y: cur_y,
-                    max_x: cur_x,
-                    max_y: cur_y,
-                    lines: vec![line],
-                    nesting_level: 0,
-                });
-                continue;
+pub struct Optimized {
+    pub lines: Vec<String>,
+    pub stats: OptimizeStats,
+}
+
+/// Length of all moves that do not burn
+pub fn travel_mm(blocks: &[Block]) -> f32 {
+    blocks
+        .iter()
+        .filter(|b| match b.kind {
+            BlockKind::Rapid(_) => true,
+            BlockKind::Linear(m) | BlockKind::Arc { motion: m, .. } => !m.burns(),
+            _ => false,
+        })
+        .map(Block::length)
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    Line,
+    Arc { center: Point, clockwise: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Segment {
+    from: Point,
+    to: Point,
+    shape: Shape,
+    feed: f32,
+    power: f32,
+}
+
+impl Segment {
+    fn reversed(self) -> Self {
+        let shape = match self.shape {
+            Shape::Line => Shape::Line,
+            Shape::Arc { center, clockwise } => Shape::Arc {
+                center,
+                clockwise: !clockwise,
+            },
+        };
+        Self {
+            from: self.to,
+            to: self.from,
+            shape,
+            ..self
+        }
+    }
+}
+
+/// Feed moves cut in one go, without a rapid or a laser mode change in between
+#[derive(Debug, Clone)]
+struct CutPath {
+    segments: Vec<Segment>,
+    /// M3/M4 (`Some(dynamic)`) or off
+    laser: Option<bool>,
+    /// Comments that introduce the path (`; Shape N: …`); they move with it
+    notes: Vec<String>,
+    /// Points along the path, arcs split, for containment tests
+    outline: Vec<Point>,
+    min: Point,
+    max: Point,
+    closed: bool,
+}
+
+impl CutPath {
+    fn start(&self) -> Point {
+        self.segments[0].from
+    }
+
+    fn end(&self) -> Point {
+        self.segments[self.segments.len() - 1].to
+    }
+
+    fn area(&self) -> f32 {
+        (self.max.0 - self.min.0) * (self.max.1 - self.min.1)
+    }
+
+    /// Lower bound of the distance from `p` to any point of the path
+    fn box_distance(&self, p: Point) -> f32 {
+        let dx = (self.min.0 - p.0).max(p.0 - self.max.0).max(0.0);
+        let dy = (self.min.1 - p.1).max(p.1 - self.max.1).max(0.0);
+        dx.hypot(dy)
+    }
+
+    fn encloses_box(&self, other: &CutPath) -> bool {
+        other.min.0 >= self.min.0 - EPSILON_MM
+            && other.min.1 >= self.min.1 - EPSILON_MM
+            && other.max.0 <= self.max.0 + EPSILON_MM
+            && other.max.1 <= self.max.1 + EPSILON_MM
+    }
+
+    /// Even-odd test against the closed outline
+    fn contains_point(&self, p: Point) -> bool {
+        let mut inside = false;
+        let mut prev = self.outline[self.outline.len() - 1];
+        for &cur in &self.outline {
+            if (cur.1 > p.1) != (prev.1 > p.1) && p.0 < (prev.0 - cur.0) * (p.1 - cur.1) / (prev.1 - cur.1) + cur.0 {
+                inside = !inside;
             }
-            if m == 5 {
-                if let Some(mut path) = current_path.take() {
-                    path.lines.push(line);
-                    burn_paths.push(path);
+            prev = cur;
+        }
+        inside
+    }
+
+    /// Most of `other` lies inside this loop
+    fn contains(&self, other: &CutPath) -> bool {
+        let n = other.outline.len();
+        let mut samples = vec![0, n / 3, 2 * n / 3];
+        samples.dedup();
+        let inside = samples
+            .iter()
+            .filter(|&&i| self.contains_point(other.outline[i]))
+            .count();
+        inside * 2 > samples.len()
+    }
+
+    /// Cheapest way in from `p`: distance, reversed, starting vertex
+    fn nearest_entry(&self, p: Point, options: &OptimizeOptions) -> (f32, bool, usize) {
+        if self.closed {
+            if !options.rotate_loops {
+                return (dist(p, self.start()), false, 0);
+            }
+            let mut best = (f32::INFINITY, false, 0);
+            for (k, seg) in self.segments.iter().enumerate() {
+                let d = dist(p, seg.from);
+                if d < best.0 {
+                    best = (d, false, k);
                 }
-                continue;
             }
+            return best;
+        }
+        let (ds, de) = (dist(p, self.start()), dist(p, self.end()));
+        if options.allow_reverse && de < ds {
+            (de, true, 0)
+        } else {
+            (ds, false, 0)
         }
+    }
 
-        if let Some(path) = current_path.as_mut() {
-            path.lines.push(line);
-            if let Some(x) = line.x {
-                path.end_x = x;
-                cur_x = x;
-                path.min_x = path.min_x.min(x);
-                path.max_x = path.max_x.max(x);
+    /// Segments in cutting order for a visit
+    fn oriented(&self, reversed: bool, vertex: usize) -> Vec<Segment> {
+        let mut segments = self.segments.clone();
+        segments.rotate_left(vertex);
+        if reversed {
+            segments.reverse();
+            for seg in &mut segments {
+                *seg = seg.reversed();
             }
-            if let Some(y) = line.y {
-                path.end_y = y;
-                cur_y = y;
-                path.min_y = path.min_y.min(y);
-                path.max_y = path.max_y.max(y);
+        }
+        segments
+    }
+}
+
+/// Modal values a reordered group must leave behind for the lines after it
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Modal {
+    motion: i32,
+    feed: f32,
+    power: f32,
+    laser: Option<bool>,
+}
+
+impl Modal {
+    fn of(state: &ModalState, laser: Option<bool>) -> Self {
+        Self {
+            motion: state.current_g,
+            feed: state.f,
+            power: state.s,
+            laser,
+        }
+    }
+}
+
+/// Lines between two fixed points of the program (layer/pass comments, air, Z moves, ...),
+/// holding paths that may be cut in any order
+#[derive(Debug, Clone)]
+struct Group {
+    entry: Point,
+    start_modal: Modal,
+    lines: Vec<String>,
+    paths: Vec<CutPath>,
+    current: Option<CutPath>,
+    notes: Vec<String>,
+    /// Lines after the last cut; they stay where they are
+    tail: Vec<String>,
+    tail_modal: Modal,
+    /// Where the original program is when the tail starts
+    tail_start: Point,
+    /// The laser is switched off between paths, rather than left on with S0 travel
+    toggles: bool,
+    /// Switched off since the last cut
+    switched_off: bool,
+}
+
+impl Group {
+    fn new(entry: Point, modal: Modal) -> Self {
+        Self {
+            entry,
+            start_modal: modal,
+            lines: Vec::new(),
+            paths: Vec::new(),
+            current: None,
+            notes: Vec::new(),
+            tail: Vec::new(),
+            tail_modal: modal,
+            tail_start: entry,
+            toggles: false,
+            switched_off: false,
+        }
+    }
+
+    fn close_path(&mut self) {
+        if let Some(mut path) = self.current.take() {
+            path.closed = dist(path.start(), path.end()) < CLOSE_TOLERANCE_MM && path.outline.len() >= 3;
+            self.paths.push(path);
+        }
+    }
+
+    fn cut(&mut self, seg: Segment, laser: Option<bool>, points: impl Iterator<Item = Point>) {
+        let joins = self
+            .current
+            .as_ref()
+            .is_some_and(|p| p.laser == laser && dist(p.end(), seg.from) < EPSILON_MM);
+        if !joins {
+            self.close_path();
+            self.current = Some(CutPath {
+                segments: Vec::new(),
+                laser,
+                notes: std::mem::take(&mut self.notes),
+                outline: vec![seg.from],
+                min: seg.from,
+                max: seg.from,
+                closed: false,
+            });
+        }
+        let path = self.current.as_mut().expect("path opened above");
+        path.segments.push(seg);
+        for p in points {
+            path.min = (path.min.0.min(p.0), path.min.1.min(p.1));
+            path.max = (path.max.0.max(p.0), path.max.1.max(p.1));
+            path.outline.push(p);
+        }
+    }
+}
+
+/// Whether a line only moves in XY, cuts or switches the laser, so that it can be regenerated elsewhere
+fn reorderable(raw: &str, state: &ModalState, blocks: &[Block]) -> bool {
+    let plain_words = words(raw).iter().all(|&(letter, value)| match letter {
+        'G' => matches!((value * 10.0).round() as i32, 0 | 10 | 20 | 30),
+        'M' => matches!(value as i32, 3..=5),
+        'X' | 'Y' | 'Z' | 'I' | 'J' | 'R' | 'F' | 'S' | 'N' => true,
+        _ => false,
+    });
+    plain_words
+        && state.absolute
+        && !state.inches
+        && !state.arc_absolute
+        && state.plane == Plane::XY
+        && blocks
+            .iter()
+            .filter_map(Block::motion)
+            .all(|m| m.from.z == m.to.z && m.from.a == m.to.a)
+}
+
+/// Whether a line's effect depends on where the head is, beyond its own X and Y words
+fn needs_position(raw: &str, absolute: bool, blocks: &[Block]) -> bool {
+    let words = words(raw);
+    let has = |letter: char| words.iter().any(|&(c, _)| c == letter);
+    // G10/G28/G30/G92 set or pass through positions relative to the current one
+    let positional = words
+        .iter()
+        .any(|&(c, v)| c == 'G' && matches!((v * 10.0).round() as i32, 100 | 280 | 300 | 920));
+    let moves = blocks.iter().any(|b| b.motion().is_some());
+    positional || (moves && !(absolute && has('X') && has('Y')))
+}
+
+/// Move back to where the original program has the head, leaving the motion mode as it was
+fn return_to(out: &mut Vec<String>, p: Point, absolute: bool, motion: i32) {
+    if !absolute {
+        out.push("G90".to_string());
+    }
+    out.push(format!("G0 X{:.3} Y{:.3}", p.0, p.1));
+    if motion != 0 {
+        out.push(format!("G{motion}"));
+    }
+    if !absolute {
+        out.push("G91".to_string());
+    }
+}
+
+/// Reorder the cutting paths of a program to shorten travel.
+///
+/// Only runs of plain XY cuts and rapids are reordered; anything else (layer and pass comments,
+/// air, dwells, Z moves, fill scans and raster images) stays in place and bounds the reordering.
+/// A path inside a closed loop is always cut before the loop. Lines in G91, G20 or G90.1 are left alone.
+pub fn optimize<S: AsRef<str>>(lines: &[S], options: &OptimizeOptions) -> Optimized {
+    let started = Instant::now();
+    let deadline = started + options.time_budget;
+    let mut stats = OptimizeStats::default();
+    let mut out = Vec::with_capacity(lines.len());
+
+    let mut interpreter = Interpreter::new();
+    let mut laser: Option<bool> = None;
+    // Inside a fill scan or raster image, whose order the generator already chose
+    let mut frozen = false;
+    let mut group: Option<Group> = None;
+    // Where the rewritten program has the head; differs from the original after a reordered group
+    let mut head = (0.0, 0.0);
+
+    for raw in lines {
+        let raw = raw.as_ref();
+        let trimmed = raw.trim();
+        let entry = (interpreter.state.x, interpreter.state.y);
+        let before = Modal::of(&interpreter.state, laser);
+        let absolute_before = interpreter.state.absolute;
+        let blocks = interpreter.push(raw);
+
+        let comment = trimmed.starts_with(';') || trimmed.starts_with('(');
+        let note = if trimmed.is_empty() {
+            true
+        } else if comment {
+            if trimmed.starts_with("; Fill Scan") {
+                frozen = true;
+            } else if trimmed.starts_with("; Shape") {
+                frozen = trimmed.contains("RasterImage");
+            } else if trimmed.starts_with("; Layer") || trimmed.starts_with("; Pass") || trimmed.starts_with(";LAYER:")
+            {
+                frozen = false;
             }
-        } else if !burn_paths.is_empty() {
-            // Footer logic: everything after the last M5
-            footer.push(line);
+            trimmed.starts_with("; Shape") && !frozen
         } else {
-            // Header logic: everything before the first M3
-            header.push(line);
-            if let Some(x) = line.x {
-                cur_x = x;
+            false
+        };
+
+        if !note && (comment || frozen || !reorderable(raw, &interpreter.state, &blocks)) {
+            if let Some(group) = group.take() {
+                head = flush(group, &mut out, head, entry, options, deadline, &mut stats);
+            }
+            // Lines that move from wherever the head is need it back where the original left it
+            let displaced = dist(head, entry) > EPSILON_MM;
+            let positional = needs_position(raw, interpreter.state.absolute, &blocks);
+            if displaced && positional {
+                return_to(&mut out, entry, absolute_before, before.motion);
+            }
+            if !displaced || positional || blocks.iter().any(|b| b.motion().is_some()) {
+                head = (interpreter.state.x, interpreter.state.y);
+            }
+            for block in &blocks {
+                match block.kind {
+                    BlockKind::LaserOn { dynamic } => laser = Some(dynamic),
+                    BlockKind::LaserOff => laser = None,
+                    _ => {}
+                }
             }
-            if let Some(y) = line.y {
-                cur_y = y;
+            out.push(raw.to_string());
+            continue;
+        }
+
+        let group = group.get_or_insert_with(|| Group::new(entry, before));
+        group.lines.push(raw.to_string());
+        if note {
+            group.notes.push(raw.to_string());
+            group.tail.push(raw.to_string());
+            continue;
+        }
+
+        let mut cuts = false;
+        for block in &blocks {
+            match block.kind {
+                BlockKind::LaserOn { dynamic } => {
+                    if laser != Some(dynamic) {
+                        group.close_path();
+                    }
+                    laser = Some(dynamic);
+                }
+                BlockKind::LaserOff => {
+                    if laser.is_some() {
+                        group.close_path();
+                        group.switched_off |= !group.paths.is_empty();
+                    }
+                    laser = None;
+                }
+                // Feed moves that don't burn are travel: they end a path and get regenerated as rapids
+                BlockKind::Rapid(_) => group.close_path(),
+                BlockKind::Linear(m) | BlockKind::Arc { motion: m, .. } if !m.burns() => group.close_path(),
+                BlockKind::Linear(m) | BlockKind::Arc { motion: m, .. } => {
+                    let shape = match block.kind {
+                        BlockKind::Arc { center, clockwise, .. } => Shape::Arc {
+                            center: (center.x, center.y),
+                            clockwise,
+                        },
+                        _ => Shape::Line,
+                    };
+                    let seg = Segment {
+                        from: (m.from.x, m.from.y),
+                        to: (m.to.x, m.to.y),
+                        shape,
+                        feed: m.feed,
+                        power: m.power,
+                    };
+                    group.toggles |= group.switched_off;
+                    group.switched_off = false;
+                    group.cut(seg, laser, block.waypoints().into_iter().map(|p| (p.x, p.y)));
+                    cuts = true;
+                }
+                _ => {}
             }
         }
+        if cuts {
+            group.tail.clear();
+            group.tail_modal = Modal::of(&interpreter.state, laser);
+            group.tail_start = (interpreter.state.x, interpreter.state.y);
+        } else {
+            group.tail.push(raw.to_string());
+        }
+    }
+    if let Some(group) = group.take() {
+        let end = (interpreter.state.x, interpreter.state.y);
+        flush(group, &mut out, head, end, options, deadline, &mut stats);
     }
 
-    // 2. Calculate nesting levels (Bounding Box Inclusion)
-    for i in 0..burn_paths.len() {
-        let mut level = 0;
-        for j in 0..burn_paths.len() {
-            if i == j {
-                continue;
+    stats.travel_before_mm = travel_mm(&interpret(lines));
+    stats.travel_after_mm = travel_mm(&interpret(&out));
+    stats.elapsed = started.elapsed();
+    Optimized { lines: out, stats }
+}
+
+/// Write a group, reordered when that pays off; returns where the head ends up.
+/// `head` is where the rewritten program starts the group, `end` where the original finishes it.
+fn flush(
+    mut group: Group,
+    out: &mut Vec<String>,
+    head: Point,
+    end: Point,
+    options: &OptimizeOptions,
+    deadline: Instant,
+    stats: &mut OptimizeStats,
+) -> Point {
+    group.close_path();
+    let tour = if group.paths.len() >= 2 {
+        stats.paths += group.paths.len();
+        let containers = containers(&group.paths, deadline);
+        if containers.is_none() {
+            stats.budget_exhausted = true;
+        }
+        containers.and_then(|containers| plan(&group.paths, &containers, head, options, deadline, stats))
+    } else {
+        None
+    };
+    let Some(tour) = tour else {
+        if dist(head, group.entry) > EPSILON_MM {
+            return_to(out, group.entry, true, group.start_modal.motion);
+        }
+        out.append(&mut group.lines);
+        return end;
+    };
+    stats.reversed += tour.reversed.iter().filter(|r| **r).count();
+    stats.rotated += tour.vertex.iter().filter(|v| **v != 0).count();
+    let head = emit(&group, &tour, out);
+
+    let has = |line: &String, letter: char| words(line).iter().any(|&(c, _)| c == letter);
+    let moves = group.tail.iter().any(|l| has(l, 'X') || has(l, 'Y'));
+    if moves && dist(head, group.tail_start) > EPSILON_MM && group.tail.iter().any(|l| has(l, 'X') != has(l, 'Y')) {
+        return_to(out, group.tail_start, true, group.tail_modal.motion);
+    }
+    out.extend(group.tail.iter().cloned());
+    if moves { end } else { head }
+}
+
+/// For every path, the closed loops around it; it must be cut before them. None when out of time.
+fn containers(paths: &[CutPath], deadline: Instant) -> Option<Vec<Vec<usize>>> {
+    let mut out = vec![Vec::new(); paths.len()];
+    for (b, outer) in paths.iter().enumerate().filter(|(_, p)| p.closed) {
+        if Instant::now() > deadline {
+            return None;
+        }
+        for (a, inner) in paths.iter().enumerate() {
+            // Smaller box first, then index, so two copies of a loop cannot contain each other
+            let smaller = inner.area() < outer.area() || (inner.area() == outer.area() && a < b);
+            if a != b && smaller && outer.encloses_box(inner) && outer.contains(inner) {
+                out[a].push(b);
             }
-            let a = &burn_paths[i];
-            let b = &burn_paths[j];
-            // If A is inside B
-            if a.min_x >= b.min_x && a.max_x <= b.max_x && a.min_y >= b.min_y && a.max_y <= b.max_y
-            {
-                level += 1;
+        }
+    }
+    Some(out)
+}
+
+/// An order of paths, each cut forward or reversed and, for loops, from a chosen vertex
+#[derive(Debug, Clone)]
+struct Tour<'a> {
+    paths: &'a [CutPath],
+    entry: Point,
+    order: Vec<usize>,
+    /// Position of every path in `order`
+    position: Vec<usize>,
+    reversed: Vec<bool>,
+    vertex: Vec<usize>,
+}
+
+impl<'a> Tour<'a> {
+    fn new(paths: &'a [CutPath], entry: Point, order: Vec<usize>) -> Self {
+        let mut tour = Self {
+            paths,
+            entry,
+            order,
+            position: vec![0; paths.len()],
+            reversed: vec![false; paths.len()],
+            vertex: vec![0; paths.len()],
+        };
+        tour.reindex();
+        tour
+    }
+
+    fn reindex(&mut self) {
+        for (k, &p) in self.order.iter().enumerate() {
+            self.position[p] = k;
+        }
+    }
+
+    fn enter(&self, p: usize) -> Point {
+        let path = &self.paths[p];
+        if path.closed {
+            path.segments[self.vertex[p]].from
+        } else if self.reversed[p] {
+            path.end()
+        } else {
+            path.start()
+        }
+    }
+
+    fn leave(&self, p: usize) -> Point {
+        let path = &self.paths[p];
+        if path.closed {
+            self.enter(p)
+        } else if self.reversed[p] {
+            path.start()
+        } else {
+            path.end()
+        }
+    }
+
+    /// Where the head is before position `k`
+    fn before(&self, k: usize) -> Point {
+        if k == 0 {
+            self.entry
+        } else {
+            self.leave(self.order[k - 1])
+        }
+    }
+
+    fn travel(&self) -> f32 {
+        (0..self.order.len())
+            .map(|k| dist(self.before(k), self.enter(self.order[k])))
+            .sum()
+    }
+
+    fn flip(&mut self, p: usize) {
+        if !self.paths[p].closed {
+            self.reversed[p] = !self.reversed[p];
+        }
+    }
+
+    fn respects(&self, containers: &[Vec<usize>]) -> bool {
+        containers
+            .iter()
+            .enumerate()
+            .all(|(p, cs)| cs.iter().all(|&c| self.position[p] < self.position[c]))
+    }
+
+    /// No path in `i..=j` has to be cut before another one in it
+    fn can_reverse(&self, i: usize, j: usize, containers: &[Vec<usize>]) -> bool {
+        self.order[i..=j]
+            .iter()
+            .all(|&p| containers[p].iter().all(|&c| !(i..=j).contains(&self.position[c])))
+    }
+
+    /// 2-opt: reverse a run of paths when that shortens travel; None when out of time
+    fn two_opt(&mut self, containers: &[Vec<usize>], deadline: Instant) -> Option<bool> {
+        let n = self.order.len();
+        let mut improved = false;
+        for i in 0..n {
+            if Instant::now() > deadline {
+                return None;
+            }
+            let prev = self.before(i);
+            for j in i + 1..n {
+                let first_in = self.enter(self.order[i]);
+                let last_out = self.leave(self.order[j]);
+                let next = self.order.get(j + 1).map(|&q| self.enter(q));
+                let old = dist(prev, first_in) + next.map_or(0.0, |nx| dist(last_out, nx));
+                let new = dist(prev, last_out) + next.map_or(0.0, |nx| dist(first_in, nx));
+                if new < old - EPSILON_MM && self.can_reverse(i, j, containers) {
+                    self.order[i..=j].reverse();
+                    for k in i..=j {
+                        self.flip(self.order[k]);
+                    }
+                    self.reindex();
+                    improved = true;
+                }
             }
         }
-        burn_paths[i].nesting_level = level;
+        Some(improved)
     }
 
-    // 3. Greedy sorting with nesting priority
-    optimized.reserve(
-        header.len() + footer.len() + burn_paths.iter().map(|p| p.lines.len()).sum::<usize>(),
-    );
-    optimized.extend(header.into_iter().cloned());
+    /// Moving `len` paths from `i` to after position `after` (None: first) keeps every hole first
+    fn can_move(&self, i: usize, len: usize, after: Option<usize>, reversed: bool, containers: &[Vec<usize>]) -> bool {
+        if reversed && !self.can_reverse(i, i + len - 1, containers) {
+            return false;
+        }
+        let chain = i..i + len;
+        match after {
+            // Moving earlier: the paths jumped over come after the chain
+            a if a.map_or(0, |a| a + 1) < i => {
+                let from = a.map_or(0, |a| a + 1);
+                self.order[from..i]
+                    .iter()
+                    .all(|&m| containers[m].iter().all(|c| !chain.contains(&self.position[*c])))
+            }
+            // Moving later: they come before it
+            a => {
+                let to = a.unwrap_or(0);
+                self.order[chain.clone()].iter().all(|&p| {
+                    containers[p]
+                        .iter()
+                        .all(|&c| !(i + len..=to).contains(&self.position[c]))
+                })
+            }
+        }
+    }
+
+    /// Or-opt: move up to [`MAX_CHAIN`] consecutive paths next to a near neighbour
+    fn or_opt(
+        &mut self,
+        containers: &[Vec<usize>],
+        neighbours: &[Vec<usize>],
+        allow_reverse: bool,
+        deadline: Instant,
+    ) -> Option<bool> {
+        let n = self.order.len();
+        let mut improved = false;
+        let mut i = 0;
+        'chains: while i < n {
+            if Instant::now() > deadline {
+                return None;
+            }
+            for len in 1..=MAX_CHAIN.min(n - i) {
+                let (first, last) = (self.order[i], self.order[i + len - 1]);
+                let prev = self.before(i);
+                let next = self.order.get(i + len).map(|&q| self.enter(q));
+                let (first_in, last_out) = (self.enter(first), self.leave(last));
+                let gain = dist(prev, first_in) + next.map_or(0.0, |nx| dist(last_out, nx) - dist(prev, nx));
+                if gain <= EPSILON_MM {
+                    continue;
+                }
 
-    // Group remaining paths by nesting level using BTreeMap of Vecs
-    let mut paths_by_level: std::collections::BTreeMap<usize, Vec<BurnPath>> =
-        std::collections::BTreeMap::new();
-    for path in burn_paths {
-        paths_by_level
-            .entry(path.nesting_level)
-            .or_default()
-            .push(path);
+                let mut slots: Vec<Option<usize>> = neighbours[first]
+                    .iter()
+                    .chain(&neighbours[last])
+                    .flat_map(|&q| {
+                        let k = self.position[q];
+                        [Some(k), k.checked_sub(1)]
+                    })
+                    .collect();
+                slots.sort_unstable();
+                slots.dedup();
+                for after in slots {
+                    // Next to where the chain already is
+                    if after.map_or(i == 0, |a| a + 1 >= i && a < i + len) {
+                        continue;
+                    }
+                    let slot_prev = after.map_or(self.entry, |a| self.leave(self.order[a]));
+                    let slot_next = self.order.get(after.map_or(0, |a| a + 1)).map(|&q| self.enter(q));
+                    let opened = slot_next.map_or(0.0, |nx| dist(slot_prev, nx));
+                    let forward = dist(slot_prev, first_in) + slot_next.map_or(0.0, |nx| dist(last_out, nx)) - opened;
+                    let backward = dist(slot_prev, last_out) + slot_next.map_or(0.0, |nx| dist(first_in, nx)) - opened;
+                    let reversed = allow_reverse && backward < forward;
+                    let cost = if reversed { backward } else { forward };
+                    if cost - gain < -EPSILON_MM && self.can_move(i, len, after, reversed, containers) {
+                        let mut chain: Vec<usize> = self.order.drain(i..i + len).collect();
+                        if reversed {
+                            chain.reverse();
+                            for &p in &chain {
+                                self.flip(p);
+                            }
+                        }
+                        let at = match after {
+                            Some(a) if a >= i => a + 1 - len,
+                            Some(a) => a + 1,
+                            None => 0,
+                        };
+                        self.order.splice(at..at, chain);
+                        self.reindex();
+                        improved = true;
+                        continue 'chains;
+                    }
+                }
+            }
+            i += 1;
+        }
+        Some(improved)
     }
 
-    let mut last_x = cur_x;
-    let mut last_y = cur_y;
+    /// Start every closed loop at the vertex closest to both of its neighbours
+    fn rotate_loops(&mut self) -> bool {
+        let mut improved = false;
+        for k in 0..self.order.len() {
+            let p = self.order[k];
+            let path = &self.paths[p];
+            if !path.closed {
+                continue;
+            }
+            let prev = self.before(k);
+            let next = self.order.get(k + 1).map(|&q| self.enter(q));
+            let cost = |v: Point| dist(prev, v) + next.map_or(0.0, |nx| dist(v, nx));
+            let current = cost(self.enter(p));
+            let (best, best_cost) = path
+                .segments
+                .iter()
+                .enumerate()
+                .map(|(v, seg)| (v, cost(seg.from)))
+                .fold((self.vertex[p], current), |best, c| if c.1 < best.1 { c } else { best });
+            if best_cost < current - EPSILON_MM {
+                self.vertex[p] = best;
+                improved = true;
+            }
+        }
+        improved
+    }
+}
 
-    while let Some((_, mut level_paths)) = paths_by_level.pop_last() {
-        while !level_paths.is_empty() {
-            let mut best_index = 0;
-            let mut min_dist_sq = f32::MAX;
+/// Paths with the nearest bounding box centres, for Or-opt; None when out of time
+fn neighbours(paths: &[CutPath], deadline: Instant) -> Option<Vec<Vec<usize>>> {
+    let centres: Vec<Point> = paths
+        .iter()
+        .map(|p| ((p.min.0 + p.max.0) / 2.0, (p.min.1 + p.max.1) / 2.0))
+        .collect();
+    let mut out = Vec::with_capacity(paths.len());
+    for (p, &c) in centres.iter().enumerate() {
+        if Instant::now() > deadline {
+            return None;
+        }
+        let mut others: Vec<(f32, usize)> = centres
+            .iter()
+            .enumerate()
+            .filter(|(q, _)| *q != p)
+            .map(|(q, &o)| (dist(c, o), q))
+            .collect();
+        let keep = NEIGHBOURS.min(others.len());
+        if keep < others.len() {
+            others.select_nth_unstable_by(keep, |a, b| a.0.total_cmp(&b.0));
+            others.truncate(keep);
+        }
+        out.push(others.into_iter().map(|(_, q)| q).collect());
+    }
+    Some(out)
+}
+
+/// Nearest-neighbour order that only offers a path once everything inside it is cut;
+/// None when out of time
+fn greedy<'a>(
+    paths: &'a [CutPath],
+    containers: &[Vec<usize>],
+    entry: Point,
+    options: &OptimizeOptions,
+    deadline: Instant,
+) -> Option<Tour<'a>> {
+    let mut waiting = vec![0usize; paths.len()];
+    for cs in containers {
+        for &c in cs {
+            waiting[c] += 1;
+        }
+    }
+    let mut ready: Vec<usize> = (0..paths.len()).filter(|&p| waiting[p] == 0).collect();
+    let mut tour = Tour::new(paths, entry, Vec::with_capacity(paths.len()));
+    let mut head = entry;
+    while !ready.is_empty() {
+        if Instant::now() > deadline {
+            return None;
+        }
+        let mut best = (f32::INFINITY, 0, false, 0);
+        for (k, &p) in ready.iter().enumerate() {
+            if paths[p].box_distance(head) > best.0 {
+                continue;
+            }
+            let (d, reversed, vertex) = paths[p].nearest_entry(head, options);
+            if d < best.0 || (d == best.0 && p < ready[best.1]) {
+                best = (d, k, reversed, vertex);
+            }
+        }
+        let p = ready.swap_remove(best.1);
+        tour.reversed[p] = best.2;
+        tour.vertex[p] = best.3;
+        tour.order.push(p);
+        head = tour.leave(p);
+        for &c in &containers[p] {
+            waiting[c] -= 1;
+            if waiting[c] == 0 {
+                ready.push(c);
+            }
+        }
+    }
+    tour.reindex();
+    Some(tour)
+}
 
-            for (i, path) in level_paths.iter().enumerate() {
-                let dist_sq = (path.start_x - last_x).powi(2) + (path.start_y - last_y).powi(2);
-                if dist_sq < min_dist_sq {
-                    min_dist_sq = dist_sq;
-                    best_index = i;
+/// Best order found in time, or None when the original one is as good
+fn plan<'a>(
+    paths: &'a [CutPath],
+    containers: &[Vec<usize>],
+    entry: Point,
+    options: &OptimizeOptions,
+    deadline: Instant,
+    stats: &mut OptimizeStats,
+) -> Option<Tour<'a>> {
+    let Some(mut tour) = greedy(paths, containers, entry, options, deadline) else {
+        stats.budget_exhausted = true;
+        return None;
+    };
+    let neighbours = neighbours(paths, deadline);
+    loop {
+        let Some(neighbours) = &neighbours else {
+            stats.budget_exhausted = true;
+            break;
+        };
+        let mut improved = false;
+        if options.allow_reverse {
+            match tour.two_opt(containers, deadline) {
+                Some(better) => improved |= better,
+                None => {
+                    stats.budget_exhausted = true;
+                    break;
                 }
             }
+        }
+        match tour.or_opt(containers, neighbours, options.allow_reverse, deadline) {
+            Some(better) => improved |= better,
+            None => {
+                stats.budget_exhausted = true;
+                break;
+            }
+        }
+        if options.rotate_loops {
+            improved |= tour.rotate_loops();
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    let original = Tour::new(paths, entry, (0..paths.len()).collect());
+    if original.respects(containers) && tour.travel() >= original.travel() - EPSILON_MM {
+        return None;
+    }
+    Some(tour)
+}
+
+fn num(value: f32) -> String {
+    let text = format!("{value:.3}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
 
-            // Remove the nearest path and process it
-            // swap_remove is O(1) compared to remove which is O(N).
-            let best_path = level_paths.swap_remove(best_index);
-            last_x = best_path.end_x;
-            last_y = best_path.end_y;
-            optimized.extend(best_path.lines.iter().copied().cloned());
+fn laser_word(laser: Option<bool>) -> &'static str {
+    match laser {
+        None => "M5",
+        Some(false) => "M3",
+        Some(true) => "M4",
+    }
+}
+
+/// Write the group's paths in tour order and put the modal state back for its tail; returns the head position
+fn emit(group: &Group, tour: &Tour, out: &mut Vec<String>) -> Point {
+    let mut modal = group.start_modal;
+    let mut head = tour.entry;
+    for &p in &tour.order {
+        let path = &group.paths[p];
+        out.extend(path.notes.iter().cloned());
+        let segments = path.oriented(tour.reversed[p], tour.vertex[p]);
+        let start = segments[0].from;
+        if dist(head, start) > EPSILON_MM {
+            if group.toggles && modal.laser.is_some() {
+                out.push("M5".to_string());
+                modal.laser = None;
+            }
+            let mut line = format!("G0 X{:.3} Y{:.3}", start.0, start.1);
+            if modal.laser.is_some() && modal.power != 0.0 {
+                line.push_str(" S0");
+                modal.power = 0.0;
+            }
+            out.push(line);
+            modal.motion = 0;
+        }
+        if modal.laser != path.laser {
+            let mut line = laser_word(path.laser).to_string();
+            if path.laser.is_some() {
+                line.push_str(&format!(" S{}", num(segments[0].power)));
+                modal.power = segments[0].power;
+            }
+            out.push(line);
+            modal.laser = path.laser;
+        }
+        for seg in &segments {
+            let mut line = match seg.shape {
+                Shape::Line => {
+                    modal.motion = 1;
+                    format!("G1 X{:.3} Y{:.3}", seg.to.0, seg.to.1)
+                }
+                Shape::Arc { center, clockwise } => {
+                    modal.motion = if clockwise { 2 } else { 3 };
+                    format!(
+                        "G{} X{:.3} Y{:.3} I{:.3} J{:.3}",
+                        modal.motion,
+                        seg.to.0,
+                        seg.to.1,
+                        center.0 - seg.from.0,
+                        center.1 - seg.from.1
+                    )
+                }
+            };
+            if seg.feed != modal.feed {
+                line.push_str(&format!(" F{}", num(seg.feed)));
+                modal.feed = seg.feed;
+            }
+            if seg.power != modal.power {
+                line.push_str(&format!(" S{}", num(seg.power)));
+                modal.power = seg.power;
+            }
+            out.push(line);
         }
+        head = segments[segments.len() - 1].to;
+    }
+
+    let target = group.tail_modal;
+    if modal.laser != target.laser {
+        out.push(laser_word(target.laser).to_string());
+    }
+    let mut restore = Vec::new();
+    if modal.motion != target.motion {
+        restore.push(format!("G{}", target.motion));
+    }
+    if modal.feed != target.feed {
+        restore.push(format!("F{}", num(target.feed)));
+    }
+    if modal.power != target.power {
+        restore.push(format!("S{}", num(target.power)));
+    }
+    if !restore.is_empty() {
+        out.push(restore.join(" "));
+    }
+    head
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32) -> Vec<String> {
+        vec![
+            format!("G0 X{x} Y{y}"),
+            "M3 S800".to_string(),
+            format!("G1 X{} Y{y} F1200", x + size),
+            format!("G1 X{} Y{}", x + size, y + size),
+            format!("G1 X{x} Y{}", y + size),
+            format!("G1 X{x} Y{y}"),
+            "M5".to_string(),
+        ]
+    }
+
+    /// Burning moves as undirected, rounded segments with their power, sorted
+    fn burns(lines: &[String]) -> Vec<(i64, i64, i64, i64, i64)> {
+        let round = |v: f32| (v * 1000.0).round() as i64;
+        let mut out: Vec<_> = interpret(lines)
+            .iter()
+            .filter_map(|b| match b.kind {
+                BlockKind::Linear(m) if m.burns() => {
+                    let (a, b) = ((round(m.from.x), round(m.from.y)), (round(m.to.x), round(m.to.y)));
+                    let (a, b) = if a <= b { (a, b) } else { (b, a) };
+                    Some((a.0, a.1, b.0, b.1, round(m.power)))
+                }
+                _ => None,
+            })
+            .collect();
+        out.sort_unstable();
+        out
+    }
+
+    /// Line of the first burning move that starts at `p`
+    fn first_burn_from(lines: &[String], p: Point) -> usize {
+        interpret(lines)
+            .iter()
+            .find(|b| matches!(b.kind, BlockKind::Linear(m) if m.burns() && dist((m.from.x, m.from.y), p) < 1e-3))
+            .expect("burn starting there")
+            .line
+    }
+
+    fn program(parts: &[Vec<String>]) -> Vec<String> {
+        let mut lines = vec!["G90".to_string(), "G21".to_string()];
+        lines.extend(parts.iter().flatten().cloned());
+        lines.push("G0 X0 Y0".to_string());
+        lines
+    }
+
+    #[test]
+    fn holes_are_cut_before_their_outline() {
+        // Outline first, as a drawing lists it; the hole sits in the middle
+        let lines = program(&[square(0.0, 0.0, 50.0), square(20.0, 20.0, 10.0)]);
+        let result = optimize(&lines, &OptimizeOptions::default());
+
+        assert!(first_burn_from(&result.lines, (20.0, 20.0)) < first_burn_from(&result.lines, (0.0, 0.0)));
+        assert_eq!(burns(&lines), burns(&result.lines));
+        assert_eq!(result.lines.last().map(String::as_str), Some("G0 X0 Y0"));
+    }
+
+    #[test]
+    fn shortens_travel_and_keeps_every_cut() {
+        let parts: Vec<Vec<String>> = [4, 0, 3, 1, 5, 2]
+            .iter()
+            .map(|&k| square(k as f32 * 20.0, 0.0, 10.0))
+            .collect();
+        let lines = program(&parts);
+        let result = optimize(&lines, &OptimizeOptions::default());
+
+        assert_eq!(result.stats.paths, 6);
+        assert!(result.stats.travel_after_mm < result.stats.travel_before_mm * 0.6);
+        assert_eq!(travel_mm(&interpret(&lines)), result.stats.travel_before_mm);
+        assert_eq!(burns(&lines), burns(&result.lines));
+        // Walks out along the row
+        let starts: Vec<usize> = (0..6)
+            .map(|k| first_burn_from(&result.lines, (k as f32 * 20.0, 0.0)))
+            .collect();
+        assert!(starts.windows(2).all(|w| w[0] < w[1]));
     }
 
-    optimized.extend(footer.into_iter().cloned());
-    optimized
+    #[test]
+    fn reverses_open_paths_and_rotates_loops() {
+        let lines = program(&[
+            // Loop listed from its far corner
+            vec![
+                "G0 X60 Y10".to_string(),
+                "M3 S500".to_string(),
+                "G1 X50 Y10 F900".to_string(),
+                "G1 X50 Y0".to_string(),
+                "G1 X60 Y0".to_string(),
+                "G1 X60 Y10".to_string(),
+                "M5".to_string(),
+            ],
+            // Open line drawn towards the origin
+            vec![
+                "G0 X20 Y0".to_string(),
+                "M3 S500".to_string(),
+                "G1 X5 Y0 F900".to_string(),
+                "M5".to_string(),
+            ],
+        ]);
+        let result = optimize(&lines, &OptimizeOptions::default());
+
+        assert_eq!((result.stats.reversed, result.stats.rotated), (1, 1));
+        assert!(result.lines.iter().any(|l| l == "G0 X5.000 Y0.000"));
+        assert!(result.lines.iter().any(|l| l == "G0 X50.000 Y0.000"));
+        assert_eq!(burns(&lines), burns(&result.lines));
+
+        let fixed = OptimizeOptions {
+            allow_reverse: false,
+            rotate_loops: false,
+            ..Default::default()
+        };
+        let result = optimize(&lines, &fixed);
+        assert_eq!((result.stats.reversed, result.stats.rotated), (0, 0));
+        assert_eq!(burns(&lines), burns(&result.lines));
+    }
+
+    #[test]
+    fn layers_passes_and_fills_stay_in_place() {
+        let mut lines = vec!["G90".to_string(), "; Layer C00 (Cut)".to_string(), "M8".to_string()];
+        lines.extend(square(40.0, 0.0, 5.0));
+        lines.extend(square(0.0, 0.0, 5.0));
+        lines.push("; Layer C01 (Engrave)".to_string());
+        lines.extend(square(80.0, 0.0, 5.0));
+        lines.extend(square(0.0, 20.0, 5.0));
+        lines.push("; Fill Scan (Layer C01, angle 0.0°)".to_string());
+        lines.extend(square(90.0, 0.0, 2.0));
+        lines.extend(square(0.0, 40.0, 2.0));
+        let result = optimize(&lines, &OptimizeOptions::default());
+
+        let at = |needle: &str| result.lines.iter().position(|l| l == needle).unwrap();
+        let (layer, fill) = (at("; Layer C01 (Engrave)"), at("; Fill Scan (Layer C01, angle 0.0°)"));
+        assert!(at("M8") < first_burn_from(&result.lines, (0.0, 0.0)));
+        assert!(first_burn_from(&result.lines, (40.0, 0.0)) < first_burn_from(&result.lines, (80.0, 0.0)));
+        assert!(first_burn_from(&result.lines, (0.0, 0.0)) < first_burn_from(&result.lines, (40.0, 0.0)));
+        assert!(layer < first_burn_from(&result.lines, (80.0, 0.0)));
+        // The fill scan is copied as it is
+        assert_eq!(result.lines[fill..], lines[lines.len() - 15..]);
+        assert_eq!(burns(&lines), burns(&result.lines));
+
+        let relative = vec![
+            "G91".to_string(),
+            "G1 X10 F600".to_string(),
+            "G0 X5".to_string(),
+            "G1 X-3".to_string(),
+        ];
+        assert_eq!(optimize(&relative, &OptimizeOptions::default()).lines, relative);
+    }
+
+    #[test]
+    fn laser_left_on_travels_at_zero_power() {
+        // Imported style: M4 once, G0 travel, power on the cut
+        let lines = vec![
+            "G90".to_string(),
+            "M4 S0".to_string(),
+            "G0 X30 Y0".to_string(),
+            "G1 X40 Y0 S600 F1500".to_string(),
+            "G0 X0 Y0 S0".to_string(),
+            "G1 X10 Y0 S600".to_string(),
+            "G0 X60 Y0 S0".to_string(),
+            "G2 X70 Y0 I5 J0 S400".to_string(),
+            "M5".to_string(),
+        ];
+        let result = optimize(&lines, &OptimizeOptions::default());
+
+        assert!(result.stats.travel_after_mm < result.stats.travel_before_mm);
+        assert_eq!(burns(&lines), burns(&result.lines));
+        assert_eq!(result.lines.iter().filter(|l| l.starts_with("M5")).count(), 1);
+        assert!(
+            result
+                .lines
+                .iter()
+                .any(|l| l.starts_with("G2 X70.000 Y0.000 I5.000 J0.000"))
+        );
+    }
+
+    #[test]
+    fn feed_moves_without_laser_are_travel() {
+        let lines: Vec<String> = [
+            "G90",
+            "G0 X50 Y0",
+            "M3 S500",
+            "G1 X60 Y0 F1000",
+            "M5",
+            "G1 X0 Y0 F3000",
+            "M3 S500",
+            "G1 X10 Y0",
+            "M5",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let result = optimize(&lines, &OptimizeOptions::default());
+
+        assert_eq!(burns(&lines), burns(&result.lines));
+        assert!(result.stats.travel_after_mm < result.stats.travel_before_mm);
+        let cold_feeds = interpret(&result.lines)
+            .iter()
+            .filter(|b| matches!(b.kind, BlockKind::Linear(m) if !m.burns() && m.xy_length() > 0.0))
+            .count();
+        assert_eq!(cold_feeds, 0, "{:?}", result.lines);
+    }
+
+    #[test]
+    fn exhausted_budget_keeps_the_original_order() {
+        let lines = program(&[square(50.0, 50.0, 5.0), square(0.0, 0.0, 5.0), square(60.0, 60.0, 5.0)]);
+        let options = OptimizeOptions {
+            time_budget: Duration::ZERO,
+            ..OptimizeOptions::default()
+        };
+        let result = optimize(&lines, &options);
+        assert!(result.stats.budget_exhausted);
+        assert_eq!(result.lines, lines);
+    }
 }
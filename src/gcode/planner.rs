@@ -0,0 +1,344 @@
+#![allow(dead_code)]
+
+use super::interpreter::{Block, BlockKind, Position};
+use crate::config::machine_profile::MachineProfile;
+
+/// Planner blocks GRBL 1.1 reports on an ATmega328p (`Bf:15,...`)
+pub const DEFAULT_PLANNER_BLOCKS: usize = 15;
+/// GRBL's `$12` factory value; arcs are planned as chords this close to the curve
+pub const DEFAULT_ARC_TOLERANCE_MM: f32 = 0.002;
+/// Z is not in the profile; GRBL's factory `$112`/`$122`
+const DEFAULT_Z_RATE: f32 = 500.0;
+const DEFAULT_Z_ACCEL: f32 = 50.0;
+const MIN_LENGTH_MM: f32 = 1e-6;
+
+/// Machine limits the planner works with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlannerConfig {
+    /// X, Y, Z in mm/min (`$110`-`$112`); rapids run at these
+    pub max_rate: [f32; 3],
+    /// X, Y, Z in mm/s² (`$120`-`$122`)
+    pub accel: [f32; 3],
+    /// `$11`, mm
+    pub junction_deviation: f32,
+    pub arc_tolerance: f32,
+    /// Moves the planner looks ahead over, the running one included
+    pub buffer_blocks: usize,
+}
+
+impl Default for PlannerConfig {
+    fn default() -> Self {
+        Self::from_profile(&MachineProfile::default())
+    }
+}
+
+impl PlannerConfig {
+    pub fn from_profile(profile: &MachineProfile) -> Self {
+        let buffer_blocks = profile
+            .detected_firmware
+            .as_ref()
+            .and_then(|info| info.planner_blocks)
+            .map_or(DEFAULT_PLANNER_BLOCKS, |blocks| blocks as usize);
+        Self {
+            max_rate: [profile.max_rate_x, profile.max_rate_y, DEFAULT_Z_RATE],
+            accel: [profile.accel_x, profile.accel_y, DEFAULT_Z_ACCEL],
+            junction_deviation: profile.junction_deviation_mm,
+            arc_tolerance: DEFAULT_ARC_TOLERANCE_MM,
+            buffer_blocks: buffer_blocks.max(2),
+        }
+    }
+}
+
+/// Time one program block takes on the machine
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PlannedBlock {
+    /// Index of the source line
+    pub line: usize,
+    pub seconds: f32,
+    pub length: f32,
+    pub rapid: bool,
+    /// Feed move with the laser armed
+    pub burn: bool,
+}
+
+/// One straight move as the planner sees it; arcs become many
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    /// Index into the planned blocks
+    owner: usize,
+    length: f32,
+    unit: [f32; 3],
+    /// mm/s
+    nominal: f32,
+    /// mm/s²
+    accel: f32,
+    /// Square of the fastest the segment may be entered at its junction
+    max_entry_sq: f32,
+}
+
+/// Largest value along `unit` that keeps every axis within its own limit
+fn axis_limited(limits: [f32; 3], unit: [f32; 3]) -> f32 {
+    let mut limit = f32::INFINITY;
+    for (max, u) in limits.iter().zip(unit) {
+        if u.abs() > 1e-9 {
+            limit = limit.min(max / u.abs());
+        }
+    }
+    limit
+}
+
+/// GRBL's junction deviation speed (squared) between two unit vectors
+fn junction_speed_sq(prev: [f32; 3], next: [f32; 3], config: &PlannerConfig) -> f32 {
+    let cos_theta = -(prev[0] * next[0] + prev[1] * next[1] + prev[2] * next[2]);
+    if cos_theta > 0.999_999 {
+        // Full reversal
+        return 0.0;
+    }
+    if cos_theta < -0.999_999 {
+        // Straight on
+        return f32::INFINITY;
+    }
+    let mut junction = [next[0] - prev[0], next[1] - prev[1], next[2] - prev[2]];
+    let norm = (junction[0] * junction[0] + junction[1] * junction[1] + junction[2] * junction[2]).sqrt();
+    junction.iter_mut().for_each(|c| *c /= norm);
+    let accel = axis_limited(config.accel, junction);
+    let sin_half = (0.5 * (1.0 - cos_theta)).sqrt();
+    (accel * config.junction_deviation.max(0.0) * sin_half / (1.0 - sin_half)).max(0.0)
+}
+
+/// Time for `length` entered at `entry`, left at `exit`, cruising at most at `nominal`
+fn trapezoid_seconds(length: f32, entry: f32, exit: f32, nominal: f32, accel: f32) -> f32 {
+    if length <= 0.0 || nominal <= 0.0 {
+        return 0.0;
+    }
+    let accelerate = (nominal * nominal - entry * entry) / (2.0 * accel);
+    let decelerate = (nominal * nominal - exit * exit) / (2.0 * accel);
+    if accelerate + decelerate <= length {
+        (nominal - entry) / accel + (nominal - exit) / accel + (length - accelerate - decelerate) / nominal
+    } else {
+        // Never reaches cruise speed
+        let peak = ((2.0 * accel * length + entry * entry + exit * exit) / 2.0).sqrt();
+        (peak - entry).max(0.0) / accel + (peak - exit).max(0.0) / accel
+    }
+}
+
+/// Run `blocks` through a GRBL-style look-ahead planner: junction deviation between moves,
+/// per-axis rate and acceleration limits, and only `buffer_blocks` moves of look-ahead, so
+/// runs of short moves can't reach full speed. Switching the laser or air and dwelling drain
+/// the planner like they do in GRBL's laser mode; S changes alone don't.
+pub fn plan(blocks: &[Block], config: &PlannerConfig) -> Vec<PlannedBlock> {
+    let mut planned = Vec::with_capacity(blocks.len());
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut stop_before_next = true;
+    // Repeating the current M3/M4/M5 or M7/M8/M9 doesn't sync, only a change does
+    let mut laser: Option<Option<bool>> = None;
+    let mut air: Option<bool> = None;
+
+    for block in blocks {
+        let owner = planned.len();
+        let mut entry = PlannedBlock {
+            line: block.line,
+            ..Default::default()
+        };
+        match block.kind {
+            BlockKind::Dwell(seconds) => {
+                entry.seconds = seconds;
+                stop_before_next = true;
+            }
+            BlockKind::LaserOn { dynamic } => stop_before_next |= laser.replace(Some(dynamic)) != Some(Some(dynamic)),
+            BlockKind::LaserOff => stop_before_next |= laser.replace(None) != Some(None),
+            BlockKind::Air(on) => stop_before_next |= air.replace(on) != Some(on),
+            BlockKind::Power(_) => {}
+            BlockKind::Rapid(motion) | BlockKind::Linear(motion) | BlockKind::Arc { motion, .. } => {
+                let rapid = block.is_rapid();
+                entry.rapid = rapid;
+                entry.burn = !rapid && motion.laser;
+                let points = match block.arc() {
+                    Some(arc) => arc.points(config.arc_tolerance),
+                    None => vec![motion.to],
+                };
+                let mut from: Position = motion.from;
+                for to in points {
+                    let delta = [to.x - from.x, to.y - from.y, to.z - from.z];
+                    let length = (delta[0] * delta[0] + delta[1] * delta[1] + delta[2] * delta[2]).sqrt();
+                    from = to;
+                    if length < MIN_LENGTH_MM {
+                        continue;
+                    }
+                    entry.length += length;
+                    let unit = delta.map(|d| d / length);
+                    let max_speed = axis_limited(config.max_rate, unit) / 60.0;
+                    // GRBL refuses a feed move without F; plan it at full speed rather than never
+                    let nominal = if rapid || motion.feed <= 0.0 {
+                        max_speed
+                    } else {
+                        (motion.feed / 60.0).min(max_speed)
+                    };
+                    let max_entry_sq = match segments.last() {
+                        Some(prev) if !stop_before_next => junction_speed_sq(prev.unit, unit, config)
+                            .min(prev.nominal * prev.nominal)
+                            .min(nominal * nominal),
+                        _ => 0.0,
+                    };
+                    stop_before_next = false;
+                    segments.push(Segment {
+                        owner,
+                        length,
+                        unit,
+                        nominal,
+                        accel: axis_limited(config.accel, unit).max(1e-3),
+                        max_entry_sq,
+                    });
+                }
+            }
+        }
+        planned.push(entry);
+    }
+
+    // Backward pass per segment over only what the buffer holds while the previous one runs;
+    // the last buffered move always plans to stop
+    let count = segments.len();
+    let window = config.buffer_blocks.max(2);
+    let mut reachable_sq = vec![0.0f32; count];
+    for k in 1..count {
+        let end = (k + window - 2).min(count - 1);
+        let mut exit_sq = 0.0f32;
+        for seg in segments[k..=end].iter().rev() {
+            exit_sq = seg.max_entry_sq.min(exit_sq + 2.0 * seg.accel * seg.length);
+        }
+        reachable_sq[k] = exit_sq;
+    }
+
+    // Forward pass: no entry faster than the previous move could accelerate to
+    let mut entry_sq = vec![0.0f32; count];
+    for k in 1..count {
+        let prev = &segments[k - 1];
+        entry_sq[k] = reachable_sq[k].min(entry_sq[k - 1] + 2.0 * prev.accel * prev.length);
+    }
+
+    for (k, seg) in segments.iter().enumerate() {
+        let exit_sq = entry_sq.get(k + 1).copied().unwrap_or(0.0);
+        planned[seg.owner].seconds +=
+            trapezoid_seconds(seg.length, entry_sq[k].sqrt(), exit_sq.sqrt(), seg.nominal, seg.accel);
+    }
+    planned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gcode::interpreter::interpret;
+    use crate::grbl::sim::{VirtualGrbl, VirtualGrblConfig};
+
+    fn config() -> PlannerConfig {
+        PlannerConfig {
+            max_rate: [6000.0, 6000.0, 500.0],
+            accel: [500.0, 500.0, 50.0],
+            junction_deviation: 0.01,
+            arc_tolerance: DEFAULT_ARC_TOLERANCE_MM,
+            buffer_blocks: DEFAULT_PLANNER_BLOCKS,
+        }
+    }
+
+    fn seconds(lines: &[&str], config: &PlannerConfig) -> f32 {
+        plan(&interpret(lines), config).iter().map(|b| b.seconds).sum()
+    }
+
+    #[test]
+    fn straight_moves_follow_the_trapezoid() {
+        // 100 mm at 50 mm/s with 500 mm/s²: 0.1 s up, 0.1 s down, 95 mm cruise
+        let t = seconds(&["G1 X100 F3000"], &config());
+        assert!((t - 2.1).abs() < 1e-4, "{t}");
+
+        // Rapids run at the axis limit; a diagonal may go faster than either axis alone
+        let t = seconds(&["G0 X100"], &config());
+        assert!((t - (0.4 + 80.0 / 100.0)).abs() < 1e-4, "{t}");
+        let diagonal = seconds(&["G0 X100 Y100"], &config());
+        assert!(diagonal < 2.0 * t, "{diagonal}");
+
+        // Collinear pieces join at full speed; M5/M3 between them forces a stop
+        let joined = seconds(&["G1 X50 F3000", "G1 X100"], &config());
+        assert!((joined - 2.1).abs() < 1e-3, "{joined}");
+        let stopped = seconds(&["G1 X50 F3000", "M5", "M3", "G1 X100"], &config());
+        assert!((stopped - joined - 0.1).abs() < 1e-3, "{stopped}");
+        // S changes alone don't stop in laser mode, dwells add their time
+        assert!((seconds(&["G1 X50 F3000 S100", "G1 X100 S900"], &config()) - joined).abs() < 1e-3);
+        assert!((seconds(&["M3 S100", "G1 X50 F3000", "M3 S900", "G1 X100"], &config()) - joined).abs() < 1e-3);
+        assert!((seconds(&["G4 P1.5"], &config()) - 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn short_buffer_limits_speed_on_short_segments() {
+        // 200 collinear 0.1 mm steps: a deep buffer sees far enough ahead to cruise
+        let lines: Vec<String> = (1..=200).map(|i| format!("G1 X{:.1} F6000", i as f32 * 0.1)).collect();
+        let blocks = interpret(&lines);
+        let deep = plan(
+            &blocks,
+            &PlannerConfig {
+                buffer_blocks: 1000,
+                ..config()
+            },
+        );
+        let shallow = plan(
+            &blocks,
+            &PlannerConfig {
+                buffer_blocks: 4,
+                ..config()
+            },
+        );
+        let total = |p: &[PlannedBlock]| p.iter().map(|b| b.seconds).sum::<f32>();
+        assert!(
+            total(&shallow) > total(&deep) * 1.5,
+            "{} vs {}",
+            total(&shallow),
+            total(&deep)
+        );
+    }
+
+    #[test]
+    fn arcs_and_raster_rows_match_reference_timings() {
+        // Full circle of r = 50 mm at 50 mm/s: the chords join far above the feed, so it is
+        // 2πr / v plus one v / a for the ramps at either end
+        let circle = seconds(&["G2 X0 Y0 I50 J0 F3000"], &config());
+        let reference = 2.0 * std::f32::consts::PI * 50.0 / 50.0 + 50.0 / 500.0;
+        assert!((circle - reference).abs() < 5e-3, "{circle} vs {reference}");
+
+        // A 10 mm raster row in 0.1 mm steps with a new S on each: laser mode keeps moving,
+        // so it times like one move that never reaches 100 mm/s, 2·sqrt(L/a); the reversal stops
+        let mut row: Vec<String> = vec!["M4 S0".to_string()];
+        row.extend((1..=100).map(|i| format!("G1 X{:.1} S{} F6000", i as f32 * 0.1, i * 10)));
+        row.extend((0..100).rev().map(|i| format!("G1 X{:.1} S{}", i as f32 * 0.1, i * 10)));
+        let deep = PlannerConfig {
+            buffer_blocks: 1000,
+            ..config()
+        };
+        let total: f32 = plan(&interpret(&row), &deep).iter().map(|b| b.seconds).sum();
+        let reference = 2.0 * 2.0 * (10.0f32 / 500.0).sqrt();
+        assert!((total - reference).abs() < 1e-3, "{total} vs {reference}");
+    }
+
+    #[test]
+    fn matches_virtual_grbl_on_a_square() {
+        let program = ["G1 X40 F2400", "G1 Y40", "G1 X0", "G1 Y0", "G0 X20 Y20"];
+        let sim_config = VirtualGrblConfig {
+            max_rate: [6000.0, 6000.0, 500.0],
+            accel: [500.0, 500.0, 50.0],
+            soft_limits: false,
+            ..Default::default()
+        };
+        let mut grbl = VirtualGrbl::new(&sim_config);
+        grbl.drain_output();
+        let start = grbl.clock();
+        for line in program {
+            grbl.write(format!("{line}\n").as_bytes());
+        }
+        grbl.run_until_idle(60.0);
+        let simulated = (grbl.clock() - start) as f32;
+
+        let estimated = seconds(&program, &config());
+        assert!(
+            (estimated - simulated).abs() / simulated < 0.03,
+            "estimated {estimated} s, simulated {simulated} s"
+        );
+    }
+}
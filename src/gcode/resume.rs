@@ -0,0 +1,359 @@
+#![allow(dead_code)]
+
+use super::interpreter::{Interpreter, MM_PER_INCH, words};
+use super::types::{ModalState, Plane};
+use crate::grbl::wcs::CoordSystem;
+
+/// Lines that may already be acknowledged but still sit in the planner when power drops
+pub const PLANNER_LOOKBACK: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LaserState {
+    #[default]
+    Off,
+    /// M3
+    Constant,
+    /// M4
+    Dynamic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AirState {
+    #[default]
+    Off,
+    /// M7
+    Mist,
+    /// M8
+    Flood,
+}
+
+/// Modal state in effect before a given program line
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModalSnapshot {
+    /// Interpreter state; lengths in mm whatever G20/G21 says
+    pub modal: ModalState,
+    pub laser: LaserState,
+    pub air: AirState,
+    pub wcs: Option<CoordSystem>,
+    /// Whether the program set Z yet; an unknown Z is left where it is
+    pub z_known: bool,
+}
+
+impl Default for ModalSnapshot {
+    fn default() -> Self {
+        Self {
+            modal: ModalState::default(),
+            laser: LaserState::Off,
+            air: AirState::Off,
+            wcs: None,
+            z_known: false,
+        }
+    }
+}
+
+impl ModalSnapshot {
+    /// State in effect before `lines[upto]`
+    pub fn scan(lines: &[String], upto: usize) -> Self {
+        let mut interpreter = Interpreter::new();
+        let mut state = Self::default();
+        for line in &lines[..upto.min(lines.len())] {
+            interpreter.push(line);
+            state.track(line);
+        }
+        state.modal = interpreter.state;
+        state
+    }
+
+    /// Modal groups the interpreter does not keep: M3 vs M4, coolant and the work system
+    fn track(&mut self, line: &str) {
+        for (letter, value) in words(line) {
+            match letter {
+                'G' => {
+                    let code = (value * 10.0).round() as i32;
+                    if (540..=590).contains(&code) && code % 10 == 0 {
+                        self.wcs = CoordSystem::from_index(((code - 540) / 10) as usize);
+                    }
+                }
+                'M' => match value as i32 {
+                    3 => self.laser = LaserState::Constant,
+                    4 => self.laser = LaserState::Dynamic,
+                    5 => self.laser = LaserState::Off,
+                    7 => self.air = AirState::Mist,
+                    8 => self.air = AirState::Flood,
+                    9 => self.air = AirState::Off,
+                    _ => {}
+                },
+                'Z' => self.z_known = true,
+                _ => {}
+            }
+        }
+    }
+
+    /// Length in the program's units, for words written back into it
+    fn program_length(&self, mm: f32) -> f32 {
+        if self.modal.inches { mm / MM_PER_INCH } else { mm }
+    }
+}
+
+fn has_axis_words(words: &[(char, f32)]) -> bool {
+    words.iter().any(|(c, _)| matches!(c, 'X' | 'Y' | 'Z'))
+}
+
+/// Index of the rapid that starts the path containing `line`, or 0
+pub fn path_start(lines: &[String], line: usize) -> usize {
+    let mut interpreter = Interpreter::new();
+    let mut start = 0;
+    for (idx, text) in lines.iter().enumerate().take(line.saturating_add(1)) {
+        if interpreter.push(text).iter().any(|block| block.is_rapid()) {
+            start = idx;
+        }
+    }
+    start
+}
+
+/// Default resume point: the start of the path that was cutting when the checkpoint was taken,
+/// stepping back over lines that may have still been in the planner
+pub fn suggested_resume_line(lines: &[String], checkpoint: usize) -> usize {
+    path_start(lines, checkpoint.saturating_sub(PLANNER_LOOKBACK))
+}
+
+/// Preamble and first line needed to continue a program at `start_line`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumePlan {
+    pub start_line: usize,
+    pub state: ModalSnapshot,
+    pub preamble: Vec<String>,
+    /// `lines[start_line]` with the modal words it relies on made explicit
+    pub first_line: String,
+}
+
+impl ResumePlan {
+    pub fn build(lines: &[String], start_line: usize) -> Result<Self, String> {
+        if start_line >= lines.len() {
+            return Err(format!(
+                "resume line {} is past the end of the program ({} lines)",
+                start_line + 1,
+                lines.len()
+            ));
+        }
+        let state = ModalSnapshot::scan(lines, start_line);
+        let modal = &state.modal;
+
+        // Laser off and absolute millimetres before any positioning move; the interpreter
+        // keeps positions in mm, so units switch back only after it
+        let mut preamble = vec!["M5".to_string(), "G21".to_string(), "G90".to_string()];
+        if let Some(system) = state.wcs {
+            preamble.push(system.gcode().to_string());
+        }
+        preamble.push(format!("G0 X{:.3} Y{:.3}", modal.x, modal.y));
+        if state.z_known {
+            preamble.push(format!("G0 Z{:.3}", modal.z));
+        }
+        if modal.inches {
+            preamble.push("G20".to_string());
+        }
+        preamble.push(
+            match modal.plane {
+                Plane::XY => "G17",
+                Plane::ZX => "G18",
+                Plane::YZ => "G19",
+            }
+            .to_string(),
+        );
+        if modal.arc_absolute {
+            preamble.push("G90.1".to_string());
+        }
+        // Back to the program's motion mode and feed after the G0 positioning, so bare axis
+        // words after a non-motion resume line don't run as rapids or lack a feed
+        let mut motion = format!("G{}", modal.current_g);
+        if modal.f > 0.0 {
+            motion.push_str(&format!(" F{}", state.program_length(modal.f)));
+        }
+        preamble.push(motion);
+        match state.air {
+            AirState::Off => {}
+            AirState::Mist => preamble.push("M7".to_string()),
+            AirState::Flood => preamble.push("M8".to_string()),
+        }
+        // Arm the laser at zero power; the first line carries the real S with its motion
+        match state.laser {
+            LaserState::Off => {}
+            LaserState::Constant => preamble.push("M3 S0".to_string()),
+            LaserState::Dynamic => preamble.push("M4 S0".to_string()),
+        }
+        if !modal.absolute {
+            preamble.push("G91".to_string());
+        }
+
+        let first_line = Self::explicit_first_line(&lines[start_line], &state);
+        Ok(Self { start_line, state, preamble, first_line })
+    }
+
+    fn explicit_first_line(line: &str, state: &ModalSnapshot) -> String {
+        let words = words(line);
+        if !has_axis_words(&words) {
+            return line.to_string();
+        }
+        let modal = &state.modal;
+        let mut out = String::new();
+        let has_motion = words
+            .iter()
+            .any(|&(c, v)| c == 'G' && matches!((v * 10.0).round() as i32, 0 | 10 | 20 | 30));
+        if !has_motion {
+            out.push_str(&format!("G{} ", modal.current_g));
+        }
+        out.push_str(line.trim());
+        if !words.iter().any(|(c, _)| *c == 'F') && modal.f > 0.0 {
+            out.push_str(&format!(" F{}", state.program_length(modal.f)));
+        }
+        if state.laser != LaserState::Off && !words.iter().any(|(c, _)| *c == 'S') {
+            out.push_str(&format!(" S{}", modal.s));
+        }
+        out
+    }
+
+    /// Full program to stream: preamble, then the rest of the job
+    pub fn program(&self, lines: &[String]) -> Vec<String> {
+        let mut out = self.preamble.clone();
+        out.push(self.first_line.clone());
+        out.extend(lines[self.start_line + 1..].iter().cloned());
+        out
+    }
+
+    /// Line of the full job that `program()[runtime_line]` stands for;
+    /// the preamble maps to the resume line itself since nothing after it has run yet
+    pub fn original_line(&self, runtime_line: usize) -> usize {
+        self.start_line + runtime_line.saturating_sub(self.preamble.len())
+    }
+
+    /// The part of the job still to run, for the preview
+    pub fn remaining<'a>(&self, lines: &'a [String]) -> &'a [String] {
+        &lines[self.start_line..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(text: &str) -> Vec<String> {
+        text.lines().map(|l| l.trim().to_string()).collect()
+    }
+
+    #[test]
+    fn scan_tracks_modal_state() {
+        let lines = program(
+            "G21 G90 G55 (setup)
+            M8
+            M4 S0
+            G0 X10 Y10
+            G1 X20 F1200 S600
+            G91
+            X5 Y5 ; relative
+            G90",
+        );
+        let state = ModalSnapshot::scan(&lines, 7);
+        assert!(!state.modal.absolute);
+        assert_eq!(state.wcs, Some(CoordSystem::G55));
+        assert_eq!(state.air, AirState::Flood);
+        assert_eq!(state.laser, LaserState::Dynamic);
+        assert_eq!((state.modal.x, state.modal.y), (25.0, 15.0));
+        assert_eq!((state.modal.f, state.modal.s, state.modal.current_g), (1200.0, 600.0, 1));
+    }
+
+    #[test]
+    fn plan_restores_plane_arc_mode_and_inches() {
+        let lines = program(
+            "G20 G18 G90.1
+            M3 S0
+            G0 X1 Z0.5
+            G1 X2 F10 S300
+            X3",
+        );
+        let plan = ResumePlan::build(&lines, 4).unwrap();
+        assert_eq!(
+            plan.preamble,
+            vec![
+                "M5",
+                "G21",
+                "G90",
+                "G0 X50.800 Y0.000",
+                "G0 Z12.700",
+                "G20",
+                "G18",
+                "G90.1",
+                "G1 F10",
+                "M3 S0"
+            ]
+        );
+        assert_eq!(plan.first_line, "G1 X3 F10 S300");
+    }
+
+    #[test]
+    fn plan_positions_with_laser_off_and_restores_state() {
+        let lines = program(
+            "G90
+            M4 S0
+            G0 X10 Y10
+            G1 X20 Y10 F900 S500
+            X20 Y20
+            X10 Y20",
+        );
+        let plan = ResumePlan::build(&lines, 5).unwrap();
+        assert_eq!(plan.preamble, vec!["M5", "G21", "G90", "G0 X20.000 Y20.000", "G17", "G1 F900", "M4 S0"]);
+        assert_eq!(plan.first_line, "G1 X10 Y20 F900 S500");
+
+        let resumed = plan.program(&lines);
+        assert_eq!(resumed.len(), plan.preamble.len() + 1);
+        assert!(ResumePlan::build(&lines, 6).is_err());
+    }
+
+    #[test]
+    fn plan_restores_motion_mode_before_a_non_motion_line() {
+        let lines = program(
+            "G90
+            G1 X10 Y10 F1500
+            M3 S800
+            X20 Y10
+            X20 Y20",
+        );
+        let plan = ResumePlan::build(&lines, 2).unwrap();
+        assert_eq!(plan.preamble, vec!["M5", "G21", "G90", "G0 X10.000 Y10.000", "G17", "G1 F1500"]);
+        assert_eq!(plan.first_line, "M3 S800");
+        assert_eq!(plan.program(&lines)[plan.preamble.len() + 1], "X20 Y10");
+    }
+
+    #[test]
+    fn runtime_lines_map_back_to_the_full_job() {
+        let lines = program(
+            "G90
+            M3 S0
+            G0 X10 Y10
+            G1 X20 Y10 F900 S500
+            X20 Y20
+            X10 Y20",
+        );
+        let plan = ResumePlan::build(&lines, 3).unwrap();
+        let resumed = plan.program(&lines);
+        let first = plan.preamble.len();
+        assert_eq!(plan.original_line(0), 3);
+        assert_eq!(plan.original_line(first), 3);
+        assert_eq!(plan.original_line(first + 2), 5);
+        assert_eq!(lines[plan.original_line(resumed.len() - 1)], resumed[resumed.len() - 1]);
+    }
+
+    #[test]
+    fn path_start_finds_positioning_rapid() {
+        let lines = program(
+            "G0 X0 Y0
+            G1 X5 F600 S100
+            G0 X10 Y10
+            G1 X15
+            G1 Y15
+            G1 X10",
+        );
+        assert_eq!(path_start(&lines, 4), 2);
+        assert_eq!(path_start(&lines, 1), 0);
+        assert_eq!(suggested_resume_line(&lines, 5), 0);
+    }
+}
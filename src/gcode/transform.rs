@@ -44,10 +44,56 @@ pub fn apply_rotary(cmd: &str, diameter_mm: f32, axis: char) -> String {
     parts.join(" ")
 }
 
+/// Dry run: turn every M3/M4 word into M5, leaving comments and other words (M30, M41…) alone
+pub fn laser_off(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.char_indices().peekable();
+    let mut in_paren = false;
+    while let Some((start, c)) = chars.next() {
+        match c {
+            ';' if !in_paren => {
+                out.push_str(&line[start..]);
+                break;
+            }
+            '(' => in_paren = true,
+            ')' => in_paren = false,
+            'M' | 'm' if !in_paren => {
+                let mut end = start + 1;
+                while let Some(&(i, d)) = chars.peek() {
+                    if d.is_ascii_digit() || d == '.' || (d == ' ' && end == start + 1) {
+                        end = i + 1;
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let word = &line[start..end];
+                match word[1..].trim().parse::<f32>() {
+                    Ok(v) if v == 3.0 || v == 4.0 => out.push_str("M5"),
+                    _ => out.push_str(word),
+                }
+                continue;
+            }
+            _ => {}
+        }
+        out.push(c);
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn laser_off_only_touches_spindle_words() {
+        assert_eq!(laser_off("M3 S1000"), "M5 S1000");
+        assert_eq!(laser_off("G1 X5 m04 S200"), "G1 X5 M5 S200");
+        assert_eq!(laser_off("M30"), "M30");
+        assert_eq!(laser_off("M41 M300 S440"), "M41 M300 S440");
+        assert_eq!(laser_off("M5 (was M3) ; M4 here"), "M5 (was M3) ; M4 here");
+    }
+
     #[test]
     fn test_apply_rotary_diameter_too_small() {
         assert_eq!(apply_rotary("G0 Y10", 0.1, 'Y'), "G0 Y10");
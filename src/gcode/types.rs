@@ -134,16 +134,41 @@ impl Default for LayerSettings {
     }
 }
 
-/// Modal state tracked while parsing a GCode file
-#[derive(Debug, Clone)]
+/// Arc plane selected by G17/G18/G19
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Plane {
+    #[default]
+    XY,
+    ZX,
+    YZ,
+}
+
+impl Plane {
+    /// Centre offset words for the plane's two axes
+    pub fn offset_words(self) -> (char, char) {
+        match self {
+            Plane::XY => ('I', 'J'),
+            Plane::ZX => ('K', 'I'),
+            Plane::YZ => ('J', 'K'),
+        }
+    }
+}
+
+/// Modal state tracked while parsing a GCode file; lengths are in mm whatever G20/G21 says
+#[derive(Debug, Clone, PartialEq)]
 pub struct ModalState {
     pub absolute: bool,
+    /// G90.1: I/J/K are absolute centre coordinates
+    pub arc_absolute: bool,
+    pub inches: bool,
+    pub plane: Plane,
     pub current_g: i32, // 0=G0, 1=G1, 2=G2, 3=G3
     pub laser_on: bool,
     pub x: f32,
     pub y: f32,
     pub z: f32,
-    pub f: f32,
+    pub a: f32,
+    pub f: f32, // mm/min
     pub s: f32,
 }
 
@@ -151,11 +176,15 @@ impl Default for ModalState {
     fn default() -> Self {
         Self {
             absolute: true,
+            arc_absolute: false,
+            inches: false,
+            plane: Plane::XY,
             current_g: 0,
             laser_on: false,
             x: 0.0,
             y: 0.0,
             z: 0.0,
+            a: 0.0,
             f: 0.0,
             s: 0.0,
         }
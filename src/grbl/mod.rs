@@ -1,3 +1,7 @@
 pub mod parser;
 pub mod protocol;
+pub mod settings;
+pub mod sim;
+pub mod streamer;
 pub mod types;
+pub mod wcs;
@@ -54,6 +54,11 @@ pub fn parse_status(line: &str) -> Option<GrblState> {
                 state.buffer_plan = nums[0].parse().unwrap_or(0);
                 state.buffer_rx = nums[1].parse().unwrap_or(0);
             }
+        } else if let Some(val) = part.strip_prefix("Pn:") {
+            state.pins = InputPins::parse(val);
+        } else if let Some(val) = part.strip_prefix("SD:") {
+            let percent = val.split(',').next().unwrap_or_default();
+            state.sd_percent = percent.trim().parse().ok();
         }
     }
 
@@ -157,6 +162,26 @@ mod tests {
         assert_eq!(status.buffer_rx, 128);
     }
 
+    #[test]
+    fn test_parse_status_sd_progress() {
+        let status = parse_status("<Run|MPos:1,2,0|FS:600,0|SD:42.5,/sd/all4laser.gcode>").unwrap();
+        assert_eq!(status.sd_percent, Some(42.5));
+        assert_eq!(parse_status("<Idle|MPos:0,0,0>").unwrap().sd_percent, None);
+    }
+
+    #[test]
+    fn test_parse_status_pins() {
+        let status = parse_status("<Door:1|MPos:0,0,0|Pn:PDX>").unwrap();
+        assert!(status.pins.door());
+        assert!(status.pins.probe());
+        assert!(status.pins.any_limit());
+        assert!(!status.pins.is_active('Y'));
+        assert_eq!(status.pins.active_letters(), "DPX");
+
+        let status = parse_status("<Idle|MPos:0,0,0>").unwrap();
+        assert!(status.pins.is_empty());
+    }
+
     #[test]
     fn test_parse_status_invalid() {
         assert!(parse_status("Idle|MPos:0,0,0").is_none());
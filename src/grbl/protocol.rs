@@ -75,6 +75,8 @@ pub const CMD_STATUS_REPORT: u8 = b'?';
 pub const CMD_CYCLE_START: u8 = b'~';
 pub const CMD_FEED_HOLD: u8 = b'!';
 pub const CMD_RESET: u8 = 0x18;
+/// Stops a `$J=` jog and flushes the queued jog moves (GRBL 1.1+)
+pub const CMD_JOG_CANCEL: u8 = 0x85;
 
 #[cfg(test)]
 mod tests {
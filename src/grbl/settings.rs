@@ -0,0 +1,511 @@
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    Integer,
+    Float,
+    Boolean,
+    /// Per-axis bit mask (bit 0 = X)
+    Mask,
+}
+
+/// Static description of one GRBL 1.1 `$` setting
+#[derive(Debug, Clone, Copy)]
+pub struct SettingDef {
+    pub id: i32,
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub kind: SettingKind,
+    pub min: f64,
+    pub max: f64,
+    pub description: &'static str,
+}
+
+const fn def(
+    id: i32,
+    name: &'static str,
+    unit: &'static str,
+    kind: SettingKind,
+    min: f64,
+    max: f64,
+    description: &'static str,
+) -> SettingDef {
+    SettingDef {
+        id,
+        name,
+        unit,
+        kind,
+        min,
+        max,
+        description,
+    }
+}
+
+use SettingKind::{Boolean, Float, Integer, Mask};
+
+#[rustfmt::skip]
+pub const GRBL_SETTINGS: &[SettingDef] = &[
+    def(0, "Step pulse time", "µs", Integer, 3.0, 255.0, "Length of the step pulse sent to the drivers; check the driver datasheet for the minimum."),
+    def(1, "Step idle delay", "ms", Integer, 0.0, 255.0, "How long steppers stay enabled after a move. 255 keeps them always enabled."),
+    def(2, "Step port invert", "mask", Mask, 0.0, 7.0, "Inverts the step signal per axis (bit 0 = X, 1 = Y, 2 = Z)."),
+    def(3, "Direction port invert", "mask", Mask, 0.0, 7.0, "Reverses the direction of an axis (bit 0 = X, 1 = Y, 2 = Z)."),
+    def(4, "Step enable invert", "bool", Boolean, 0.0, 1.0, "Inverts the stepper enable pin for drivers that are active-high."),
+    def(5, "Limit pins invert", "bool", Boolean, 0.0, 1.0, "Inverts the limit inputs for normally-closed switches without the pull-up wiring."),
+    def(6, "Probe pin invert", "bool", Boolean, 0.0, 1.0, "Inverts the probe input."),
+    def(10, "Status report options", "mask", Mask, 0.0, 3.0, "Bit 0 reports MPos (else WPos), bit 1 adds buffer state to status reports."),
+    def(11, "Junction deviation", "mm", Float, 0.0, 1.0, "Cornering speed: larger values take corners faster, smaller values are gentler."),
+    def(12, "Arc tolerance", "mm", Float, 0.0001, 1.0, "Maximum chord error when G2/G3 arcs are split into segments."),
+    def(13, "Report in inches", "bool", Boolean, 0.0, 1.0, "Reports positions in inches instead of millimeters."),
+    def(20, "Soft limits", "bool", Boolean, 0.0, 1.0, "Rejects moves beyond max travel. Requires homing ($22=1)."),
+    def(21, "Hard limits", "bool", Boolean, 0.0, 1.0, "Stops immediately with an alarm when a limit switch triggers."),
+    def(22, "Homing cycle", "bool", Boolean, 0.0, 1.0, "Enables $H and locks the machine in alarm on power-up until homed."),
+    def(23, "Homing direction invert", "mask", Mask, 0.0, 7.0, "Homes towards the negative end of an axis (bit 0 = X, 1 = Y, 2 = Z)."),
+    def(24, "Homing feed", "mm/min", Float, 1.0, 100_000.0, "Slow rate used to locate the switch precisely."),
+    def(25, "Homing seek", "mm/min", Float, 1.0, 100_000.0, "Fast rate used to find the switch."),
+    def(26, "Homing debounce", "ms", Integer, 0.0, 65_535.0, "Delay between homing phases to let the switches settle."),
+    def(27, "Homing pull-off", "mm", Float, 0.0, 100.0, "Distance backed off the switch after homing so it is released."),
+    def(30, "Maximum spindle speed", "RPM", Float, 0.0, 1_000_000.0, "S value that gives full PWM output (full laser power)."),
+    def(31, "Minimum spindle speed", "RPM", Float, 0.0, 1_000_000.0, "S value that gives the minimum PWM output."),
+    def(32, "Laser mode", "bool", Boolean, 0.0, 1.0, "Keeps moving through S changes and turns the laser off when not moving (M4 dynamic power)."),
+    def(100, "X steps/mm", "steps/mm", Float, 0.001, 100_000.0, "Steps the X axis needs to travel 1 mm."),
+    def(101, "Y steps/mm", "steps/mm", Float, 0.001, 100_000.0, "Steps the Y axis needs to travel 1 mm."),
+    def(102, "Z steps/mm", "steps/mm", Float, 0.001, 100_000.0, "Steps the Z axis needs to travel 1 mm."),
+    def(110, "X max rate", "mm/min", Float, 1.0, 1_000_000.0, "Fastest speed the X axis is allowed to move."),
+    def(111, "Y max rate", "mm/min", Float, 1.0, 1_000_000.0, "Fastest speed the Y axis is allowed to move."),
+    def(112, "Z max rate", "mm/min", Float, 1.0, 1_000_000.0, "Fastest speed the Z axis is allowed to move."),
+    def(120, "X acceleration", "mm/s²", Float, 0.1, 100_000.0, "Acceleration of the X axis."),
+    def(121, "Y acceleration", "mm/s²", Float, 0.1, 100_000.0, "Acceleration of the Y axis."),
+    def(122, "Z acceleration", "mm/s²", Float, 0.1, 100_000.0, "Acceleration of the Z axis."),
+    def(130, "X max travel", "mm", Float, 0.0, 100_000.0, "Usable X travel, used by soft limits and homing."),
+    def(131, "Y max travel", "mm", Float, 0.0, 100_000.0, "Usable Y travel, used by soft limits and homing."),
+    def(132, "Z max travel", "mm", Float, 0.0, 100_000.0, "Usable Z travel, used by soft limits and homing."),
+];
+
+pub fn definition(id: i32) -> Option<&'static SettingDef> {
+    GRBL_SETTINGS.iter().find(|d| d.id == id)
+}
+
+/// Strip the GRBL 0.9 trailing comment: `$0=10 (step pulse, usec)` → `10`
+pub fn clean_value(raw: &str) -> String {
+    raw.split('(').next().unwrap_or("").trim().to_string()
+}
+
+/// Check an edited value and return it in the form it will be sent
+pub fn validate(id: i32, value: &str) -> Result<String, String> {
+    let value = clean_value(value);
+    if value.is_empty() {
+        return Err(format!("${id} needs a value"));
+    }
+    let Some(def) = definition(id) else {
+        // Firmware-specific setting (grblHAL, FluidNC): only reject what GRBL cannot parse
+        if value.parse::<f64>().is_err() {
+            return Err(format!("${id}: '{value}' is not a number"));
+        }
+        return Ok(value);
+    };
+    let number: f64 = value
+        .parse()
+        .map_err(|_| format!("{} (${id}): '{value}' is not a number", def.name))?;
+    if !number.is_finite() {
+        return Err(format!("{} (${id}): '{value}' is not a number", def.name));
+    }
+    match def.kind {
+        Integer | Mask | Boolean if number.fract() != 0.0 => {
+            return Err(format!("{} (${id}) must be a whole number", def.name));
+        }
+        _ => {}
+    }
+    if number < def.min || number > def.max {
+        return Err(format!(
+            "{} (${id}) must be between {} and {} {}",
+            def.name, def.min, def.max, def.unit
+        ));
+    }
+    Ok(match def.kind {
+        Integer | Mask | Boolean => format!("{}", number as i64),
+        Float => value,
+    })
+}
+
+/// Rules GRBL enforces across settings (or that silently break a laser)
+pub fn cross_check(values: &BTreeMap<i32, String>) -> Vec<String> {
+    let num = |id: i32| {
+        values
+            .get(&id)
+            .and_then(|v| clean_value(v).parse::<f64>().ok())
+    };
+    let mut issues = Vec::new();
+    if num(20) == Some(1.0) && num(22) == Some(0.0) {
+        issues.push("Soft limits ($20) need the homing cycle ($22) enabled".to_string());
+    }
+    if let (Some(max), Some(min)) = (num(30), num(31))
+        && min > max
+    {
+        issues.push("Minimum spindle speed ($31) is above the maximum ($30)".to_string());
+    }
+    if num(32) == Some(0.0) {
+        issues.push("Laser mode ($32) is off: the laser stays on during S changes".to_string());
+    }
+    issues
+}
+
+/// Numeric comparison that ignores GRBL's float formatting
+pub fn same_value(a: &str, b: &str) -> bool {
+    let (a, b) = (clean_value(a), clean_value(b));
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        // GRBL prints floats with three decimals; `80` and `80.000` are the same setting.
+        (Ok(x), Ok(y)) => (x - y).abs() <= 1e-3_f64.max(x.abs().max(y.abs()) * 1e-6),
+        _ => a == b,
+    }
+}
+
+/// Full `$$` dump kept in the machine profile
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GrblSettingsSnapshot {
+    pub label: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Firmware summary at the time of the backup
+    #[serde(default)]
+    pub firmware: String,
+    pub values: BTreeMap<i32, String>,
+}
+
+impl GrblSettingsSnapshot {
+    pub fn new(
+        label: impl Into<String>,
+        firmware: impl Into<String>,
+        values: BTreeMap<i32, String>,
+    ) -> Self {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            label: label.into(),
+            timestamp,
+            firmware: firmware.into(),
+            values: values
+                .into_iter()
+                .map(|(id, v)| (id, clean_value(&v)))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingDiff {
+    pub id: i32,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// Settings that differ between two sets, by id
+pub fn diff(left: &BTreeMap<i32, String>, right: &BTreeMap<i32, String>) -> Vec<SettingDiff> {
+    let mut ids: Vec<i32> = left.keys().chain(right.keys()).copied().collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids.into_iter()
+        .filter_map(|id| {
+            let (l, r) = (left.get(&id), right.get(&id));
+            let differs = match (l, r) {
+                (Some(a), Some(b)) => !same_value(a, b),
+                _ => true,
+            };
+            differs.then(|| SettingDiff {
+                id,
+                left: l.map(|v| clean_value(v)),
+                right: r.map(|v| clean_value(v)),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestoreStep {
+    Send(String),
+    Waiting,
+    Finished(RestoreReport),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RestoreReport {
+    pub written: usize,
+    /// (setting, GRBL error code)
+    pub rejected: Vec<(i32, i32)>,
+    /// Values that did not read back as written
+    pub mismatched: Vec<SettingDiff>,
+}
+
+impl RestoreReport {
+    pub fn is_clean(&self) -> bool {
+        self.rejected.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RestorePhase {
+    Writing,
+    Verifying,
+}
+
+/// Writes a backup one `$n=value` line at a time (each waits for its `ok`, as EEPROM writes
+/// stall GRBL), then reads `$$` back and compares.
+#[derive(Debug, Clone)]
+pub struct SettingsRestore {
+    queue: VecDeque<(i32, String)>,
+    in_flight: Option<i32>,
+    expected: BTreeMap<i32, String>,
+    readback: BTreeMap<i32, String>,
+    report: RestoreReport,
+    total: usize,
+    phase: RestorePhase,
+}
+
+impl SettingsRestore {
+    /// Only settings that differ from `current` are written; an empty `current` writes them all.
+    pub fn new(target: &BTreeMap<i32, String>, current: &BTreeMap<i32, String>) -> Self {
+        let mut queue: Vec<(i32, String)> = target
+            .iter()
+            .filter(|(id, v)| current.get(id).is_none_or(|c| !same_value(c, v)))
+            .map(|(&id, v)| (id, clean_value(v)))
+            .collect();
+        // GRBL refuses $20=1 while homing is still off, so soft limits go last.
+        queue.sort_by_key(|(id, _)| (*id == 20, *id));
+        Self {
+            total: queue.len(),
+            queue: queue.into(),
+            in_flight: None,
+            expected: target.iter().map(|(&id, v)| (id, clean_value(v))).collect(),
+            readback: BTreeMap::new(),
+            report: RestoreReport::default(),
+            phase: RestorePhase::Writing,
+        }
+    }
+
+    /// First line to send
+    pub fn start(&mut self) -> RestoreStep {
+        self.advance()
+    }
+
+    /// (written or rejected, total writes)
+    pub fn progress(&self) -> (usize, usize) {
+        (self.report.written + self.report.rejected.len(), self.total)
+    }
+
+    pub fn is_verifying(&self) -> bool {
+        self.phase == RestorePhase::Verifying
+    }
+
+    pub fn on_ok(&mut self) -> RestoreStep {
+        match self.phase {
+            RestorePhase::Writing => {
+                if self.in_flight.take().is_some() {
+                    self.report.written += 1;
+                }
+                self.advance()
+            }
+            RestorePhase::Verifying => self.finish(),
+        }
+    }
+
+    pub fn on_error(&mut self, code: i32) -> RestoreStep {
+        match self.phase {
+            RestorePhase::Writing => {
+                if let Some(id) = self.in_flight.take() {
+                    self.report.rejected.push((id, code));
+                }
+                self.advance()
+            }
+            // `$$` itself failed (machine not idle): report what was written.
+            RestorePhase::Verifying => self.finish(),
+        }
+    }
+
+    pub fn on_setting(&mut self, id: i32, value: &str) {
+        if self.phase == RestorePhase::Verifying {
+            self.readback.insert(id, clean_value(value));
+        }
+    }
+
+    fn advance(&mut self) -> RestoreStep {
+        if let Some((id, value)) = self.queue.pop_front() {
+            self.in_flight = Some(id);
+            return RestoreStep::Send(format!("${id}={value}"));
+        }
+        self.phase = RestorePhase::Verifying;
+        RestoreStep::Send("$$".to_string())
+    }
+
+    fn finish(&mut self) -> RestoreStep {
+        let rejected: Vec<i32> = self.report.rejected.iter().map(|(id, _)| *id).collect();
+        let expected: BTreeMap<i32, String> = self
+            .expected
+            .iter()
+            .filter(|(id, _)| !rejected.contains(id))
+            .map(|(&id, v)| (id, v.clone()))
+            .collect();
+        self.report.mismatched = diff(&expected, &self.readback)
+            .into_iter()
+            // Settings the board does not have are already reported as rejected writes.
+            .filter(|d| d.left.is_some())
+            .collect();
+        RestoreStep::Finished(std::mem::take(&mut self.report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grbl::parser;
+    use crate::grbl::sim::{VirtualGrbl, VirtualGrblConfig};
+    use crate::grbl::types::GrblResponse;
+
+    fn values(pairs: &[(i32, &str)]) -> BTreeMap<i32, String> {
+        pairs.iter().map(|(id, v)| (*id, v.to_string())).collect()
+    }
+
+    #[test]
+    fn validates_ranges_kinds_and_cross_rules() {
+        assert_eq!(validate(100, " 80 ").as_deref(), Ok("80"));
+        assert_eq!(validate(22, "1.0").as_deref(), Ok("1"));
+        assert!(validate(0, "2").is_err(), "below the 3 µs minimum");
+        assert!(validate(22, "2").is_err(), "boolean");
+        assert!(validate(3, "1.5").is_err(), "mask must be whole");
+        assert!(validate(110, "fast").is_err());
+        assert_eq!(validate(0, "10 (step pulse, usec)").as_deref(), Ok("10"));
+        assert_eq!(
+            validate(341, "3").as_deref(),
+            Ok("3"),
+            "unknown ids pass through"
+        );
+
+        let issues = cross_check(&values(&[
+            (20, "1"),
+            (22, "0"),
+            (30, "1000"),
+            (31, "0"),
+            (32, "1"),
+        ]));
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("$22"));
+    }
+
+    #[test]
+    fn diff_ignores_float_formatting() {
+        let left = values(&[(100, "80"), (110, "3000.000"), (32, "1")]);
+        let right = values(&[(100, "80.000"), (110, "6000.000"), (130, "400.000")]);
+        let d = diff(&left, &right);
+        assert_eq!(
+            d,
+            vec![
+                SettingDiff {
+                    id: 32,
+                    left: Some("1".into()),
+                    right: None
+                },
+                SettingDiff {
+                    id: 110,
+                    left: Some("3000.000".into()),
+                    right: Some("6000.000".into())
+                },
+                SettingDiff {
+                    id: 130,
+                    left: None,
+                    right: Some("400.000".into())
+                },
+            ]
+        );
+    }
+
+    /// Runs a restore against the virtual machine the way the app does: one line per `ok`
+    fn run_restore(
+        grbl: &mut VirtualGrbl,
+        restore: &mut SettingsRestore,
+    ) -> (RestoreReport, Vec<String>) {
+        let mut sent = Vec::new();
+        let mut step = restore.start();
+        for _ in 0..200 {
+            match step {
+                RestoreStep::Send(line) => {
+                    grbl.write(format!("{line}\n").as_bytes());
+                    sent.push(line);
+                    step = RestoreStep::Waiting;
+                }
+                RestoreStep::Finished(report) => return (report, sent),
+                RestoreStep::Waiting => {}
+            }
+            grbl.advance(0.01);
+            for line in grbl.drain_output() {
+                step = match parser::parse_response(&line) {
+                    GrblResponse::Ok => restore.on_ok(),
+                    GrblResponse::Error(code) => restore.on_error(code),
+                    GrblResponse::Setting(id, value) => {
+                        restore.on_setting(id, &value);
+                        continue;
+                    }
+                    _ => continue,
+                };
+            }
+        }
+        panic!("restore did not finish");
+    }
+
+    fn read_all(grbl: &mut VirtualGrbl) -> BTreeMap<i32, String> {
+        grbl.write(b"$$\n");
+        grbl.advance(0.01);
+        grbl.drain_output()
+            .iter()
+            .filter_map(|l| match parser::parse_response(l) {
+                GrblResponse::Setting(id, v) => Some((id, v)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn restore_writes_differences_and_verifies_readback() {
+        let mut grbl = VirtualGrbl::new(&VirtualGrblConfig::default());
+        grbl.drain_output();
+        let backup = GrblSettingsSnapshot::new("before swap", "GRBL 1.1h", read_all(&mut grbl));
+
+        // Simulated EEPROM wipe: factory defaults come back.
+        grbl.write(b"$RST=$\n");
+        grbl.advance(0.01);
+        grbl.drain_output();
+        let wiped = read_all(&mut grbl);
+        let changes = diff(&backup.values, &wiped);
+        assert!(!changes.is_empty());
+
+        let mut target = backup.values.clone();
+        target.insert(22, "1".into());
+        target.insert(20, "1".into());
+        target.insert(999, "1".into());
+        let mut restore = SettingsRestore::new(&target, &wiped);
+        let (report, sent) = run_restore(&mut grbl, &mut restore);
+
+        let pos_22 = sent
+            .iter()
+            .position(|l| l == "$22=1")
+            .expect("homing written");
+        let pos_20 = sent
+            .iter()
+            .position(|l| l == "$20=1")
+            .expect("soft limits written");
+        assert!(pos_22 < pos_20, "homing must be enabled before soft limits");
+        assert_eq!(sent.last().map(String::as_str), Some("$$"));
+        assert_eq!(report.rejected, vec![(999, 3)]);
+        assert!(report.mismatched.is_empty(), "{:?}", report.mismatched);
+        // Everything that differed except the id the board does not have
+        assert_eq!(report.written, diff(&target, &wiped).len() - 1);
+        assert!(
+            diff(&read_all(&mut grbl), &target)
+                .iter()
+                .all(|d| d.id == 999)
+        );
+    }
+}
@@ -0,0 +1,1706 @@
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, VecDeque};
+
+use super::protocol;
+use crate::config::machine_profile::MachineProfile;
+
+pub const STARTUP_BANNER: &str = "Grbl 1.1h ['$' for help]";
+pub const BUILD_INFO: &str = "[VER:1.1h.20190825:Virtual]";
+pub const LEGACY_BANNER: &str = "Grbl 0.9j ['$' for help]";
+const UNLOCK_HINT: &str = "[MSG:'$H'|'$X' to unlock]";
+
+/// Integration step; motion is simulated in slices no longer than this
+const STEP_S: f32 = 0.001;
+const EPS: f32 = 1e-4;
+
+// GRBL 1.1 status codes used by the simulator
+const ERR_EXPECTED_COMMAND_LETTER: u8 = 1;
+const ERR_BAD_NUMBER_FORMAT: u8 = 2;
+const ERR_INVALID_STATEMENT: u8 = 3;
+const ERR_SETTING_DISABLED: u8 = 5;
+const ERR_IDLE: u8 = 8;
+const ERR_SYSTEM_GC_LOCK: u8 = 9;
+const ERR_TRAVEL_EXCEEDED: u8 = 15;
+const ERR_INVALID_JOG: u8 = 16;
+const ERR_UNSUPPORTED_COMMAND: u8 = 20;
+const ERR_UNDEFINED_FEED_RATE: u8 = 22;
+const ERR_VALUE_WORD_MISSING: u8 = 28;
+const ERR_UNSUPPORTED_COORD_SYS: u8 = 29;
+const ERR_INVALID_TARGET: u8 = 33;
+
+const ALARM_SOFT_LIMIT: u8 = 2;
+const ALARM_ABORT_CYCLE: u8 = 3;
+
+// Settings the simulator reads back while running
+const SET_STATUS_MASK: u16 = 10;
+const SET_JUNCTION_DEVIATION: u16 = 11;
+const SET_SOFT_LIMITS: u16 = 20;
+const SET_HOMING: u16 = 22;
+const SET_HOMING_SEEK: u16 = 25;
+const SET_MAX_SPINDLE: u16 = 30;
+const SET_LASER_MODE: u16 = 32;
+const SET_STEPS_PER_MM: u16 = 100;
+const SET_MAX_RATE: u16 = 110;
+const SET_ACCEL: u16 = 120;
+const SET_TRAVEL: u16 = 130;
+
+/// GRBL 1.1 factory settings (`$RST=$`)
+const DEFAULT_SETTINGS: &[(u16, f32)] = &[
+    (0, 10.0),
+    (1, 25.0),
+    (2, 0.0),
+    (3, 0.0),
+    (4, 0.0),
+    (5, 0.0),
+    (6, 0.0),
+    (10, 1.0),
+    (11, 0.010),
+    (12, 0.002),
+    (13, 0.0),
+    (20, 0.0),
+    (21, 0.0),
+    (22, 0.0),
+    (23, 0.0),
+    (24, 25.0),
+    (25, 500.0),
+    (26, 250.0),
+    (27, 1.0),
+    (30, 1000.0),
+    (31, 0.0),
+    (32, 1.0),
+    (100, 250.0),
+    (101, 250.0),
+    (102, 250.0),
+    (110, 500.0),
+    (111, 500.0),
+    (112, 500.0),
+    (120, 10.0),
+    (121, 10.0),
+    (122, 10.0),
+    (130, 200.0),
+    (131, 200.0),
+    (132, 200.0),
+];
+
+/// Settings GRBL prints without decimals
+fn is_integer_setting(id: u16) -> bool {
+    id < 100 && !matches!(id, 11 | 12 | 24 | 25 | 27)
+}
+
+/// Machine the simulator pretends to be. Travel is the positive machine space `0..=travel`;
+/// an axis with zero travel is unbounded.
+#[derive(Debug, Clone)]
+pub struct VirtualGrblConfig {
+    /// Usable serial RX bytes (127 on an ATmega328p)
+    pub rx_buffer_size: usize,
+    /// Planner blocks reported by `Bf:` (15 on an ATmega328p)
+    pub planner_blocks: usize,
+    pub steps_per_mm: [f32; 3],
+    /// mm/min
+    pub max_rate: [f32; 3],
+    /// mm/s²
+    pub accel: [f32; 3],
+    /// mm
+    pub travel: [f32; 3],
+    pub soft_limits: bool,
+    pub homing: bool,
+    /// GRBL 0.9: no `$J=` or jog cancel; `ok` still means the line was planned, not run
+    pub legacy_0_9: bool,
+}
+
+impl Default for VirtualGrblConfig {
+    fn default() -> Self {
+        Self::from_profile(&MachineProfile::default())
+    }
+}
+
+impl VirtualGrblConfig {
+    pub fn from_profile(profile: &MachineProfile) -> Self {
+        Self {
+            rx_buffer_size: profile.grbl_rx_buffer_size.max(32),
+            planner_blocks: 15,
+            steps_per_mm: [profile.steps_per_mm_x, profile.steps_per_mm_y, 250.0],
+            max_rate: [profile.max_rate_x, profile.max_rate_y, 500.0],
+            accel: [profile.accel_x, profile.accel_y, 50.0],
+            travel: [profile.workspace_x_mm, profile.workspace_y_mm, 0.0],
+            soft_limits: true,
+            homing: false,
+            legacy_0_9: false,
+        }
+    }
+
+    fn settings(&self) -> BTreeMap<u16, f32> {
+        let mut settings: BTreeMap<u16, f32> = DEFAULT_SETTINGS.iter().copied().collect();
+        settings.insert(SET_SOFT_LIMITS, if self.soft_limits { 1.0 } else { 0.0 });
+        settings.insert(SET_HOMING, if self.homing { 1.0 } else { 0.0 });
+        for axis in 0..3u16 {
+            let i = axis as usize;
+            settings.insert(SET_STEPS_PER_MM + axis, self.steps_per_mm[i]);
+            settings.insert(SET_MAX_RATE + axis, self.max_rate[i]);
+            settings.insert(SET_ACCEL + axis, self.accel[i]);
+            settings.insert(SET_TRAVEL + axis, self.travel[i]);
+        }
+        settings
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MotionMode {
+    Rapid,
+    Linear,
+    CwArc,
+    CcwArc,
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpindleState {
+    Off,
+    Cw,
+    Ccw,
+}
+
+/// Parser modal state (`$G`)
+#[derive(Debug, Clone, Copy)]
+struct Modal {
+    motion: MotionMode,
+    /// 0 = G17, 1 = G18, 2 = G19
+    plane: usize,
+    inches: bool,
+    absolute: bool,
+    /// 0 = G54 … 5 = G59
+    wcs: usize,
+    spindle: SpindleState,
+    mist: bool,
+    flood: bool,
+    /// mm/min
+    feed: f32,
+    spindle_speed: f32,
+}
+
+impl Default for Modal {
+    fn default() -> Self {
+        Self {
+            motion: MotionMode::Rapid,
+            plane: 0,
+            inches: false,
+            absolute: true,
+            wcs: 0,
+            spindle: SpindleState::Off,
+            mist: false,
+            flood: false,
+            feed: 0.0,
+            spindle_speed: 0.0,
+        }
+    }
+}
+
+/// (first, second, linear) axes of G17/G18/G19
+const PLANE_AXES: [[usize; 3]; 3] = [[0, 1, 2], [2, 0, 1], [1, 2, 0]];
+
+#[derive(Debug, Clone, Copy)]
+enum Path {
+    Line {
+        start: [f32; 3],
+        end: [f32; 3],
+    },
+    Arc {
+        start: [f32; 3],
+        end: [f32; 3],
+        axes: [usize; 3],
+        center: [f32; 2],
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+    },
+}
+
+impl Path {
+    fn length(&self) -> f32 {
+        match *self {
+            Path::Line { start, end } => distance(start, end),
+            Path::Arc {
+                start,
+                end,
+                axes,
+                radius,
+                sweep,
+                ..
+            } => {
+                let linear = end[axes[2]] - start[axes[2]];
+                (radius * sweep).hypot(linear)
+            }
+        }
+    }
+
+    fn end(&self) -> [f32; 3] {
+        match *self {
+            Path::Line { end, .. } | Path::Arc { end, .. } => end,
+        }
+    }
+
+    fn point_at(&self, fraction: f32) -> [f32; 3] {
+        if fraction >= 1.0 {
+            return self.end();
+        }
+        match *self {
+            Path::Line { start, end } => lerp(start, end, fraction),
+            Path::Arc {
+                start,
+                end,
+                axes,
+                center,
+                radius,
+                start_angle,
+                sweep,
+            } => {
+                let angle = start_angle + sweep * fraction;
+                let mut p = start;
+                p[axes[0]] = center[0] + radius * angle.cos();
+                p[axes[1]] = center[1] + radius * angle.sin();
+                p[axes[2]] = start[axes[2]] + (end[axes[2]] - start[axes[2]]) * fraction;
+                p
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Move {
+    path: Path,
+    length: f32,
+    done: f32,
+    /// Programmed feed in mm/min; rapids use the axis maximums instead
+    feed: f32,
+    rapid: bool,
+    jog: bool,
+    homing: bool,
+    spindle: f32,
+}
+
+impl Move {
+    fn new(path: Path, feed: f32, rapid: bool, spindle: f32) -> Self {
+        Self {
+            length: path.length(),
+            path,
+            done: 0.0,
+            feed,
+            rapid,
+            jog: false,
+            homing: false,
+            spindle,
+        }
+    }
+
+    fn position(&self) -> [f32; 3] {
+        if self.length <= EPS {
+            return self.path.end();
+        }
+        self.path.point_at(self.done / self.length)
+    }
+
+    /// Unit direction of travel at distance `s` along the block
+    fn direction_at(&self, s: f32) -> [f32; 3] {
+        if self.length <= EPS {
+            return [0.0; 3];
+        }
+        let probe = (self.length * 0.01).clamp(EPS, 0.05);
+        let (a, b) = if s + probe <= self.length {
+            (s, s + probe)
+        } else {
+            ((self.length - probe).max(0.0), self.length)
+        };
+        let p0 = self.path.point_at(a / self.length);
+        let p1 = self.path.point_at(b / self.length);
+        normalize(sub(p1, p0))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Block {
+    Move(Move),
+    /// G4 dwell, seconds left
+    Dwell(f32),
+}
+
+/// Work deferred until the planner drains: the `ok` of G4, M0, M2 and `$H`
+#[derive(Debug, Clone, Default)]
+struct SyncAction {
+    messages: Vec<String>,
+    then_hold: bool,
+}
+
+enum Ack {
+    Ok,
+    /// `ok` is sent once the planner has drained
+    Sync(SyncAction),
+    /// An alarm was raised instead of answering
+    Silent,
+}
+
+/// In-process GRBL 1.1 controller. Bytes go in through [`VirtualGrbl::write`], replies come out of
+/// [`VirtualGrbl::drain_output`] and motion advances only when [`VirtualGrbl::advance`] is called,
+/// so tests can run jobs in simulated time.
+#[derive(Debug, Clone)]
+pub struct VirtualGrbl {
+    settings: BTreeMap<u16, f32>,
+    rx_capacity: usize,
+    planner_capacity: usize,
+    rx: VecDeque<u8>,
+    out: VecDeque<String>,
+    planner: VecDeque<Block>,
+    mpos: [f32; 3],
+    /// mm/s along the executing block
+    speed: f32,
+    /// Parser position: the end of the last accepted motion
+    planned: [f32; 3],
+    modal: Modal,
+    coord_sys: [[f32; 3]; 6],
+    g92: [f32; 3],
+    g28: [f32; 3],
+    g30: [f32; 3],
+    feed_ov: u16,
+    rapid_ov: u16,
+    spindle_ov: u16,
+    alarm: bool,
+    /// Soft/hard limit alarms ignore everything but a reset
+    critical: bool,
+    check_mode: bool,
+    hold: bool,
+    jog_cancel: bool,
+    legacy: bool,
+    sync: Option<SyncAction>,
+    reset_after_ok: bool,
+    overflowed: bool,
+    clock: f64,
+}
+
+impl VirtualGrbl {
+    pub fn new(config: &VirtualGrblConfig) -> Self {
+        let mut machine = Self {
+            settings: config.settings(),
+            rx_capacity: config.rx_buffer_size.max(1),
+            planner_capacity: config.planner_blocks.max(1),
+            rx: VecDeque::new(),
+            out: VecDeque::new(),
+            planner: VecDeque::new(),
+            mpos: [0.0; 3],
+            speed: 0.0,
+            planned: [0.0; 3],
+            modal: Modal::default(),
+            coord_sys: [[0.0; 3]; 6],
+            g92: [0.0; 3],
+            g28: [0.0; 3],
+            g30: [0.0; 3],
+            feed_ov: 100,
+            rapid_ov: 100,
+            spindle_ov: 100,
+            alarm: config.homing,
+            critical: false,
+            check_mode: false,
+            hold: false,
+            jog_cancel: false,
+            legacy: config.legacy_0_9,
+            sync: None,
+            reset_after_ok: false,
+            overflowed: false,
+            clock: 0.0,
+        };
+        machine.out.push_back(if machine.legacy { LEGACY_BANNER } else { STARTUP_BANNER }.to_string());
+        if machine.alarm {
+            machine.out.push_back(UNLOCK_HINT.to_string());
+        }
+        machine
+    }
+
+    /// Bytes from the host. Real-time commands act immediately; everything else lands in the
+    /// RX buffer, and bytes that do not fit are lost like on the real controller.
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            match byte {
+                protocol::CMD_STATUS_REPORT => {
+                    let report = self.status_report();
+                    self.out.push_back(report);
+                }
+                protocol::CMD_FEED_HOLD => self.feed_hold(),
+                protocol::CMD_CYCLE_START => self.hold = false,
+                protocol::CMD_RESET => self.soft_reset(),
+                protocol::CMD_JOG_CANCEL if !self.legacy => self.cancel_jog(),
+                protocol::FEED_OV_RESET => self.feed_ov = 100,
+                protocol::FEED_OV_PLUS_10 => self.feed_ov = (self.feed_ov + 10).min(200),
+                protocol::FEED_OV_MINUS_10 => {
+                    self.feed_ov = self.feed_ov.saturating_sub(10).max(10)
+                }
+                protocol::FEED_OV_PLUS_1 => self.feed_ov = (self.feed_ov + 1).min(200),
+                protocol::FEED_OV_MINUS_1 => self.feed_ov = self.feed_ov.saturating_sub(1).max(10),
+                protocol::RAPID_OV_100 => self.rapid_ov = 100,
+                protocol::RAPID_OV_50 => self.rapid_ov = 50,
+                protocol::RAPID_OV_25 => self.rapid_ov = 25,
+                protocol::SPINDLE_OV_RESET => self.spindle_ov = 100,
+                protocol::SPINDLE_OV_PLUS_10 => self.spindle_ov = (self.spindle_ov + 10).min(200),
+                protocol::SPINDLE_OV_MINUS_10 => {
+                    self.spindle_ov = self.spindle_ov.saturating_sub(10).max(10)
+                }
+                protocol::SPINDLE_OV_PLUS_1 => self.spindle_ov = (self.spindle_ov + 1).min(200),
+                protocol::SPINDLE_OV_MINUS_1 => {
+                    self.spindle_ov = self.spindle_ov.saturating_sub(1).max(10)
+                }
+                // Safety door, coolant toggles and other extended real-time bytes
+                0x80..=0xFF => {}
+                // Line endings are normalised to '\n'
+                b'\r' => {}
+                _ => {
+                    if self.rx.len() < self.rx_capacity {
+                        self.rx.push_back(byte);
+                    } else {
+                        self.overflowed = true;
+                    }
+                }
+            }
+        }
+        self.process_rx();
+    }
+
+    /// Lines the controller has sent since the last call
+    pub fn drain_output(&mut self) -> Vec<String> {
+        self.out.drain(..).collect()
+    }
+
+    /// Run the machine for `seconds` of simulated time
+    pub fn advance(&mut self, seconds: f32) {
+        let mut remaining = seconds;
+        while remaining > 0.0 {
+            if self.planner.is_empty() && self.sync.is_none() {
+                self.speed = 0.0;
+                self.clock += remaining as f64;
+                self.process_rx();
+                if self.planner.is_empty() {
+                    return;
+                }
+                continue;
+            }
+            let h = remaining.min(STEP_S);
+            self.step(h);
+            self.clock += h as f64;
+            remaining -= h;
+            self.process_rx();
+        }
+    }
+
+    /// Advance until the planner drains (or `max_seconds` pass); returns the simulated time spent
+    pub fn run_until_idle(&mut self, max_seconds: f32) -> f32 {
+        let start = self.clock;
+        while (self.clock - start) < max_seconds as f64 {
+            if self.is_idle() {
+                break;
+            }
+            self.advance(0.01);
+        }
+        (self.clock - start) as f32
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.planner.is_empty() && self.sync.is_none() && !self.rx.contains(&b'\n')
+    }
+
+    pub fn machine_position(&self) -> [f32; 3] {
+        self.mpos
+    }
+
+    pub fn work_position(&self) -> [f32; 3] {
+        sub(self.mpos, self.wco())
+    }
+
+    pub fn planner_len(&self) -> usize {
+        self.planner.len()
+    }
+
+    pub fn rx_len(&self) -> usize {
+        self.rx.len()
+    }
+
+    /// True once any byte was dropped because the RX buffer was full
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// Simulated seconds since power-up
+    pub fn clock(&self) -> f64 {
+        self.clock
+    }
+
+    pub fn status_report(&self) -> String {
+        let mask = self.setting(SET_STATUS_MASK) as u32;
+        let (label, pos) = if mask & 1 != 0 {
+            ("MPos", self.mpos)
+        } else {
+            ("WPos", self.work_position())
+        };
+        let mut report = format!(
+            "<{}|{label}:{:.3},{:.3},{:.3}",
+            self.state_name(),
+            pos[0],
+            pos[1],
+            pos[2]
+        );
+        if mask & 2 != 0 {
+            report.push_str(&format!(
+                "|Bf:{},{}",
+                self.planner_capacity.saturating_sub(self.planner.len()),
+                self.rx_capacity.saturating_sub(self.rx.len())
+            ));
+        }
+        report.push_str(&format!(
+            "|FS:{:.0},{:.0}",
+            self.speed * 60.0,
+            self.current_spindle()
+        ));
+        // Real GRBL only refreshes these every few reports; sending them every time keeps
+        // clients that rebuild their state from each report simple.
+        let wco = self.wco();
+        report.push_str(&format!(
+            "|WCO:{:.3},{:.3},{:.3}|Ov:{},{},{}>",
+            wco[0], wco[1], wco[2], self.feed_ov, self.rapid_ov, self.spindle_ov
+        ));
+        report
+    }
+
+    fn state_name(&self) -> &'static str {
+        let front = match self.planner.front() {
+            Some(Block::Move(mv)) => Some(mv),
+            _ => None,
+        };
+        if self.alarm {
+            "Alarm"
+        } else if front.is_some_and(|mv| mv.homing) {
+            "Home"
+        } else if self.check_mode {
+            "Check"
+        } else if self.hold {
+            if self.speed > 0.0 { "Hold:1" } else { "Hold:0" }
+        } else if front.is_some_and(|mv| mv.jog) {
+            "Jog"
+        } else if !self.planner.is_empty() || self.sync.is_some() {
+            "Run"
+        } else {
+            "Idle"
+        }
+    }
+
+    fn setting(&self, id: u16) -> f32 {
+        self.settings.get(&id).copied().unwrap_or(0.0)
+    }
+
+    fn axis_setting(&self, base: u16) -> [f32; 3] {
+        [
+            self.setting(base),
+            self.setting(base + 1),
+            self.setting(base + 2),
+        ]
+    }
+
+    fn wco(&self) -> [f32; 3] {
+        add(self.coord_sys[self.modal.wcs], self.g92)
+    }
+
+    fn current_spindle(&self) -> f32 {
+        let programmed = match self.planner.front() {
+            Some(Block::Move(mv)) => mv.spindle,
+            _ if self.modal.spindle == SpindleState::Off => 0.0,
+            _ => self.modal.spindle_speed,
+        };
+        (programmed * self.spindle_ov as f32 / 100.0).min(self.setting(SET_MAX_SPINDLE))
+    }
+
+    fn feed_hold(&mut self) {
+        if self.alarm || self.check_mode {
+            return;
+        }
+        if matches!(self.planner.front(), Some(Block::Move(mv)) if mv.jog) {
+            self.cancel_jog();
+        } else {
+            self.hold = true;
+        }
+    }
+
+    fn cancel_jog(&mut self) {
+        if matches!(self.planner.front(), Some(Block::Move(mv)) if mv.jog) {
+            self.jog_cancel = true;
+        }
+    }
+
+    fn soft_reset(&mut self) {
+        let moving = self.speed > 0.0 || (!self.planner.is_empty() && !self.hold);
+        if moving && !self.alarm {
+            self.alarm = true;
+            self.out.push_back(format!("ALARM:{ALARM_ABORT_CYCLE}"));
+        }
+        self.rx.clear();
+        self.planner.clear();
+        self.sync = None;
+        self.speed = 0.0;
+        self.hold = false;
+        self.jog_cancel = false;
+        self.check_mode = false;
+        self.critical = false;
+        self.reset_after_ok = false;
+        self.planned = self.mpos;
+        self.modal = Modal::default();
+        self.g92 = [0.0; 3];
+        self.feed_ov = 100;
+        self.rapid_ov = 100;
+        self.spindle_ov = 100;
+        self.out.push_back(if self.legacy { LEGACY_BANNER } else { STARTUP_BANNER }.to_string());
+        if self.alarm {
+            self.out.push_back(UNLOCK_HINT.to_string());
+        }
+    }
+
+    fn raise_critical_alarm(&mut self, code: u8) {
+        self.planner.clear();
+        self.sync = None;
+        self.speed = 0.0;
+        self.alarm = true;
+        self.critical = true;
+        self.out.push_back(format!("ALARM:{code}"));
+        self.out.push_back("[MSG:Reset to continue]".to_string());
+    }
+
+    /// Pull complete lines out of the RX buffer while the planner has room
+    fn process_rx(&mut self) {
+        loop {
+            self.finish_sync();
+            if self.critical || self.sync.is_some() || self.planner.len() >= self.planner_capacity {
+                return;
+            }
+            let Some(end) = self.rx.iter().position(|&b| b == b'\n') else {
+                return;
+            };
+            let bytes: Vec<u8> = self.rx.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&bytes[..end]).trim().to_string();
+            let result = if line.is_empty() {
+                Ok(Ack::Ok)
+            } else if line.starts_with('$') {
+                self.execute_system(&line)
+            } else if self.alarm || self.jogging() {
+                Err(ERR_SYSTEM_GC_LOCK)
+            } else {
+                self.execute_gcode(&line, false)
+            };
+            match result {
+                Ok(Ack::Ok) => {
+                    self.out.push_back("ok".to_string());
+                    if std::mem::take(&mut self.reset_after_ok) {
+                        self.soft_reset();
+                    }
+                }
+                Ok(Ack::Sync(action)) => self.sync = Some(action),
+                Ok(Ack::Silent) => {}
+                Err(code) => self.out.push_back(format!("error:{code}")),
+            }
+        }
+    }
+
+    fn finish_sync(&mut self) {
+        if !self.planner.is_empty() || self.speed > 0.0 {
+            return;
+        }
+        if let Some(action) = self.sync.take() {
+            self.out.extend(action.messages);
+            self.out.push_back("ok".to_string());
+            if action.then_hold {
+                self.hold = true;
+            }
+        }
+    }
+
+    fn jogging(&self) -> bool {
+        self.planner
+            .iter()
+            .any(|block| matches!(block, Block::Move(mv) if mv.jog))
+    }
+
+    /// Idle for `$` commands that need the machine at rest (alarm counts as rest)
+    fn at_rest(&self) -> bool {
+        self.planner.is_empty() && self.sync.is_none() && !self.hold
+    }
+
+    fn execute_system(&mut self, line: &str) -> Result<Ack, u8> {
+        let cmd = line[1..].trim();
+        let upper = cmd.to_ascii_uppercase();
+
+        if upper.starts_with("J=") && self.legacy {
+            return Err(ERR_UNSUPPORTED_COMMAND);
+        }
+        if let Some(jog) = upper.strip_prefix("J=") {
+            let jog_ok = !self.alarm
+                && !self.check_mode
+                && !self.hold
+                && self.sync.is_none()
+                && self
+                    .planner
+                    .iter()
+                    .all(|block| matches!(block, Block::Move(mv) if mv.jog));
+            if !jog_ok {
+                return Err(ERR_IDLE);
+            }
+            return self.execute_gcode(jog, true);
+        }
+
+        match upper.as_str() {
+            "" => {
+                self.out.push_back(
+                    "[HLP:$$ $# $G $I $N $x=val $Nx=line $J=line $C $X $H ~ ! ? ctrl-x]"
+                        .to_string(),
+                );
+            }
+            "$" => {
+                if !self.at_rest() {
+                    return Err(ERR_IDLE);
+                }
+                let lines: Vec<String> = self
+                    .settings
+                    .iter()
+                    .map(|(&id, &value)| {
+                        if is_integer_setting(id) {
+                            format!("${id}={}", value as i64)
+                        } else {
+                            format!("${id}={value:.3}")
+                        }
+                    })
+                    .collect();
+                self.out.extend(lines);
+            }
+            "#" => {
+                if !self.at_rest() {
+                    return Err(ERR_IDLE);
+                }
+                let mut lines = Vec::new();
+                for (i, offset) in self.coord_sys.iter().enumerate() {
+                    lines.push(format!("[G{}:{}]", 54 + i, fmt_point(*offset)));
+                }
+                lines.push(format!("[G28:{}]", fmt_point(self.g28)));
+                lines.push(format!("[G30:{}]", fmt_point(self.g30)));
+                lines.push(format!("[G92:{}]", fmt_point(self.g92)));
+                lines.push("[TLO:0.000]".to_string());
+                lines.push("[PRB:0.000,0.000,0.000:0]".to_string());
+                self.out.extend(lines);
+            }
+            "G" => {
+                let line = self.gcode_state_report();
+                self.out.push_back(line);
+            }
+            "I" => {
+                self.out.push_back(BUILD_INFO.to_string());
+                self.out.push_back(format!(
+                    "[OPT:VNM,{},{}]",
+                    self.planner_capacity, self.rx_capacity
+                ));
+            }
+            "N" => {
+                self.out.push_back("$N0=".to_string());
+                self.out.push_back("$N1=".to_string());
+            }
+            "X" => {
+                if self.alarm {
+                    self.alarm = false;
+                    self.out.push_back("[MSG:Caution: Unlocked]".to_string());
+                }
+            }
+            "H" => {
+                if self.setting(SET_HOMING) == 0.0 {
+                    return Err(ERR_SETTING_DISABLED);
+                }
+                if !self.at_rest() || self.check_mode {
+                    return Err(ERR_IDLE);
+                }
+                self.alarm = false;
+                let target = [0.0, 0.0, self.mpos[2]];
+                let mut mv = Move::new(
+                    Path::Line {
+                        start: self.mpos,
+                        end: target,
+                    },
+                    self.setting(SET_HOMING_SEEK),
+                    false,
+                    0.0,
+                );
+                mv.homing = true;
+                self.planner.push_back(Block::Move(mv));
+                self.planned = target;
+                return Ok(Ack::Sync(SyncAction::default()));
+            }
+            "C" => {
+                if self.check_mode {
+                    self.out.push_back("[MSG:Disabled]".to_string());
+                    self.reset_after_ok = true;
+                } else {
+                    if self.alarm || !self.at_rest() {
+                        return Err(ERR_IDLE);
+                    }
+                    self.check_mode = true;
+                    self.out.push_back("[MSG:Enabled]".to_string());
+                }
+            }
+            "RST=$" | "RST=#" | "RST=*" => {
+                if !self.at_rest() {
+                    return Err(ERR_IDLE);
+                }
+                if upper != "RST=#" {
+                    self.settings = DEFAULT_SETTINGS.iter().copied().collect();
+                }
+                if upper != "RST=$" {
+                    self.coord_sys = [[0.0; 3]; 6];
+                    self.g28 = [0.0; 3];
+                    self.g30 = [0.0; 3];
+                }
+            }
+            _ => {
+                let Some((id, value)) = cmd.split_once('=') else {
+                    return Err(ERR_INVALID_STATEMENT);
+                };
+                let id: u16 = id.trim().parse().map_err(|_| ERR_INVALID_STATEMENT)?;
+                let value: f32 = value.trim().parse().map_err(|_| ERR_BAD_NUMBER_FORMAT)?;
+                if !self.settings.contains_key(&id) {
+                    return Err(ERR_INVALID_STATEMENT);
+                }
+                if !self.at_rest() {
+                    return Err(ERR_IDLE);
+                }
+                self.settings.insert(id, value);
+            }
+        }
+        Ok(Ack::Ok)
+    }
+
+    fn gcode_state_report(&self) -> String {
+        let m = &self.modal;
+        let motion = match m.motion {
+            MotionMode::Rapid => "G0",
+            MotionMode::Linear => "G1",
+            MotionMode::CwArc => "G2",
+            MotionMode::CcwArc => "G3",
+            MotionMode::Cancel => "G80",
+        };
+        let spindle = match m.spindle {
+            SpindleState::Off => "M5",
+            SpindleState::Cw => "M3",
+            SpindleState::Ccw => "M4",
+        };
+        let coolant = match (m.mist, m.flood) {
+            (false, false) => "M9",
+            (true, false) => "M7",
+            (false, true) => "M8",
+            (true, true) => "M7 M8",
+        };
+        format!(
+            "[GC:{motion} G{} G{} G{} G{} G94 {spindle} {coolant} T0 F{} S{}]",
+            54 + m.wcs,
+            17 + m.plane,
+            if m.inches { 20 } else { 21 },
+            if m.absolute { 90 } else { 91 },
+            m.feed,
+            m.spindle_speed
+        )
+    }
+
+    fn within_travel(&self, target: [f32; 3]) -> bool {
+        if self.setting(SET_SOFT_LIMITS) == 0.0 {
+            return true;
+        }
+        let travel = self.axis_setting(SET_TRAVEL);
+        target
+            .iter()
+            .zip(travel)
+            .all(|(&pos, max)| max <= 0.0 || (-EPS..=max + EPS).contains(&pos))
+    }
+
+    fn path_within_travel(&self, path: &Path) -> bool {
+        match path {
+            Path::Line { end, .. } => self.within_travel(*end),
+            Path::Arc { .. } => {
+                (0..=32).all(|i| self.within_travel(path.point_at(i as f32 / 32.0)))
+            }
+        }
+    }
+
+    fn execute_gcode(&mut self, line: &str, jog: bool) -> Result<Ack, u8> {
+        let words = parse_words(line)?;
+        let mut modal = self.modal;
+        let mut motion: Option<MotionMode> = None;
+        let mut non_modal: Option<i32> = None;
+        let mut machine_coords = false;
+        let mut program_flow: Option<u8> = None;
+        let mut axis_words: [Option<f32>; 3] = [None; 3];
+        let mut offset_words: [Option<f32>; 3] = [None; 3];
+        let (mut f, mut s, mut p, mut l, mut r) = (None, None, None, None, None);
+
+        for (letter, value) in words {
+            match letter {
+                'G' => {
+                    let code = (value * 10.0).round() as i32;
+                    if jog && !matches!(code, 200 | 210 | 530 | 900 | 910) {
+                        return Err(ERR_INVALID_JOG);
+                    }
+                    match code {
+                        0 => motion = Some(MotionMode::Rapid),
+                        10 => motion = Some(MotionMode::Linear),
+                        20 => motion = Some(MotionMode::CwArc),
+                        30 => motion = Some(MotionMode::CcwArc),
+                        800 => motion = Some(MotionMode::Cancel),
+                        40 | 100 | 280 | 281 | 300 | 301 | 920 | 921 => non_modal = Some(code),
+                        530 => machine_coords = true,
+                        170 => modal.plane = 0,
+                        180 => modal.plane = 1,
+                        190 => modal.plane = 2,
+                        200 => modal.inches = true,
+                        210 => modal.inches = false,
+                        900 => modal.absolute = true,
+                        910 => modal.absolute = false,
+                        540 | 550 | 560 | 570 | 580 | 590 => {
+                            modal.wcs = ((code - 540) / 10) as usize
+                        }
+                        // Accepted no-ops: G40, G49, G61, G91.1, G94
+                        400 | 490 | 610 | 911 | 940 => {}
+                        _ => return Err(ERR_UNSUPPORTED_COMMAND),
+                    }
+                }
+                'M' if !jog => {
+                    if value.fract() != 0.0 {
+                        return Err(ERR_UNSUPPORTED_COMMAND);
+                    }
+                    match value as i32 {
+                        0 | 1 => program_flow = Some(0),
+                        2 | 30 => program_flow = Some(2),
+                        3 => modal.spindle = SpindleState::Cw,
+                        4 => modal.spindle = SpindleState::Ccw,
+                        5 => modal.spindle = SpindleState::Off,
+                        7 => modal.mist = true,
+                        8 => modal.flood = true,
+                        9 => {
+                            modal.mist = false;
+                            modal.flood = false;
+                        }
+                        _ => return Err(ERR_UNSUPPORTED_COMMAND),
+                    }
+                }
+                'X' => axis_words[0] = Some(value),
+                'Y' => axis_words[1] = Some(value),
+                'Z' => axis_words[2] = Some(value),
+                'F' => f = Some(value),
+                'I' if !jog => offset_words[0] = Some(value),
+                'J' if !jog => offset_words[1] = Some(value),
+                'K' if !jog => offset_words[2] = Some(value),
+                'S' if !jog => s = Some(value),
+                'P' if !jog => p = Some(value),
+                'L' if !jog => l = Some(value),
+                'R' if !jog => r = Some(value),
+                'N' | 'T' if !jog => {}
+                _ if jog => return Err(ERR_INVALID_JOG),
+                _ => return Err(ERR_UNSUPPORTED_COMMAND),
+            }
+        }
+
+        let unit = if modal.inches { 25.4 } else { 1.0 };
+        let axis_words = axis_words.map(|w| w.map(|v| v * unit));
+        let offset_words = offset_words.map(|w| w.map(|v| v * unit));
+        let r = r.map(|v| v * unit);
+        let has_axis = axis_words.iter().any(Option::is_some);
+        let wco = add(self.coord_sys[modal.wcs], self.g92);
+        let mut target = self.planned;
+        for (i, word) in axis_words.iter().enumerate() {
+            if let Some(v) = *word {
+                target[i] = if machine_coords {
+                    v
+                } else if modal.absolute {
+                    v + wco[i]
+                } else {
+                    self.planned[i] + v
+                };
+            }
+        }
+
+        if jog {
+            let Some(feed) = f else {
+                return Err(ERR_UNDEFINED_FEED_RATE);
+            };
+            if !has_axis {
+                return Err(ERR_INVALID_JOG);
+            }
+            if !self.within_travel(target) {
+                return Err(ERR_TRAVEL_EXCEEDED);
+            }
+            let mut mv = Move::new(
+                Path::Line {
+                    start: self.planned,
+                    end: target,
+                },
+                feed * unit,
+                false,
+                0.0,
+            );
+            mv.jog = true;
+            self.planned = target;
+            if mv.length > EPS {
+                self.planner.push_back(Block::Move(mv));
+            }
+            return Ok(Ack::Ok);
+        }
+
+        if let Some(feed) = f {
+            modal.feed = feed * unit;
+        }
+        if let Some(speed) = s {
+            modal.spindle_speed = speed;
+        }
+        if let Some(mode) = motion {
+            modal.motion = mode;
+        }
+
+        let laser_mode = self.setting(SET_LASER_MODE) != 0.0;
+        let spindle = if modal.spindle == SpindleState::Off {
+            0.0
+        } else {
+            modal.spindle_speed
+        };
+        let mut blocks: Vec<Block> = Vec::new();
+        let mut end = self.planned;
+        let mut sync: Option<SyncAction> = None;
+        let rapid_to = |from: [f32; 3], to: [f32; 3]| {
+            let spindle = if laser_mode { 0.0 } else { spindle };
+            Move::new(
+                Path::Line {
+                    start: from,
+                    end: to,
+                },
+                0.0,
+                true,
+                spindle,
+            )
+        };
+
+        match non_modal {
+            Some(40) => {
+                let Some(seconds) = p else {
+                    return Err(ERR_VALUE_WORD_MISSING);
+                };
+                blocks.push(Block::Dwell(seconds.max(0.0)));
+                sync = Some(SyncAction::default());
+            }
+            Some(100) => {
+                let index = match p.unwrap_or(0.0) as usize {
+                    0 => modal.wcs,
+                    n @ 1..=6 => n - 1,
+                    _ => return Err(ERR_UNSUPPORTED_COORD_SYS),
+                };
+                match l.map(|v| v as i32) {
+                    Some(2) => {
+                        for (i, word) in axis_words.iter().enumerate() {
+                            if let Some(v) = *word {
+                                self.coord_sys[index][i] = v;
+                            }
+                        }
+                    }
+                    Some(20) => {
+                        for (i, word) in axis_words.iter().enumerate() {
+                            if let Some(v) = *word {
+                                self.coord_sys[index][i] = self.planned[i] - self.g92[i] - v;
+                            }
+                        }
+                    }
+                    _ => return Err(ERR_UNSUPPORTED_COMMAND),
+                }
+            }
+            Some(280) | Some(300) => {
+                let stored = if non_modal == Some(280) {
+                    self.g28
+                } else {
+                    self.g30
+                };
+                if has_axis {
+                    blocks.push(Block::Move(rapid_to(end, target)));
+                    end = target;
+                }
+                blocks.push(Block::Move(rapid_to(end, stored)));
+                end = stored;
+            }
+            Some(281) => self.g28 = self.planned,
+            Some(301) => self.g30 = self.planned,
+            Some(920) => {
+                for (i, word) in axis_words.iter().enumerate() {
+                    if let Some(v) = *word {
+                        self.g92[i] = self.planned[i] - self.coord_sys[modal.wcs][i] - v;
+                    }
+                }
+            }
+            Some(921) => self.g92 = [0.0; 3],
+            _ => {
+                if has_axis {
+                    match modal.motion {
+                        MotionMode::Rapid => {
+                            blocks.push(Block::Move(rapid_to(end, target)));
+                        }
+                        MotionMode::Linear => {
+                            if modal.feed <= 0.0 {
+                                return Err(ERR_UNDEFINED_FEED_RATE);
+                            }
+                            blocks.push(Block::Move(Move::new(
+                                Path::Line {
+                                    start: end,
+                                    end: target,
+                                },
+                                modal.feed,
+                                false,
+                                spindle,
+                            )));
+                        }
+                        MotionMode::CwArc | MotionMode::CcwArc => {
+                            if modal.feed <= 0.0 {
+                                return Err(ERR_UNDEFINED_FEED_RATE);
+                            }
+                            let path = arc_path(
+                                end,
+                                target,
+                                PLANE_AXES[modal.plane],
+                                offset_words,
+                                r,
+                                modal.motion == MotionMode::CwArc,
+                            )?;
+                            blocks.push(Block::Move(Move::new(path, modal.feed, false, spindle)));
+                        }
+                        MotionMode::Cancel => {}
+                    }
+                    end = target;
+                }
+            }
+        }
+
+        if let Some(flow) = program_flow {
+            let mut action = sync.take().unwrap_or_default();
+            if flow == 0 {
+                action.then_hold = true;
+            } else {
+                action.messages.push("[MSG:Pgm End]".to_string());
+                modal.motion = MotionMode::Linear;
+                modal.plane = 0;
+                modal.absolute = true;
+                modal.wcs = 0;
+                modal.spindle = SpindleState::Off;
+                modal.mist = false;
+                modal.flood = false;
+                self.feed_ov = 100;
+                self.rapid_ov = 100;
+                self.spindle_ov = 100;
+            }
+            sync = Some(action);
+        }
+
+        for block in &blocks {
+            if let Block::Move(mv) = block
+                && !self.path_within_travel(&mv.path)
+            {
+                self.raise_critical_alarm(ALARM_SOFT_LIMIT);
+                return Ok(Ack::Silent);
+            }
+        }
+
+        self.modal = modal;
+        self.planned = end;
+        if self.check_mode {
+            return Ok(Ack::Ok);
+        }
+        self.planner
+            .extend(blocks.into_iter().filter(|block| match block {
+                Block::Move(mv) => mv.length > EPS,
+                Block::Dwell(_) => true,
+            }));
+        Ok(sync.map_or(Ack::Ok, Ack::Sync))
+    }
+
+    /// Cruise speed for a block in mm/s, overrides and per-axis limits applied
+    fn nominal_speed(&self, mv: &Move, dir: [f32; 3]) -> f32 {
+        let axis_max = axis_limited(self.axis_setting(SET_MAX_RATE), dir) / 60.0;
+        if mv.rapid {
+            axis_max * self.rapid_ov as f32 / 100.0
+        } else if mv.jog || mv.homing {
+            (mv.feed / 60.0).min(axis_max)
+        } else {
+            (mv.feed / 60.0 * self.feed_ov as f32 / 100.0).min(axis_max)
+        }
+    }
+
+    /// Speed the executing block may still have when it ends (GRBL junction deviation)
+    fn exit_speed(&self) -> f32 {
+        let (Some(Block::Move(current)), Some(Block::Move(next))) =
+            (self.planner.front(), self.planner.get(1))
+        else {
+            return 0.0;
+        };
+        if current.jog != next.jog || current.homing != next.homing {
+            return 0.0;
+        }
+        let accel_limits = self.axis_setting(SET_ACCEL);
+        let exit_dir = current.direction_at(current.length);
+        let entry_dir = next.direction_at(0.0);
+        let cruise = self
+            .nominal_speed(current, exit_dir)
+            .min(self.nominal_speed(next, entry_dir));
+        let cos = dot(exit_dir, entry_dir);
+        if cos > 0.999_999 {
+            return cruise;
+        }
+        if cos < -0.999_999 {
+            return 0.0;
+        }
+        let accel = axis_limited(accel_limits, exit_dir).min(axis_limited(accel_limits, entry_dir));
+        let sin_half = (0.5 * (1.0 + cos)).sqrt().min(0.999_999);
+        let deviation = self.setting(SET_JUNCTION_DEVIATION).max(0.0);
+        (accel * deviation * sin_half / (1.0 - sin_half))
+            .sqrt()
+            .min(cruise)
+    }
+
+    fn step(&mut self, h: f32) {
+        let stopping = self.hold || self.jog_cancel;
+        let exit = if stopping { 0.0 } else { self.exit_speed() };
+        let accel_limits = self.axis_setting(SET_ACCEL);
+
+        let (nominal, accel) = match self.planner.front() {
+            None => {
+                self.speed = 0.0;
+                self.jog_cancel = false;
+                return;
+            }
+            Some(Block::Dwell(_)) => (0.0, 0.0),
+            Some(Block::Move(mv)) => {
+                let dir = mv.direction_at(mv.done);
+                (self.nominal_speed(mv, dir), axis_limited(accel_limits, dir))
+            }
+        };
+
+        match self.planner.front_mut() {
+            Some(Block::Dwell(left)) if !self.hold => {
+                *left -= h;
+                if *left <= 0.0 {
+                    self.planner.pop_front();
+                }
+            }
+            Some(Block::Move(mv)) => {
+                let remaining = mv.length - mv.done;
+                let v = self.speed;
+                let target = if stopping {
+                    0.0
+                } else if v * v - exit * exit >= 2.0 * accel * remaining {
+                    exit
+                } else {
+                    nominal
+                };
+                let next_v = if v < target {
+                    (v + accel * h).min(target)
+                } else {
+                    (v - accel * h).max(target)
+                };
+                let ds = 0.5 * (v + next_v) * h;
+                self.speed = next_v;
+                if ds >= remaining - EPS && !(stopping && next_v == 0.0 && ds < remaining) {
+                    self.mpos = mv.path.end();
+                    self.planner.pop_front();
+                } else {
+                    mv.done += ds;
+                    self.mpos = mv.position();
+                }
+            }
+            Some(Block::Dwell(_)) | None => {}
+        }
+
+        if self.jog_cancel && self.speed == 0.0 {
+            self.planner
+                .retain(|block| !matches!(block, Block::Move(mv) if mv.jog));
+            self.jog_cancel = false;
+            self.planned = self.mpos;
+        }
+    }
+}
+
+/// Split a G-code line into (letter, value) words; comments and spaces are dropped
+fn parse_words(line: &str) -> Result<Vec<(char, f32)>, u8> {
+    let mut cleaned = String::new();
+    let mut in_comment = false;
+    for c in line.chars() {
+        match c {
+            '(' => in_comment = true,
+            ')' => in_comment = false,
+            ';' if !in_comment => break,
+            _ if in_comment || c.is_whitespace() => {}
+            _ => cleaned.push(c.to_ascii_uppercase()),
+        }
+    }
+
+    let bytes = cleaned.as_bytes();
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let letter = bytes[i] as char;
+        if !letter.is_ascii_alphabetic() {
+            return Err(ERR_EXPECTED_COMMAND_LETTER);
+        }
+        i += 1;
+        let start = i;
+        while i < bytes.len() && matches!(bytes[i], b'0'..=b'9' | b'.' | b'-' | b'+') {
+            i += 1;
+        }
+        let value: f32 = cleaned[start..i]
+            .parse()
+            .map_err(|_| ERR_BAD_NUMBER_FORMAT)?;
+        words.push((letter, value));
+    }
+    Ok(words)
+}
+
+/// Arc from `start` to `end` in the given plane, from I/J/K offsets or an R radius
+fn arc_path(
+    start: [f32; 3],
+    end: [f32; 3],
+    axes: [usize; 3],
+    offsets: [Option<f32>; 3],
+    radius_word: Option<f32>,
+    clockwise: bool,
+) -> Result<Path, u8> {
+    let (a0, a1) = (axes[0], axes[1]);
+    let dx = end[a0] - start[a0];
+    let dy = end[a1] - start[a1];
+
+    let (ci, cj) = if let Some(r) = radius_word {
+        // Same construction as GRBL's gc_execute_line for radius-format arcs
+        let d2 = dx * dx + dy * dy;
+        if d2 <= EPS * EPS {
+            return Err(ERR_INVALID_TARGET);
+        }
+        let mut h_x2_div_d = 4.0 * r * r - d2;
+        if h_x2_div_d < 0.0 {
+            return Err(ERR_INVALID_TARGET);
+        }
+        h_x2_div_d = -h_x2_div_d.sqrt() / d2.sqrt();
+        if !clockwise {
+            h_x2_div_d = -h_x2_div_d;
+        }
+        // Negative R selects the long way round
+        if r < 0.0 {
+            h_x2_div_d = -h_x2_div_d;
+        }
+        (0.5 * (dx - dy * h_x2_div_d), 0.5 * (dy + dx * h_x2_div_d))
+    } else {
+        let i = offsets[a0].unwrap_or(0.0);
+        let j = offsets[a1].unwrap_or(0.0);
+        if offsets[a0].is_none() && offsets[a1].is_none() {
+            return Err(ERR_VALUE_WORD_MISSING);
+        }
+        (i, j)
+    };
+
+    let center = [start[a0] + ci, start[a1] + cj];
+    let radius = ci.hypot(cj);
+    let end_radius = (end[a0] - center[0]).hypot(end[a1] - center[1]);
+    if radius <= EPS || (end_radius - radius).abs() > 0.005_f32.max(radius * 0.001) {
+        return Err(ERR_INVALID_TARGET);
+    }
+
+    let start_angle = (start[a1] - center[1]).atan2(start[a0] - center[0]);
+    let end_angle = (end[a1] - center[1]).atan2(end[a0] - center[0]);
+    let mut sweep = end_angle - start_angle;
+    if clockwise {
+        if sweep >= -EPS {
+            sweep -= std::f32::consts::TAU;
+        }
+    } else if sweep <= EPS {
+        sweep += std::f32::consts::TAU;
+    }
+
+    Ok(Path::Arc {
+        start,
+        end,
+        axes,
+        center,
+        radius,
+        start_angle,
+        sweep,
+    })
+}
+
+/// Tightest per-axis limit along `dir` (rate or acceleration)
+fn axis_limited(limits: [f32; 3], dir: [f32; 3]) -> f32 {
+    limits
+        .iter()
+        .zip(dir)
+        .filter(|(_, d)| d.abs() > 1e-6)
+        .map(|(&limit, d)| limit.max(0.1) / d.abs())
+        .fold(f32::MAX, f32::min)
+}
+
+fn fmt_point(p: [f32; 3]) -> String {
+    format!("{:.3},{:.3},{:.3}", p[0], p[1], p[2])
+}
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    dot(sub(b, a), sub(b, a)).sqrt()
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let len = dot(v, v).sqrt();
+    if len <= 1e-9 {
+        [0.0; 3]
+    } else {
+        [v[0] / len, v[1] / len, v[2] / len]
+    }
+}
+
+fn lerp(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grbl::parser;
+    use crate::grbl::streamer::CharCountStreamer;
+    use crate::grbl::types::{GrblResponse, MacStatus};
+
+    fn config() -> VirtualGrblConfig {
+        VirtualGrblConfig {
+            rx_buffer_size: 127,
+            planner_blocks: 15,
+            steps_per_mm: [80.0; 3],
+            max_rate: [6000.0, 6000.0, 600.0],
+            accel: [1000.0, 1000.0, 100.0],
+            travel: [300.0, 200.0, 0.0],
+            soft_limits: true,
+            homing: false,
+            legacy_0_9: false,
+        }
+    }
+
+    fn booted() -> VirtualGrbl {
+        let mut grbl = VirtualGrbl::new(&config());
+        assert_eq!(grbl.drain_output(), vec![STARTUP_BANNER.to_string()]);
+        grbl
+    }
+
+    fn send(grbl: &mut VirtualGrbl, line: &str) -> Vec<String> {
+        grbl.write(format!("{line}\n").as_bytes());
+        grbl.drain_output()
+    }
+
+    fn status(grbl: &mut VirtualGrbl) -> crate::grbl::types::GrblState {
+        grbl.write(b"?");
+        let report = grbl.drain_output().pop().expect("status report");
+        match parser::parse_response(&report) {
+            GrblResponse::Status(state) => state,
+            other => panic!("not a status report: {report} -> {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reports_status_and_build_info() {
+        let mut grbl = booted();
+        assert_eq!(
+            grbl.status_report(),
+            "<Idle|MPos:0.000,0.000,0.000|FS:0,0|WCO:0.000,0.000,0.000|Ov:100,100,100>"
+        );
+        assert_eq!(
+            send(&mut grbl, "$I"),
+            vec![BUILD_INFO, "[OPT:VNM,15,127]", "ok"]
+        );
+        assert_eq!(send(&mut grbl, "$10=3"), vec!["ok"]);
+        assert!(grbl.status_report().contains("|Bf:15,127|"));
+        assert_eq!(send(&mut grbl, "$999=1"), vec!["error:3"]);
+        assert_eq!(send(&mut grbl, "G5"), vec!["error:20"]);
+    }
+
+    #[test]
+    fn straight_move_follows_trapezoid_timing() {
+        let mut grbl = booted();
+        assert_eq!(send(&mut grbl, "G1 X100 F6000"), vec!["ok"]);
+        grbl.advance(0.5);
+        let mid = status(&mut grbl);
+        assert_eq!(mid.status, MacStatus::Run);
+        assert!((mid.feed_rate - 6000.0).abs() < 1.0);
+        // 100 mm at 100 mm/s with 1000 mm/s² ramps: 1.0 s cruise + 0.1 s ramp losses
+        let elapsed = 0.5 + grbl.run_until_idle(10.0);
+        assert!((elapsed - 1.1).abs() < 0.02, "took {elapsed}");
+        assert_eq!(grbl.machine_position(), [100.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn full_circle_arc_runs_clockwise() {
+        let mut grbl = booted();
+        send(&mut grbl, "G0 X20 Y10");
+        grbl.run_until_idle(5.0);
+        assert_eq!(send(&mut grbl, "G2 X20 Y10 I-10 J0 F600"), vec!["ok"]);
+        // A quarter turn clockwise from (20,10) around (10,10) ends at (10,0)
+        grbl.advance(std::f32::consts::FRAC_PI_2);
+        let quarter = grbl.machine_position();
+        assert!(
+            (quarter[0] - 10.0).abs() < 0.3 && quarter[1].abs() < 0.3,
+            "{quarter:?}"
+        );
+        let elapsed = std::f32::consts::FRAC_PI_2 + grbl.run_until_idle(20.0);
+        assert!(
+            (elapsed - std::f32::consts::TAU).abs() < 0.1,
+            "took {elapsed}"
+        );
+        let end = grbl.machine_position();
+        assert!((end[0] - 20.0).abs() < 1e-3 && (end[1] - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn streams_a_job_with_the_char_counting_streamer() {
+        let lines: Vec<String> = (0..120)
+            .map(|i| {
+                format!(
+                    "G1 X{:.2} Y{:.2} F3000 S{}",
+                    (i % 40) as f32 * 2.5,
+                    i as f32 * 0.5,
+                    i % 1000
+                )
+            })
+            .collect();
+        let mut grbl = booted();
+        let mut streamer = CharCountStreamer::new(127);
+        let mut next = 0;
+        let mut acked = 0;
+        let mut max_planner = 0;
+        for _ in 0..200_000 {
+            while next < lines.len() && streamer.can_send(&lines[next]) {
+                grbl.write(format!("{}\n", lines[next]).as_bytes());
+                streamer.push(next, &lines[next]);
+                next += 1;
+            }
+            grbl.advance(0.005);
+            max_planner = max_planner.max(grbl.planner_len());
+            for reply in grbl.drain_output() {
+                assert_eq!(reply, "ok");
+                assert_eq!(streamer.acknowledge().flatten(), Some(acked));
+                acked += 1;
+            }
+            if acked == lines.len() && grbl.is_idle() {
+                break;
+            }
+        }
+        assert_eq!(acked, lines.len());
+        assert!(!grbl.overflowed());
+        assert!(max_planner <= 15 && max_planner > 1);
+        assert_eq!(grbl.machine_position(), [97.5, 59.5, 0.0]);
+    }
+
+    #[test]
+    fn soft_limits_alarm_and_unlock() {
+        let mut grbl = booted();
+        assert_eq!(send(&mut grbl, "$J=G91 X-5 F1000"), vec!["error:15"]);
+        assert_eq!(
+            send(&mut grbl, "G0 X500"),
+            vec!["ALARM:2", "[MSG:Reset to continue]"]
+        );
+        // Locked until reset; the line stays unanswered in RX
+        assert!(send(&mut grbl, "G0 X1").is_empty());
+        grbl.write(&[protocol::CMD_RESET]);
+        assert_eq!(grbl.drain_output(), vec![STARTUP_BANNER, UNLOCK_HINT]);
+        assert_eq!(send(&mut grbl, "G0 X1"), vec!["error:9"]);
+        assert_eq!(send(&mut grbl, "$X"), vec!["[MSG:Caution: Unlocked]", "ok"]);
+        assert_eq!(send(&mut grbl, "G0 X1"), vec!["ok"]);
+    }
+
+    #[test]
+    fn legacy_firmware_keeps_its_banner_after_reset() {
+        let mut grbl = VirtualGrbl::new(&VirtualGrblConfig {
+            legacy_0_9: true,
+            ..config()
+        });
+        assert_eq!(grbl.drain_output(), vec![LEGACY_BANNER]);
+        grbl.write(&[protocol::CMD_RESET]);
+        assert_eq!(grbl.drain_output(), vec![LEGACY_BANNER]);
+    }
+
+    #[test]
+    fn check_mode_parses_without_moving() {
+        let mut grbl = booted();
+        assert_eq!(send(&mut grbl, "$C"), vec!["[MSG:Enabled]", "ok"]);
+        assert_eq!(status(&mut grbl).status, MacStatus::Check);
+        assert_eq!(send(&mut grbl, "G1 X10"), vec!["error:22"]);
+        assert_eq!(send(&mut grbl, "G1 X10 F500"), vec!["ok"]);
+        grbl.advance(1.0);
+        assert_eq!(grbl.machine_position(), [0.0; 3]);
+        assert_eq!(
+            send(&mut grbl, "$C"),
+            vec!["[MSG:Disabled]", "ok", STARTUP_BANNER]
+        );
+        assert_eq!(status(&mut grbl).status, MacStatus::Idle);
+    }
+
+    #[test]
+    fn feed_hold_overrides_and_jog_cancel() {
+        let mut grbl = booted();
+        send(&mut grbl, "G1 X200 F3000");
+        grbl.advance(0.5);
+        grbl.write(&[protocol::CMD_FEED_HOLD]);
+        grbl.advance(0.5);
+        let held = status(&mut grbl);
+        assert_eq!(held.status, MacStatus::Hold);
+        assert_eq!(held.feed_rate, 0.0);
+        let x = grbl.machine_position()[0];
+        grbl.advance(1.0);
+        assert_eq!(grbl.machine_position()[0], x);
+
+        grbl.write(&[protocol::FEED_OV_PLUS_10, protocol::CMD_CYCLE_START]);
+        grbl.advance(1.0);
+        let resumed = status(&mut grbl);
+        assert_eq!(resumed.status, MacStatus::Run);
+        assert_eq!(resumed.override_feed, 110);
+        assert!((resumed.feed_rate - 3300.0).abs() < 1.0);
+        grbl.run_until_idle(10.0);
+
+        assert_eq!(send(&mut grbl, "$J=G91 Y100 F1200"), vec!["ok"]);
+        grbl.advance(0.5);
+        assert_eq!(status(&mut grbl).status, MacStatus::Jog);
+        assert_eq!(send(&mut grbl, "G1 X0"), vec!["error:9"]);
+        grbl.write(&[protocol::CMD_JOG_CANCEL]);
+        grbl.run_until_idle(2.0);
+        assert_eq!(status(&mut grbl).status, MacStatus::Idle);
+        assert!(grbl.machine_position()[1] < 20.0);
+    }
+
+    #[test]
+    fn work_offsets_and_parameter_report() {
+        let mut grbl = booted();
+        send(&mut grbl, "G0 X50 Y40");
+        grbl.run_until_idle(5.0);
+        assert_eq!(send(&mut grbl, "G10 L20 P1 X0 Y0"), vec!["ok"]);
+        let state = status(&mut grbl);
+        assert!((state.wco.x - 50.0).abs() < 1e-3 && (state.wco.y - 40.0).abs() < 1e-3);
+        assert!(state.wpos.x.abs() < 1e-3);
+
+        let report = send(&mut grbl, "$#");
+        assert_eq!(report[0], "[G54:50.000,40.000,0.000]");
+        assert_eq!(report.last().map(String::as_str), Some("ok"));
+
+        send(&mut grbl, "G0 X10 Y10");
+        grbl.run_until_idle(5.0);
+        assert_eq!(grbl.machine_position(), [60.0, 50.0, 0.0]);
+    }
+}
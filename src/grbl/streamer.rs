@@ -0,0 +1,251 @@
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+
+/// GRBL 1.1 serial RX buffer on an ATmega328p
+pub const DEFAULT_RX_BUFFER_SIZE: usize = 127;
+
+/// Character-counting stream bookkeeping: keeps GRBL's RX buffer full without overflowing it.
+/// Every line sent is remembered until its `ok`/`error:N` arrives, in order.
+#[derive(Debug, Clone)]
+pub struct CharCountStreamer {
+    rx_buffer_size: usize,
+    /// (program line index, bytes incl. newline); `None` for out-of-band commands
+    in_flight: VecDeque<(Option<usize>, usize)>,
+    bytes_in_flight: usize,
+    /// Out-of-band commands waiting for RX space; they go before further program lines
+    waiting: VecDeque<String>,
+}
+
+impl Default for CharCountStreamer {
+    fn default() -> Self {
+        Self::new(DEFAULT_RX_BUFFER_SIZE)
+    }
+}
+
+impl CharCountStreamer {
+    pub fn new(rx_buffer_size: usize) -> Self {
+        Self {
+            rx_buffer_size: rx_buffer_size.max(1),
+            in_flight: VecDeque::new(),
+            bytes_in_flight: 0,
+            waiting: VecDeque::new(),
+        }
+    }
+
+    pub fn rx_buffer_size(&self) -> usize {
+        self.rx_buffer_size
+    }
+
+    /// Whether `line` fits in the free RX space. A line longer than the whole buffer
+    /// is still allowed once everything before it has been acknowledged.
+    pub fn can_send(&self, line: &str) -> bool {
+        let len = line.len() + 1;
+        self.in_flight.is_empty() || self.bytes_in_flight + len <= self.rx_buffer_size
+    }
+
+    /// Record a program line that was just written
+    pub fn push(&mut self, line_index: usize, line: &str) {
+        self.push_entry(Some(line_index), line);
+    }
+
+    /// Record a command sent outside the program (air assist, overrides…) so its `ok` is not
+    /// credited to a program line
+    pub fn push_untracked(&mut self, line: &str) {
+        self.push_entry(None, line);
+    }
+
+    /// Hold an out-of-band command until it fits; see [`Self::next_waiting`]
+    pub fn queue_untracked(&mut self, line: &str) {
+        self.waiting.push_back(line.to_string());
+    }
+
+    /// Oldest waiting command, if it fits now; it is recorded as in flight
+    pub fn next_waiting(&mut self) -> Option<String> {
+        if !self.can_send(self.waiting.front()?) {
+            return None;
+        }
+        let line = self.waiting.pop_front()?;
+        self.push_untracked(&line);
+        Some(line)
+    }
+
+    pub fn has_waiting(&self) -> bool {
+        !self.waiting.is_empty()
+    }
+
+    fn push_entry(&mut self, line_index: Option<usize>, line: &str) {
+        let len = line.len() + 1;
+        self.bytes_in_flight += len;
+        self.in_flight.push_back((line_index, len));
+    }
+
+    /// Consume the oldest in-flight entry for an `ok` or `error:N`.
+    /// Returns `Some(None)` for untracked commands and `None` when nothing was in flight.
+    pub fn acknowledge(&mut self) -> Option<Option<usize>> {
+        let (line_index, len) = self.in_flight.pop_front()?;
+        self.bytes_in_flight -= len;
+        Some(line_index)
+    }
+
+    pub fn bytes_in_flight(&self) -> usize {
+        self.bytes_in_flight
+    }
+
+    pub fn lines_in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.in_flight.is_empty()
+    }
+
+    /// Forget everything in flight; waiting commands are dropped with the stream
+    pub fn reset(&mut self) {
+        self.in_flight.clear();
+        self.bytes_in_flight = 0;
+        self.waiting.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Simulated GRBL: a bounded RX buffer drained one line per `tick`,
+    /// answering `ok` or `error:20` (unsupported command) for `G999`.
+    struct SimulatedGrbl {
+        rx_capacity: usize,
+        rx: VecDeque<String>,
+        rx_bytes: usize,
+        overflowed: bool,
+        executed: Vec<String>,
+    }
+
+    enum Reply {
+        Ok,
+        Error(i32),
+    }
+
+    impl SimulatedGrbl {
+        fn new(rx_capacity: usize) -> Self {
+            Self {
+                rx_capacity,
+                rx: VecDeque::new(),
+                rx_bytes: 0,
+                overflowed: false,
+                executed: Vec::new(),
+            }
+        }
+
+        fn write_line(&mut self, line: &str) {
+            self.rx_bytes += line.len() + 1;
+            if self.rx_bytes > self.rx_capacity {
+                self.overflowed = true;
+            }
+            self.rx.push_back(line.to_string());
+        }
+
+        fn tick(&mut self) -> Option<Reply> {
+            let line = self.rx.pop_front()?;
+            self.rx_bytes -= line.len() + 1;
+            let reply = if line.contains("G999") {
+                Reply::Error(20)
+            } else {
+                Reply::Ok
+            };
+            self.executed.push(line);
+            Some(reply)
+        }
+    }
+
+    fn program(n: usize) -> Vec<String> {
+        (0..n)
+            .map(|i| format!("G1 X{}.{:03} Y{}.{:03} S{}", i, i * 7 % 1000, i / 3, i % 997, i % 1000))
+            .collect()
+    }
+
+    /// Drive the streamer against the simulator; returns (acked lines, failing line)
+    fn stream(lines: &[String], rx: usize) -> (Vec<usize>, Option<(usize, i32)>, SimulatedGrbl) {
+        let mut grbl = SimulatedGrbl::new(rx);
+        let mut streamer = CharCountStreamer::new(rx);
+        let mut next = 0;
+        let mut acked = Vec::new();
+        let mut max_in_flight = 0;
+
+        loop {
+            while next < lines.len() && streamer.can_send(&lines[next]) {
+                grbl.write_line(&lines[next]);
+                streamer.push(next, &lines[next]);
+                next += 1;
+            }
+            max_in_flight = max_in_flight.max(streamer.lines_in_flight());
+            match grbl.tick() {
+                Some(Reply::Ok) => acked.push(streamer.acknowledge().flatten().expect("tracked")),
+                Some(Reply::Error(code)) => {
+                    let line = streamer.acknowledge().flatten().expect("tracked");
+                    return (acked, Some((line, code)), grbl);
+                }
+                None => break,
+            }
+        }
+        assert!(max_in_flight > 1 || lines.len() <= 1, "streamer never pipelined");
+        assert!(streamer.is_idle());
+        (acked, None, grbl)
+    }
+
+    #[test]
+    fn never_overflows_rx_buffer_and_acks_every_line_in_order() {
+        let lines = program(200);
+        let (acked, failure, grbl) = stream(&lines, DEFAULT_RX_BUFFER_SIZE);
+        assert!(failure.is_none());
+        assert!(!grbl.overflowed);
+        assert_eq!(acked, (0..200).collect::<Vec<_>>());
+        assert_eq!(grbl.executed, lines);
+    }
+
+    #[test]
+    fn error_is_attributed_to_the_exact_line() {
+        let mut lines = program(50);
+        lines[31] = "G999 X1".to_string();
+        let (acked, failure, _) = stream(&lines, 64);
+        assert_eq!(failure, Some((31, 20)));
+        assert_eq!(acked, (0..31).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn oversized_line_waits_for_an_empty_buffer() {
+        let mut streamer = CharCountStreamer::new(16);
+        assert!(streamer.can_send("G1 X1"));
+        streamer.push(0, "G1 X1");
+        let long = "G1 X123.456 Y789.012 F3000";
+        assert!(!streamer.can_send(long));
+        assert_eq!(streamer.acknowledge(), Some(Some(0)));
+        assert!(streamer.can_send(long));
+    }
+
+    #[test]
+    fn untracked_commands_do_not_shift_line_attribution() {
+        let mut streamer = CharCountStreamer::default();
+        streamer.push_untracked("M8");
+        streamer.push(0, "G1 X1");
+        assert_eq!(streamer.bytes_in_flight(), 3 + 6);
+        assert_eq!(streamer.acknowledge(), Some(None));
+        assert_eq!(streamer.acknowledge(), Some(Some(0)));
+        assert_eq!(streamer.acknowledge(), None);
+    }
+
+    #[test]
+    fn queued_commands_wait_for_rx_space() {
+        let mut streamer = CharCountStreamer::new(16);
+        streamer.push(0, "G1 X10 Y10");
+        streamer.queue_untracked("M8 ; air");
+        assert_eq!(streamer.next_waiting(), None);
+        assert!(streamer.has_waiting());
+
+        assert_eq!(streamer.acknowledge(), Some(Some(0)));
+        assert_eq!(streamer.next_waiting().as_deref(), Some("M8 ; air"));
+        assert!(!streamer.has_waiting());
+        assert_eq!(streamer.acknowledge(), Some(None));
+    }
+}
@@ -90,6 +90,56 @@ pub enum JogDirection {
     Home,
 }
 
+/// Input pins reported in the `Pn:` status field, one bit per letter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputPins(u32);
+
+impl InputPins {
+    pub fn parse(field: &str) -> Self {
+        let mut pins = Self::default();
+        for c in field.chars() {
+            pins.set(c);
+        }
+        pins
+    }
+
+    fn bit(letter: char) -> Option<u32> {
+        let c = letter.to_ascii_uppercase();
+        c.is_ascii_uppercase().then(|| 1 << (c as u32 - 'A' as u32))
+    }
+
+    pub fn set(&mut self, letter: char) {
+        if let Some(bit) = Self::bit(letter) {
+            self.0 |= bit;
+        }
+    }
+
+    pub fn is_active(&self, letter: char) -> bool {
+        Self::bit(letter).is_some_and(|bit| self.0 & bit != 0)
+    }
+
+    pub fn door(&self) -> bool {
+        self.is_active('D')
+    }
+
+    pub fn probe(&self) -> bool {
+        self.is_active('P')
+    }
+
+    pub fn any_limit(&self) -> bool {
+        "XYZABC".chars().any(|c| self.is_active(c))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Active pins as the letters GRBL reports, e.g. "DPX"
+    pub fn active_letters(&self) -> String {
+        ('A'..='Z').filter(|&c| self.is_active(c)).collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GrblState {
     pub status: MacStatus,
@@ -103,6 +153,9 @@ pub struct GrblState {
     pub override_spindle: i32,
     pub buffer_plan: i32,
     pub buffer_rx: i32,
+    pub pins: InputPins,
+    /// Progress of a file running from the controller's storage (`SD:pct,name`)
+    pub sd_percent: Option<f32>,
 }
 
 impl Default for GrblState {
@@ -119,6 +172,8 @@ impl Default for GrblState {
             override_spindle: 100,
             buffer_plan: 0,
             buffer_rx: 0,
+            pins: InputPins::default(),
+            sd_percent: None,
         }
     }
 }
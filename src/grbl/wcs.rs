@@ -0,0 +1,191 @@
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+
+/// GRBL work coordinate systems G54-G59
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CoordSystem {
+    G54,
+    G55,
+    G56,
+    G57,
+    G58,
+    G59,
+}
+
+impl CoordSystem {
+    pub const ALL: [CoordSystem; 6] = [
+        CoordSystem::G54,
+        CoordSystem::G55,
+        CoordSystem::G56,
+        CoordSystem::G57,
+        CoordSystem::G58,
+        CoordSystem::G59,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// `G54` ... `G59`
+    pub fn gcode(self) -> &'static str {
+        match self {
+            Self::G54 => "G54",
+            Self::G55 => "G55",
+            Self::G56 => "G56",
+            Self::G57 => "G57",
+            Self::G58 => "G58",
+            Self::G59 => "G59",
+        }
+    }
+
+    /// P number used by G10 L2/L20
+    pub fn p_number(self) -> usize {
+        self.index() + 1
+    }
+
+    pub fn parse(word: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.gcode().eq_ignore_ascii_case(word))
+    }
+
+    /// `G10 L2 P<n>`: store an absolute machine offset
+    pub fn set_offset_command(self, offset: [f32; 3]) -> String {
+        format!(
+            "G10 L2 P{} X{:.3} Y{:.3} Z{:.3}",
+            self.p_number(),
+            offset[0],
+            offset[1],
+            offset[2]
+        )
+    }
+
+    /// `G10 L20 P<n>`: make the current position the XY origin
+    pub fn zero_here_command(self) -> String {
+        format!("G10 L20 P{} X0 Y0", self.p_number())
+    }
+}
+
+/// Offsets from the `$#` report plus the active system from `$G`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WcsTable {
+    pub offsets: [Option<[f32; 3]>; 6],
+    pub g92: Option<[f32; 3]>,
+    pub tlo: Option<f32>,
+    pub active: Option<CoordSystem>,
+}
+
+impl WcsTable {
+    /// Take in a `[G54:...]`, `[G92:...]`, `[TLO:...]` or `[GC:...]` line; false if it is none of those
+    pub fn apply_line(&mut self, line: &str) -> bool {
+        let Some(inner) = line.trim().strip_prefix('[').and_then(|l| l.strip_suffix(']')) else {
+            return false;
+        };
+        let Some((key, value)) = inner.split_once(':') else {
+            return false;
+        };
+        if key == "GC" {
+            if let Some(system) = value.split_whitespace().find_map(CoordSystem::parse) {
+                self.active = Some(system);
+            }
+            return true;
+        }
+        if key == "TLO" {
+            self.tlo = value.trim().parse().ok();
+            return true;
+        }
+        let Some(point) = parse_point(value) else {
+            return false;
+        };
+        if key == "G92" {
+            self.g92 = Some(point);
+        } else if let Some(system) = CoordSystem::parse(key) {
+            self.offsets[system.index()] = Some(point);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    pub fn offset(&self, system: CoordSystem) -> Option<[f32; 3]> {
+        self.offsets[system.index()]
+    }
+
+    /// Machine position of work 0,0 in `system`, G92 included
+    pub fn origin_xy(&self, system: CoordSystem) -> Option<(f32, f32)> {
+        let offset = self.offset(system)?;
+        let g92 = self.g92.unwrap_or_default();
+        Some((offset[0] + g92[0], offset[1] + g92[1]))
+    }
+
+    pub fn has_offsets(&self) -> bool {
+        self.offsets.iter().any(Option::is_some)
+    }
+}
+
+/// A saved origin, e.g. for a jig, written back with G10 L2
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedOffset {
+    pub name: String,
+    pub system: CoordSystem,
+    pub offset: [f32; 3],
+}
+
+fn parse_point(value: &str) -> Option<[f32; 3]> {
+    // "[PRB:x,y,z:1]" carries a trailing flag; only XYZ are kept
+    let coords = value.split(':').next()?;
+    let mut nums = coords.split(',').map(|n| n.trim().parse::<f32>());
+    let point = [nums.next()?.ok()?, nums.next()?.ok()?, nums.next()?.ok()?];
+    Some(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grbl::sim::{VirtualGrbl, VirtualGrblConfig};
+
+    #[test]
+    fn parses_parameter_report() {
+        let mut table = WcsTable::default();
+        assert!(table.apply_line("[G55:100.000,50.000,-1.500]"));
+        assert!(table.apply_line("[G92:2.000,0.000,0.000]"));
+        assert!(table.apply_line("[TLO:0.250]"));
+        assert!(table.apply_line("[GC:G0 G55 G17 G21 G90 G94 M5 M9 T0 F0 S0]"));
+        assert!(!table.apply_line("[MSG:Caution: Unlocked]"));
+        assert!(!table.apply_line("ok"));
+
+        assert_eq!(table.offset(CoordSystem::G55), Some([100.0, 50.0, -1.5]));
+        assert_eq!(table.origin_xy(CoordSystem::G55), Some((102.0, 50.0)));
+        assert_eq!(table.origin_xy(CoordSystem::G56), None);
+        assert_eq!(table.tlo, Some(0.25));
+        assert_eq!(table.active, Some(CoordSystem::G55));
+    }
+
+    #[test]
+    fn commands_round_trip_through_virtual_grbl() {
+        let mut grbl = VirtualGrbl::new(&VirtualGrblConfig::default());
+        grbl.drain_output();
+
+        for line in [
+            CoordSystem::G56.set_offset_command([30.0, 20.0, 0.0]),
+            "G0 X40 Y25".to_string(),
+        ] {
+            grbl.write(format!("{line}\n").as_bytes());
+            grbl.run_until_idle(5.0);
+        }
+        grbl.write(CoordSystem::G57.zero_here_command().as_bytes());
+        grbl.write(b"\n$#\n$G\n");
+        grbl.run_until_idle(1.0);
+
+        let mut table = WcsTable::default();
+        for line in grbl.drain_output() {
+            table.apply_line(&line);
+        }
+        assert_eq!(table.offset(CoordSystem::G56), Some([30.0, 20.0, 0.0]));
+        assert_eq!(table.origin_xy(CoordSystem::G57), Some((40.0, 25.0)));
+        assert_eq!(table.active, Some(CoordSystem::G54));
+    }
+}
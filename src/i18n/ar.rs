@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "التخطيط");
     m.insert("Language", "اللغة");
     m.insert("Controller", "المتحكم");
+    m.insert("Auto-detect on connect", "الكشف التلقائي عند الاتصال");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "فحص المنفذ التسلسلي بحثًا عن GRBL أو grblHAL أو FluidNC أو Marlin أو Smoothieware قبل الاتصال");
+    m.insert("Firmware", "البرنامج الثابت");
     m.insert("Modern (recommended)", "حديث (موصى به)");
     m.insert("Pro (new)", "احترافي (جديد)");
     m.insert("Industrial (advanced)", "صناعي (متقدم)");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "فتح مشروع");
     m.insert("Save Project", "حفظ مشروع");
     m.insert("Export Job Report", "تصدير تقرير العمل");
+    m.insert("Export Machine File", "تصدير ملف الآلة");
     m.insert("Frame", "إطار");
     m.insert("Framing laser power (%)", "طاقة ليزر الإطار (%)");
     m.insert("Dry Run", "تشغيل تجريبي");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "تبليط");
     m.insert("Auto Nesting", "تداخل تلقائي");
     m.insert("Job Queue", "قائمة الانتظار");
+    m.insert("Estimate Accuracy", "دقة التقدير");
+    m.insert("No finished jobs measured yet.", "لم يتم قياس أي مهمة مكتملة بعد.");
+    m.insert("Mean error, last 10 jobs:", "متوسط الخطأ، آخر 10 مهام:");
+    m.insert("Corrections", "التصحيحات");
+    m.insert("Vector", "متجه");
+    m.insert("Raster", "نقطي");
+    m.insert("not measured", "لم يُقس");
+    m.insert("move", "حركة");
+    m.insert("jobs", "مهام");
+    m.insert("Date", "التاريخ");
+    m.insert("Job", "المهمة");
+    m.insert("Estimated", "المقدّر");
+    m.insert("Actual", "الفعلي");
+    m.insert("Error", "الخطأ");
+    m.insert("Export CSV", "تصدير CSV");
+    m.insert("Reset Calibration", "إعادة ضبط المعايرة");
     m.insert("Shortcuts", "اختصارات");
     m.insert("Dark UI", "واجهة داكنة");
     m.insert("Light UI", "واجهة فاتحة");
@@ -133,6 +153,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "إعدادات القطع");
     m.insert("Speed", "السرعة");
     m.insert("Power", "الطاقة");
+    m.insert("Sync from Controller", "مزامنة من وحدة التحكم");
+    m.insert("Reading machine settings…", "جارٍ قراءة إعدادات الآلة…");
+    m.insert("value(s) differ from the profile", "قيمة (قيم) تختلف عن الملف الشخصي");
+    m.insert("Profile", "الملف الشخصي");
+    m.insert("Apply to Profile", "تطبيق على الملف الشخصي");
+    m.insert("Hold arrow keys or buttons to jog", "اضغط مطولاً على مفاتيح الأسهم أو الأزرار للتحريك");
+    m.insert("Continuous (hold to jog)", "مستمر (اضغط مطولاً للتحريك)");
+    m.insert("Controller Files", "ملفات وحدة التحكم");
+    m.insert("No files", "لا توجد ملفات");
+    m.insert("Run on controller", "تشغيل على وحدة التحكم");
+    m.insert("Upload current job as", "رفع المهمة الحالية باسم");
+    m.insert("Upload", "رفع");
+    m.insert("Uploading…", "جارٍ الرفع…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "منفذ واجهة الويب؛ الطرفية على المنفذ التالي");
+    m.insert("Remote serial (RFC 2217)", "منفذ تسلسلي بعيد (RFC 2217)");
+    m.insert("Reset board", "إعادة تشغيل اللوحة");
+    m.insert("Pulse DTR/RTS to restart the controller", "نبضة DTR/RTS لإعادة تشغيل وحدة التحكم");
+    m.insert("Lines", "الخطوط");
+    m.insert("Resume Job", "استئناف المهمة");
+    m.insert("Checkpoint", "نقطة التحقق");
+    m.insert("Resume at line", "الاستئناف عند السطر");
+    m.insert("Path start", "بداية المسار");
+    m.insert("Back up to the rapid that starts this path", "الرجوع إلى الحركة السريعة التي تبدأ هذا المسار");
+    m.insert("Preamble", "المقدمة");
+    m.insert("line(s) remaining", "سطر متبقٍ");
+    m.insert("Work Offsets", "إزاحات العمل");
+    m.insert("Make this the active coordinate system", "اجعل هذا نظام الإحداثيات النشط");
+    m.insert("Set XY origin here", "تعيين أصل XY هنا");
+    m.insert("Named offsets", "إزاحات مسماة");
+    m.insert("Jig name", "اسم القالب");
+    m.insert("Save the active offset under this name", "احفظ الإزاحة النشطة بهذا الاسم");
+    m.insert("Active", "النشط");
+    m.insert("Inputs", "المدخلات");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "أطراف الإدخال النشطة (D الباب، P المسبار، X/Y/Z الحدود)");
     m.insert("Rapid", "سريع");
     m.insert("Passes", "التمريرات");
     m.insert("Pass", "تمريرة");
@@ -202,6 +257,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "الوضع");
     m.insert("Serial", "تسلسلي");
     m.insert("Network (TCP/IP)", "شبكة (TCP/IP)");
+    m.insert("Virtual machine", "جهاز افتراضي");
+    m.insert("Replay session", "إعادة تشغيل جلسة");
+    m.insert("Record session", "تسجيل الجلسة");
+    m.insert("Session file", "ملف الجلسة");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "GRBL 1.1 محاكى باستخدام سرعات ومسافات هذه الآلة");
     m.insert("Host", "المضيف");
     m.insert("Port", "المنفذ");
     m.insert("Baud", "معدل البود");
@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "Layout");
     m.insert("Language", "Sprache");
     m.insert("Controller", "Controller");
+    m.insert("Auto-detect on connect", "Beim Verbinden automatisch erkennen");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "Seriellen Port vor dem Verbinden auf GRBL, grblHAL, FluidNC, Marlin oder Smoothieware prüfen");
+    m.insert("Firmware", "Firmware");
     m.insert("Modern (recommended)", "Modern (empfohlen)");
     m.insert("Pro (new)", "Pro (neu)");
     m.insert("Industrial (advanced)", "Industriell (erweitert)");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "Projekt öffnen");
     m.insert("Save Project", "Projekt speichern");
     m.insert("Export Job Report", "Jobbericht exportieren");
+    m.insert("Export Machine File", "Maschinendatei exportieren");
     m.insert("Frame", "Rahmen");
     m.insert("Framing laser power (%)", "Rahmen-Laserleistung (%)");
     m.insert("Dry Run", "Testlauf");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "Kachelung");
     m.insert("Auto Nesting", "Auto-Verschachtelung");
     m.insert("Job Queue", "Auftragswarteschlange");
+    m.insert("Estimate Accuracy", "Schätzgenauigkeit");
+    m.insert("No finished jobs measured yet.", "Noch keine abgeschlossenen Aufträge gemessen.");
+    m.insert("Mean error, last 10 jobs:", "Mittlerer Fehler, letzte 10 Aufträge:");
+    m.insert("Corrections", "Korrekturen");
+    m.insert("Vector", "Vektor");
+    m.insert("Raster", "Raster");
+    m.insert("not measured", "nicht gemessen");
+    m.insert("move", "Bewegung");
+    m.insert("jobs", "Aufträge");
+    m.insert("Date", "Datum");
+    m.insert("Job", "Auftrag");
+    m.insert("Estimated", "Geschätzt");
+    m.insert("Actual", "Tatsächlich");
+    m.insert("Error", "Fehler");
+    m.insert("Export CSV", "CSV exportieren");
+    m.insert("Reset Calibration", "Kalibrierung zurücksetzen");
     m.insert("Shortcuts", "Tastenkürzel");
     m.insert("Dark UI", "Dunkle Oberfläche");
     m.insert("Light UI", "Helle Oberfläche");
@@ -145,6 +165,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "Schnitteinstellungen");
     m.insert("Speed", "Geschwindigkeit");
     m.insert("Power", "Leistung");
+    m.insert("Sync from Controller", "Vom Controller übernehmen");
+    m.insert("Reading machine settings…", "Maschineneinstellungen werden gelesen…");
+    m.insert("value(s) differ from the profile", "Wert(e) weichen vom Profil ab");
+    m.insert("Profile", "Profil");
+    m.insert("Apply to Profile", "Ins Profil übernehmen");
+    m.insert("Hold arrow keys or buttons to jog", "Pfeiltasten oder Schaltflächen gedrückt halten zum Verfahren");
+    m.insert("Continuous (hold to jog)", "Kontinuierlich (gedrückt halten)");
+    m.insert("Controller Files", "Controller-Dateien");
+    m.insert("No files", "Keine Dateien");
+    m.insert("Run on controller", "Auf dem Controller ausführen");
+    m.insert("Upload current job as", "Aktuellen Auftrag hochladen als");
+    m.insert("Upload", "Hochladen");
+    m.insert("Uploading…", "Wird hochgeladen…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Web-UI-Port; die Konsole liegt auf dem nächsten Port");
+    m.insert("Remote serial (RFC 2217)", "Entfernte serielle Schnittstelle (RFC 2217)");
+    m.insert("Reset board", "Board zurücksetzen");
+    m.insert("Pulse DTR/RTS to restart the controller", "DTR/RTS pulsen, um die Steuerung neu zu starten");
+    m.insert("Lines", "Leitungen");
+    m.insert("Resume Job", "Auftrag fortsetzen");
+    m.insert("Checkpoint", "Prüfpunkt");
+    m.insert("Resume at line", "Fortsetzen ab Zeile");
+    m.insert("Path start", "Pfadanfang");
+    m.insert("Back up to the rapid that starts this path", "Zur Eilgangbewegung am Anfang dieses Pfads zurückgehen");
+    m.insert("Preamble", "Vorspann");
+    m.insert("line(s) remaining", "Zeile(n) verbleibend");
+    m.insert("Work Offsets", "Werkstück-Nullpunkte");
+    m.insert("Make this the active coordinate system", "Als aktives Koordinatensystem setzen");
+    m.insert("Set XY origin here", "XY-Nullpunkt hier setzen");
+    m.insert("Named offsets", "Benannte Nullpunkte");
+    m.insert("Jig name", "Vorrichtungsname");
+    m.insert("Save the active offset under this name", "Aktiven Nullpunkt unter diesem Namen speichern");
+    m.insert("Active", "Aktiv");
+    m.insert("Inputs", "Eingänge");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "Aktive Eingänge (D Tür, P Sonde, X/Y/Z Endschalter)");
     m.insert("Rapid", "Eilgang");
     m.insert("Speed (mm/min)", "Geschwindigkeit (mm/min)");
     m.insert("Max Power (%)", "Max. Leistung (%)");
@@ -210,6 +265,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "Modus");
     m.insert("Serial", "Seriell");
     m.insert("Network (TCP/IP)", "Netzwerk (TCP/IP)");
+    m.insert("Virtual machine", "Virtuelle Maschine");
+    m.insert("Replay session", "Sitzung abspielen");
+    m.insert("Record session", "Sitzung aufzeichnen");
+    m.insert("Session file", "Sitzungsdatei");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "Simuliertes GRBL 1.1 mit den Geschwindigkeiten und Verfahrwegen dieser Maschine");
     m.insert("Host", "Host");
     m.insert("Port", "Port");
     m.insert("Baud", "Baudrate");
@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "Diseño");
     m.insert("Language", "Idioma");
     m.insert("Controller", "Controlador");
+    m.insert("Auto-detect on connect", "Detectar automáticamente al conectar");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "Sondear el puerto serie en busca de GRBL, grblHAL, FluidNC, Marlin o Smoothieware antes de conectar");
+    m.insert("Firmware", "Firmware");
     m.insert("Modern (recommended)", "Moderno (recomendado)");
     m.insert("Pro (new)", "Pro (nuevo)");
     m.insert("Industrial (advanced)", "Industrial (avanzado)");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "Abrir proyecto");
     m.insert("Save Project", "Guardar proyecto");
     m.insert("Export Job Report", "Exportar informe");
+    m.insert("Export Machine File", "Exportar archivo de máquina");
     m.insert("Frame", "Encuadre");
     m.insert("Framing laser power (%)", "Potencia láser encuadre (%)");
     m.insert("Dry Run", "Prueba en seco");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "Mosaico");
     m.insert("Auto Nesting", "Anidación automática");
     m.insert("Job Queue", "Cola de trabajos");
+    m.insert("Estimate Accuracy", "Precisión de la estimación");
+    m.insert("No finished jobs measured yet.", "Aún no se ha medido ningún trabajo terminado.");
+    m.insert("Mean error, last 10 jobs:", "Error medio, últimos 10 trabajos:");
+    m.insert("Corrections", "Correcciones");
+    m.insert("Vector", "Vector");
+    m.insert("Raster", "Ráster");
+    m.insert("not measured", "sin medir");
+    m.insert("move", "movimiento");
+    m.insert("jobs", "trabajos");
+    m.insert("Date", "Fecha");
+    m.insert("Job", "Trabajo");
+    m.insert("Estimated", "Estimado");
+    m.insert("Actual", "Real");
+    m.insert("Error", "Error");
+    m.insert("Export CSV", "Exportar CSV");
+    m.insert("Reset Calibration", "Restablecer calibración");
     m.insert("Shortcuts", "Atajos");
     m.insert("Dark UI", "Interfaz oscura");
     m.insert("Light UI", "Interfaz clara");
@@ -135,6 +155,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "Ajustes de corte");
     m.insert("Speed", "Velocidad");
     m.insert("Power", "Potencia");
+    m.insert("Sync from Controller", "Sincronizar desde el controlador");
+    m.insert("Reading machine settings…", "Leyendo la configuración de la máquina…");
+    m.insert("value(s) differ from the profile", "valor(es) difieren del perfil");
+    m.insert("Profile", "Perfil");
+    m.insert("Apply to Profile", "Aplicar al perfil");
+    m.insert("Hold arrow keys or buttons to jog", "Mantén pulsadas las flechas o los botones para mover");
+    m.insert("Continuous (hold to jog)", "Continuo (mantener para mover)");
+    m.insert("Controller Files", "Archivos del controlador");
+    m.insert("No files", "No hay archivos");
+    m.insert("Run on controller", "Ejecutar en el controlador");
+    m.insert("Upload current job as", "Subir el trabajo actual como");
+    m.insert("Upload", "Subir");
+    m.insert("Uploading…", "Subiendo…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Puerto de la interfaz web; la consola usa el siguiente puerto");
+    m.insert("Remote serial (RFC 2217)", "Serie remoto (RFC 2217)");
+    m.insert("Reset board", "Reiniciar placa");
+    m.insert("Pulse DTR/RTS to restart the controller", "Pulsar DTR/RTS para reiniciar el controlador");
+    m.insert("Lines", "Líneas");
+    m.insert("Resume Job", "Reanudar trabajo");
+    m.insert("Checkpoint", "Punto de control");
+    m.insert("Resume at line", "Reanudar en la línea");
+    m.insert("Path start", "Inicio del trazado");
+    m.insert("Back up to the rapid that starts this path", "Retroceder al movimiento rápido que inicia este trazado");
+    m.insert("Preamble", "Preámbulo");
+    m.insert("line(s) remaining", "línea(s) restantes");
+    m.insert("Work Offsets", "Orígenes de trabajo");
+    m.insert("Make this the active coordinate system", "Usar como sistema de coordenadas activo");
+    m.insert("Set XY origin here", "Fijar origen XY aquí");
+    m.insert("Named offsets", "Orígenes con nombre");
+    m.insert("Jig name", "Nombre de plantilla");
+    m.insert("Save the active offset under this name", "Guardar el origen activo con este nombre");
+    m.insert("Active", "Activo");
+    m.insert("Inputs", "Entradas");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "Pines de entrada activos (D puerta, P sonda, X/Y/Z límites)");
     m.insert("Rapid", "Rápido");
     m.insert("Speed (mm/min)", "Velocidad (mm/min)");
     m.insert("Max Power (%)", "Potencia máx. (%)");
@@ -313,6 +368,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "Modo");
     m.insert("Serial", "Serie");
     m.insert("Network (TCP/IP)", "Red (TCP/IP)");
+    m.insert("Virtual machine", "Máquina virtual");
+    m.insert("Replay session", "Reproducir sesión");
+    m.insert("Record session", "Grabar sesión");
+    m.insert("Session file", "Archivo de sesión");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "GRBL 1.1 simulado con las velocidades y recorridos de esta máquina");
     m.insert("Host", "Host");
     m.insert("Port", "Puerto");
     m.insert("Baud", "Baudios");
@@ -70,6 +70,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "Disposition");
     m.insert("Language", "Langue");
     m.insert("Controller", "Contrôleur");
+    m.insert("Auto-detect on connect", "Détection automatique à la connexion");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "Interroger le port série pour GRBL, grblHAL, FluidNC, Marlin ou Smoothieware avant la connexion");
+    m.insert("Firmware", "Micrologiciel");
     m.insert("Modern (recommended)", "Moderne (recommandé)");
     m.insert("Pro (new)", "Pro (nouveau)");
     m.insert("Industrial (advanced)", "Industriel (avancé)");
@@ -117,6 +120,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "Ouvrir un projet");
     m.insert("Save Project", "Enregistrer le projet");
     m.insert("Export Job Report", "Exporter le rapport");
+    m.insert("Export Machine File", "Exporter le fichier machine");
     m.insert("Frame", "Cadrage");
     m.insert("Framing laser power (%)", "Puissance laser cadrage (%)");
     m.insert("Dry Run", "Test à vide");
@@ -129,6 +133,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "Pavage");
     m.insert("Auto Nesting", "Imbrication auto");
     m.insert("Job Queue", "File d'attente");
+    m.insert("Estimate Accuracy", "Précision de l'estimation");
+    m.insert("No finished jobs measured yet.", "Aucune tâche terminée mesurée pour l'instant.");
+    m.insert("Mean error, last 10 jobs:", "Erreur moyenne, 10 dernières tâches :");
+    m.insert("Corrections", "Corrections");
+    m.insert("Vector", "Vecteur");
+    m.insert("Raster", "Raster");
+    m.insert("not measured", "non mesuré");
+    m.insert("move", "déplacement");
+    m.insert("jobs", "tâches");
+    m.insert("Date", "Date");
+    m.insert("Job", "Tâche");
+    m.insert("Estimated", "Estimé");
+    m.insert("Actual", "Réel");
+    m.insert("Error", "Erreur");
+    m.insert("Export CSV", "Exporter en CSV");
+    m.insert("Reset Calibration", "Réinitialiser l'étalonnage");
     m.insert("Shortcuts", "Raccourcis");
     m.insert("Dark UI", "Interface sombre");
     m.insert("Light UI", "Interface claire");
@@ -185,6 +205,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "Paramètres de coupe");
     m.insert("Speed", "Vitesse");
     m.insert("Power", "Puissance");
+    m.insert("Sync from Controller", "Synchroniser depuis le contrôleur");
+    m.insert("Reading machine settings…", "Lecture des réglages de la machine…");
+    m.insert("value(s) differ from the profile", "valeur(s) diffèrent du profil");
+    m.insert("Profile", "Profil");
+    m.insert("Apply to Profile", "Appliquer au profil");
+    m.insert("Hold arrow keys or buttons to jog", "Maintenez les flèches ou les boutons pour déplacer");
+    m.insert("Continuous (hold to jog)", "Continu (maintenir pour déplacer)");
+    m.insert("Controller Files", "Fichiers du contrôleur");
+    m.insert("No files", "Aucun fichier");
+    m.insert("Run on controller", "Exécuter sur le contrôleur");
+    m.insert("Upload current job as", "Téléverser la tâche actuelle sous");
+    m.insert("Upload", "Téléverser");
+    m.insert("Uploading…", "Téléversement…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Port de l'interface web ; la console est sur le port suivant");
+    m.insert("Remote serial (RFC 2217)", "Série distante (RFC 2217)");
+    m.insert("Reset board", "Réinitialiser la carte");
+    m.insert("Pulse DTR/RTS to restart the controller", "Impulsion DTR/RTS pour redémarrer le contrôleur");
+    m.insert("Lines", "Lignes");
+    m.insert("Resume Job", "Reprendre le travail");
+    m.insert("Checkpoint", "Point de contrôle");
+    m.insert("Resume at line", "Reprendre à la ligne");
+    m.insert("Path start", "Début du tracé");
+    m.insert("Back up to the rapid that starts this path", "Revenir au déplacement rapide qui commence ce tracé");
+    m.insert("Preamble", "Préambule");
+    m.insert("line(s) remaining", "ligne(s) restante(s)");
+    m.insert("Work Offsets", "Origines de travail");
+    m.insert("Make this the active coordinate system", "Utiliser comme système de coordonnées actif");
+    m.insert("Set XY origin here", "Définir l'origine XY ici");
+    m.insert("Named offsets", "Origines nommées");
+    m.insert("Jig name", "Nom du gabarit");
+    m.insert("Save the active offset under this name", "Enregistrer l'origine active sous ce nom");
+    m.insert("Active", "Actif");
+    m.insert("Inputs", "Entrées");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "Entrées actives (D porte, P sonde, X/Y/Z fins de course)");
     m.insert("Rapid", "Rapide");
     m.insert("Speed (mm/min)", "Vitesse (mm/min)");
     m.insert("Max Power (%)", "Puissance max (%)");
@@ -279,6 +334,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "Mode");
     m.insert("Serial", "Série");
     m.insert("Network (TCP/IP)", "Réseau (TCP/IP)");
+    m.insert("Virtual machine", "Machine virtuelle");
+    m.insert("Replay session", "Rejouer une session");
+    m.insert("Record session", "Enregistrer la session");
+    m.insert("Session file", "Fichier de session");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "GRBL 1.1 simulé avec les vitesses et courses de cette machine");
     m.insert("Host", "Hôte");
     m.insert("Port", "Port");
     m.insert("Baud", "Débit");
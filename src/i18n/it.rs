@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "Layout");
     m.insert("Language", "Lingua");
     m.insert("Controller", "Controller");
+    m.insert("Auto-detect on connect", "Rilevamento automatico alla connessione");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "Interroga la porta seriale per GRBL, grblHAL, FluidNC, Marlin o Smoothieware prima di connettersi");
+    m.insert("Firmware", "Firmware");
     m.insert("Modern (recommended)", "Moderno (consigliato)");
     m.insert("Pro (new)", "Pro (nuovo)");
     m.insert("Industrial (advanced)", "Industriale (avanzato)");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "Apri progetto");
     m.insert("Save Project", "Salva progetto");
     m.insert("Export Job Report", "Esporta rapporto lavoro");
+    m.insert("Export Machine File", "Esporta file macchina");
     m.insert("Frame", "Cornice");
     m.insert("Framing laser power (%)", "Potenza laser cornice (%)");
     m.insert("Dry Run", "Prova a vuoto");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "Piastrellatura");
     m.insert("Auto Nesting", "Nesting automatico");
     m.insert("Job Queue", "Coda lavori");
+    m.insert("Estimate Accuracy", "Precisione della stima");
+    m.insert("No finished jobs measured yet.", "Nessun lavoro completato misurato finora.");
+    m.insert("Mean error, last 10 jobs:", "Errore medio, ultimi 10 lavori:");
+    m.insert("Corrections", "Correzioni");
+    m.insert("Vector", "Vettore");
+    m.insert("Raster", "Raster");
+    m.insert("not measured", "non misurato");
+    m.insert("move", "movimento");
+    m.insert("jobs", "lavori");
+    m.insert("Date", "Data");
+    m.insert("Job", "Lavoro");
+    m.insert("Estimated", "Stimato");
+    m.insert("Actual", "Effettivo");
+    m.insert("Error", "Errore");
+    m.insert("Export CSV", "Esporta CSV");
+    m.insert("Reset Calibration", "Azzera calibrazione");
     m.insert("Shortcuts", "Scorciatoie");
     m.insert("Dark UI", "Interfaccia scura");
     m.insert("Light UI", "Interfaccia chiara");
@@ -136,6 +156,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "Impostazioni taglio");
     m.insert("Speed", "Velocità");
     m.insert("Power", "Potenza");
+    m.insert("Sync from Controller", "Sincronizza dal controller");
+    m.insert("Reading machine settings…", "Lettura delle impostazioni della macchina…");
+    m.insert("value(s) differ from the profile", "valore/i diversi dal profilo");
+    m.insert("Profile", "Profilo");
+    m.insert("Apply to Profile", "Applica al profilo");
+    m.insert("Hold arrow keys or buttons to jog", "Tieni premuti i tasti freccia o i pulsanti per muovere");
+    m.insert("Continuous (hold to jog)", "Continuo (tieni premuto)");
+    m.insert("Controller Files", "File del controller");
+    m.insert("No files", "Nessun file");
+    m.insert("Run on controller", "Esegui sul controller");
+    m.insert("Upload current job as", "Carica il lavoro corrente come");
+    m.insert("Upload", "Carica");
+    m.insert("Uploading…", "Caricamento…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Porta dell'interfaccia web; la console è sulla porta successiva");
+    m.insert("Remote serial (RFC 2217)", "Seriale remota (RFC 2217)");
+    m.insert("Reset board", "Reset scheda");
+    m.insert("Pulse DTR/RTS to restart the controller", "Impulso DTR/RTS per riavviare il controller");
+    m.insert("Lines", "Linee");
+    m.insert("Resume Job", "Riprendi lavoro");
+    m.insert("Checkpoint", "Punto di controllo");
+    m.insert("Resume at line", "Riprendi dalla riga");
+    m.insert("Path start", "Inizio percorso");
+    m.insert("Back up to the rapid that starts this path", "Torna al rapido che inizia questo percorso");
+    m.insert("Preamble", "Preambolo");
+    m.insert("line(s) remaining", "riga/e rimanenti");
+    m.insert("Work Offsets", "Origini di lavoro");
+    m.insert("Make this the active coordinate system", "Imposta come sistema di coordinate attivo");
+    m.insert("Set XY origin here", "Imposta origine XY qui");
+    m.insert("Named offsets", "Origini con nome");
+    m.insert("Jig name", "Nome dima");
+    m.insert("Save the active offset under this name", "Salva l'origine attiva con questo nome");
+    m.insert("Active", "Attivo");
+    m.insert("Inputs", "Ingressi");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "Ingressi attivi (D porta, P sonda, X/Y/Z finecorsa)");
     m.insert("Rapid", "Rapido");
     m.insert("Speed (mm/min)", "Velocità (mm/min)");
     m.insert("Max Power (%)", "Potenza max (%)");
@@ -211,6 +266,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "Modalità");
     m.insert("Serial", "Seriale");
     m.insert("Network (TCP/IP)", "Rete (TCP/IP)");
+    m.insert("Virtual machine", "Macchina virtuale");
+    m.insert("Replay session", "Riproduci sessione");
+    m.insert("Record session", "Registra sessione");
+    m.insert("Session file", "File di sessione");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "GRBL 1.1 simulato con le velocità e le corse di questa macchina");
     m.insert("Host", "Host");
     m.insert("Port", "Porta");
     m.insert("Baud", "Baud");
@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "レイアウト");
     m.insert("Language", "言語");
     m.insert("Controller", "コントローラー");
+    m.insert("Auto-detect on connect", "接続時に自動検出");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "接続前にシリアルポートでGRBL、grblHAL、FluidNC、Marlin、Smoothiewareを検出");
+    m.insert("Firmware", "ファームウェア");
     m.insert("Modern (recommended)", "モダン（推奨）");
     m.insert("Pro (new)", "プロ (新規)");
     m.insert("Industrial (advanced)", "インダストリアル（上級者向け）");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "プロジェクトを開く");
     m.insert("Save Project", "プロジェクトを保存");
     m.insert("Export Job Report", "ジョブレポートをエクスポート");
+    m.insert("Export Machine File", "マシンファイルをエクスポート");
     m.insert("Frame", "フレーム");
     m.insert("Framing laser power (%)", "フレームレーザー出力 (%)");
     m.insert("Dry Run", "テスト実行");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "タイリング");
     m.insert("Auto Nesting", "自動ネスティング");
     m.insert("Job Queue", "ジョブキュー");
+    m.insert("Estimate Accuracy", "見積もり精度");
+    m.insert("No finished jobs measured yet.", "完了したジョブの計測はまだありません。");
+    m.insert("Mean error, last 10 jobs:", "平均誤差（直近10件）:");
+    m.insert("Corrections", "補正");
+    m.insert("Vector", "ベクター");
+    m.insert("Raster", "ラスター");
+    m.insert("not measured", "未計測");
+    m.insert("move", "移動");
+    m.insert("jobs", "件");
+    m.insert("Date", "日付");
+    m.insert("Job", "ジョブ");
+    m.insert("Estimated", "見積もり");
+    m.insert("Actual", "実測");
+    m.insert("Error", "誤差");
+    m.insert("Export CSV", "CSVをエクスポート");
+    m.insert("Reset Calibration", "補正をリセット");
     m.insert("Shortcuts", "ショートカット");
     m.insert("Dark UI", "ダークUI");
     m.insert("Light UI", "ライトUI");
@@ -135,6 +155,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "カット設定");
     m.insert("Speed", "速度");
     m.insert("Power", "出力");
+    m.insert("Sync from Controller", "コントローラーから同期");
+    m.insert("Reading machine settings…", "マシン設定を読み込み中…");
+    m.insert("value(s) differ from the profile", "件の値がプロファイルと異なります");
+    m.insert("Profile", "プロファイル");
+    m.insert("Apply to Profile", "プロファイルに適用");
+    m.insert("Hold arrow keys or buttons to jog", "矢印キーまたはボタンを押し続けてジョグ");
+    m.insert("Continuous (hold to jog)", "連続 (押し続けてジョグ)");
+    m.insert("Controller Files", "コントローラーのファイル");
+    m.insert("No files", "ファイルなし");
+    m.insert("Run on controller", "コントローラーで実行");
+    m.insert("Upload current job as", "現在のジョブをアップロード");
+    m.insert("Upload", "アップロード");
+    m.insert("Uploading…", "アップロード中…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Web UIのポート。コンソールは次のポートです");
+    m.insert("Remote serial (RFC 2217)", "リモートシリアル (RFC 2217)");
+    m.insert("Reset board", "ボードをリセット");
+    m.insert("Pulse DTR/RTS to restart the controller", "DTR/RTSをパルスしてコントローラーを再起動");
+    m.insert("Lines", "信号線");
+    m.insert("Resume Job", "ジョブを再開");
+    m.insert("Checkpoint", "チェックポイント");
+    m.insert("Resume at line", "再開する行");
+    m.insert("Path start", "パスの先頭");
+    m.insert("Back up to the rapid that starts this path", "このパスを開始する早送りまで戻る");
+    m.insert("Preamble", "前処理");
+    m.insert("line(s) remaining", "行残り");
+    m.insert("Work Offsets", "ワーク座標オフセット");
+    m.insert("Make this the active coordinate system", "この座標系を有効にする");
+    m.insert("Set XY origin here", "ここをXY原点にする");
+    m.insert("Named offsets", "名前付きオフセット");
+    m.insert("Jig name", "治具名");
+    m.insert("Save the active offset under this name", "有効なオフセットをこの名前で保存");
+    m.insert("Active", "有効");
+    m.insert("Inputs", "入力");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "アクティブな入力ピン (D ドア, P プローブ, X/Y/Z リミット)");
     m.insert("Rapid", "早送り");
     m.insert("Speed (mm/min)", "速度 (mm/min)");
     m.insert("Max Power (%)", "最大出力 (%)");
@@ -219,6 +274,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "モード");
     m.insert("Serial", "シリアル");
     m.insert("Network (TCP/IP)", "ネットワーク (TCP/IP)");
+    m.insert("Virtual machine", "仮想マシン");
+    m.insert("Replay session", "セッション再生");
+    m.insert("Record session", "セッションを記録");
+    m.insert("Session file", "セッションファイル");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "このマシンの速度とストロークでGRBL 1.1をシミュレート");
     m.insert("Host", "ホスト");
     m.insert("Port", "ポート");
     m.insert("Baud", "ボーレート");
@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "레이아웃");
     m.insert("Language", "언어");
     m.insert("Controller", "컨트롤러");
+    m.insert("Auto-detect on connect", "연결 시 자동 감지");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "연결 전에 시리얼 포트에서 GRBL, grblHAL, FluidNC, Marlin 또는 Smoothieware를 확인");
+    m.insert("Firmware", "펌웨어");
     m.insert("Cuts", "절단");
     m.insert("Move", "이동");
     m.insert("Laser", "레이저");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "프로젝트 열기");
     m.insert("Save Project", "프로젝트 저장");
     m.insert("Export Job Report", "작업 보고서 내보내기");
+    m.insert("Export Machine File", "장비 파일 내보내기");
     m.insert("Frame", "프레임");
     m.insert("Framing laser power (%)", "프레임 레이저 출력 (%)");
     m.insert("Dry Run", "테스트 실행");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "타일링");
     m.insert("Auto Nesting", "자동 배치");
     m.insert("Job Queue", "작업 대기열");
+    m.insert("Estimate Accuracy", "예상 정확도");
+    m.insert("No finished jobs measured yet.", "아직 측정된 완료 작업이 없습니다.");
+    m.insert("Mean error, last 10 jobs:", "평균 오차, 최근 10개 작업:");
+    m.insert("Corrections", "보정");
+    m.insert("Vector", "벡터");
+    m.insert("Raster", "래스터");
+    m.insert("not measured", "측정 안 됨");
+    m.insert("move", "이동");
+    m.insert("jobs", "작업");
+    m.insert("Date", "날짜");
+    m.insert("Job", "작업");
+    m.insert("Estimated", "예상");
+    m.insert("Actual", "실제");
+    m.insert("Error", "오차");
+    m.insert("Export CSV", "CSV 내보내기");
+    m.insert("Reset Calibration", "보정 초기화");
     m.insert("Shortcuts", "단축키");
     m.insert("Dark UI", "다크 UI");
     m.insert("Light UI", "라이트 UI");
@@ -133,6 +153,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "절단 설정");
     m.insert("Speed", "속도");
     m.insert("Power", "출력");
+    m.insert("Sync from Controller", "컨트롤러에서 동기화");
+    m.insert("Reading machine settings…", "기계 설정을 읽는 중…");
+    m.insert("value(s) differ from the profile", "개 값이 프로필과 다릅니다");
+    m.insert("Profile", "프로필");
+    m.insert("Apply to Profile", "프로필에 적용");
+    m.insert("Hold arrow keys or buttons to jog", "화살표 키나 버튼을 누르고 있으면 조그");
+    m.insert("Continuous (hold to jog)", "연속 (누르고 있는 동안 조그)");
+    m.insert("Controller Files", "컨트롤러 파일");
+    m.insert("No files", "파일 없음");
+    m.insert("Run on controller", "컨트롤러에서 실행");
+    m.insert("Upload current job as", "현재 작업을 다음 이름으로 업로드");
+    m.insert("Upload", "업로드");
+    m.insert("Uploading…", "업로드 중…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "웹 UI 포트, 콘솔은 다음 포트입니다");
+    m.insert("Remote serial (RFC 2217)", "원격 시리얼 (RFC 2217)");
+    m.insert("Reset board", "보드 리셋");
+    m.insert("Pulse DTR/RTS to restart the controller", "DTR/RTS 펄스로 컨트롤러 재시작");
+    m.insert("Lines", "신호선");
+    m.insert("Resume Job", "작업 재개");
+    m.insert("Checkpoint", "체크포인트");
+    m.insert("Resume at line", "재개할 줄");
+    m.insert("Path start", "경로 시작");
+    m.insert("Back up to the rapid that starts this path", "이 경로를 시작하는 급속 이동으로 되돌아갑니다");
+    m.insert("Preamble", "준비 코드");
+    m.insert("line(s) remaining", "줄 남음");
+    m.insert("Work Offsets", "작업 좌표 오프셋");
+    m.insert("Make this the active coordinate system", "이 좌표계를 활성화");
+    m.insert("Set XY origin here", "여기를 XY 원점으로 설정");
+    m.insert("Named offsets", "이름 있는 오프셋");
+    m.insert("Jig name", "지그 이름");
+    m.insert("Save the active offset under this name", "활성 오프셋을 이 이름으로 저장");
+    m.insert("Active", "활성");
+    m.insert("Inputs", "입력");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "활성 입력 핀 (D 도어, P 프로브, X/Y/Z 리밋)");
     m.insert("Rapid", "급속");
     m.insert("Passes", "패스 횟수");
     m.insert("Pass", "패스");
@@ -202,6 +257,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "모드");
     m.insert("Serial", "시리얼");
     m.insert("Network (TCP/IP)", "네트워크 (TCP/IP)");
+    m.insert("Virtual machine", "가상 머신");
+    m.insert("Replay session", "세션 재생");
+    m.insert("Record session", "세션 기록");
+    m.insert("Session file", "세션 파일");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "이 기계의 속도와 이동 범위로 시뮬레이션한 GRBL 1.1");
     m.insert("Host", "호스트");
     m.insert("Port", "포트");
     m.insert("Baud", "보드레이트");
@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "Układ");
     m.insert("Language", "Język");
     m.insert("Controller", "Kontroler");
+    m.insert("Auto-detect on connect", "Wykryj automatycznie przy połączeniu");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "Sprawdź port szeregowy pod kątem GRBL, grblHAL, FluidNC, Marlin lub Smoothieware przed połączeniem");
+    m.insert("Firmware", "Oprogramowanie układowe");
     m.insert("Cuts", "Cięcia");
     m.insert("Move", "Przesuń");
     m.insert("Laser", "Laser");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "Otwórz projekt");
     m.insert("Save Project", "Zapisz projekt");
     m.insert("Export Job Report", "Eksportuj raport");
+    m.insert("Export Machine File", "Eksportuj plik maszyny");
     m.insert("Frame", "Ramka");
     m.insert("Framing laser power (%)", "Moc lasera ramki (%)");
     m.insert("Dry Run", "Próba na sucho");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "Kafelkowanie");
     m.insert("Auto Nesting", "Automatyczne rozmieszczenie");
     m.insert("Job Queue", "Kolejka zadań");
+    m.insert("Estimate Accuracy", "Dokładność szacowania");
+    m.insert("No finished jobs measured yet.", "Nie zmierzono jeszcze żadnego ukończonego zadania.");
+    m.insert("Mean error, last 10 jobs:", "Średni błąd, ostatnie 10 zadań:");
+    m.insert("Corrections", "Korekty");
+    m.insert("Vector", "Wektor");
+    m.insert("Raster", "Raster");
+    m.insert("not measured", "nie zmierzono");
+    m.insert("move", "ruch");
+    m.insert("jobs", "zadań");
+    m.insert("Date", "Data");
+    m.insert("Job", "Zadanie");
+    m.insert("Estimated", "Szacowany");
+    m.insert("Actual", "Rzeczywisty");
+    m.insert("Error", "Błąd");
+    m.insert("Export CSV", "Eksportuj CSV");
+    m.insert("Reset Calibration", "Resetuj kalibrację");
     m.insert("Shortcuts", "Skróty klawiszowe");
     m.insert("Dark UI", "Ciemny interfejs");
     m.insert("Light UI", "Jasny interfejs");
@@ -134,6 +154,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "Ustawienia cięcia");
     m.insert("Speed", "Prędkość");
     m.insert("Power", "Moc");
+    m.insert("Sync from Controller", "Synchronizuj z kontrolera");
+    m.insert("Reading machine settings…", "Odczytywanie ustawień maszyny…");
+    m.insert("value(s) differ from the profile", "wartość(i) różni się od profilu");
+    m.insert("Profile", "Profil");
+    m.insert("Apply to Profile", "Zastosuj do profilu");
+    m.insert("Hold arrow keys or buttons to jog", "Przytrzymaj strzałki lub przyciski, aby przesuwać");
+    m.insert("Continuous (hold to jog)", "Ciągły (przytrzymaj)");
+    m.insert("Controller Files", "Pliki kontrolera");
+    m.insert("No files", "Brak plików");
+    m.insert("Run on controller", "Uruchom na kontrolerze");
+    m.insert("Upload current job as", "Wyślij bieżące zadanie jako");
+    m.insert("Upload", "Wyślij");
+    m.insert("Uploading…", "Wysyłanie…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Port interfejsu WWW; konsola jest na następnym porcie");
+    m.insert("Remote serial (RFC 2217)", "Zdalny port szeregowy (RFC 2217)");
+    m.insert("Reset board", "Resetuj płytkę");
+    m.insert("Pulse DTR/RTS to restart the controller", "Impuls DTR/RTS, aby zrestartować sterownik");
+    m.insert("Lines", "Linie");
+    m.insert("Resume Job", "Wznów zadanie");
+    m.insert("Checkpoint", "Punkt kontrolny");
+    m.insert("Resume at line", "Wznów od linii");
+    m.insert("Path start", "Początek ścieżki");
+    m.insert("Back up to the rapid that starts this path", "Cofnij do ruchu szybkiego rozpoczynającego tę ścieżkę");
+    m.insert("Preamble", "Preambuła");
+    m.insert("line(s) remaining", "pozostałych linii");
+    m.insert("Work Offsets", "Punkty zerowe");
+    m.insert("Make this the active coordinate system", "Ustaw jako aktywny układ współrzędnych");
+    m.insert("Set XY origin here", "Ustaw tutaj zero XY");
+    m.insert("Named offsets", "Nazwane punkty zerowe");
+    m.insert("Jig name", "Nazwa przyrządu");
+    m.insert("Save the active offset under this name", "Zapisz aktywny punkt zerowy pod tą nazwą");
+    m.insert("Active", "Aktywny");
+    m.insert("Inputs", "Wejścia");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "Aktywne wejścia (D drzwi, P sonda, X/Y/Z krańcówki)");
     m.insert("Rapid", "Szybki");
     m.insert("Passes", "Przejścia");
     m.insert("Pass", "Przejście");
@@ -203,6 +258,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "Tryb");
     m.insert("Serial", "Szeregowy");
     m.insert("Network (TCP/IP)", "Sieć (TCP/IP)");
+    m.insert("Virtual machine", "Maszyna wirtualna");
+    m.insert("Replay session", "Odtwórz sesję");
+    m.insert("Record session", "Nagrywaj sesję");
+    m.insert("Session file", "Plik sesji");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "Symulowany GRBL 1.1 z prędkościami i zakresem ruchu tej maszyny");
     m.insert("Host", "Host");
     m.insert("Port", "Port");
     m.insert("Baud", "Prędkość");
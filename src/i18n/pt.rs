@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "Layout");
     m.insert("Language", "Idioma");
     m.insert("Controller", "Controlador");
+    m.insert("Auto-detect on connect", "Detectar automaticamente ao conectar");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "Sondar a porta serial por GRBL, grblHAL, FluidNC, Marlin ou Smoothieware antes de conectar");
+    m.insert("Firmware", "Firmware");
     m.insert("Modern (recommended)", "Moderno (recomendado)");
     m.insert("Pro (new)", "Pro (novo)");
     m.insert("Industrial (advanced)", "Industrial (avançado)");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "Abrir projeto");
     m.insert("Save Project", "Salvar projeto");
     m.insert("Export Job Report", "Exportar relatório");
+    m.insert("Export Machine File", "Exportar arquivo da máquina");
     m.insert("Frame", "Enquadramento");
     m.insert("Framing laser power (%)", "Potência laser enquadramento (%)");
     m.insert("Dry Run", "Teste a seco");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "Ladrilhamento");
     m.insert("Auto Nesting", "Encaixe automático");
     m.insert("Job Queue", "Fila de trabalhos");
+    m.insert("Estimate Accuracy", "Precisão da estimativa");
+    m.insert("No finished jobs measured yet.", "Nenhum trabalho concluído foi medido ainda.");
+    m.insert("Mean error, last 10 jobs:", "Erro médio, últimos 10 trabalhos:");
+    m.insert("Corrections", "Correções");
+    m.insert("Vector", "Vetor");
+    m.insert("Raster", "Raster");
+    m.insert("not measured", "não medido");
+    m.insert("move", "movimento");
+    m.insert("jobs", "trabalhos");
+    m.insert("Date", "Data");
+    m.insert("Job", "Trabalho");
+    m.insert("Estimated", "Estimado");
+    m.insert("Actual", "Real");
+    m.insert("Error", "Erro");
+    m.insert("Export CSV", "Exportar CSV");
+    m.insert("Reset Calibration", "Redefinir calibração");
     m.insert("Shortcuts", "Atalhos");
     m.insert("Dark UI", "Interface escura");
     m.insert("Light UI", "Interface clara");
@@ -135,6 +155,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "Configurações de corte");
     m.insert("Speed", "Velocidade");
     m.insert("Power", "Potência");
+    m.insert("Sync from Controller", "Sincronizar do controlador");
+    m.insert("Reading machine settings…", "Lendo as configurações da máquina…");
+    m.insert("value(s) differ from the profile", "valor(es) diferem do perfil");
+    m.insert("Profile", "Perfil");
+    m.insert("Apply to Profile", "Aplicar ao perfil");
+    m.insert("Hold arrow keys or buttons to jog", "Mantenha as setas ou os botões pressionados para mover");
+    m.insert("Continuous (hold to jog)", "Contínuo (manter pressionado)");
+    m.insert("Controller Files", "Arquivos do controlador");
+    m.insert("No files", "Nenhum arquivo");
+    m.insert("Run on controller", "Executar no controlador");
+    m.insert("Upload current job as", "Enviar o trabalho atual como");
+    m.insert("Upload", "Enviar");
+    m.insert("Uploading…", "Enviando…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Porta da interface web; o console fica na porta seguinte");
+    m.insert("Remote serial (RFC 2217)", "Serial remota (RFC 2217)");
+    m.insert("Reset board", "Reiniciar placa");
+    m.insert("Pulse DTR/RTS to restart the controller", "Pulsar DTR/RTS para reiniciar o controlador");
+    m.insert("Lines", "Linhas");
+    m.insert("Resume Job", "Retomar trabalho");
+    m.insert("Checkpoint", "Ponto de verificação");
+    m.insert("Resume at line", "Retomar na linha");
+    m.insert("Path start", "Início do caminho");
+    m.insert("Back up to the rapid that starts this path", "Voltar ao movimento rápido que inicia este caminho");
+    m.insert("Preamble", "Preâmbulo");
+    m.insert("line(s) remaining", "linha(s) restantes");
+    m.insert("Work Offsets", "Origens de trabalho");
+    m.insert("Make this the active coordinate system", "Tornar este o sistema de coordenadas ativo");
+    m.insert("Set XY origin here", "Definir origem XY aqui");
+    m.insert("Named offsets", "Origens nomeadas");
+    m.insert("Jig name", "Nome do gabarito");
+    m.insert("Save the active offset under this name", "Salvar a origem ativa com este nome");
+    m.insert("Active", "Ativo");
+    m.insert("Inputs", "Entradas");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "Entradas ativas (D porta, P sonda, X/Y/Z limites)");
     m.insert("Rapid", "Rápido");
     m.insert("Speed (mm/min)", "Velocidade (mm/min)");
     m.insert("Max Power (%)", "Potência máx. (%)");
@@ -237,6 +292,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "Modo");
     m.insert("Serial", "Serial");
     m.insert("Network (TCP/IP)", "Rede (TCP/IP)");
+    m.insert("Virtual machine", "Máquina virtual");
+    m.insert("Replay session", "Reproduzir sessão");
+    m.insert("Record session", "Gravar sessão");
+    m.insert("Session file", "Arquivo de sessão");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "GRBL 1.1 simulado com as velocidades e cursos desta máquina");
     m.insert("Host", "Host");
     m.insert("Port", "Porta");
     m.insert("Baud", "Baud");
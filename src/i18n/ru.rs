@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "Макет");
     m.insert("Language", "Язык");
     m.insert("Controller", "Контроллер");
+    m.insert("Auto-detect on connect", "Автоопределение при подключении");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "Опросить последовательный порт на GRBL, grblHAL, FluidNC, Marlin или Smoothieware перед подключением");
+    m.insert("Firmware", "Прошивка");
     m.insert("Cuts", "Резка");
     m.insert("Move", "Перемещение");
     m.insert("Laser", "Лазер");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "Открыть проект");
     m.insert("Save Project", "Сохранить проект");
     m.insert("Export Job Report", "Экспорт отчёта");
+    m.insert("Export Machine File", "Экспорт файла станка");
     m.insert("Frame", "Рамка");
     m.insert("Framing laser power (%)", "Мощность лазера рамки (%)");
     m.insert("Dry Run", "Пробный запуск");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "Мозаика");
     m.insert("Auto Nesting", "Автораскрой");
     m.insert("Job Queue", "Очередь заданий");
+    m.insert("Estimate Accuracy", "Точность оценки");
+    m.insert("No finished jobs measured yet.", "Завершённые задания ещё не измерены.");
+    m.insert("Mean error, last 10 jobs:", "Средняя ошибка, последние 10 заданий:");
+    m.insert("Corrections", "Поправки");
+    m.insert("Vector", "Вектор");
+    m.insert("Raster", "Растр");
+    m.insert("not measured", "не измерено");
+    m.insert("move", "перемещение");
+    m.insert("jobs", "заданий");
+    m.insert("Date", "Дата");
+    m.insert("Job", "Задание");
+    m.insert("Estimated", "Оценка");
+    m.insert("Actual", "Факт");
+    m.insert("Error", "Ошибка");
+    m.insert("Export CSV", "Экспорт CSV");
+    m.insert("Reset Calibration", "Сбросить калибровку");
     m.insert("Shortcuts", "Горячие клавиши");
     m.insert("Dark UI", "Тёмный интерфейс");
     m.insert("Light UI", "Светлый интерфейс");
@@ -133,6 +153,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "Настройки реза");
     m.insert("Speed", "Скорость");
     m.insert("Power", "Мощность");
+    m.insert("Sync from Controller", "Синхронизировать с контроллера");
+    m.insert("Reading machine settings…", "Чтение настроек станка…");
+    m.insert("value(s) differ from the profile", "значение(й) отличаются от профиля");
+    m.insert("Profile", "Профиль");
+    m.insert("Apply to Profile", "Применить к профилю");
+    m.insert("Hold arrow keys or buttons to jog", "Удерживайте стрелки или кнопки для перемещения");
+    m.insert("Continuous (hold to jog)", "Непрерывно (удерживать)");
+    m.insert("Controller Files", "Файлы контроллера");
+    m.insert("No files", "Нет файлов");
+    m.insert("Run on controller", "Запустить на контроллере");
+    m.insert("Upload current job as", "Загрузить текущее задание как");
+    m.insert("Upload", "Загрузить");
+    m.insert("Uploading…", "Загрузка…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Порт веб-интерфейса; консоль на следующем порту");
+    m.insert("Remote serial (RFC 2217)", "Удалённый последовательный порт (RFC 2217)");
+    m.insert("Reset board", "Сбросить плату");
+    m.insert("Pulse DTR/RTS to restart the controller", "Импульс DTR/RTS для перезапуска контроллера");
+    m.insert("Lines", "Линии");
+    m.insert("Resume Job", "Возобновить задание");
+    m.insert("Checkpoint", "Контрольная точка");
+    m.insert("Resume at line", "Продолжить со строки");
+    m.insert("Path start", "Начало контура");
+    m.insert("Back up to the rapid that starts this path", "Вернуться к холостому ходу в начале контура");
+    m.insert("Preamble", "Преамбула");
+    m.insert("line(s) remaining", "строк осталось");
+    m.insert("Work Offsets", "Рабочие смещения");
+    m.insert("Make this the active coordinate system", "Сделать активной системой координат");
+    m.insert("Set XY origin here", "Установить ноль XY здесь");
+    m.insert("Named offsets", "Именованные смещения");
+    m.insert("Jig name", "Имя оснастки");
+    m.insert("Save the active offset under this name", "Сохранить активное смещение под этим именем");
+    m.insert("Active", "Активная");
+    m.insert("Inputs", "Входы");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "Активные входы (D дверь, P щуп, X/Y/Z концевики)");
     m.insert("Rapid", "Быстрый");
     m.insert("Speed (mm/min)", "Скорость (мм/мин)");
     m.insert("Max Power (%)", "Макс. мощность (%)");
@@ -206,6 +261,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "Режим");
     m.insert("Serial", "Последовательный");
     m.insert("Network (TCP/IP)", "Сеть (TCP/IP)");
+    m.insert("Virtual machine", "Виртуальный станок");
+    m.insert("Replay session", "Воспроизвести сеанс");
+    m.insert("Record session", "Записывать сеанс");
+    m.insert("Session file", "Файл сеанса");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "Симуляция GRBL 1.1 со скоростями и ходами этого станка");
     m.insert("Host", "Хост");
     m.insert("Port", "Порт");
     m.insert("Baud", "Скорость");
@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "Düzen");
     m.insert("Language", "Dil");
     m.insert("Controller", "Denetleyici");
+    m.insert("Auto-detect on connect", "Bağlanırken otomatik algıla");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "Bağlanmadan önce seri portu GRBL, grblHAL, FluidNC, Marlin veya Smoothieware için yokla");
+    m.insert("Firmware", "Donanım yazılımı");
     m.insert("Cuts", "Kesimler");
     m.insert("Move", "Taşı");
     m.insert("Laser", "Lazer");
@@ -78,6 +81,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "Proje Aç");
     m.insert("Save Project", "Projeyi Kaydet");
     m.insert("Export Job Report", "İş Raporu Dışa Aktar");
+    m.insert("Export Machine File", "Makine dosyasını dışa aktar");
     m.insert("Frame", "Çerçeve");
     m.insert("Framing laser power (%)", "Çerçeve lazer gücü (%)");
     m.insert("Dry Run", "Kuru Çalışma");
@@ -90,6 +94,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "Döşeme");
     m.insert("Auto Nesting", "Otomatik Yerleşim");
     m.insert("Job Queue", "İş Kuyruğu");
+    m.insert("Estimate Accuracy", "Tahmin Doğruluğu");
+    m.insert("No finished jobs measured yet.", "Henüz ölçülmüş tamamlanan iş yok.");
+    m.insert("Mean error, last 10 jobs:", "Ortalama hata, son 10 iş:");
+    m.insert("Corrections", "Düzeltmeler");
+    m.insert("Vector", "Vektör");
+    m.insert("Raster", "Raster");
+    m.insert("not measured", "ölçülmedi");
+    m.insert("move", "hareket");
+    m.insert("jobs", "iş");
+    m.insert("Date", "Tarih");
+    m.insert("Job", "İş");
+    m.insert("Estimated", "Tahmini");
+    m.insert("Actual", "Gerçek");
+    m.insert("Error", "Hata");
+    m.insert("Export CSV", "CSV Dışa Aktar");
+    m.insert("Reset Calibration", "Kalibrasyonu Sıfırla");
     m.insert("Shortcuts", "Kısayollar");
     m.insert("Dark UI", "Koyu Arayüz");
     m.insert("Light UI", "Açık Arayüz");
@@ -133,6 +153,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "Kesim Ayarları");
     m.insert("Speed", "Hız");
     m.insert("Power", "Güç");
+    m.insert("Sync from Controller", "Denetleyiciden eşitle");
+    m.insert("Reading machine settings…", "Makine ayarları okunuyor…");
+    m.insert("value(s) differ from the profile", "değer profilden farklı");
+    m.insert("Profile", "Profil");
+    m.insert("Apply to Profile", "Profile uygula");
+    m.insert("Hold arrow keys or buttons to jog", "Hareket için ok tuşlarını veya düğmeleri basılı tutun");
+    m.insert("Continuous (hold to jog)", "Sürekli (basılı tut)");
+    m.insert("Controller Files", "Denetleyici dosyaları");
+    m.insert("No files", "Dosya yok");
+    m.insert("Run on controller", "Denetleyicide çalıştır");
+    m.insert("Upload current job as", "Geçerli işi şu adla yükle");
+    m.insert("Upload", "Yükle");
+    m.insert("Uploading…", "Yükleniyor…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Web arayüzü portu; konsol bir sonraki portta");
+    m.insert("Remote serial (RFC 2217)", "Uzak seri port (RFC 2217)");
+    m.insert("Reset board", "Kartı sıfırla");
+    m.insert("Pulse DTR/RTS to restart the controller", "Denetleyiciyi yeniden başlatmak için DTR/RTS darbesi");
+    m.insert("Lines", "Hatlar");
+    m.insert("Resume Job", "İşe devam et");
+    m.insert("Checkpoint", "Kontrol noktası");
+    m.insert("Resume at line", "Devam satırı");
+    m.insert("Path start", "Yol başlangıcı");
+    m.insert("Back up to the rapid that starts this path", "Bu yolu başlatan hızlı harekete geri dön");
+    m.insert("Preamble", "Giriş kodu");
+    m.insert("line(s) remaining", "satır kaldı");
+    m.insert("Work Offsets", "İş ofsetleri");
+    m.insert("Make this the active coordinate system", "Bunu etkin koordinat sistemi yap");
+    m.insert("Set XY origin here", "XY orijinini buraya ayarla");
+    m.insert("Named offsets", "Adlandırılmış ofsetler");
+    m.insert("Jig name", "Fikstür adı");
+    m.insert("Save the active offset under this name", "Etkin ofseti bu adla kaydet");
+    m.insert("Active", "Etkin");
+    m.insert("Inputs", "Girişler");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "Etkin giriş pinleri (D kapı, P prob, X/Y/Z limit)");
     m.insert("Rapid", "Hızlı");
     m.insert("Passes", "Geçişler");
     m.insert("Pass", "Geçiş");
@@ -202,6 +257,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "Mod");
     m.insert("Serial", "Seri");
     m.insert("Network (TCP/IP)", "Ağ (TCP/IP)");
+    m.insert("Virtual machine", "Sanal makine");
+    m.insert("Replay session", "Oturumu yeniden oynat");
+    m.insert("Record session", "Oturumu kaydet");
+    m.insert("Session file", "Oturum dosyası");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "Bu makinenin hızları ve hareket alanıyla simüle edilen GRBL 1.1");
     m.insert("Host", "Host");
     m.insert("Port", "Port");
     m.insert("Baud", "Baud Hızı");
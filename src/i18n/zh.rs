@@ -31,6 +31,9 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Layout", "布局");
     m.insert("Language", "语言");
     m.insert("Controller", "控制器");
+    m.insert("Auto-detect on connect", "连接时自动检测");
+    m.insert("Probe the serial port for GRBL, grblHAL, FluidNC, Marlin or Smoothieware before connecting", "连接前探测串口上的 GRBL、grblHAL、FluidNC、Marlin 或 Smoothieware");
+    m.insert("Firmware", "固件");
     m.insert("Cuts", "切割");
     m.insert("Move", "移动");
     m.insert("Laser", "激光");
@@ -66,6 +69,7 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Open Project", "打开项目");
     m.insert("Save Project", "保存项目");
     m.insert("Export Job Report", "导出作业报告");
+    m.insert("Export Machine File", "导出机器文件");
     m.insert("Frame", "框架");
     m.insert("Framing laser power (%)", "框架激光功率 (%)");
     m.insert("Dry Run", "空运行");
@@ -78,6 +82,22 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Tiling", "平铺");
     m.insert("Auto Nesting", "自动排版");
     m.insert("Job Queue", "作业队列");
+    m.insert("Estimate Accuracy", "估算准确度");
+    m.insert("No finished jobs measured yet.", "尚未测量任何已完成的任务。");
+    m.insert("Mean error, last 10 jobs:", "平均误差（最近 10 个任务）：");
+    m.insert("Corrections", "校正");
+    m.insert("Vector", "矢量");
+    m.insert("Raster", "光栅");
+    m.insert("not measured", "未测量");
+    m.insert("move", "移动");
+    m.insert("jobs", "个任务");
+    m.insert("Date", "日期");
+    m.insert("Job", "任务");
+    m.insert("Estimated", "估算");
+    m.insert("Actual", "实际");
+    m.insert("Error", "误差");
+    m.insert("Export CSV", "导出 CSV");
+    m.insert("Reset Calibration", "重置校准");
     m.insert("Shortcuts", "快捷键");
     m.insert("Dark UI", "深色界面");
     m.insert("Light UI", "浅色界面");
@@ -133,6 +153,41 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Cut Settings", "切割设置");
     m.insert("Speed", "速度");
     m.insert("Power", "功率");
+    m.insert("Sync from Controller", "从控制器同步");
+    m.insert("Reading machine settings…", "正在读取机器设置…");
+    m.insert("value(s) differ from the profile", "个值与配置文件不同");
+    m.insert("Profile", "配置文件");
+    m.insert("Apply to Profile", "应用到配置文件");
+    m.insert("Hold arrow keys or buttons to jog", "按住方向键或按钮进行点动");
+    m.insert("Continuous (hold to jog)", "连续 (按住点动)");
+    m.insert("Controller Files", "控制器文件");
+    m.insert("No files", "无文件");
+    m.insert("Run on controller", "在控制器上运行");
+    m.insert("Upload current job as", "将当前任务上传为");
+    m.insert("Upload", "上传");
+    m.insert("Uploading…", "正在上传…");
+    m.insert("FluidNC / ESP3D (WebSocket)", "FluidNC / ESP3D (WebSocket)");
+    m.insert("Web UI port; the console is on the next port", "Web 界面端口；控制台在下一个端口");
+    m.insert("Remote serial (RFC 2217)", "远程串口 (RFC 2217)");
+    m.insert("Reset board", "复位主板");
+    m.insert("Pulse DTR/RTS to restart the controller", "脉冲 DTR/RTS 以重启控制器");
+    m.insert("Lines", "信号线");
+    m.insert("Resume Job", "恢复任务");
+    m.insert("Checkpoint", "检查点");
+    m.insert("Resume at line", "从此行恢复");
+    m.insert("Path start", "路径起点");
+    m.insert("Back up to the rapid that starts this path", "退回到该路径起始的快速移动");
+    m.insert("Preamble", "前导代码");
+    m.insert("line(s) remaining", "行剩余");
+    m.insert("Work Offsets", "工件偏移");
+    m.insert("Make this the active coordinate system", "设为当前坐标系");
+    m.insert("Set XY origin here", "将此处设为XY原点");
+    m.insert("Named offsets", "命名偏移");
+    m.insert("Jig name", "夹具名称");
+    m.insert("Save the active offset under this name", "以此名称保存当前偏移");
+    m.insert("Active", "当前");
+    m.insert("Inputs", "输入");
+    m.insert("Active input pins (D door, P probe, X/Y/Z limits)", "活动输入引脚 (D 门, P 探针, X/Y/Z 限位)");
     m.insert("Rapid", "快速");
     m.insert("Speed (mm/min)", "速度 (mm/min)");
     m.insert("Max Power (%)", "最大功率 (%)");
@@ -206,6 +261,11 @@ pub fn entries() -> HashMap<&'static str, &'static str> {
     m.insert("Mode", "模式");
     m.insert("Serial", "串口");
     m.insert("Network (TCP/IP)", "网络 (TCP/IP)");
+    m.insert("Virtual machine", "虚拟机器");
+    m.insert("Replay session", "回放会话");
+    m.insert("Record session", "记录会话");
+    m.insert("Session file", "会话文件");
+    m.insert("Simulated GRBL 1.1 using this machine's rates and travel", "使用本机速度和行程模拟的 GRBL 1.1");
     m.insert("Host", "主机");
     m.insert("Port", "端口");
     m.insert("Baud", "波特率");
@@ -29,7 +29,7 @@ use sample::SampleDriver;
 use imodela::IModelaMillDriver;
 use k3::K3EngraverDriver;
 use hpgl::GoldCutHpglDriver;
-use k40::K40NanoBridgeDriver;
+use k40::{K40EgvDriver, K40NanoBridgeDriver};
 use laos::LaosCutterBridgeDriver;
 use lasersaur::LasersaurGcodeDriver;
 use lasertools::LaserToolsTechnicsDriver;
@@ -37,7 +37,7 @@ use makeblock::MakeBlockXYPlotterDriver;
 use marlin::MarlinLineDriver;
 use epilog::{EpilogHelixBridgeDriver, EpilogZingBridgeDriver};
 use fullspectrum::FullSpectrumBridgeDriver;
-use ruida::RuidaLineBridgeDriver;
+use ruida::{RuidaLineBridgeDriver, RuidaRdDriver};
 use smoothie::SmoothieGcodeDriver;
 use trocen::TrocenLineBridgeDriver;
 
@@ -86,15 +86,31 @@ pub(crate) fn validate_common_job(
         return Err(DriverError::EmptyJob);
     }
 
-    if let Some((_, _, max_x, max_y)) = job.bounds_mm()
-        && (max_x > machine.workspace_x_mm || max_y > machine.workspace_y_mm)
+    if let Some((min_x, min_y, max_x, max_y)) = job.machine_bounds_mm() {
+        let travel = job.travel_box_mm(machine);
+        if max_x > travel.max.0 || max_y > travel.max.1 {
+            return Err(DriverError::JobOutOfBounds {
+                max_x_mm: max_x - travel.min.0,
+                max_y_mm: max_y - travel.min.1,
+                workspace_x_mm: machine.workspace_x_mm,
+                workspace_y_mm: machine.workspace_y_mm,
+            });
+        }
+        if job.wcs_origin_mm.is_some() && (min_x < travel.min.0 || min_y < travel.min.1) {
+            issues.push(DriverValidationIssue::error(format!(
+                "job starts outside machine travel at {min_x:.2},{min_y:.2} mm in {}",
+                job.wcs.map(|s| s.gcode()).unwrap_or("the pinned coordinate system")
+            )));
+        }
+    }
+
+    if let Some(system) = job.wcs
+        && job.wcs_origin_mm.is_none()
     {
-        return Err(DriverError::JobOutOfBounds {
-            max_x_mm: max_x,
-            max_y_mm: max_y,
-            workspace_x_mm: machine.workspace_x_mm,
-            workspace_y_mm: machine.workspace_y_mm,
-        });
+        issues.push(DriverValidationIssue::warning(format!(
+            "{} offset unknown; bounds checked in work coordinates",
+            system.gcode()
+        )));
     }
 
     if job.rotary_enabled {
@@ -157,10 +173,12 @@ pub enum LaserDriverProfile {
     GrblDeviceSafe,
     MarlinLineProtocol,
     RuidaLineProtocol,
+    RuidaNative,
     TrocenLineProtocol,
     SmoothieGcode,
     LasersaurGcode,
     K40NanoBridge,
+    K40Egv,
     GoldCutHpgl,
     EpilogZingBridge,
     EpilogHelixBridge,
@@ -175,6 +193,11 @@ pub enum LaserDriverProfile {
 }
 
 impl LaserDriverProfile {
+    /// Drivers with no transport: they write machine files but cannot run a job
+    pub fn is_export_only(self) -> bool {
+        matches!(self, Self::K40Egv)
+    }
+
     pub fn label(self) -> &'static str {
         match self {
             Self::Auto => "Auto (by controller)",
@@ -182,10 +205,12 @@ impl LaserDriverProfile {
             Self::GrblDeviceSafe => "GRBL Device-Safe",
             Self::MarlinLineProtocol => "Marlin Line Protocol",
             Self::RuidaLineProtocol => "Ruida Line Bridge",
+            Self::RuidaNative => "Ruida RD (native)",
             Self::TrocenLineProtocol => "Trocen Line Bridge",
             Self::SmoothieGcode => "Smoothie GCode",
             Self::LasersaurGcode => "Lasersaur GCode",
             Self::K40NanoBridge => "K40 Nano Bridge",
+            Self::K40Egv => "K40 EGV (M2/M3 Nano)",
             Self::GoldCutHpgl => "GoldCut HPGL",
             Self::EpilogZingBridge => "Epilog Zing Bridge",
             Self::EpilogHelixBridge => "Epilog Helix Bridge",
@@ -209,10 +234,12 @@ impl LaserDriverProfile {
             }
             Self::MarlinLineProtocol => "Marlin compatibility driver with laser command normalization.",
             Self::RuidaLineProtocol => "Line-oriented bridge for Ruida serial gateways.",
+            Self::RuidaNative => "Encodes jobs as native Ruida binary (.rd) files.",
             Self::TrocenLineProtocol => "Line-oriented bridge for Trocen serial gateways.",
             Self::SmoothieGcode => "Smoothie-compatible GCode profile with safety normalization.",
             Self::LasersaurGcode => "Lasersaur-compatible GCode profile for open hardware cutters.",
             Self::K40NanoBridge => "Experimental K40 Nano bridge profile for command adaptation.",
+            Self::K40Egv => "Writes native EGV files for stock Lihuiyu M2/M3 Nano boards (export only).",
             Self::GoldCutHpgl => "HPGL-oriented profile inspired by GoldCut controller workflows.",
             Self::EpilogZingBridge => "Experimental Epilog Zing bridge profile.",
             Self::EpilogHelixBridge => "Experimental Epilog Helix bridge profile.",
@@ -246,6 +273,7 @@ pub fn available_driver_profiles(kind: ControllerKind) -> Vec<LaserDriverProfile
             profiles.push(LaserDriverProfile::SmoothieGcode);
             profiles.push(LaserDriverProfile::LasersaurGcode);
             profiles.push(LaserDriverProfile::K40NanoBridge);
+            profiles.push(LaserDriverProfile::K40Egv);
             profiles.push(LaserDriverProfile::MakeBlockXYPlotter);
             profiles.push(LaserDriverProfile::IModelaMill);
             profiles.push(LaserDriverProfile::K3Engraver);
@@ -263,6 +291,7 @@ pub fn available_driver_profiles(kind: ControllerKind) -> Vec<LaserDriverProfile
         }
         ControllerKind::Ruida => {
             profiles.push(LaserDriverProfile::RuidaLineProtocol);
+            profiles.push(LaserDriverProfile::RuidaNative);
             profiles.push(LaserDriverProfile::EpilogZingBridge);
             profiles.push(LaserDriverProfile::EpilogHelixBridge);
             profiles.push(LaserDriverProfile::FullSpectrumBridge);
@@ -318,6 +347,15 @@ pub trait LaserDriver: Send + Sync {
 
     fn prepare_program(&self, job: &LaserJob, machine: &MachineProfile) -> Result<Vec<String>, DriverError>;
 
+    /// Machine-native file for drivers that speak a binary format (None for GCode-only drivers)
+    fn prepare_binary(
+        &self,
+        _job: &LaserJob,
+        _machine: &MachineProfile,
+    ) -> Result<Option<DriverBinaryProgram>, DriverError> {
+        Ok(None)
+    }
+
     fn send_program(
         &self,
         lines: &[String],
@@ -334,6 +372,13 @@ pub trait LaserDriver: Send + Sync {
     }
 }
 
+/// Encoded job in a controller's own file format
+#[derive(Debug, Clone)]
+pub struct DriverBinaryProgram {
+    pub file_extension: &'static str,
+    pub bytes: Vec<u8>,
+}
+
 pub trait DriverProgramSender {
     fn send_line(&mut self, line: &str);
 }
@@ -343,16 +388,38 @@ pub fn create_driver(
     profile: LaserDriverProfile,
 ) -> Result<Box<dyn LaserDriver>, DriverError> {
     let resolved = resolve_driver_profile(controller_kind, profile);
-    let driver: Box<dyn LaserDriver> = match resolved {
+    let driver = build_driver(resolved);
+    if driver.supports(controller_kind) {
+        Ok(driver)
+    } else if resolved.is_export_only() {
+        Err(DriverError::Unsupported(format!(
+            "{} is export only; use File > Export Machine File",
+            resolved.label()
+        )))
+    } else {
+        Err(DriverError::UnsupportedController(controller_kind))
+    }
+}
+
+/// Driver for writing machine files. Export-only drivers (no transport to stream to) are allowed
+/// as long as the profile is offered for the controller.
+pub fn create_export_driver(controller_kind: ControllerKind, profile: LaserDriverProfile) -> Box<dyn LaserDriver> {
+    build_driver(resolve_driver_profile(controller_kind, profile))
+}
+
+fn build_driver(resolved: LaserDriverProfile) -> Box<dyn LaserDriver> {
+    match resolved {
         LaserDriverProfile::Auto => unreachable!(),
         LaserDriverProfile::GrblGeneric => Box::new(GrblGenericDriver),
         LaserDriverProfile::GrblDeviceSafe => Box::new(GrblDeviceSafeDriver),
         LaserDriverProfile::MarlinLineProtocol => Box::new(MarlinLineDriver),
         LaserDriverProfile::RuidaLineProtocol => Box::new(RuidaLineBridgeDriver),
+        LaserDriverProfile::RuidaNative => Box::new(RuidaRdDriver),
         LaserDriverProfile::TrocenLineProtocol => Box::new(TrocenLineBridgeDriver),
         LaserDriverProfile::SmoothieGcode => Box::new(SmoothieGcodeDriver),
         LaserDriverProfile::LasersaurGcode => Box::new(LasersaurGcodeDriver),
         LaserDriverProfile::K40NanoBridge => Box::new(K40NanoBridgeDriver),
+        LaserDriverProfile::K40Egv => Box::new(K40EgvDriver),
         LaserDriverProfile::GoldCutHpgl => Box::new(GoldCutHpglDriver),
         LaserDriverProfile::EpilogZingBridge => Box::new(EpilogZingBridgeDriver),
         LaserDriverProfile::EpilogHelixBridge => Box::new(EpilogHelixBridgeDriver),
@@ -364,12 +431,6 @@ pub fn create_driver(
         LaserDriverProfile::Sample => Box::new(SampleDriver),
         LaserDriverProfile::IModelaMill => Box::new(IModelaMillDriver),
         LaserDriverProfile::K3Engraver => Box::new(K3EngraverDriver),
-    };
-
-    if driver.supports(controller_kind) {
-        Ok(driver)
-    } else {
-        Err(DriverError::UnsupportedController(controller_kind))
     }
 }
 
@@ -409,6 +470,43 @@ mod tests {
             .any(|i| i.severity == DriverValidationSeverity::Error));
     }
 
+    #[test]
+    fn validation_applies_pinned_wcs_origin() {
+        let driver = create_driver(ControllerKind::Grbl, LaserDriverProfile::Auto).expect("driver");
+        let mut job = LaserJob::from_program_lines(&["G1 X40 Y40".to_string()], "job.gcode");
+        job.wcs = Some(crate::grbl::wcs::CoordSystem::G55);
+        job.wcs_origin_mm = Some((50.0, 10.0));
+        assert!(driver.validate_job(&job, &machine()).expect("fits").is_empty());
+
+        job.wcs_origin_mm = Some((70.0, 10.0));
+        let err = driver.validate_job(&job, &machine()).expect_err("110 mm > 100 mm");
+        assert!(matches!(err, DriverError::JobOutOfBounds { max_x_mm, .. } if max_x_mm == 110.0));
+
+        job.wcs_origin_mm = None;
+        let issues = driver.validate_job(&job, &machine()).expect("unknown offset only warns");
+        assert!(issues.iter().all(|i| i.severity == DriverValidationSeverity::Warning));
+    }
+
+    #[test]
+    fn validation_follows_negative_machine_space() {
+        let driver = create_driver(ControllerKind::Grbl, LaserDriverProfile::Auto).expect("driver");
+        let mut job = LaserJob::from_program_lines(&["G1 X40 Y40".to_string()], "job.gcode");
+        job.wcs = Some(crate::grbl::wcs::CoordSystem::G54);
+        // Homed to the far corner: the bed spans -100..0
+        job.machine_pos_mm = Some((-2.0, -2.0));
+        job.wcs_origin_mm = Some((-90.0, -60.0));
+        assert!(driver.validate_job(&job, &machine()).expect("fits").is_empty());
+        assert!(!job.exceeds_workspace(&machine()));
+
+        job.wcs_origin_mm = Some((-30.0, -60.0));
+        let err = driver.validate_job(&job, &machine()).expect_err("crosses home");
+        assert!(matches!(err, DriverError::JobOutOfBounds { max_x_mm, .. } if max_x_mm == 110.0));
+
+        job.wcs_origin_mm = Some((-120.0, -60.0));
+        let issues = driver.validate_job(&job, &machine()).expect("issue");
+        assert!(issues.iter().any(|i| i.severity == DriverValidationSeverity::Error));
+    }
+
     #[test]
     fn safe_grbl_driver_strips_control_lines() {
         let driver =
@@ -488,6 +586,65 @@ mod tests {
         assert!(lines.iter().all(|line| !line.contains("M220")));
     }
 
+    #[test]
+    fn ruida_native_driver_emits_rd_file() {
+        let driver =
+            create_driver(ControllerKind::Ruida, LaserDriverProfile::RuidaNative).expect("driver");
+        let job = LaserJob::from_program_lines(
+            &[
+                "G0 X5 Y5".to_string(),
+                "M3 S500".to_string(),
+                "G1 X20 Y5 F1200".to_string(),
+                "M5".to_string(),
+            ],
+            "job.gcode",
+        );
+
+        let binary = driver
+            .prepare_binary(&job, &machine())
+            .expect("encode should work")
+            .expect("native driver should produce a binary file");
+        assert_eq!(binary.file_extension, "rd");
+        assert_eq!(binary.bytes.last(), Some(&0x60));
+
+        let grbl = create_driver(ControllerKind::Grbl, LaserDriverProfile::GrblGeneric).expect("driver");
+        assert!(grbl.prepare_binary(&job, &machine()).expect("no error").is_none());
+    }
+
+    #[test]
+    fn k40_egv_driver_emits_egv_file() {
+        // No EGV transport: Run points at the export instead of streaming
+        let Err(DriverError::Unsupported(message)) =
+            create_driver(ControllerKind::Grbl, LaserDriverProfile::K40Egv)
+        else {
+            panic!("EGV driver must not stream");
+        };
+        assert!(message.contains("Export Machine File"));
+        let driver = create_export_driver(ControllerKind::Grbl, LaserDriverProfile::K40Egv);
+        let job = LaserJob::from_program_lines(
+            &[
+                "M3 S1000".to_string(),
+                "G1 X10 Y10 F600".to_string(),
+                "M5".to_string(),
+            ],
+            "job.gcode",
+        );
+
+        let binary = driver
+            .prepare_binary(&job, &machine())
+            .expect("encode should work")
+            .expect("EGV driver should produce a file");
+        assert_eq!(binary.file_extension, "egv");
+        let text = String::from_utf8(binary.bytes).expect("EGV is ASCII");
+        assert!(text.starts_with("Document type : LHYMICRO-GL file"));
+        assert!(text.contains("S1E"));
+        assert!(text.contains("FNSE-"));
+        assert!(matches!(
+            driver.prepare_program(&job, &machine()),
+            Err(DriverError::Unsupported(_))
+        ));
+    }
+
     #[test]
     fn additional_liblasercut_profiles_are_constructible() {
         let sample = LaserJob::from_program_lines(
@@ -510,6 +667,7 @@ mod tests {
             (ControllerKind::Trocen, LaserDriverProfile::LaosCutterBridge),
             (ControllerKind::Marlin, LaserDriverProfile::MakeBlockXYPlotter),
             (ControllerKind::Trocen, LaserDriverProfile::LaserToolsTechnics),
+            (ControllerKind::Ruida, LaserDriverProfile::RuidaNative),
         ];
 
         for (kind, profile) in profiles {
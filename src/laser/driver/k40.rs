@@ -1,8 +1,11 @@
 use crate::config::machine_profile::MachineProfile;
 use crate::controller::ControllerKind;
 
-use super::{DriverError, DriverValidationIssue, LaserDriver, validate_common_job};
+use super::{
+    DriverBinaryProgram, DriverError, DriverValidationIssue, LaserDriver, validate_common_job,
+};
 use crate::laser::job::LaserJob;
+use crate::lihuiyu::egv_job::{EgvEncoder, EgvEncoderConfig, EgvReport, egv_file};
 
 #[derive(Default)]
 pub(crate) struct K40NanoBridgeDriver;
@@ -50,3 +53,80 @@ impl LaserDriver for K40NanoBridgeDriver {
         Ok(out)
     }
 }
+
+/// Native driver for stock Lihuiyu M2/M3 Nano boards: emits the EGV (LHYMICRO-GL) byte protocol.
+/// Export only: there is no EGV transport, so it never streams to a G-code connection.
+#[derive(Default)]
+pub(crate) struct K40EgvDriver;
+
+impl K40EgvDriver {
+    fn encode(&self, job: &LaserJob, machine: &MachineProfile) -> Result<(Vec<u8>, EgvReport), DriverError> {
+        let config = EgvEncoderConfig {
+            board: machine.lihuiyu_board,
+            bed_height_mm: machine.workspace_y_mm,
+            ..Default::default()
+        };
+        EgvEncoder::new(config)
+            .encode_job(job)
+            .map_err(DriverError::Unsupported)
+    }
+}
+
+impl LaserDriver for K40EgvDriver {
+    fn model_name(&self) -> &'static str {
+        "K40 EGV (Lihuiyu Nano)"
+    }
+
+    fn supports(&self, _kind: ControllerKind) -> bool {
+        false
+    }
+
+    fn validate_job(
+        &self,
+        job: &LaserJob,
+        machine: &MachineProfile,
+    ) -> Result<Vec<DriverValidationIssue>, DriverError> {
+        let mut issues = validate_common_job(job, machine)?;
+        if job.lines.iter().any(|line| line.contains("G2") || line.contains("G3")) {
+            issues.push(DriverValidationIssue::warning(
+                "EGV encoder approximates arc moves (G2/G3) as straight segments",
+            ));
+        }
+
+        let (_, report) = self.encode(job, machine)?;
+        if report.mixed_power {
+            issues.push(DriverValidationIssue::warning(
+                "Lihuiyu Nano boards have no power control; all cuts run at the panel power",
+            ));
+        }
+        if report.raster_fallbacks > 0 {
+            issues.push(DriverValidationIssue::warning(format!(
+                "{} raster part(s) are not bidirectional with a fixed pitch and will be cut as vectors",
+                report.raster_fallbacks
+            )));
+        }
+        Ok(issues)
+    }
+
+    fn prepare_program(
+        &self,
+        _job: &LaserJob,
+        _machine: &MachineProfile,
+    ) -> Result<Vec<String>, DriverError> {
+        Err(DriverError::Unsupported(
+            "K40 EGV output cannot be streamed; export an .egv machine file instead".to_string(),
+        ))
+    }
+
+    fn prepare_binary(
+        &self,
+        job: &LaserJob,
+        machine: &MachineProfile,
+    ) -> Result<Option<DriverBinaryProgram>, DriverError> {
+        let (commands, _) = self.encode(job, machine)?;
+        Ok(Some(DriverBinaryProgram {
+            file_extension: "egv",
+            bytes: egv_file(&commands),
+        }))
+    }
+}
@@ -1,8 +1,12 @@
 use crate::config::machine_profile::MachineProfile;
 use crate::controller::ControllerKind;
 
-use super::{DriverError, DriverValidationIssue, LaserDriver, validate_common_job};
+use super::{
+    DriverBinaryProgram, DriverError, DriverValidationIssue, LaserDriver, validate_common_job,
+};
 use crate::laser::job::LaserJob;
+use crate::ruida::protocol::DEFAULT_MAGIC;
+use crate::ruida::rd_job::{RdEncoderConfig, RdProgram};
 
 #[derive(Default)]
 pub(crate) struct RuidaLineBridgeDriver;
@@ -112,3 +116,77 @@ impl LaserDriver for RuidaLineBridgeDriver {
         Ok(out)
     }
 }
+
+/// Native Ruida driver: encodes the job to the controller's binary `.rd` format
+#[derive(Default)]
+pub(crate) struct RuidaRdDriver;
+
+impl RuidaRdDriver {
+    fn encode(&self, job: &LaserJob, machine: &MachineProfile) -> Result<RdProgram, DriverError> {
+        // Keep M7/M8/M9 here: unlike the bridge, the RD format carries air-assist flags.
+        let lines: Vec<String> = job.lines.iter().filter_map(|l| sanitize_line(l)).collect();
+        let config = RdEncoderConfig {
+            bed_height_mm: machine.workspace_y_mm,
+            s_max: machine.max_spindle_s,
+            ..Default::default()
+        };
+        RdProgram::from_gcode_lines(&lines, &config).map_err(DriverError::Unsupported)
+    }
+}
+
+impl LaserDriver for RuidaRdDriver {
+    fn model_name(&self) -> &'static str {
+        "Ruida RD"
+    }
+
+    fn supports(&self, kind: ControllerKind) -> bool {
+        kind == ControllerKind::Ruida
+    }
+
+    fn validate_job(
+        &self,
+        job: &LaserJob,
+        machine: &MachineProfile,
+    ) -> Result<Vec<DriverValidationIssue>, DriverError> {
+        let mut issues = validate_common_job(job, machine)?;
+
+        if job
+            .lines
+            .iter()
+            .any(|line| line.contains("G2") || line.contains("G3"))
+        {
+            issues.push(DriverValidationIssue::warning(
+                "Ruida RD encoder approximates arc moves (G2/G3) as straight segments",
+            ));
+        }
+
+        let program = self.encode(job, machine)?;
+        if program.cut_count() == 0 {
+            issues.push(DriverValidationIssue::warning(
+                "Ruida RD file contains no laser-on moves",
+            ));
+        }
+
+        Ok(issues)
+    }
+
+    fn prepare_program(
+        &self,
+        job: &LaserJob,
+        machine: &MachineProfile,
+    ) -> Result<Vec<String>, DriverError> {
+        RuidaLineBridgeDriver.prepare_program(job, machine)
+    }
+
+    fn prepare_binary(
+        &self,
+        job: &LaserJob,
+        machine: &MachineProfile,
+    ) -> Result<Option<DriverBinaryProgram>, DriverError> {
+        let program = self.encode(job, machine)?;
+        Ok(Some(DriverBinaryProgram {
+            file_extension: "rd",
+            bytes: program.to_rd_bytes(DEFAULT_MAGIC),
+        }))
+    }
+}
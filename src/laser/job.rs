@@ -1,5 +1,8 @@
 use crate::config::machine_profile::MachineProfile;
+use crate::controller::jog::JogLimits;
 use crate::gcode::file::GCodeFile;
+use crate::grbl::types::GPoint;
+use crate::grbl::wcs::CoordSystem;
 
 #[derive(Clone, Debug, Default)]
 pub struct RasterPartConfig {
@@ -32,6 +35,14 @@ pub struct LaserJob {
     pub start_y_mm: f32,
     pub rotary_enabled: bool,
     pub rotary_diameter_mm: Option<f32>,
+    /// Coordinate system the job is pinned to (selected before the first move)
+    pub wcs: Option<CoordSystem>,
+    /// Machine XY of the pinned system's origin, when known from `$#`
+    pub wcs_origin_mm: Option<(f32, f32)>,
+    /// Coordinate system active before the job, selected again when it ends
+    pub restore_wcs: Option<CoordSystem>,
+    /// Last reported machine XY; tells which side of home the machine travels on
+    pub machine_pos_mm: Option<(f32, f32)>,
 }
 
 impl LaserJob {
@@ -46,6 +57,10 @@ impl LaserJob {
             start_y_mm: 0.0,
             rotary_enabled: false,
             rotary_diameter_mm: None,
+            wcs: None,
+            wcs_origin_mm: None,
+            restore_wcs: None,
+            machine_pos_mm: None,
         }
     }
 
@@ -79,6 +94,10 @@ impl LaserJob {
             start_y_mm: 0.0,
             rotary_enabled: false,
             rotary_diameter_mm: None,
+            wcs: None,
+            wcs_origin_mm: None,
+            restore_wcs: None,
+            machine_pos_mm: None,
         }
     }
 
@@ -108,11 +127,30 @@ impl LaserJob {
         file.bounds()
     }
 
+    /// Bounds in machine coordinates: work bounds shifted by the pinned origin
+    pub fn machine_bounds_mm(&self) -> Option<(f32, f32, f32, f32)> {
+        let (min_x, min_y, max_x, max_y) = self.bounds_mm()?;
+        let (ox, oy) = self.wcs_origin_mm.unwrap_or_default();
+        Some((min_x + ox, min_y + oy, max_x + ox, max_y + oy))
+    }
+
+    /// Box `machine_bounds_mm` must fit in: the bed in work coordinates, or for a pinned job the
+    /// machine travel, negative when GRBL homes to the far corner (as [`JogLimits::around`])
+    pub fn travel_box_mm(&self, machine: &MachineProfile) -> JogLimits {
+        let Some((ox, oy)) = self.wcs_origin_mm else {
+            return JogLimits::around(GPoint::zero(), machine.workspace_x_mm, machine.workspace_y_mm);
+        };
+        let (px, py) = self.machine_pos_mm.unwrap_or((ox, oy));
+        let reference = GPoint::new(px.min(ox), py.min(oy), 0.0);
+        JogLimits::around(reference, machine.workspace_x_mm, machine.workspace_y_mm)
+    }
+
     pub fn exceeds_workspace(&self, machine: &MachineProfile) -> bool {
-        let Some((min_x, min_y, max_x, max_y)) = self.bounds_mm() else {
+        let Some((min_x, min_y, max_x, max_y)) = self.machine_bounds_mm() else {
             return false;
         };
-        min_x < 0.0 || min_y < 0.0 || max_x > machine.workspace_x_mm || max_y > machine.workspace_y_mm
+        let travel = self.travel_box_mm(machine);
+        min_x < travel.min.0 || min_y < travel.min.1 || max_x > travel.max.0 || max_y > travel.max.1
     }
 }
 
@@ -2,8 +2,8 @@ use crate::config::machine_profile::MachineProfile;
 use crate::controller::ControllerKind;
 
 use super::driver::{
-    DriverError, DriverValidationIssue, LaserDriverProfile, create_driver,
-    effective_driver_profile,
+    DriverBinaryProgram, DriverError, DriverValidationIssue, LaserDriverProfile, create_driver,
+    create_export_driver, effective_driver_profile,
 };
 use super::job::LaserJob;
 
@@ -34,7 +34,21 @@ pub fn prepare_program(
         ));
     }
 
-    let lines = driver.prepare_program(job, machine)?;
+    let mut lines = driver.prepare_program(job, machine)?;
+    if let Some(system) = job.wcs
+        && matches!(controller_kind, ControllerKind::Grbl | ControllerKind::Marlin)
+    {
+        lines.insert(0, system.gcode().to_string());
+        // Leave the controller in the system it was in, ahead of any program end
+        if let Some(previous) = job.restore_wcs.filter(|&previous| previous != system) {
+            let end = lines
+                .iter()
+                .rposition(|line| !line.trim().is_empty())
+                .filter(|&i| is_program_end(&lines[i]))
+                .unwrap_or(lines.len());
+            lines.insert(end, previous.gcode().to_string());
+        }
+    }
 
     Ok(PreparedProgram {
         driver_name: driver.model_name(),
@@ -44,6 +58,33 @@ pub fn prepare_program(
     })
 }
 
+fn is_program_end(line: &str) -> bool {
+    crate::gcode::interpreter::words(line)
+        .iter()
+        .any(|&(c, v)| c == 'M' && matches!(v.round() as i32, 2 | 30))
+}
+
+/// Encode the job in the driver's native file format, if it has one
+pub fn prepare_machine_file(
+    controller_kind: ControllerKind,
+    machine: &MachineProfile,
+    job: &LaserJob,
+) -> Result<Option<DriverBinaryProgram>, DriverError> {
+    let driver = create_export_driver(controller_kind, machine.laser_driver_profile);
+
+    let validation_issues = driver.validate_job(job, machine)?;
+    if validation_issues
+        .iter()
+        .any(|issue| issue.severity == super::driver::DriverValidationSeverity::Error)
+    {
+        return Err(DriverError::Unsupported(
+            "job validation contains blocking issues".to_string(),
+        ));
+    }
+
+    driver.prepare_binary(job, machine)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,6 +126,28 @@ mod tests {
         assert_eq!(prepared.lines, expected);
     }
 
+    #[test]
+    fn pipeline_selects_pinned_coordinate_system() {
+        let machine = MachineProfile::default();
+        let mut job = LaserJob::from_program_lines(&["G1 X10 Y5 F1000".to_string()], "sample.gcode");
+        job.wcs = Some(crate::grbl::wcs::CoordSystem::G56);
+
+        let prepared =
+            prepare_program(ControllerKind::Grbl, &machine, &job).expect("pipeline should succeed");
+
+        assert_eq!(prepared.lines.first().map(String::as_str), Some("G56"));
+
+        job.restore_wcs = Some(crate::grbl::wcs::CoordSystem::G54);
+        let prepared =
+            prepare_program(ControllerKind::Grbl, &machine, &job).expect("pipeline should succeed");
+        assert_eq!(prepared.lines.last().map(String::as_str), Some("G54"));
+
+        job.lines.push("M30".to_string());
+        let prepared =
+            prepare_program(ControllerKind::Grbl, &machine, &job).expect("pipeline should succeed");
+        assert_eq!(prepared.lines[prepared.lines.len() - 2..], ["G54", "M30"]);
+    }
+
     #[test]
     fn pipeline_marlin_normalizes_m4_golden() {
         let mut machine = MachineProfile::default();
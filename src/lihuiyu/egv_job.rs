@@ -0,0 +1,516 @@
+#![allow(dead_code)]
+
+use crate::gcode::interpreter;
+use crate::laser::job::{LaserJob, LaserJobPart};
+
+use super::protocol::*;
+
+/// Header K40 Whisperer and LaserDRW write at the top of `.egv` files
+pub const EGV_FILE_HEADER: &str = "Document type : LHYMICRO-GL file\nFile version: 1.0.01\n%0%0%0%0%\n";
+
+#[derive(Debug, Clone, Copy)]
+pub struct EgvEncoderConfig {
+    pub board: LihuiyuBoard,
+    /// The board homes top-left, so Y is measured down from the bed top
+    pub flip_y: bool,
+    pub bed_height_mm: f32,
+}
+
+impl Default for EgvEncoderConfig {
+    fn default() -> Self {
+        Self {
+            board: LihuiyuBoard::default(),
+            flip_y: true,
+            bed_height_mm: 230.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EgvOp {
+    Move { x: i64, y: i64 },
+    Cut { x: i64, y: i64, speed_mm_s: f32 },
+}
+
+/// Encoding summary surfaced as driver warnings
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EgvReport {
+    pub blocks: usize,
+    pub raster_blocks: usize,
+    /// Raster parts that could not use board-side stepping and were cut as vectors
+    pub raster_fallbacks: usize,
+    /// The Nano has no PWM: any S value above zero fires at the panel's power setting
+    pub mixed_power: bool,
+}
+
+/// Accumulates the EGV command stream for one job
+pub struct EgvEncoder {
+    config: EgvEncoderConfig,
+    out: String,
+    pos: (i64, i64),
+    report: EgvReport,
+    seen_power: Option<f32>,
+}
+
+impl EgvEncoder {
+    pub fn new(config: EgvEncoderConfig) -> Self {
+        Self {
+            config,
+            out: String::new(),
+            pos: (0, 0),
+            report: EgvReport::default(),
+            seen_power: None,
+        }
+    }
+
+    /// Encode every part of the job and return the command stream (no file header)
+    pub fn encode_job(mut self, job: &LaserJob) -> Result<(Vec<u8>, EgvReport), String> {
+        for part in &job.parts {
+            match part {
+                LaserJobPart::Vector { lines } => {
+                    let ops = self.interpret(lines)?;
+                    self.emit_vector(&ops);
+                }
+                LaserJobPart::Raster { lines, .. } => {
+                    let ops = self.interpret(lines)?;
+                    if !self.emit_raster(&ops) {
+                        self.report.raster_fallbacks += 1;
+                        self.emit_vector(&ops);
+                    }
+                }
+            }
+        }
+        // Park back at the origin like the stock software does.
+        self.rapid_to((0, 0));
+        Ok((self.out.into_bytes(), self.report))
+    }
+
+    fn device_steps(&self, x_mm: f32, y_mm: f32) -> (i64, i64) {
+        let y_mm = if self.config.flip_y {
+            self.config.bed_height_mm - y_mm
+        } else {
+            y_mm
+        };
+        (mm_to_steps(x_mm), mm_to_steps(y_mm))
+    }
+
+    fn interpret(&mut self, lines: &[String]) -> Result<Vec<EgvOp>, String> {
+        let mut ops = Vec::new();
+        for block in interpreter::interpret(lines) {
+            let Some(motion) = block.motion() else {
+                continue;
+            };
+            if !motion.is_xy() {
+                continue;
+            }
+            let cutting = !block.is_rapid() && motion.burns();
+            if cutting {
+                if motion.feed <= 0.0 {
+                    return Err(format!("cut move without feed rate: {}", lines[block.line].trim()));
+                }
+                match self.seen_power {
+                    Some(p) if (p - motion.power).abs() > f32::EPSILON => self.report.mixed_power = true,
+                    _ => self.seen_power = Some(motion.power),
+                }
+            }
+            // Arcs go out as short lines
+            for point in block.waypoints() {
+                let (x, y) = self.device_steps(point.x, point.y);
+                ops.push(if cutting {
+                    EgvOp::Cut {
+                        x,
+                        y,
+                        speed_mm_s: motion.feed / 60.0,
+                    }
+                } else {
+                    EgvOp::Move { x, y }
+                });
+            }
+        }
+        Ok(ops)
+    }
+
+    /// Rapid (non-program) move: `I<moves>S1P`
+    fn rapid_to(&mut self, target: (i64, i64)) {
+        if target == self.pos {
+            return;
+        }
+        self.out.push(CMD_INIT as char);
+        let (dx, dy) = (target.0 - self.pos.0, target.1 - self.pos.1);
+        // Rapids run axis by axis; diagonals are only valid in program mode.
+        push_axis(&mut self.out, dx, DIR_RIGHT, DIR_LEFT);
+        push_axis(&mut self.out, dy, DIR_BOTTOM, DIR_TOP);
+        self.out.push_str(SUFFIX_RAPID);
+        self.out.push('\n');
+        self.pos = target;
+    }
+
+    fn begin_program(&mut self, speed_code: &str, x_dir: u8, y_dir: u8) {
+        self.out.push(CMD_INIT as char);
+        self.out.push_str(speed_code);
+        self.out.push('N');
+        self.out.push(x_dir as char);
+        self.out.push(y_dir as char);
+        self.out.push_str(SUFFIX_PROGRAM);
+    }
+
+    fn end_program(&mut self) {
+        self.out.push_str(FINISH);
+        self.out.push('\n');
+    }
+
+    fn emit_vector(&mut self, ops: &[EgvOp]) {
+        let mut idx = 0;
+        while idx < ops.len() {
+            // Rapid through travel until the next cut.
+            let Some(first_cut) = ops[idx..].iter().position(|op| matches!(op, EgvOp::Cut { .. })) else {
+                if let Some(EgvOp::Move { x, y }) = ops.last() {
+                    self.rapid_to((*x, *y));
+                }
+                return;
+            };
+            if first_cut > 0
+                && let EgvOp::Move { x, y } = ops[idx + first_cut - 1]
+            {
+                self.rapid_to((x, y));
+            }
+            idx += first_cut;
+
+            let EgvOp::Cut { speed_mm_s, .. } = ops[idx] else {
+                unreachable!()
+            };
+            // A block runs until the speed changes; travel inside it stays in program mode.
+            let end = ops[idx..]
+                .iter()
+                .position(|op| matches!(op, EgvOp::Cut { speed_mm_s: s, .. } if (s - speed_mm_s).abs() > 0.001))
+                .map_or(ops.len(), |n| idx + n);
+            let end = ops[idx..end]
+                .iter()
+                .rposition(|op| matches!(op, EgvOp::Cut { .. }))
+                .map_or(end, |n| idx + n + 1);
+
+            let code = vector_speed_code(self.config.board, speed_mm_s);
+            self.begin_program(&code, DIR_RIGHT, DIR_BOTTOM);
+            let mut laser = false;
+            for op in &ops[idx..end] {
+                let (target, cut) = match *op {
+                    EgvOp::Move { x, y } => ((x, y), false),
+                    EgvOp::Cut { x, y, .. } => ((x, y), true),
+                };
+                if cut != laser {
+                    self.out.push(if cut { CMD_LASER_ON } else { CMD_LASER_OFF } as char);
+                    laser = cut;
+                }
+                push_line(&mut self.out, target.0 - self.pos.0, target.1 - self.pos.1);
+                self.pos = target;
+            }
+            if laser {
+                self.out.push(CMD_LASER_OFF as char);
+            }
+            self.end_program();
+            self.report.blocks += 1;
+            idx = end;
+        }
+    }
+
+    /// Raster mode: horizontal passes only, the board steps Y by `step` on every X reversal.
+    /// Returns false when the part does not have that shape.
+    fn emit_raster(&mut self, ops: &[EgvOp]) -> bool {
+        let Some(plan) = plan_raster(ops) else {
+            return false;
+        };
+        self.rapid_to(plan.start);
+        let code = raster_speed_code(self.config.board, plan.speed_mm_s, plan.step);
+        let y_dir = if plan.step_down { DIR_BOTTOM } else { DIR_TOP };
+        let x_dir = if plan.rows[0].0 >= plan.start.0 { DIR_RIGHT } else { DIR_LEFT };
+        self.begin_program(&code, x_dir, y_dir);
+
+        let mut laser = false;
+        for &(row_end_x, ref spans) in &plan.rows {
+            for &(x, cut) in spans {
+                if cut != laser {
+                    self.out.push(if cut { CMD_LASER_ON } else { CMD_LASER_OFF } as char);
+                    laser = cut;
+                }
+                push_axis(&mut self.out, x - self.pos.0, DIR_RIGHT, DIR_LEFT);
+                self.pos.0 = x;
+            }
+            debug_assert_eq!(self.pos.0, row_end_x);
+        }
+        if laser {
+            self.out.push(CMD_LASER_OFF as char);
+        }
+        self.pos = plan.end;
+        self.end_program();
+        self.report.blocks += 1;
+        self.report.raster_blocks += 1;
+        true
+    }
+}
+
+struct RasterPlan {
+    start: (i64, i64),
+    end: (i64, i64),
+    speed_mm_s: f32,
+    step: u32,
+    step_down: bool,
+    /// (row end x, spans of (x, laser on)) in execution order
+    rows: Vec<(i64, Vec<(i64, bool)>)>,
+}
+
+/// Check that a raster part is a bidirectional scan with a constant row pitch
+fn plan_raster(ops: &[EgvOp]) -> Option<RasterPlan> {
+    let first = ops.iter().position(|op| matches!(op, EgvOp::Cut { .. }))?;
+    let start = match first.checked_sub(1).map(|i| ops[i]) {
+        Some(EgvOp::Move { x, y }) => (x, y),
+        _ => return None,
+    };
+    let mut speed: Option<f32> = None;
+    let mut rows: Vec<(i64, Vec<(i64, bool)>)> = vec![(start.0, Vec::new())];
+    let mut pos = start;
+    let mut step: Option<i64> = None;
+    let mut last_dir = 0i64;
+
+    for op in &ops[first..] {
+        let (target, cut, op_speed) = match *op {
+            EgvOp::Move { x, y } => ((x, y), false, None),
+            EgvOp::Cut { x, y, speed_mm_s } => ((x, y), true, Some(speed_mm_s)),
+        };
+        if let Some(s) = op_speed {
+            match speed {
+                Some(existing) if (existing - s).abs() > 0.001 => return None,
+                _ => speed = Some(s),
+            }
+        }
+        if target == pos {
+            continue;
+        }
+        if target.1 != pos.1 {
+            // Row change: pure Y move with the laser off, constant pitch.
+            if target.0 != pos.0 || cut {
+                return None;
+            }
+            let dy = target.1 - pos.1;
+            match step {
+                Some(s) if s != dy => return None,
+                _ => step = Some(dy),
+            }
+            rows.push((pos.0, Vec::new()));
+            pos = target;
+            continue;
+        }
+        let dir = (target.0 - pos.0).signum();
+        let row_count = rows.len();
+        let row = rows.last_mut()?;
+        if row.1.is_empty() && row_count > 1 && dir == last_dir {
+            // Unidirectional scans need the board to reverse, which would step Y.
+            return None;
+        }
+        if !row.1.is_empty() && dir != last_dir {
+            return None;
+        }
+        row.1.push((target.0, cut));
+        row.0 = target.0;
+        last_dir = dir;
+        pos = target;
+    }
+
+    let step = step?;
+    rows.retain(|(_, spans)| !spans.is_empty());
+    if step.unsigned_abs() > 999 || rows.is_empty() {
+        return None;
+    }
+    Some(RasterPlan {
+        start,
+        end: pos,
+        speed_mm_s: speed?,
+        step: step.unsigned_abs() as u32,
+        step_down: step > 0,
+        rows,
+    })
+}
+
+fn push_axis(out: &mut String, delta: i64, positive: u8, negative: u8) {
+    if delta == 0 {
+        return;
+    }
+    out.push(if delta > 0 { positive } else { negative } as char);
+    out.push_str(&encode_distance(delta.unsigned_abs() as u32));
+}
+
+/// Program-mode line: split into runs of orthogonal and 45° diagonal steps (Bresenham)
+fn push_line(out: &mut String, dx: i64, dy: i64) {
+    let (ax, ay) = (dx.unsigned_abs(), dy.unsigned_abs());
+    if ax == 0 || ay == 0 {
+        push_axis(out, dx, DIR_RIGHT, DIR_LEFT);
+        push_axis(out, dy, DIR_BOTTOM, DIR_TOP);
+        return;
+    }
+    let x_dir = if dx > 0 { DIR_RIGHT } else { DIR_LEFT };
+    let y_dir = if dy > 0 { DIR_BOTTOM } else { DIR_TOP };
+    let (major, minor) = (ax.max(ay) as i64, ax.min(ay) as i64);
+
+    let mut runs: Vec<(bool, u64)> = Vec::new();
+    let mut err = 0i64;
+    for _ in 0..major {
+        err += minor;
+        let diagonal = 2 * err >= major;
+        if diagonal {
+            err -= major;
+        }
+        match runs.last_mut() {
+            Some((kind, n)) if *kind == diagonal => *n += 1,
+            _ => runs.push((diagonal, 1)),
+        }
+    }
+
+    for (diagonal, n) in runs {
+        if diagonal {
+            out.push(x_dir as char);
+            out.push(y_dir as char);
+            out.push(CMD_DIAGONAL as char);
+        } else {
+            out.push(if ax >= ay { x_dir } else { y_dir } as char);
+        }
+        out.push_str(&encode_distance(n as u32));
+    }
+}
+
+/// Full `.egv` file: text header followed by the command stream
+pub fn egv_file(commands: &[u8]) -> Vec<u8> {
+    let mut file = EGV_FILE_HEADER.as_bytes().to_vec();
+    file.extend_from_slice(commands);
+    file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::laser::job::{JobMetadata, RasterPartConfig};
+
+    fn config() -> EgvEncoderConfig {
+        EgvEncoderConfig {
+            flip_y: false,
+            ..Default::default()
+        }
+    }
+
+    fn lines(src: &[&str]) -> Vec<String> {
+        src.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn encode(parts: Vec<LaserJobPart>) -> (String, EgvReport) {
+        let job = LaserJob::from_parts(JobMetadata::default(), parts);
+        let (bytes, report) = EgvEncoder::new(config()).encode_job(&job).expect("encode");
+        (String::from_utf8(bytes).expect("ascii"), report)
+    }
+
+    #[test]
+    fn vector_square_golden_stream() {
+        // 2.54 mm = 100 mils per side, cut at 25.4 mm/s
+        let (egv, report) = encode(vec![LaserJobPart::Vector {
+            lines: lines(&[
+                "G0 X2.54 Y2.54",
+                "M3 S1000",
+                "G1 X5.08 F1524",
+                "G1 Y5.08",
+                "G1 X2.54",
+                "G1 Y2.54",
+                "M5",
+            ]),
+        }]);
+        assert_eq!(report.blocks, 1);
+        assert_eq!(
+            egv,
+            "IB100R100S1P\n\
+             ICV1881681017151NBRS1EDB100R100T100L100UFNSE-\n\
+             IT100L100S1P\n"
+        );
+    }
+
+    #[test]
+    fn diagonal_moves_use_m_runs() {
+        let mut out = String::new();
+        push_line(&mut out, 10, 10);
+        assert_eq!(out, "BRMj");
+
+        let mut out = String::new();
+        push_line(&mut out, 4, -2);
+        // Bresenham: diag, orth, diag, orth
+        assert_eq!(out, "BLMaBaBLMaBa");
+    }
+
+    #[test]
+    fn speed_change_starts_new_block() {
+        let (egv, report) = encode(vec![LaserJobPart::Vector {
+            lines: lines(&[
+                "M3 S1000",
+                "G1 X2.54 F600",
+                "G1 X5.08 F1200",
+                "M5",
+            ]),
+        }]);
+        assert_eq!(report.blocks, 2);
+        assert_eq!(egv.matches("FNSE-").count(), 2);
+    }
+
+    #[test]
+    fn bidirectional_raster_uses_board_stepping() {
+        let (egv, report) = encode(vec![LaserJobPart::Raster {
+            lines: lines(&[
+                "G0 X0 Y0",
+                "M3 S500",
+                "G1 X2.54 F6000",
+                "G0 Y0.0762",
+                "G1 X0",
+                "G0 Y0.1524",
+                "G1 X2.54",
+                "M5",
+            ]),
+            config: RasterPartConfig::default(),
+        }]);
+        assert_eq!(report.raster_blocks, 1);
+        assert_eq!(report.raster_fallbacks, 0);
+        // 100 mm/s raster at 3 mil pitch; rows alternate B/T with no explicit Y moves
+        assert!(egv.contains("G003NBRS1E"), "{egv}");
+        assert!(egv.contains("DB100T100B100U"), "{egv}");
+    }
+
+    #[test]
+    fn unidirectional_raster_falls_back_to_vector() {
+        let (_, report) = encode(vec![LaserJobPart::Raster {
+            lines: lines(&[
+                "G0 X0 Y0",
+                "M3 S500",
+                "G1 X2.54 F6000",
+                "G0 X0 Y0.0762",
+                "G1 X2.54",
+                "M5",
+            ]),
+            config: RasterPartConfig::default(),
+        }]);
+        assert_eq!(report.raster_blocks, 0);
+        assert_eq!(report.raster_fallbacks, 1);
+    }
+
+    #[test]
+    fn varying_power_is_reported() {
+        let (_, report) = encode(vec![LaserJobPart::Vector {
+            lines: lines(&["M3 S200", "G1 X1 F600", "G1 X2 S800", "M5"]),
+        }]);
+        assert!(report.mixed_power);
+    }
+
+    #[test]
+    fn packets_carry_the_stream() {
+        let (egv, _) = encode(vec![LaserJobPart::Vector {
+            lines: lines(&["M3 S1000", "G1 X25.4 Y25.4 F600", "M5"]),
+        }]);
+        let commands = egv.replace('\n', "");
+        let sent = packets(egv.as_bytes());
+        assert_eq!(sent.len(), commands.len().div_ceil(PACKET_PAYLOAD_LEN));
+        let payload = packet_payload(&sent);
+        assert!(!payload.contains(&b'\n'));
+        assert!(payload.starts_with(commands.as_bytes()));
+    }
+}
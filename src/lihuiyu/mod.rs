@@ -0,0 +1,2 @@
+pub mod egv_job;
+pub mod protocol;
@@ -0,0 +1,289 @@
+#![allow(dead_code)]
+
+use serde::{Deserialize, Serialize};
+
+/// Motion unit of every Lihuiyu board: 1 mil (1/1000 inch)
+pub const MM_PER_STEP: f32 = 0.0254;
+
+/// Direction letters (board frame: origin top-left, Y grows toward the front)
+pub const DIR_RIGHT: u8 = b'B';
+pub const DIR_LEFT: u8 = b'T';
+pub const DIR_TOP: u8 = b'L';
+pub const DIR_BOTTOM: u8 = b'R';
+pub const CMD_DIAGONAL: u8 = b'M';
+pub const CMD_LASER_ON: u8 = b'D';
+pub const CMD_LASER_OFF: u8 = b'U';
+pub const CMD_INIT: u8 = b'I';
+
+/// Executes the buffered rapid moves immediately
+pub const SUFFIX_RAPID: &str = "S1P";
+/// Enters program (cutting) mode with the preceding speed code
+pub const SUFFIX_PROGRAM: &str = "S1E";
+/// Leaves program mode and waits until the board is idle
+pub const FINISH: &str = "FNSE-";
+
+/// Speeds below this use the low-speed ("C" suffix) equation on boards that have it
+pub const SLOW_SPEED_MM_S: f32 = 7.0;
+
+/// Ratio of diagonal to orthogonal step period on M2-family boards
+const DIAGONAL_RATIO: f64 = 0.261_199_033_289;
+
+/// USB packet layout: header, 30 payload bytes, footer, CRC
+pub const PACKET_HEADER: u8 = 0xA6;
+pub const PACKET_PAYLOAD_LEN: usize = 30;
+pub const PACKET_LEN: usize = PACKET_PAYLOAD_LEN + 4;
+pub const PACKET_PAD: u8 = b'F';
+
+/// Board revision printed on the Lihuiyu controller; each uses its own speed table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum LihuiyuBoard {
+    A,
+    B,
+    B1,
+    B2,
+    M,
+    M1,
+    #[default]
+    M2,
+    M3,
+}
+
+impl LihuiyuBoard {
+    pub const ALL: [LihuiyuBoard; 8] = [
+        Self::A,
+        Self::B,
+        Self::B1,
+        Self::B2,
+        Self::M,
+        Self::M1,
+        Self::M2,
+        Self::M3,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::A => "6C6879-LASER-A",
+            Self::B => "6C6879-LASER-B",
+            Self::B1 => "6C6879-LASER-B1",
+            Self::B2 => "6C6879-LASER-B2",
+            Self::M => "6C6879-LASER-M",
+            Self::M1 => "6C6879-LASER-M1",
+            Self::M2 => "6C6879-LASER-M2",
+            Self::M3 => "6C6879-LASER-M3",
+        }
+    }
+
+    /// Newer boards with the low-speed table and diagonal correction
+    fn is_m2_family(self) -> bool {
+        matches!(self, Self::B2 | Self::M2 | Self::M3)
+    }
+
+    /// Step period equation `period = b + m / speed_in_inch_per_s`
+    fn speed_equation(self, slow: bool) -> (f64, f64) {
+        match self {
+            Self::B2 | Self::M2 | Self::M3 if slow => (8.0, 1010.0),
+            Self::B2 | Self::M2 | Self::M3 => (5120.0, 12120.0),
+            Self::B1 | Self::M1 => (896.0, 2000.0),
+            Self::A | Self::B | Self::M => (784.0, 2000.0),
+        }
+    }
+}
+
+/// Acceleration gear picked by the board from the speed (1 = gentlest)
+fn gear_for_speed(mm_per_s: f32, raster: bool) -> u8 {
+    let tiers: [f32; 3] = if raster {
+        [25.4, 127.0, 320.0]
+    } else {
+        [25.4, 60.0, 127.0]
+    };
+    1 + tiers.iter().filter(|&&limit| mm_per_s > limit).count() as u8
+}
+
+/// Encode a 16-bit timer value as two zero-padded decimal bytes ("%03d%03d")
+fn encode_timer(value: u16) -> String {
+    format!("{:03}{:03}", value >> 8, value & 0xFF)
+}
+
+fn step_period(board: LihuiyuBoard, mm_per_s: f32) -> (f64, bool) {
+    let mm_per_s = mm_per_s.max(0.1);
+    let slow = board.is_m2_family() && mm_per_s < SLOW_SPEED_MM_S;
+    let (b, m) = board.speed_equation(slow);
+    (b + m * 25.4 / mm_per_s as f64, slow)
+}
+
+fn timer_value(period: f64) -> u16 {
+    (65536.0 - period).round().clamp(0.0, 65535.0) as u16
+}
+
+/// Speed code for vector cutting: `CV<timer><gear>[<diagonal>][C]`
+pub fn vector_speed_code(board: LihuiyuBoard, mm_per_s: f32) -> String {
+    let (period, slow) = step_period(board, mm_per_s);
+    let mut code = format!(
+        "CV{}{}",
+        encode_timer(timer_value(period)),
+        gear_for_speed(mm_per_s, false)
+    );
+    if board.is_m2_family() {
+        let diagonal = (DIAGONAL_RATIO * period).round().clamp(0.0, 65535.0) as u16;
+        code.push_str(&encode_timer(diagonal));
+    }
+    if slow {
+        code.push('C');
+    }
+    code
+}
+
+/// Speed code for raster engraving: `V<timer><gear>G<step>`; the board steps Y on each X reversal
+pub fn raster_speed_code(board: LihuiyuBoard, mm_per_s: f32, step: u32) -> String {
+    let (period, _) = step_period(board, mm_per_s);
+    format!(
+        "V{}{}G{:03}",
+        encode_timer(timer_value(period)),
+        gear_for_speed(mm_per_s, true),
+        step.min(999)
+    )
+}
+
+/// Encode a distance in mils: `a`..`y` = 1..25, `|a`..`|z` = 26..51, 3 digits up to 254, `z` = 255
+pub fn encode_distance(mut steps: u32) -> String {
+    let mut out = "z".repeat((steps / 255) as usize);
+    steps %= 255;
+    match steps {
+        0 => {}
+        1..=25 => out.push((b'a' + (steps - 1) as u8) as char),
+        26..=51 => {
+            out.push('|');
+            out.push((b'a' + (steps - 26) as u8) as char);
+        }
+        _ => out.push_str(&format!("{steps:03}")),
+    }
+    out
+}
+
+pub fn mm_to_steps(mm: f32) -> i64 {
+    (mm / MM_PER_STEP).round() as i64
+}
+
+/// CRC-8 Dallas/Maxim (reflected 0x31) used by the Nano's USB packets
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        let mut b = byte;
+        for _ in 0..8 {
+            let mix = (crc ^ b) & 0x01;
+            crc >>= 1;
+            if mix != 0 {
+                crc ^= 0x8C;
+            }
+            b >>= 1;
+        }
+    }
+    crc
+}
+
+/// Wrap up to 30 command bytes into one USB packet, padding with `F`
+pub fn build_packet(payload: &[u8]) -> [u8; PACKET_LEN] {
+    let mut body = [PACKET_PAD; PACKET_PAYLOAD_LEN];
+    let len = payload.len().min(PACKET_PAYLOAD_LEN);
+    body[..len].copy_from_slice(&payload[..len]);
+
+    let mut packet = [0u8; PACKET_LEN];
+    packet[0] = PACKET_HEADER;
+    packet[1] = 0x00;
+    packet[2..2 + PACKET_PAYLOAD_LEN].copy_from_slice(&body);
+    packet[PACKET_LEN - 2] = PACKET_HEADER;
+    packet[PACKET_LEN - 1] = crc8(&body);
+    packet
+}
+
+/// Split an EGV command stream into USB packets. Line breaks only lay out `.egv` files;
+/// the board never receives them.
+pub fn packets(commands: &[u8]) -> Vec<[u8; PACKET_LEN]> {
+    let bytes: Vec<u8> = commands
+        .iter()
+        .copied()
+        .filter(|&b| b != b'\n' && b != b'\r')
+        .collect();
+    bytes.chunks(PACKET_PAYLOAD_LEN).map(build_packet).collect()
+}
+
+/// Command bytes carried by packets, padding included
+pub fn packet_payload(packets: &[[u8; PACKET_LEN]]) -> Vec<u8> {
+    packets
+        .iter()
+        .flat_map(|p| p[2..2 + PACKET_PAYLOAD_LEN].iter().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_encoding_golden() {
+        assert_eq!(encode_distance(0), "");
+        assert_eq!(encode_distance(1), "a");
+        assert_eq!(encode_distance(25), "y");
+        assert_eq!(encode_distance(26), "|a");
+        assert_eq!(encode_distance(51), "|z");
+        assert_eq!(encode_distance(52), "052");
+        assert_eq!(encode_distance(254), "254");
+        assert_eq!(encode_distance(255), "z");
+        assert_eq!(encode_distance(300), "z|t");
+        assert_eq!(encode_distance(600), "zz090");
+    }
+
+    #[test]
+    fn m2_speed_codes_match_reference_tables() {
+        // 25.4 mm/s (1 in/s): period 17240 -> timer 48296 = 188/168, diagonal 4503 = 017/151
+        assert_eq!(vector_speed_code(LihuiyuBoard::M2, 25.4), "CV1881681017151");
+        // Slow table below 7 mm/s adds the C suffix
+        let slow = vector_speed_code(LihuiyuBoard::M2, 5.0);
+        assert!(slow.starts_with("CV"));
+        assert!(slow.ends_with('C'));
+        // Older boards have no diagonal correction and no low-speed table
+        assert_eq!(vector_speed_code(LihuiyuBoard::B, 25.4), "CV2450321");
+        assert_eq!(raster_speed_code(LihuiyuBoard::M2, 200.0, 3), "V2292533G003");
+    }
+
+    #[test]
+    fn gears_follow_vector_and_raster_tiers() {
+        assert_eq!(gear_for_speed(20.0, false), 1);
+        assert_eq!(gear_for_speed(50.0, false), 2);
+        assert_eq!(gear_for_speed(100.0, false), 3);
+        assert_eq!(gear_for_speed(200.0, false), 4);
+        assert_eq!(gear_for_speed(100.0, true), 2);
+        assert_eq!(gear_for_speed(300.0, true), 3);
+        assert_eq!(gear_for_speed(400.0, true), 4);
+    }
+
+    #[test]
+    fn crc8_check_value() {
+        assert_eq!(crc8(b"123456789"), 0xA1);
+    }
+
+    #[test]
+    fn packets_are_padded_and_checksummed() {
+        let commands = b"IBzzzS1P";
+        let sent = packets(commands);
+        assert_eq!(sent.len(), 1);
+
+        let packet = sent[0];
+        assert_eq!(packet[0], PACKET_HEADER);
+        assert_eq!(packet[1], 0x00);
+        assert_eq!(&packet[2..10], commands);
+        assert!(packet[10..32].iter().all(|&b| b == PACKET_PAD));
+        assert_eq!(packet[32], PACKET_HEADER);
+        assert_eq!(packet[33], crc8(&packet[2..32]));
+
+        let long = vec![b'B'; 65];
+        let sent = packets(&long);
+        assert_eq!(sent.len(), 3);
+        assert_eq!(&packet_payload(&sent)[..65], &long[..]);
+
+        // File line breaks stay out of the packets
+        let sent = packets(b"IBzzzS1P\nITzzzS1P\n");
+        assert_eq!(&packet_payload(&sent)[..16], b"IBzzzS1PITzzzS1P");
+        assert!(packet_payload(&sent).iter().all(|&b| b != b'\n'));
+    }
+}
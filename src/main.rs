@@ -11,7 +11,9 @@ mod grbl;
 mod i18n;
 mod imaging;
 mod laser;
+mod lihuiyu;
 mod preview;
+mod ruida;
 mod serial;
 mod theme;
 mod ui;
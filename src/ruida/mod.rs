@@ -0,0 +1,3 @@
+pub mod protocol;
+pub mod rd_job;
+pub mod udp;
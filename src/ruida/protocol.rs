@@ -0,0 +1,229 @@
+#![allow(dead_code)]
+
+/// Default scramble key used by RDC644x controllers and RDWorks `.rd` files.
+pub const DEFAULT_MAGIC: u8 = 0x88;
+
+/// Largest magnitude representable by a 14-bit relative coordinate (µm).
+pub const REL_COORD_LIMIT: i64 = 8191;
+
+/// Ruida command bytes (plain, before swizzling)
+pub const CMD_MOVE_ABS: u8 = 0x88;
+pub const CMD_MOVE_REL: u8 = 0x89;
+pub const CMD_MOVE_REL_X: u8 = 0x8A;
+pub const CMD_MOVE_REL_Y: u8 = 0x8B;
+pub const CMD_CUT_ABS: u8 = 0xA8;
+pub const CMD_CUT_REL: u8 = 0xA9;
+pub const CMD_CUT_REL_X: u8 = 0xAA;
+pub const CMD_CUT_REL_Y: u8 = 0xAB;
+pub const CMD_POWER: u8 = 0xC6;
+pub const CMD_SPEED: u8 = 0xC9;
+pub const CMD_LAYER: u8 = 0xCA;
+pub const CMD_EOF: u8 = 0xD7;
+pub const CMD_PROCESS: u8 = 0xD8;
+pub const CMD_MEMORY: u8 = 0xDA;
+pub const CMD_FILE_LAYOUT: u8 = 0xE7;
+pub const CMD_FINISH: u8 = 0xEB;
+
+/// Second bytes for `CMD_POWER`
+pub const POWER_LASER1_MIN: u8 = 0x01;
+pub const POWER_LASER1_MAX: u8 = 0x02;
+pub const POWER_LAYER_LASER1_MIN: u8 = 0x31;
+pub const POWER_LAYER_LASER1_MAX: u8 = 0x32;
+
+/// Second bytes for `CMD_SPEED`
+pub const SPEED_LASER1: u8 = 0x02;
+pub const SPEED_LAYER: u8 = 0x04;
+
+/// Second bytes for `CMD_LAYER`
+pub const LAYER_FLAGS: u8 = 0x01;
+pub const LAYER_SELECT: u8 = 0x02;
+pub const LAYER_COLOR: u8 = 0x06;
+pub const LAYER_COUNT: u8 = 0x22;
+
+/// Values for `CMD_LAYER LAYER_FLAGS`
+pub const FLAG_END: u8 = 0x00;
+pub const FLAG_AIR_OFF: u8 = 0x12;
+pub const FLAG_AIR_ON: u8 = 0x13;
+
+/// Second bytes for `CMD_PROCESS`
+pub const PROCESS_START: u8 = 0x00;
+pub const PROCESS_STOP: u8 = 0x01;
+pub const PROCESS_PAUSE: u8 = 0x02;
+pub const PROCESS_RESUME: u8 = 0x03;
+pub const PROCESS_REF_POINT_MACHINE: u8 = 0x10;
+pub const PROCESS_REF_POINT_ANCHOR: u8 = 0x11;
+pub const PROCESS_REF_POINT_CURRENT: u8 = 0x12;
+
+/// Second bytes for `CMD_FILE_LAYOUT`
+pub const LAYOUT_BLOCK_END: u8 = 0x00;
+pub const LAYOUT_TOP_LEFT: u8 = 0x03;
+pub const LAYOUT_REPEAT: u8 = 0x04;
+pub const LAYOUT_ARRAY_DIRECTION: u8 = 0x05;
+pub const LAYOUT_BOTTOM_RIGHT: u8 = 0x07;
+pub const LAYOUT_DOCUMENT_MIN: u8 = 0x50;
+pub const LAYOUT_DOCUMENT_MAX: u8 = 0x51;
+pub const LAYOUT_LAYER_TOP_LEFT: u8 = 0x52;
+pub const LAYOUT_LAYER_BOTTOM_RIGHT: u8 = 0x53;
+
+/// Reply bytes (plain) sent by the controller for every UDP datagram
+pub const REPLY_ACK: u8 = 0xCC;
+pub const REPLY_NAK: u8 = 0xCD;
+
+/// Second bytes for `CMD_MEMORY`
+pub const MEMORY_READ: u8 = 0x00;
+pub const MEMORY_REPLY: u8 = 0x01;
+
+/// Memory registers polled for status
+pub const MEM_MACHINE_STATUS: u16 = 0x0400;
+pub const MEM_POSITION_X: u16 = 0x0421;
+pub const MEM_POSITION_Y: u16 = 0x0431;
+pub const MEM_POSITION_Z: u16 = 0x0441;
+
+/// Bits of `MEM_MACHINE_STATUS`
+pub const STATUS_BIT_RUNNING: i64 = 0x01;
+pub const STATUS_BIT_PAUSED: i64 = 0x10;
+
+/// Scramble one byte the way Ruida controllers expect on the wire and in `.rd` files
+pub fn swizzle_byte(b: u8, magic: u8) -> u8 {
+    let mut b = b;
+    b ^= b >> 7;
+    b ^= b << 7;
+    b ^= b >> 7;
+    b ^= magic;
+    b.wrapping_add(1)
+}
+
+/// Inverse of [`swizzle_byte`]
+pub fn unswizzle_byte(b: u8, magic: u8) -> u8 {
+    let mut b = b.wrapping_sub(1);
+    b ^= magic;
+    b ^= b >> 7;
+    b ^= b << 7;
+    b ^= b >> 7;
+    b
+}
+
+pub fn swizzle(data: &[u8], magic: u8) -> Vec<u8> {
+    data.iter().map(|&b| swizzle_byte(b, magic)).collect()
+}
+
+pub fn unswizzle(data: &[u8], magic: u8) -> Vec<u8> {
+    data.iter().map(|&b| unswizzle_byte(b, magic)).collect()
+}
+
+/// Encode an absolute value (coordinates in µm, speeds in µm/s, colors) as 5 x 7-bit bytes
+pub fn encode_abs(value: i64) -> [u8; 5] {
+    let v = (value as u64) & 0x7_FFFF_FFFF;
+    [
+        ((v >> 28) & 0x7F) as u8,
+        ((v >> 21) & 0x7F) as u8,
+        ((v >> 14) & 0x7F) as u8,
+        ((v >> 7) & 0x7F) as u8,
+        (v & 0x7F) as u8,
+    ]
+}
+
+/// Decode a 5-byte absolute value, sign-extending from 35 bits
+pub fn decode_abs(bytes: &[u8]) -> Option<i64> {
+    if bytes.len() < 5 {
+        return None;
+    }
+    let mut v: i64 = 0;
+    for &b in &bytes[..5] {
+        v = (v << 7) | (b & 0x7F) as i64;
+    }
+    if v & (1 << 34) != 0 {
+        v -= 1 << 35;
+    }
+    Some(v)
+}
+
+/// Encode a relative coordinate (µm) as a 14-bit two's complement value
+pub fn encode_rel(value: i64) -> [u8; 2] {
+    let v = (value as u64) & 0x3FFF;
+    [((v >> 7) & 0x7F) as u8, (v & 0x7F) as u8]
+}
+
+pub fn decode_rel(bytes: &[u8]) -> Option<i64> {
+    if bytes.len() < 2 {
+        return None;
+    }
+    let mut v = (((bytes[0] & 0x7F) as i64) << 7) | (bytes[1] & 0x7F) as i64;
+    if v & 0x2000 != 0 {
+        v -= 0x4000;
+    }
+    Some(v)
+}
+
+pub fn fits_rel(value: i64) -> bool {
+    (-REL_COORD_LIMIT - 1..=REL_COORD_LIMIT).contains(&value)
+}
+
+/// Encode a laser power percentage (0..100) as the controller's 14-bit scale
+pub fn encode_power(percent: f32) -> [u8; 2] {
+    let value = (percent.clamp(0.0, 100.0) / 100.0 * 0x3FFF as f32).round() as i64;
+    encode_rel(value)
+}
+
+/// Encode a speed in mm/s (the controller works in µm/s)
+pub fn encode_speed(mm_per_s: f32) -> [u8; 5] {
+    encode_abs((mm_per_s.max(0.0) * 1000.0).round() as i64)
+}
+
+/// Encode a coordinate in mm as µm
+pub fn encode_coord_mm(mm: f32) -> [u8; 5] {
+    encode_abs(mm_to_um(mm))
+}
+
+pub fn mm_to_um(mm: f32) -> i64 {
+    (mm as f64 * 1000.0).round() as i64
+}
+
+/// Sum-of-bytes checksum prefixed to every UDP datagram
+pub fn checksum(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |acc, &b| acc.wrapping_add(b as u16))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swizzle_matches_known_controller_bytes() {
+        // ACK (0xCC) and checksum error (0xCD) as captured from RDC6445 replies.
+        assert_eq!(swizzle_byte(0xCC, DEFAULT_MAGIC), 0xC6);
+        assert_eq!(swizzle_byte(0xCD, DEFAULT_MAGIC), 0x46);
+        // End-of-file marker that terminates every `.rd` file.
+        assert_eq!(swizzle_byte(CMD_EOF, DEFAULT_MAGIC), 0x60);
+    }
+
+    #[test]
+    fn swizzle_round_trips_every_byte() {
+        for magic in [0x11u8, 0x38, DEFAULT_MAGIC] {
+            for b in 0..=255u8 {
+                assert_eq!(unswizzle_byte(swizzle_byte(b, magic), magic), b);
+            }
+        }
+    }
+
+    #[test]
+    fn abs_and_rel_encoding_golden() {
+        assert_eq!(encode_abs(0), [0, 0, 0, 0, 0]);
+        assert_eq!(encode_abs(100_000), [0x00, 0x00, 0x06, 0x0D, 0x20]);
+        assert_eq!(decode_abs(&encode_abs(100_000)), Some(100_000));
+        assert_eq!(decode_abs(&encode_abs(-1_500)), Some(-1_500));
+
+        assert_eq!(encode_rel(1000), [0x07, 0x68]);
+        assert_eq!(encode_rel(-1), [0x7F, 0x7F]);
+        assert_eq!(decode_rel(&encode_rel(-8192)), Some(-8192));
+        assert!(fits_rel(8191));
+        assert!(!fits_rel(8192));
+    }
+
+    #[test]
+    fn power_and_speed_encoding_golden() {
+        assert_eq!(encode_power(100.0), [0x7F, 0x7F]);
+        assert_eq!(encode_power(50.0), [0x40, 0x00]);
+        assert_eq!(encode_speed(100.0), encode_abs(100_000));
+    }
+}
@@ -0,0 +1,421 @@
+#![allow(dead_code)]
+
+use crate::gcode::interpreter::{self, BlockKind};
+
+use super::protocol::*;
+
+/// Laser S value that maps to 100% power (GRBL `$30` default)
+pub const DEFAULT_S_MAX: f32 = 1000.0;
+
+/// Layer colors shown on the controller panel, cycled per layer (0x00BBGGRR)
+const LAYER_COLORS: [i64; 8] = [
+    0x0000_00FF,
+    0x0000_FF00,
+    0x00FF_0000,
+    0x0000_FFFF,
+    0x00FF_00FF,
+    0x00FF_FF00,
+    0x0000_80FF,
+    0x0080_8080,
+];
+
+/// Layer ids go out as a single data byte, and data bytes keep the MSB clear
+/// (bytes with it set are commands), so ids stop at 0x7F
+const MAX_LAYERS: usize = 128;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RdEncoderConfig {
+    /// Controllers home at the top of the bed, so Y grows downward
+    pub flip_y: bool,
+    pub bed_height_mm: f32,
+    pub s_max: f32,
+}
+
+impl Default for RdEncoderConfig {
+    fn default() -> Self {
+        Self {
+            flip_y: true,
+            bed_height_mm: 400.0,
+            s_max: DEFAULT_S_MAX,
+        }
+    }
+}
+
+/// Cut settings shared by every vector in a layer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RdLayer {
+    pub speed_mm_s: f32,
+    pub min_power_pct: f32,
+    pub max_power_pct: f32,
+    pub min_um: (i64, i64),
+    pub max_um: (i64, i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RdOp {
+    Move { x: i64, y: i64 },
+    Cut { x: i64, y: i64, layer: usize },
+    Air(bool),
+}
+
+/// A job lowered to Ruida moves, ready to be serialized
+#[derive(Debug, Clone, Default)]
+pub struct RdProgram {
+    pub layers: Vec<RdLayer>,
+    ops: Vec<RdOp>,
+    pub min_um: (i64, i64),
+    pub max_um: (i64, i64),
+}
+
+impl RdProgram {
+    /// Lower GCode lines to Ruida moves; one layer per distinct speed/power pair
+    pub fn from_gcode_lines(lines: &[String], config: &RdEncoderConfig) -> Result<Self, String> {
+        let mut program = RdProgram {
+            min_um: (i64::MAX, i64::MAX),
+            max_um: (i64::MIN, i64::MIN),
+            ..Default::default()
+        };
+        for block in interpreter::interpret(lines) {
+            let motion = match block.kind {
+                BlockKind::Air(on) => {
+                    program.ops.push(RdOp::Air(on));
+                    continue;
+                }
+                _ => match block.motion() {
+                    Some(motion) if motion.is_xy() => *motion,
+                    _ => continue,
+                },
+            };
+
+            let layer = if !block.is_rapid() && motion.burns() {
+                if motion.feed <= 0.0 {
+                    return Err(format!("cut move without feed rate: {}", lines[block.line].trim()));
+                }
+                Some(program.layer_for(motion.feed / 60.0, motion.power / config.s_max * 100.0)?)
+            } else {
+                None
+            };
+            // Arcs go out as short lines
+            for point in block.waypoints() {
+                let (ux, uy) = config.device_um(point.x, point.y);
+                if let Some(layer) = layer {
+                    program.ops.push(RdOp::Cut { x: ux, y: uy, layer });
+                    program.extend_bounds(layer, ux, uy);
+                } else {
+                    program.ops.push(RdOp::Move { x: ux, y: uy });
+                }
+            }
+        }
+
+        if program.layers.is_empty() {
+            program.min_um = (0, 0);
+            program.max_um = (0, 0);
+        }
+        Ok(program)
+    }
+
+    fn layer_for(&mut self, speed_mm_s: f32, power_pct: f32) -> Result<usize, String> {
+        let power_pct = power_pct.clamp(0.0, 100.0);
+        if let Some(idx) = self.layers.iter().position(|l| {
+            (l.speed_mm_s - speed_mm_s).abs() < 0.001 && (l.max_power_pct - power_pct).abs() < 0.01
+        }) {
+            return Ok(idx);
+        }
+        if self.layers.len() >= MAX_LAYERS {
+            return Err(format!("job uses more than {MAX_LAYERS} speed/power combinations"));
+        }
+        self.layers.push(RdLayer {
+            speed_mm_s,
+            min_power_pct: power_pct,
+            max_power_pct: power_pct,
+            min_um: (i64::MAX, i64::MAX),
+            max_um: (i64::MIN, i64::MIN),
+        });
+        Ok(self.layers.len() - 1)
+    }
+
+    fn extend_bounds(&mut self, layer: usize, x: i64, y: i64) {
+        let l = &mut self.layers[layer];
+        l.min_um = (l.min_um.0.min(x), l.min_um.1.min(y));
+        l.max_um = (l.max_um.0.max(x), l.max_um.1.max(y));
+        self.min_um = (self.min_um.0.min(x), self.min_um.1.min(y));
+        self.max_um = (self.max_um.0.max(x), self.max_um.1.max(y));
+    }
+
+    pub fn cut_count(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|op| matches!(op, RdOp::Cut { .. }))
+            .count()
+    }
+
+    /// Serialize to the plain (unswizzled) Ruida command stream
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_header(&mut out);
+        self.encode_body(&mut out);
+        out.push(CMD_FINISH);
+        out.extend([CMD_FILE_LAYOUT, LAYOUT_BLOCK_END]);
+        out.push(CMD_EOF);
+        out
+    }
+
+    /// Serialize to the scrambled bytes stored in `.rd` files and sent over UDP
+    pub fn to_rd_bytes(&self, magic: u8) -> Vec<u8> {
+        swizzle(&self.encode(), magic)
+    }
+
+    fn encode_header(&self, out: &mut Vec<u8>) {
+        out.extend([CMD_PROCESS, PROCESS_REF_POINT_CURRENT]);
+        push_point(out, &[CMD_FILE_LAYOUT, LAYOUT_TOP_LEFT], self.min_um);
+        push_point(out, &[CMD_FILE_LAYOUT, LAYOUT_BOTTOM_RIGHT], self.max_um);
+        push_point(out, &[CMD_FILE_LAYOUT, LAYOUT_DOCUMENT_MIN], self.min_um);
+        push_point(out, &[CMD_FILE_LAYOUT, LAYOUT_DOCUMENT_MAX], self.max_um);
+        // Run once, no array copies.
+        out.extend([CMD_FILE_LAYOUT, LAYOUT_REPEAT, 0x00, 0x01, 0x00, 0x01]);
+        out.extend([0x00; 10]);
+        out.extend([CMD_FILE_LAYOUT, LAYOUT_ARRAY_DIRECTION, 0x00]);
+
+        for (idx, layer) in self.layers.iter().enumerate() {
+            let id = idx as u8;
+            out.extend([CMD_SPEED, SPEED_LAYER, id]);
+            out.extend(encode_speed(layer.speed_mm_s));
+            out.extend([CMD_POWER, POWER_LAYER_LASER1_MIN, id]);
+            out.extend(encode_power(layer.min_power_pct));
+            out.extend([CMD_POWER, POWER_LAYER_LASER1_MAX, id]);
+            out.extend(encode_power(layer.max_power_pct));
+            out.extend([CMD_LAYER, LAYER_COLOR, id]);
+            out.extend(encode_abs(LAYER_COLORS[idx % LAYER_COLORS.len()]));
+            push_point(out, &[CMD_FILE_LAYOUT, LAYOUT_LAYER_TOP_LEFT, id], layer.min_um);
+            push_point(out, &[CMD_FILE_LAYOUT, LAYOUT_LAYER_BOTTOM_RIGHT, id], layer.max_um);
+        }
+        let max_layer = self.layers.len().saturating_sub(1) as u8;
+        out.extend([CMD_LAYER, LAYER_COUNT, max_layer]);
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) {
+        let mut pos: Option<(i64, i64)> = None;
+        let mut active_layer: Option<usize> = None;
+
+        for op in &self.ops {
+            match *op {
+                RdOp::Air(on) => {
+                    out.extend([CMD_LAYER, LAYER_FLAGS, if on { FLAG_AIR_ON } else { FLAG_AIR_OFF }]);
+                }
+                RdOp::Move { x, y } => {
+                    push_motion(out, pos, (x, y), false);
+                    pos = Some((x, y));
+                }
+                RdOp::Cut { x, y, layer } => {
+                    if active_layer != Some(layer) {
+                        let settings = &self.layers[layer];
+                        out.extend([CMD_LAYER, LAYER_SELECT, layer as u8]);
+                        out.extend([CMD_SPEED, SPEED_LASER1]);
+                        out.extend(encode_speed(settings.speed_mm_s));
+                        out.extend([CMD_POWER, POWER_LASER1_MIN]);
+                        out.extend(encode_power(settings.min_power_pct));
+                        out.extend([CMD_POWER, POWER_LASER1_MAX]);
+                        out.extend(encode_power(settings.max_power_pct));
+                        active_layer = Some(layer);
+                    }
+                    push_motion(out, pos, (x, y), true);
+                    pos = Some((x, y));
+                }
+            }
+        }
+    }
+}
+
+impl RdEncoderConfig {
+    fn device_um(&self, x_mm: f32, y_mm: f32) -> (i64, i64) {
+        let y_mm = if self.flip_y {
+            self.bed_height_mm - y_mm
+        } else {
+            y_mm
+        };
+        (mm_to_um(x_mm), mm_to_um(y_mm))
+    }
+}
+
+fn push_point(out: &mut Vec<u8>, prefix: &[u8], (x, y): (i64, i64)) {
+    out.extend_from_slice(prefix);
+    out.extend(encode_abs(x));
+    out.extend(encode_abs(y));
+}
+
+/// Emit the shortest encoding for a move: axis-relative, relative, or absolute
+fn push_motion(out: &mut Vec<u8>, from: Option<(i64, i64)>, to: (i64, i64), cut: bool) {
+    let (abs, rel, rel_x, rel_y) = if cut {
+        (CMD_CUT_ABS, CMD_CUT_REL, CMD_CUT_REL_X, CMD_CUT_REL_Y)
+    } else {
+        (CMD_MOVE_ABS, CMD_MOVE_REL, CMD_MOVE_REL_X, CMD_MOVE_REL_Y)
+    };
+
+    let Some(from) = from else {
+        push_point(out, &[abs], to);
+        return;
+    };
+    let dx = to.0 - from.0;
+    let dy = to.1 - from.1;
+    if dx == 0 && dy == 0 {
+        return;
+    }
+    if !fits_rel(dx) || !fits_rel(dy) {
+        push_point(out, &[abs], to);
+    } else if dy == 0 {
+        out.push(rel_x);
+        out.extend(encode_rel(dx));
+    } else if dx == 0 {
+        out.push(rel_y);
+        out.extend(encode_rel(dy));
+    } else {
+        out.push(rel);
+        out.extend(encode_rel(dx));
+        out.extend(encode_rel(dy));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RdEncoderConfig {
+        RdEncoderConfig {
+            flip_y: false,
+            ..Default::default()
+        }
+    }
+
+    fn lines(src: &[&str]) -> Vec<String> {
+        src.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn square_body_golden_bytes() {
+        let program = RdProgram::from_gcode_lines(
+            &lines(&[
+                "G90",
+                "G0 X10 Y10",
+                "M3 S500",
+                "G1 X15 Y10 F600",
+                "G1 X15 Y12",
+                "G1 X10 Y10",
+                "M5",
+            ]),
+            &config(),
+        )
+        .expect("encode");
+        assert_eq!(program.layers.len(), 1);
+
+        let mut body = Vec::new();
+        program.encode_body(&mut body);
+
+        let mut expected = vec![CMD_MOVE_ABS];
+        expected.extend(encode_abs(10_000));
+        expected.extend(encode_abs(10_000));
+        expected.extend([CMD_LAYER, LAYER_SELECT, 0x00]);
+        // 600 mm/min = 10 mm/s = 10000 µm/s
+        expected.extend([CMD_SPEED, SPEED_LASER1, 0x00, 0x00, 0x00, 0x4E, 0x10]);
+        // S500 of 1000 = 50%
+        expected.extend([CMD_POWER, POWER_LASER1_MIN, 0x40, 0x00]);
+        expected.extend([CMD_POWER, POWER_LASER1_MAX, 0x40, 0x00]);
+        // +5 mm along X only
+        expected.extend([CMD_CUT_REL_X, 0x27, 0x08]);
+        // +2 mm along Y only
+        expected.extend([CMD_CUT_REL_Y, 0x0F, 0x50]);
+        // -5 mm / -2 mm diagonal
+        expected.extend([CMD_CUT_REL, 0x58, 0x78, 0x70, 0x30]);
+        assert_eq!(body, expected);
+    }
+
+    #[test]
+    fn long_moves_fall_back_to_absolute() {
+        let program = RdProgram::from_gcode_lines(
+            &lines(&["G0 X0 Y0", "M3 S1000", "G1 X100 Y0 F6000", "M5"]),
+            &config(),
+        )
+        .expect("encode");
+        let mut body = Vec::new();
+        program.encode_body(&mut body);
+        let tail = &body[body.len() - 11..];
+        assert_eq!(tail[0], CMD_CUT_ABS);
+        assert_eq!(&tail[1..6], &encode_abs(100_000));
+    }
+
+    #[test]
+    fn distinct_speed_power_pairs_become_layers() {
+        let program = RdProgram::from_gcode_lines(
+            &lines(&[
+                "M3 S200",
+                "G1 X1 Y1 F1200",
+                "G1 X2 Y1 S800",
+                "G1 X3 Y1 F600",
+                "G1 X4 Y1 S200 F1200",
+                "M5",
+            ]),
+            &config(),
+        )
+        .expect("encode");
+        assert_eq!(program.layers.len(), 3);
+        assert_eq!(program.cut_count(), 4);
+    }
+
+    #[test]
+    fn file_is_swizzled_and_terminated() {
+        let program = RdProgram::from_gcode_lines(
+            &lines(&["M3 S1000", "G1 X5 Y5 F600", "M5"]),
+            &RdEncoderConfig::default(),
+        )
+        .expect("encode");
+        let plain = program.encode();
+        assert_eq!(&plain[..2], &[CMD_PROCESS, PROCESS_REF_POINT_CURRENT]);
+        assert_eq!(plain.last(), Some(&CMD_EOF));
+
+        let rd = program.to_rd_bytes(DEFAULT_MAGIC);
+        assert_eq!(rd.len(), plain.len());
+        assert_eq!(rd.last(), Some(&0x60));
+        assert_eq!(unswizzle(&rd, DEFAULT_MAGIC), plain);
+    }
+
+    #[test]
+    fn flip_y_measures_from_bed_top() {
+        let cfg = RdEncoderConfig {
+            flip_y: true,
+            bed_height_mm: 300.0,
+            s_max: DEFAULT_S_MAX,
+        };
+        assert_eq!(cfg.device_um(10.0, 50.0), (10_000, 250_000));
+    }
+
+    #[test]
+    fn layer_ids_stay_data_bytes() {
+        let mut src = vec!["M3".to_string()];
+        src.extend((0..=MAX_LAYERS).map(|i| format!("G1 X{} Y1 S{} F600", i % 2, i + 1)));
+        let err = RdProgram::from_gcode_lines(&src, &config()).expect_err("too many layers");
+        assert!(err.contains("128"), "{err}");
+
+        src.truncate(MAX_LAYERS + 1);
+        let program = RdProgram::from_gcode_lines(&src, &config()).expect("encode");
+        assert_eq!(program.layers.len(), MAX_LAYERS);
+        let mut header = Vec::new();
+        program.encode_header(&mut header);
+        assert_eq!(header[header.len() - 3..], [CMD_LAYER, LAYER_COUNT, 0x7F]);
+    }
+
+    #[test]
+    fn power_scales_to_the_machine_s_max() {
+        let cfg = RdEncoderConfig {
+            s_max: 255.0,
+            ..config()
+        };
+        let program =
+            RdProgram::from_gcode_lines(&lines(&["M3 S255", "G1 X5 Y5 F600", "M5"]), &cfg).expect("encode");
+        assert!((program.layers[0].max_power_pct - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn cut_without_feed_is_rejected() {
+        let err = RdProgram::from_gcode_lines(&lines(&["M3 S500", "G1 X5 Y5"]), &config());
+        assert!(err.is_err());
+    }
+}
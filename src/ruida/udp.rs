@@ -0,0 +1,436 @@
+#![allow(dead_code)]
+
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use crate::grbl::types::{GPoint, GrblState, MacStatus};
+
+use super::protocol::*;
+
+/// Port the controller listens on for commands
+pub const RUIDA_UDP_PORT: u16 = 50200;
+
+/// Port the controller sends ACKs and replies to
+pub const RUIDA_REPLY_PORT: u16 = 40200;
+
+/// Largest swizzled payload per datagram (the controller drops larger packets)
+pub const MAX_PAYLOAD: usize = 1470;
+
+#[derive(Debug, Clone)]
+pub struct RuidaUdpConfig {
+    pub magic: u8,
+    /// Local port to bind; `None` picks an ephemeral port (stand-ins reply to the sender)
+    pub reply_port: Option<u16>,
+    pub ack_timeout: Duration,
+    pub retries: u32,
+}
+
+impl Default for RuidaUdpConfig {
+    fn default() -> Self {
+        Self {
+            magic: DEFAULT_MAGIC,
+            reply_port: Some(RUIDA_REPLY_PORT),
+            ack_timeout: Duration::from_millis(500),
+            retries: 3,
+        }
+    }
+}
+
+/// Position and run state read from the controller's memory registers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuidaStatus {
+    pub x_mm: f32,
+    pub y_mm: f32,
+    pub z_mm: f32,
+    pub running: bool,
+    pub paused: bool,
+}
+
+impl RuidaStatus {
+    pub fn from_registers(status: i64, x_um: i64, y_um: i64, z_um: i64) -> Self {
+        Self {
+            x_mm: x_um as f32 / 1000.0,
+            y_mm: y_um as f32 / 1000.0,
+            z_mm: z_um as f32 / 1000.0,
+            running: status & STATUS_BIT_RUNNING != 0,
+            paused: status & STATUS_BIT_PAUSED != 0,
+        }
+    }
+
+    pub fn mac_status(&self) -> MacStatus {
+        if self.paused {
+            MacStatus::Hold
+        } else if self.running {
+            MacStatus::Run
+        } else {
+            MacStatus::Idle
+        }
+    }
+
+    pub fn to_grbl_state(self) -> GrblState {
+        let pos = GPoint::new(self.x_mm, self.y_mm, self.z_mm);
+        GrblState {
+            status: self.mac_status(),
+            mpos: pos,
+            wpos: pos,
+            ..Default::default()
+        }
+    }
+
+    /// Human readable line for the console log
+    pub fn summary(&self) -> String {
+        format!(
+            "<{}|MPos:{:.3},{:.3},{:.3}>",
+            self.mac_status(),
+            self.x_mm,
+            self.y_mm,
+            self.z_mm
+        )
+    }
+}
+
+/// Swizzle a plain payload and prefix the big-endian checksum
+pub fn frame_packet(payload: &[u8], magic: u8) -> Vec<u8> {
+    let body = swizzle(payload, magic);
+    let sum = checksum(&body);
+    let mut packet = Vec::with_capacity(body.len() + 2);
+    packet.extend(sum.to_be_bytes());
+    packet.extend(body);
+    packet
+}
+
+/// Split a framed datagram into its plain payload, rejecting bad checksums
+pub fn unframe_packet(packet: &[u8], magic: u8) -> Option<Vec<u8>> {
+    if packet.len() < 2 {
+        return None;
+    }
+    let expected = u16::from_be_bytes([packet[0], packet[1]]);
+    let body = &packet[2..];
+    (checksum(body) == expected).then(|| unswizzle(body, magic))
+}
+
+/// Blocking client for the Ruida port-50200 datagram protocol
+pub struct RuidaUdpClient {
+    socket: UdpSocket,
+    target: SocketAddr,
+    config: RuidaUdpConfig,
+}
+
+impl RuidaUdpClient {
+    pub fn connect(host: &str, port: u16, config: RuidaUdpConfig) -> Result<Self, String> {
+        let target = (host, port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Failed to resolve {host}:{port}: {e}"))?
+            .next()
+            .ok_or_else(|| format!("No address found for {host}:{port}"))?;
+        let bind_addr = if target.is_ipv4() { "0.0.0.0" } else { "[::]" };
+        let local_port = config.reply_port.unwrap_or(0);
+        let socket = UdpSocket::bind(format!("{bind_addr}:{local_port}"))
+            .map_err(|e| format!("Failed to bind UDP port {local_port}: {e}"))?;
+        socket
+            .set_read_timeout(Some(config.ack_timeout))
+            .map_err(|e| format!("Failed to set UDP read timeout: {e}"))?;
+        Ok(Self {
+            socket,
+            target,
+            config,
+        })
+    }
+
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    /// Send one payload and wait for the controller's ACK, retransmitting on NAK or timeout
+    pub fn send_payload(&self, payload: &[u8]) -> Result<(), String> {
+        if payload.len() > MAX_PAYLOAD {
+            return Err(format!(
+                "Ruida payload of {} bytes exceeds the {MAX_PAYLOAD}-byte datagram limit",
+                payload.len()
+            ));
+        }
+        let packet = frame_packet(payload, self.config.magic);
+        let mut last_error = String::new();
+
+        for _ in 0..=self.config.retries {
+            self.socket
+                .send_to(&packet, self.target)
+                .map_err(|e| format!("UDP send to {} failed: {e}", self.target))?;
+
+            match self.recv_plain() {
+                Ok(reply) => match reply.first() {
+                    Some(&REPLY_ACK) => return Ok(()),
+                    Some(&REPLY_NAK) => last_error = "controller rejected checksum".to_string(),
+                    _ => last_error = format!("unexpected reply {reply:02X?}"),
+                },
+                Err(e) => last_error = e,
+            }
+        }
+
+        Err(format!(
+            "No ACK from {} after {} attempt(s): {last_error}",
+            self.target,
+            self.config.retries + 1
+        ))
+    }
+
+    /// Read a 35-bit value from a memory register
+    pub fn read_memory(&self, addr: u16) -> Result<i64, String> {
+        let [hi, lo] = addr.to_be_bytes();
+        self.send_payload(&[CMD_MEMORY, MEMORY_READ, hi, lo])?;
+
+        let reply = self.recv_plain()?;
+        match reply.as_slice() {
+            [CMD_MEMORY, MEMORY_REPLY, rhi, rlo, value @ ..] if [*rhi, *rlo] == [hi, lo] => {
+                decode_abs(value).ok_or_else(|| format!("short memory reply for 0x{addr:04X}"))
+            }
+            _ => Err(format!("unexpected reply to memory read 0x{addr:04X}: {reply:02X?}")),
+        }
+    }
+
+    pub fn read_status(&self) -> Result<RuidaStatus, String> {
+        let status = self.read_memory(MEM_MACHINE_STATUS)?;
+        let x = self.read_memory(MEM_POSITION_X)?;
+        let y = self.read_memory(MEM_POSITION_Y)?;
+        let z = self.read_memory(MEM_POSITION_Z)?;
+        Ok(RuidaStatus::from_registers(status, x, y, z))
+    }
+
+    pub fn start(&self) -> Result<(), String> {
+        self.send_payload(&[CMD_PROCESS, PROCESS_START])
+    }
+
+    pub fn pause(&self) -> Result<(), String> {
+        self.send_payload(&[CMD_PROCESS, PROCESS_PAUSE])
+    }
+
+    pub fn resume(&self) -> Result<(), String> {
+        self.send_payload(&[CMD_PROCESS, PROCESS_RESUME])
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        self.send_payload(&[CMD_PROCESS, PROCESS_STOP])
+    }
+
+    /// Stream a plain (unswizzled) RD program; the controller runs it as it arrives.
+    /// `progress` receives the number of bytes acknowledged so far and returns false to
+    /// abandon the rest of the program.
+    pub fn upload(&self, program: &[u8], mut progress: impl FnMut(usize) -> bool) -> Result<(), String> {
+        let mut sent = 0;
+        for chunk in program.chunks(MAX_PAYLOAD) {
+            self.send_payload(chunk)?;
+            sent += chunk.len();
+            if !progress(sent) {
+                return Err(format!("upload abandoned after {sent} of {} byte(s)", program.len()));
+            }
+        }
+        Ok(())
+    }
+
+    fn recv_plain(&self) -> Result<Vec<u8>, String> {
+        let mut buf = [0u8; 2048];
+        loop {
+            let (len, from) = self.socket.recv_from(&mut buf).map_err(|e| match e.kind() {
+                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => {
+                    "timed out waiting for controller".to_string()
+                }
+                _ => format!("UDP receive failed: {e}"),
+            })?;
+            // Ignore stray datagrams from other hosts on the reply port.
+            if from.ip() != self.target.ip() {
+                continue;
+            }
+            return Ok(unswizzle(&buf[..len], self.config.magic));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Minimal controller stand-in: checks checksums, ACKs, answers memory reads
+    struct StandIn {
+        port: u16,
+        received: Arc<Mutex<Vec<Vec<u8>>>>,
+        naks_to_send: Arc<AtomicU32>,
+        stop: Arc<AtomicBool>,
+    }
+
+    impl StandIn {
+        fn spawn(registers: HashMap<u16, i64>) -> Self {
+            let socket = UdpSocket::bind("127.0.0.1:0").expect("bind stand-in");
+            socket
+                .set_read_timeout(Some(Duration::from_millis(20)))
+                .expect("timeout");
+            let port = socket.local_addr().expect("addr").port();
+            let received = Arc::new(Mutex::new(Vec::new()));
+            let naks_to_send = Arc::new(AtomicU32::new(0));
+            let stop = Arc::new(AtomicBool::new(false));
+
+            let (rx_log, naks, stop_flag) = (received.clone(), naks_to_send.clone(), stop.clone());
+            std::thread::spawn(move || {
+                let mut buf = [0u8; 2048];
+                while !stop_flag.load(Ordering::Relaxed) {
+                    let Ok((len, from)) = socket.recv_from(&mut buf) else {
+                        continue;
+                    };
+                    let reply = |plain: &[u8]| {
+                        let _ = socket.send_to(&swizzle(plain, DEFAULT_MAGIC), from);
+                    };
+                    let Some(payload) = unframe_packet(&buf[..len], DEFAULT_MAGIC) else {
+                        reply(&[REPLY_NAK]);
+                        continue;
+                    };
+                    if naks.load(Ordering::Relaxed) > 0 {
+                        naks.fetch_sub(1, Ordering::Relaxed);
+                        reply(&[REPLY_NAK]);
+                        continue;
+                    }
+                    rx_log.lock().expect("log").push(payload.clone());
+                    reply(&[REPLY_ACK]);
+                    if let [CMD_MEMORY, MEMORY_READ, hi, lo] = payload[..] {
+                        let addr = u16::from_be_bytes([hi, lo]);
+                        let mut data = vec![CMD_MEMORY, MEMORY_REPLY, hi, lo];
+                        data.extend(encode_abs(registers.get(&addr).copied().unwrap_or(0)));
+                        data.extend(encode_abs(0));
+                        reply(&data);
+                    }
+                }
+            });
+
+            Self {
+                port,
+                received,
+                naks_to_send,
+                stop,
+            }
+        }
+
+        fn client(&self) -> RuidaUdpClient {
+            let config = RuidaUdpConfig {
+                reply_port: None,
+                ack_timeout: Duration::from_millis(200),
+                ..Default::default()
+            };
+            RuidaUdpClient::connect("127.0.0.1", self.port, config).expect("client")
+        }
+
+        fn received(&self) -> Vec<Vec<u8>> {
+            self.received.lock().expect("log").clone()
+        }
+    }
+
+    impl Drop for StandIn {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn frame_prefixes_checksum_of_swizzled_body() {
+        let packet = frame_packet(&[CMD_PROCESS, PROCESS_START], DEFAULT_MAGIC);
+        let body = swizzle(&[CMD_PROCESS, PROCESS_START], DEFAULT_MAGIC);
+        let sum = body[0] as u16 + body[1] as u16;
+        assert_eq!(packet[..2], sum.to_be_bytes());
+        assert_eq!(packet[2..], body[..]);
+        assert_eq!(
+            unframe_packet(&packet, DEFAULT_MAGIC),
+            Some(vec![CMD_PROCESS, PROCESS_START])
+        );
+
+        let mut corrupt = packet.clone();
+        corrupt[2] ^= 0x01;
+        assert_eq!(unframe_packet(&corrupt, DEFAULT_MAGIC), None);
+    }
+
+    #[test]
+    fn process_commands_are_acknowledged() {
+        let standin = StandIn::spawn(HashMap::new());
+        let client = standin.client();
+        client.start().expect("start");
+        client.pause().expect("pause");
+        client.resume().expect("resume");
+        client.stop().expect("stop");
+        assert_eq!(
+            standin.received(),
+            vec![
+                vec![CMD_PROCESS, PROCESS_START],
+                vec![CMD_PROCESS, PROCESS_PAUSE],
+                vec![CMD_PROCESS, PROCESS_RESUME],
+                vec![CMD_PROCESS, PROCESS_STOP],
+            ]
+        );
+    }
+
+    #[test]
+    fn nak_triggers_retransmit() {
+        let standin = StandIn::spawn(HashMap::new());
+        standin.naks_to_send.store(2, Ordering::Relaxed);
+        let client = standin.client();
+        client.stop().expect("stop after retransmits");
+        assert_eq!(standin.received(), vec![vec![CMD_PROCESS, PROCESS_STOP]]);
+    }
+
+    #[test]
+    fn gives_up_when_controller_never_acks() {
+        let standin = StandIn::spawn(HashMap::new());
+        standin.naks_to_send.store(u32::MAX, Ordering::Relaxed);
+        let client = standin.client();
+        let err = client.start().expect_err("should fail");
+        assert!(err.contains("4 attempt(s)"), "{err}");
+    }
+
+    #[test]
+    fn status_is_read_from_memory_registers() {
+        let registers = HashMap::from([
+            (MEM_MACHINE_STATUS, STATUS_BIT_RUNNING),
+            (MEM_POSITION_X, 125_500),
+            (MEM_POSITION_Y, 42_000),
+        ]);
+        let standin = StandIn::spawn(registers);
+        let status = standin.client().read_status().expect("status");
+        assert!(status.running);
+        assert!(!status.paused);
+        assert!((status.x_mm - 125.5).abs() < 1e-4);
+        assert!((status.y_mm - 42.0).abs() < 1e-4);
+
+        let state = status.to_grbl_state();
+        assert_eq!(state.status, MacStatus::Run);
+        assert_eq!(state.mpos.x, status.x_mm);
+    }
+
+    #[test]
+    fn upload_splits_program_into_acknowledged_chunks() {
+        let standin = StandIn::spawn(HashMap::new());
+        let program: Vec<u8> = (0..(MAX_PAYLOAD * 2 + 10)).map(|i| (i % 251) as u8).collect();
+        let mut progress = Vec::new();
+        standin
+            .client()
+            .upload(&program, |n| {
+                progress.push(n);
+                true
+            })
+            .expect("upload");
+
+        let received = standin.received();
+        assert_eq!(received.len(), 3);
+        assert_eq!(received.concat(), program);
+        assert_eq!(progress, vec![MAX_PAYLOAD, MAX_PAYLOAD * 2, program.len()]);
+    }
+
+    #[test]
+    fn upload_stops_when_progress_says_so() {
+        let standin = StandIn::spawn(HashMap::new());
+        let program = vec![0x11u8; MAX_PAYLOAD * 3];
+        let err = standin
+            .client()
+            .upload(&program, |sent| sent < MAX_PAYLOAD)
+            .expect_err("abandoned");
+        assert!(err.contains("abandoned"), "{err}");
+        assert_eq!(standin.received().len(), 1);
+    }
+}
@@ -1,12 +1,31 @@
 #![allow(dead_code)]
 
 use crossbeam_channel::{Receiver, Sender, unbounded};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::net::{Shutdown, TcpStream};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::controller::{ControllerBackend, ControllerResponse};
+use crate::grbl::sim::{VirtualGrbl, VirtualGrblConfig};
+use crate::grbl::types::GrblResponse;
+use crate::ruida::udp::{RuidaUdpClient, RuidaUdpConfig};
+
+use super::fluidnc::{self, FluidNcConfig, FrameDecoder};
+use super::rfc2217::{self, ComPortCommand, ModemState, Rfc2217Client, TelnetDecoder, TelnetEvent};
+use super::session::{SessionEvent, SessionRecord, SessionRecorder, SessionTap, TappedSender};
+
+/// Consecutive failed status polls before a UDP controller is considered gone
+const UDP_MAX_MISSED_POLLS: u32 = 3;
+
+/// How often the virtual machine advances when the host is quiet
+const VIRTUAL_TICK: Duration = Duration::from_millis(5);
+
+/// How long an RFC 2217 server gets to accept COM-PORT-OPTION
+const RFC2217_NEGOTIATION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long DTR/RTS are held for a board reset
+const RESET_PULSE: Duration = Duration::from_millis(100);
 
 /// Messages from serial reader thread to the main app
 #[derive(Debug, Clone)]
@@ -18,6 +37,11 @@ pub enum SerialMsg {
     Connected(String),
     Disconnected(String),
     Error(String),
+    /// Bytes of a binary upload the controller has acknowledged so far
+    UploadProgress {
+        sent: usize,
+        total: usize,
+    },
 }
 
 /// Messages from the main app to the serial writer
@@ -25,6 +49,18 @@ pub enum SerialMsg {
 pub enum SerialCmd {
     SendLine(String),
     SendByte(u8),
+    /// Whole job in the controller's binary format (Ruida UDP upload)
+    SendBinary(Vec<u8>),
+    /// Store a file on the controller and optionally run it (FluidNC only)
+    UploadFile {
+        name: String,
+        bytes: Vec<u8>,
+        run: bool,
+    },
+    /// Change the port speed (serial and RFC 2217 only)
+    SetBaud(u32),
+    /// Pulse DTR/RTS to reset the board (serial and RFC 2217 only)
+    ResetBoard,
     Disconnect,
 }
 
@@ -32,6 +68,13 @@ pub struct SerialConnection {
     pub rx: Receiver<SerialMsg>,
     pub cmd_tx: Sender<SerialCmd>,
     connected: Arc<Mutex<bool>>,
+    binary_jobs: bool,
+    /// Jobs are uploaded as a G-code file and run by the controller
+    file_jobs: bool,
+    /// Baud and DTR/RTS can be changed on this connection
+    line_control: bool,
+    modem: Arc<Mutex<Option<ModemState>>>,
+    tap: SessionTap,
 }
 
 impl SerialConnection {
@@ -47,6 +90,8 @@ impl SerialConnection {
             .map_err(|e| format!("Failed to open {port_name}: {e}"))?;
 
         let (msg_tx, msg_rx) = unbounded::<SerialMsg>();
+        let tap = SessionTap::default();
+        let msg_tx = TappedSender::new(msg_tx, tap.clone());
         let (cmd_tx, cmd_rx) = unbounded::<SerialCmd>();
         let cloned_port = port.try_clone().map_err(|e| format!("Failed to clone port {port_name}: {e}"))?;
         let port_handle = Arc::new(Mutex::new(Some(cloned_port)));
@@ -120,6 +165,36 @@ impl SerialConnection {
                         }
                         Err(_) => break,
                     },
+                    Ok(SerialCmd::SendBinary(bytes)) => match port_for_writer.lock() {
+                        Ok(mut guard) => {
+                            if let Some(ref mut port) = *guard {
+                                let _ = port.write_all(&bytes);
+                                let _ = port.flush();
+                            }
+                        }
+                        Err(_) => break,
+                    },
+                    Ok(SerialCmd::SetBaud(baud)) => match port_for_writer.lock() {
+                        Ok(mut guard) => {
+                            if let Some(ref mut port) = *guard {
+                                let _ = port.set_baud_rate(baud);
+                            }
+                        }
+                        Err(_) => break,
+                    },
+                    Ok(SerialCmd::ResetBoard) => match port_for_writer.lock() {
+                        Ok(mut guard) => {
+                            if let Some(ref mut port) = *guard {
+                                let _ = port.write_data_terminal_ready(false);
+                                let _ = port.write_request_to_send(true);
+                                std::thread::sleep(RESET_PULSE);
+                                let _ = port.write_data_terminal_ready(true);
+                                let _ = port.write_request_to_send(false);
+                            }
+                        }
+                        Err(_) => break,
+                    },
+                    Ok(SerialCmd::UploadFile { .. }) => {}
                     Ok(SerialCmd::Disconnect) | Err(_) => {
                         match port_for_writer.lock() {
                             Ok(mut guard) => {
@@ -142,6 +217,11 @@ impl SerialConnection {
             rx: msg_rx,
             cmd_tx,
             connected,
+            binary_jobs: false,
+            file_jobs: false,
+            line_control: true,
+            modem: Arc::default(),
+            tap,
         })
     }
 
@@ -166,6 +246,8 @@ impl SerialConnection {
         let writer_stream = Arc::new(Mutex::new(Some(stream)));
 
         let (msg_tx, msg_rx) = unbounded::<SerialMsg>();
+        let tap = SessionTap::default();
+        let msg_tx = TappedSender::new(msg_tx, tap.clone());
         let (cmd_tx, cmd_rx) = unbounded::<SerialCmd>();
         let connected = Arc::new(Mutex::new(true));
 
@@ -233,6 +315,19 @@ impl SerialConnection {
                         }
                         Err(_) => break,
                     },
+                    Ok(SerialCmd::SendBinary(bytes)) => match writer_stream.lock() {
+                        Ok(mut guard) => {
+                            if let Some(ref mut stream) = *guard {
+                                let _ = stream.write_all(&bytes);
+                                let _ = stream.flush();
+                            }
+                        }
+                        Err(_) => break,
+                    },
+                    // A raw socket has no way to reach the port's line settings
+                    Ok(SerialCmd::SetBaud(_))
+                    | Ok(SerialCmd::ResetBoard)
+                    | Ok(SerialCmd::UploadFile { .. }) => {}
                     Ok(SerialCmd::Disconnect) | Err(_) => {
                         if let Ok(mut guard) = writer_stream.lock()
                             && let Some(stream) = guard.take()
@@ -252,17 +347,723 @@ impl SerialConnection {
             rx: msg_rx,
             cmd_tx,
             connected,
+            binary_jobs: false,
+            file_jobs: false,
+            line_control: false,
+            modem: Arc::default(),
+            tap,
+        })
+    }
+
+    /// Connect to a serial port behind an RFC 2217 server (ser2net, ESP-Link…).
+    /// Unlike `connect_tcp`, the remote port's baud and DTR/RTS can be set and modem lines are reported.
+    pub fn connect_rfc2217(
+        host: &str,
+        port: u16,
+        baud_rate: u32,
+        backend: Arc<dyn ControllerBackend>,
+    ) -> Result<Self, String> {
+        let addr = format!("{host}:{port}");
+        let mut stream = TcpStream::connect(&addr)
+            .map_err(|e| format!("Failed to connect to {addr}: {e}"))?;
+        stream
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .map_err(|e| format!("Failed to set TCP read timeout for {addr}: {e}"))?;
+        stream
+            .set_write_timeout(Some(Duration::from_millis(100)))
+            .map_err(|e| format!("Failed to set TCP write timeout for {addr}: {e}"))?;
+
+        // Negotiate before the threads start so a plain TCP port fails here
+        let mut client = Rfc2217Client::default();
+        let mut decoder = TelnetDecoder::default();
+        let mut early_events = Vec::new();
+        stream
+            .write_all(&client.opening())
+            .map_err(|e| format!("Failed to write to {addr}: {e}"))?;
+        let deadline = Instant::now() + RFC2217_NEGOTIATION_TIMEOUT;
+        let mut buf = [0u8; 1024];
+        while client.com_port.is_none() && Instant::now() < deadline {
+            let n = match stream.read(&mut buf) {
+                Ok(0) => return Err(format!("{addr} closed the connection during negotiation")),
+                Ok(n) => n,
+                Err(e) if is_timeout(&e) => continue,
+                Err(e) => return Err(format!("Failed to read from {addr}: {e}")),
+            };
+            for event in decoder.feed(&buf[..n]) {
+                let reply = client.handle(&event);
+                if !reply.is_empty() {
+                    let _ = stream.write_all(&reply);
+                }
+                early_events.push(event);
+            }
+        }
+        if client.com_port != Some(true) {
+            return Err(format!(
+                "{addr} did not accept COM-PORT-OPTION; is it an RFC 2217 server?"
+            ));
+        }
+        stream
+            .write_all(&Rfc2217Client::setup(baud_rate))
+            .map_err(|e| format!("Failed to configure remote port on {addr}: {e}"))?;
+
+        let mut reader_stream = stream
+            .try_clone()
+            .map_err(|e| format!("Failed to clone TCP stream for {addr}: {e}"))?;
+        let writer_stream = Arc::new(Mutex::new(Some(stream)));
+
+        let (msg_tx, msg_rx) = unbounded::<SerialMsg>();
+        let tap = SessionTap::default();
+        let msg_tx = TappedSender::new(msg_tx, tap.clone());
+        let (cmd_tx, cmd_rx) = unbounded::<SerialCmd>();
+        let connected = Arc::new(Mutex::new(true));
+        let modem = Arc::new(Mutex::new(client.modem));
+
+        let reader_tx = msg_tx.clone();
+        let connected_for_reader = connected.clone();
+        let modem_for_reader = modem.clone();
+        let stream_for_replies = writer_stream.clone();
+        let addr_for_reader = addr.clone();
+        std::thread::spawn(move || {
+            let _ = reader_tx.send(SerialMsg::Connected(format!("rfc2217://{addr_for_reader}")));
+            let mut line = Vec::new();
+            let mut pending = early_events;
+
+            loop {
+                for event in pending.drain(..) {
+                    let reply = client.handle(&event);
+                    if !reply.is_empty()
+                        && let Ok(mut guard) = stream_for_replies.lock()
+                        && let Some(ref mut stream) = *guard
+                    {
+                        let _ = stream.write_all(&reply);
+                    }
+                    match event {
+                        TelnetEvent::Data(data) => {
+                            for byte in data {
+                                if byte != b'\n' {
+                                    line.push(byte);
+                                    continue;
+                                }
+                                let trimmed = String::from_utf8_lossy(&line).trim().to_string();
+                                line.clear();
+                                if trimmed.is_empty() {
+                                    continue;
+                                }
+                                let response = backend.parse_response(&trimmed);
+                                let _ = reader_tx.send(SerialMsg::Parsed {
+                                    raw: trimmed,
+                                    response,
+                                });
+                            }
+                        }
+                        TelnetEvent::ComPort(ComPortCommand::NotifyModemState(state), true) => {
+                            if let Ok(mut slot) = modem_for_reader.lock() {
+                                *slot = Some(state);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                match reader_stream.read(&mut buf) {
+                    Ok(0) => {
+                        let _ = reader_tx
+                            .send(SerialMsg::Disconnected("Connection closed by server".to_string()));
+                        break;
+                    }
+                    Ok(n) => pending = decoder.feed(&buf[..n]),
+                    Err(e) if is_timeout(&e) => {}
+                    Err(e) => {
+                        let _ = reader_tx.send(SerialMsg::Disconnected(e.to_string()));
+                        break;
+                    }
+                }
+            }
+
+            if let Ok(mut flag) = connected_for_reader.lock() {
+                *flag = false;
+            }
+        });
+
+        let connected_for_writer = connected.clone();
+        std::thread::spawn(move || {
+            let write = |bytes: &[u8]| -> bool {
+                match writer_stream.lock() {
+                    Ok(mut guard) => {
+                        if let Some(ref mut stream) = *guard {
+                            let _ = stream.write_all(bytes);
+                            let _ = stream.flush();
+                        }
+                        true
+                    }
+                    Err(_) => false,
+                }
+            };
+            let set_control = |values: [u8; 2]| {
+                values
+                    .into_iter()
+                    .all(|v| write(&ComPortCommand::SetControl(v).encode(false)))
+            };
+
+            loop {
+                let keep_going = match cmd_rx.recv() {
+                    Ok(SerialCmd::SendLine(line)) => {
+                        write(&rfc2217::escape(format!("{line}\n").as_bytes()))
+                    }
+                    Ok(SerialCmd::SendByte(byte)) => write(&rfc2217::escape(&[byte])),
+                    Ok(SerialCmd::SendBinary(bytes)) => write(&rfc2217::escape(&bytes)),
+                    Ok(SerialCmd::SetBaud(baud)) => {
+                        write(&ComPortCommand::SetBaudRate(baud).encode(false))
+                    }
+                    Ok(SerialCmd::ResetBoard) => {
+                        let [hold, release] = rfc2217::RESET_PULSE;
+                        let held = set_control(hold);
+                        std::thread::sleep(RESET_PULSE);
+                        held && set_control(release)
+                    }
+                    Ok(SerialCmd::UploadFile { .. }) => true,
+                    Ok(SerialCmd::Disconnect) | Err(_) => {
+                        if let Ok(mut guard) = writer_stream.lock()
+                            && let Some(stream) = guard.take()
+                        {
+                            let _ = stream.shutdown(Shutdown::Both);
+                        }
+                        false
+                    }
+                };
+                if !keep_going {
+                    break;
+                }
+            }
+
+            if let Ok(mut flag) = connected_for_writer.lock() {
+                *flag = false;
+            }
+        });
+
+        Ok(Self {
+            rx: msg_rx,
+            cmd_tx,
+            connected,
+            binary_jobs: false,
+            file_jobs: false,
+            line_control: true,
+            modem,
+            tap,
         })
     }
 
+    /// Connect to a FluidNC/ESP3D controller: console over its WebSocket, jobs uploaded over HTTP
+    /// and run from the controller's own storage so Wi-Fi hiccups can't drop G-code bytes.
+    pub fn connect_fluidnc(
+        host: &str,
+        config: FluidNcConfig,
+        backend: Arc<dyn ControllerBackend>,
+    ) -> Result<Self, String> {
+        let addr = format!("{host}:{}", config.ws_port);
+        let mut stream = TcpStream::connect(&addr)
+            .map_err(|e| format!("Failed to connect to {addr}: {e}"))?;
+        stream
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .map_err(|e| format!("Failed to set TCP read timeout for {addr}: {e}"))?;
+        stream
+            .set_write_timeout(Some(Duration::from_millis(500)))
+            .map_err(|e| format!("Failed to set TCP write timeout for {addr}: {e}"))?;
+        let early = fluidnc::handshake(&mut stream, host, config.ws_port)?;
+
+        let mut reader_stream = stream
+            .try_clone()
+            .map_err(|e| format!("Failed to clone TCP stream for {addr}: {e}"))?;
+        let writer_stream = Arc::new(Mutex::new(Some(stream)));
+
+        let (msg_tx, msg_rx) = unbounded::<SerialMsg>();
+        let tap = SessionTap::default();
+        let msg_tx = TappedSender::new(msg_tx, tap.clone());
+        let (cmd_tx, cmd_rx) = unbounded::<SerialCmd>();
+        let connected = Arc::new(Mutex::new(true));
+
+        let reader_tx = msg_tx.clone();
+        let connected_for_reader = connected.clone();
+        let stream_for_pongs = writer_stream.clone();
+        let addr_for_reader = addr.clone();
+        std::thread::spawn(move || {
+            let _ = reader_tx.send(SerialMsg::Connected(format!("ws://{addr_for_reader}")));
+            let mut decoder = FrameDecoder::default();
+            let mut frames = decoder.feed(&early);
+            let mut line = Vec::new();
+            let mut buf = [0u8; 2048];
+
+            'read: loop {
+                for frame in frames.drain(..) {
+                    match frame.opcode {
+                        fluidnc::OP_TEXT
+                            if fluidnc::is_websocket_control(&String::from_utf8_lossy(&frame.payload)) => {}
+                        fluidnc::OP_TEXT | fluidnc::OP_BINARY | fluidnc::OP_CONTINUATION => {
+                            for byte in frame.payload {
+                                if byte != b'\n' {
+                                    line.push(byte);
+                                    continue;
+                                }
+                                let trimmed = String::from_utf8_lossy(&line).trim().to_string();
+                                line.clear();
+                                if trimmed.is_empty() {
+                                    continue;
+                                }
+                                let response = backend.parse_response(&trimmed);
+                                let _ = reader_tx.send(SerialMsg::Parsed {
+                                    raw: trimmed,
+                                    response,
+                                });
+                            }
+                        }
+                        fluidnc::OP_PING => {
+                            if let Ok(mut guard) = stream_for_pongs.lock()
+                                && let Some(ref mut stream) = *guard
+                            {
+                                let _ = stream
+                                    .write_all(&fluidnc::encode_frame(fluidnc::OP_PONG, &frame.payload));
+                            }
+                        }
+                        fluidnc::OP_CLOSE => {
+                            let _ = reader_tx
+                                .send(SerialMsg::Disconnected("WebSocket closed by controller".to_string()));
+                            break 'read;
+                        }
+                        _ => {}
+                    }
+                }
+
+                match reader_stream.read(&mut buf) {
+                    Ok(0) => {
+                        let _ = reader_tx
+                            .send(SerialMsg::Disconnected("Connection closed by controller".to_string()));
+                        break;
+                    }
+                    Ok(n) => frames = decoder.feed(&buf[..n]),
+                    Err(e) if is_timeout(&e) => {}
+                    Err(e) => {
+                        let _ = reader_tx.send(SerialMsg::Disconnected(e.to_string()));
+                        break;
+                    }
+                }
+            }
+
+            if let Ok(mut flag) = connected_for_reader.lock() {
+                *flag = false;
+            }
+        });
+
+        let connected_for_writer = connected.clone();
+        let host = host.to_string();
+        std::thread::spawn(move || {
+            let write = |opcode: u8, payload: &[u8]| -> bool {
+                match writer_stream.lock() {
+                    Ok(mut guard) => {
+                        if let Some(ref mut stream) = *guard {
+                            let _ = stream.write_all(&fluidnc::encode_frame(opcode, payload));
+                        }
+                        true
+                    }
+                    Err(_) => false,
+                }
+            };
+
+            loop {
+                let keep_going = match cmd_rx.recv() {
+                    Ok(SerialCmd::SendLine(line)) => {
+                        write(fluidnc::OP_TEXT, format!("{line}\n").as_bytes())
+                    }
+                    // Real-time bytes above 0x7F are not valid text
+                    Ok(SerialCmd::SendByte(byte)) => write(fluidnc::OP_BINARY, &[byte]),
+                    Ok(SerialCmd::SendBinary(bytes)) => write(fluidnc::OP_BINARY, &bytes),
+                    // The upload can take minutes; it gets its own thread so feed hold,
+                    // reset and status queries queued behind it still go out at once
+                    Ok(SerialCmd::UploadFile { name, bytes, run }) => {
+                        let host = host.clone();
+                        let config = config.clone();
+                        let msg_tx = msg_tx.clone();
+                        let writer_stream = writer_stream.clone();
+                        std::thread::spawn(move || {
+                            let (raw, response) = match fluidnc::upload(&host, &config, &name, &bytes) {
+                                Ok(()) => (
+                                    format!(
+                                        "[fluidnc] uploaded {} byte(s) to {} /{name}",
+                                        bytes.len(),
+                                        config.store.label()
+                                    ),
+                                    ControllerResponse::Message,
+                                ),
+                                Err(e) => (
+                                    format!("error: {e}"),
+                                    ControllerResponse::Grbl(GrblResponse::Error(-1)),
+                                ),
+                            };
+                            let uploaded = matches!(response, ControllerResponse::Message);
+                            let _ = msg_tx.send(SerialMsg::Parsed { raw, response });
+                            if uploaded
+                                && run
+                                && let Ok(mut guard) = writer_stream.lock()
+                                && let Some(ref mut stream) = *guard
+                            {
+                                let command = format!("{}\n", config.store.run_command(&name));
+                                let _ = stream.write_all(&fluidnc::encode_frame(fluidnc::OP_TEXT, command.as_bytes()));
+                            }
+                        });
+                        true
+                    }
+                    Ok(SerialCmd::ResetBoard) | Ok(SerialCmd::SetBaud(_)) => true,
+                    Ok(SerialCmd::Disconnect) | Err(_) => {
+                        if let Ok(mut guard) = writer_stream.lock()
+                            && let Some(mut stream) = guard.take()
+                        {
+                            let _ = stream.write_all(&fluidnc::encode_frame(fluidnc::OP_CLOSE, &[]));
+                            let _ = stream.shutdown(Shutdown::Both);
+                        }
+                        false
+                    }
+                };
+                if !keep_going {
+                    break;
+                }
+            }
+
+            if let Ok(mut flag) = connected_for_writer.lock() {
+                *flag = false;
+            }
+        });
+
+        Ok(Self {
+            rx: msg_rx,
+            cmd_tx,
+            connected,
+            binary_jobs: false,
+            file_jobs: true,
+            line_control: false,
+            modem: Arc::default(),
+            tap,
+        })
+    }
+
+    /// Connect to a Ruida controller over its UDP datagram protocol.
+    /// Status, pause/resume/stop arrive as the Ruida backend's text commands; jobs as `SendBinary`.
+    pub fn connect_ruida_udp(host: &str, port: u16, config: RuidaUdpConfig) -> Result<Self, String> {
+        let client = RuidaUdpClient::connect(host, port, config)?;
+        // Probe once so a wrong address fails here instead of on the first poll.
+        client.read_status()?;
+
+        let (msg_tx, msg_rx) = unbounded::<SerialMsg>();
+        let tap = SessionTap::default();
+        let msg_tx = TappedSender::new(msg_tx, tap.clone());
+        let (cmd_tx, cmd_rx) = unbounded::<SerialCmd>();
+        let connected = Arc::new(Mutex::new(true));
+
+        let connected_for_worker = connected.clone();
+        let addr = format!("udp://{}", client.target());
+        std::thread::spawn(move || {
+            let _ = msg_tx.send(SerialMsg::Connected(addr));
+            let mut missed_polls = 0;
+
+            loop {
+                match cmd_rx.recv() {
+                    Ok(SerialCmd::SendLine(line)) => {
+                        if !run_ruida_command(&client, &line, &msg_tx, &mut missed_polls) {
+                            break;
+                        }
+                    }
+                    Ok(SerialCmd::SendByte(_))
+                    | Ok(SerialCmd::SetBaud(_))
+                    | Ok(SerialCmd::ResetBoard)
+                    | Ok(SerialCmd::UploadFile { .. }) => {}
+                    Ok(SerialCmd::SendBinary(bytes)) => {
+                        let total = bytes.len();
+                        let mut stopped = false;
+                        let mut alive = true;
+                        // The controller cuts while the file arrives: keep stop, pause and status
+                        // going between chunks instead of behind the whole upload
+                        let result = client.upload(&bytes, |sent| {
+                            let _ = msg_tx.send(SerialMsg::UploadProgress { sent, total });
+                            while let Ok(cmd) = cmd_rx.try_recv() {
+                                match cmd {
+                                    SerialCmd::SendLine(line) => {
+                                        stopped |= line.trim() == "stop";
+                                        alive &= run_ruida_command(&client, &line, &msg_tx, &mut missed_polls);
+                                    }
+                                    SerialCmd::Disconnect => alive = false,
+                                    _ => {}
+                                }
+                            }
+                            alive && !stopped
+                        });
+                        let (raw, response) = match result {
+                            Ok(()) => (
+                                format!("[ruida] uploaded {total} byte(s)"),
+                                ControllerResponse::Message,
+                            ),
+                            Err(e) if stopped => (format!("[ruida] job stopped, {e}"), ControllerResponse::Message),
+                            Err(e) => (
+                                format!("error: upload failed: {e}"),
+                                ControllerResponse::Grbl(GrblResponse::Error(-1)),
+                            ),
+                        };
+                        let _ = msg_tx.send(SerialMsg::Parsed { raw, response });
+                        if !alive {
+                            break;
+                        }
+                    }
+                    Ok(SerialCmd::Disconnect) | Err(_) => break,
+                }
+            }
+
+            if let Ok(mut flag) = connected_for_worker.lock() {
+                *flag = false;
+            }
+        });
+
+        Ok(Self {
+            rx: msg_rx,
+            cmd_tx,
+            connected,
+            binary_jobs: true,
+            file_jobs: false,
+            line_control: false,
+            modem: Arc::default(),
+            tap,
+        })
+    }
+
+    /// Run an in-process GRBL 1.1 simulator instead of talking to hardware.
+    /// Motion advances in real time from the configured rates and accelerations.
+    pub fn connect_virtual(
+        config: VirtualGrblConfig,
+        backend: Arc<dyn ControllerBackend>,
+    ) -> Result<Self, String> {
+        let (msg_tx, msg_rx) = unbounded::<SerialMsg>();
+        let tap = SessionTap::default();
+        let msg_tx = TappedSender::new(msg_tx, tap.clone());
+        let (cmd_tx, cmd_rx) = unbounded::<SerialCmd>();
+        let connected = Arc::new(Mutex::new(true));
+
+        let connected_for_worker = connected.clone();
+        std::thread::spawn(move || {
+            let mut machine = VirtualGrbl::new(&config);
+            let _ = msg_tx.send(SerialMsg::Connected("virtual://grbl".to_string()));
+            let mut last_tick = Instant::now();
+
+            loop {
+                match cmd_rx.recv_timeout(VIRTUAL_TICK) {
+                    Ok(SerialCmd::SendLine(line)) => machine.write(format!("{line}\n").as_bytes()),
+                    Ok(SerialCmd::SendByte(byte)) => machine.write(&[byte]),
+                    Ok(SerialCmd::SendBinary(bytes)) => machine.write(&bytes),
+                    Ok(SerialCmd::SetBaud(_))
+                    | Ok(SerialCmd::ResetBoard)
+                    | Ok(SerialCmd::UploadFile { .. }) => {}
+                    Ok(SerialCmd::Disconnect)
+                    | Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+                    Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+                }
+
+                let now = Instant::now();
+                machine.advance((now - last_tick).as_secs_f32());
+                last_tick = now;
+
+                for line in machine.drain_output() {
+                    let response = backend.parse_response(&line);
+                    let _ = msg_tx.send(SerialMsg::Parsed {
+                        raw: line,
+                        response,
+                    });
+                }
+            }
+
+            if let Ok(mut flag) = connected_for_worker.lock() {
+                *flag = false;
+            }
+        });
+
+        Ok(Self {
+            rx: msg_rx,
+            cmd_tx,
+            connected,
+            binary_jobs: false,
+            file_jobs: false,
+            line_control: false,
+            modem: Arc::default(),
+            tap,
+        })
+    }
+
+    /// Play a recorded session back as a fake port. Replies are released in lockstep with the
+    /// host: everything recorded after a TX line waits until the host sends its next line.
+    /// Real-time bytes never gate (status polls are timer driven). With `pace`, the recorded
+    /// gaps between replies are kept.
+    pub fn connect_replay(
+        records: Vec<SessionRecord>,
+        backend: Arc<dyn ControllerBackend>,
+        pace: bool,
+    ) -> Result<Self, String> {
+        if records.is_empty() {
+            return Err("Session has no records".to_string());
+        }
+        let (msg_tx, msg_rx) = unbounded::<SerialMsg>();
+        let (cmd_tx, cmd_rx) = unbounded::<SerialCmd>();
+        let tap = SessionTap::default();
+        let msg_tx = TappedSender::new(msg_tx, tap.clone());
+        let connected = Arc::new(Mutex::new(true));
+
+        let connected_for_worker = connected.clone();
+        std::thread::spawn(move || {
+            let _ = msg_tx.send(SerialMsg::Connected("replay://session".to_string()));
+            let mut cursor = 0;
+            let mut last_at = records[0].at;
+
+            'replay: loop {
+                while let Some(record) = records.get(cursor) {
+                    if matches!(record.event, SessionEvent::Tx(_)) {
+                        break;
+                    }
+                    if pace {
+                        std::thread::sleep(
+                            record.at.saturating_sub(last_at).min(Duration::from_secs(2)),
+                        );
+                    }
+                    last_at = record.at;
+                    let msg = match &record.event {
+                        SessionEvent::Rx(line) => Some(SerialMsg::Parsed {
+                            raw: line.clone(),
+                            response: backend.parse_response(line),
+                        }),
+                        SessionEvent::Disconnected(reason) => {
+                            Some(SerialMsg::Disconnected(reason.clone()))
+                        }
+                        SessionEvent::Error(err) => Some(SerialMsg::Error(err.clone())),
+                        SessionEvent::Tx(_)
+                        | SessionEvent::Realtime(_)
+                        | SessionEvent::Binary(_)
+                        | SessionEvent::Connected(_) => None,
+                    };
+                    cursor += 1;
+                    if let Some(msg) = msg {
+                        let disconnected = matches!(msg, SerialMsg::Disconnected(_));
+                        let _ = msg_tx.send(msg);
+                        if disconnected {
+                            break 'replay;
+                        }
+                    }
+                }
+
+                loop {
+                    match cmd_rx.recv() {
+                        Ok(SerialCmd::SendLine(line)) => {
+                            if let Some(SessionRecord {
+                                at,
+                                event: SessionEvent::Tx(expected),
+                            }) = records.get(cursor)
+                            {
+                                if expected.trim() != line.trim() {
+                                    let _ = msg_tx.send(SerialMsg::Error(format!(
+                                        "Replay diverged at {:.3}s: recorded '{expected}', host sent '{line}'",
+                                        at.as_secs_f64()
+                                    )));
+                                }
+                                last_at = *at;
+                                cursor += 1;
+                                continue 'replay;
+                            }
+                        }
+                        Ok(SerialCmd::SendByte(_))
+                        | Ok(SerialCmd::SendBinary(_))
+                        | Ok(SerialCmd::SetBaud(_))
+                        | Ok(SerialCmd::ResetBoard)
+                        | Ok(SerialCmd::UploadFile { .. }) => {}
+                        Ok(SerialCmd::Disconnect) | Err(_) => break 'replay,
+                    }
+                }
+            }
+
+            if let Ok(mut flag) = connected_for_worker.lock() {
+                *flag = false;
+            }
+        });
+
+        Ok(Self {
+            rx: msg_rx,
+            cmd_tx,
+            connected,
+            binary_jobs: false,
+            file_jobs: false,
+            line_control: false,
+            modem: Arc::default(),
+            tap,
+        })
+    }
+
+    /// Record every TX line, real-time byte and RX line of this connection to `path`
+    pub fn start_recording(&self, path: &std::path::Path) -> Result<(), String> {
+        self.tap.start(SessionRecorder::create(path)?);
+        Ok(())
+    }
+
+    pub fn stop_recording(&self) {
+        self.tap.stop();
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.tap.is_recording()
+    }
+
     pub fn send(&self, line: &str) {
+        self.tap.record_with(|| SessionEvent::Tx(line.to_string()));
         let _ = self.cmd_tx.send(SerialCmd::SendLine(line.to_string()));
     }
 
     pub fn send_byte(&self, byte: u8) {
+        self.tap.record_with(|| SessionEvent::Realtime(byte));
         let _ = self.cmd_tx.send(SerialCmd::SendByte(byte));
     }
 
+    pub fn send_binary(&self, bytes: Vec<u8>) {
+        self.tap.record_with(|| SessionEvent::Binary(bytes.len()));
+        let _ = self.cmd_tx.send(SerialCmd::SendBinary(bytes));
+    }
+
+    /// True when jobs must be uploaded whole via `send_binary` instead of streamed line by line
+    pub fn accepts_binary_jobs(&self) -> bool {
+        self.binary_jobs
+    }
+
+    /// True when jobs go to the controller's storage via `upload_file` instead of being streamed
+    pub fn accepts_file_jobs(&self) -> bool {
+        self.file_jobs
+    }
+
+    pub fn upload_file(&self, name: &str, bytes: Vec<u8>, run: bool) {
+        self.tap.record_with(|| SessionEvent::Binary(bytes.len()));
+        let _ = self.cmd_tx.send(SerialCmd::UploadFile {
+            name: name.to_string(),
+            bytes,
+            run,
+        });
+    }
+
+    /// True when `set_baud_rate` and `reset_board` reach a real port
+    pub fn supports_line_control(&self) -> bool {
+        self.line_control
+    }
+
+    pub fn set_baud_rate(&self, baud: u32) {
+        let _ = self.cmd_tx.send(SerialCmd::SetBaud(baud));
+    }
+
+    /// Pulse DTR/RTS, which resets most USB controller boards
+    pub fn reset_board(&self) {
+        let _ = self.cmd_tx.send(SerialCmd::ResetBoard);
+    }
+
+    /// Modem lines last reported by an RFC 2217 server
+    pub fn modem_state(&self) -> Option<ModemState> {
+        self.modem.lock().ok().and_then(|m| *m)
+    }
+
     pub fn disconnect(&self) {
         let _ = self.cmd_tx.send(SerialCmd::Disconnect);
     }
@@ -310,3 +1111,45 @@ pub fn list_ports() -> Vec<String> {
     ports.sort();
     ports
 }
+
+fn is_timeout(e: &std::io::Error) -> bool {
+    matches!(e.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock)
+}
+
+/// Run one of the Ruida backend's text commands over UDP; false once the link is given up
+fn run_ruida_command(client: &RuidaUdpClient, line: &str, msg_tx: &TappedSender, missed_polls: &mut u32) -> bool {
+    let result = match line.trim() {
+        "status" => match client.read_status() {
+            Ok(status) => {
+                *missed_polls = 0;
+                let _ = msg_tx.send(SerialMsg::Parsed {
+                    raw: status.summary(),
+                    response: ControllerResponse::Grbl(GrblResponse::Status(status.to_grbl_state())),
+                });
+                Ok(())
+            }
+            Err(e) => {
+                *missed_polls += 1;
+                if *missed_polls >= UDP_MAX_MISSED_POLLS {
+                    let _ = msg_tx.send(SerialMsg::Disconnected(e));
+                    return false;
+                }
+                Err(e)
+            }
+        },
+        "pause" => client.pause(),
+        "resume" => client.resume(),
+        "stop" => client.stop(),
+        other => {
+            let _ = msg_tx.send(SerialMsg::Parsed {
+                raw: format!("[ruida] ignored text command: {other}"),
+                response: ControllerResponse::Message,
+            });
+            Ok(())
+        }
+    };
+    if let Err(e) = result {
+        let _ = msg_tx.send(SerialMsg::Error(e));
+    }
+    true
+}
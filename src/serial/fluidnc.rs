@@ -0,0 +1,444 @@
+#![allow(dead_code)]
+
+//! FluidNC / ESP3D network access: WebSocket console framing and the HTTP file upload API.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+
+pub const DEFAULT_HTTP_PORT: u16 = 80;
+
+/// Name the prepared program is uploaded under
+pub const JOB_FILE_NAME: &str = "all4laser.gcode";
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(3);
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub const OP_CONTINUATION: u8 = 0x0;
+pub const OP_TEXT: u8 = 0x1;
+pub const OP_BINARY: u8 = 0x2;
+pub const OP_CLOSE: u8 = 0x8;
+pub const OP_PING: u8 = 0x9;
+pub const OP_PONG: u8 = 0xA;
+
+/// Where uploaded programs are stored on the controller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum FileStore {
+    #[default]
+    Sd,
+    /// Internal flash (LittleFS/SPIFFS)
+    Flash,
+}
+
+impl FileStore {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Sd => "SD card",
+            Self::Flash => "Flash",
+        }
+    }
+
+    /// HTTP endpoint that accepts multipart uploads
+    pub fn upload_path(self) -> &'static str {
+        match self {
+            Self::Sd => "/upload",
+            Self::Flash => "/files",
+        }
+    }
+
+    /// Console command that runs a stored file
+    pub fn run_command(self, name: &str) -> String {
+        match self {
+            Self::Sd => format!("$SD/Run=/{name}"),
+            Self::Flash => format!("$LocalFS/Run=/{name}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FluidNcConfig {
+    pub http_port: u16,
+    /// FluidNC and ESP3D serve the console one port above the web UI
+    pub ws_port: u16,
+    pub store: FileStore,
+}
+
+impl FluidNcConfig {
+    pub fn for_http_port(http_port: u16, store: FileStore) -> Self {
+        Self {
+            http_port,
+            ws_port: http_port.saturating_add(1),
+            store,
+        }
+    }
+}
+
+impl Default for FluidNcConfig {
+    fn default() -> Self {
+        Self::for_http_port(DEFAULT_HTTP_PORT, FileStore::Sd)
+    }
+}
+
+/// Cheap per-process randomness for masking keys and the handshake nonce
+fn random_bytes<const N: usize>() -> [u8; N] {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    let mut x = (nanos ^ COUNTER.fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)) | 1;
+    let mut out = [0u8; N];
+    for b in &mut out {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        *b = x as u8;
+    }
+    out
+}
+
+/// A client-to-server frame; clients must mask every payload
+pub fn encode_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 14);
+    out.push(0x80 | opcode);
+    match payload.len() {
+        len @ 0..=125 => out.push(0x80 | len as u8),
+        len @ 126..=0xFFFF => {
+            out.push(0x80 | 126);
+            out.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            out.push(0x80 | 127);
+            out.extend((len as u64).to_be_bytes());
+        }
+    }
+    let key: [u8; 4] = random_bytes();
+    out.extend(key);
+    out.extend(payload.iter().enumerate().map(|(i, b)| b ^ key[i % 4]));
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsFrame {
+    pub opcode: u8,
+    pub payload: Vec<u8>,
+}
+
+/// Reassembles frames from a byte stream that may split them anywhere
+#[derive(Debug, Default)]
+pub struct FrameDecoder {
+    buf: Vec<u8>,
+}
+
+impl FrameDecoder {
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<WsFrame> {
+        self.buf.extend_from_slice(bytes);
+        let mut frames = Vec::new();
+        while let Some((frame, used)) = Self::parse(&self.buf) {
+            self.buf.drain(..used);
+            frames.push(frame);
+        }
+        frames
+    }
+
+    fn parse(buf: &[u8]) -> Option<(WsFrame, usize)> {
+        let (&b0, rest) = buf.split_first()?;
+        let &b1 = rest.first()?;
+        let mut pos = 2;
+        let len = match b1 & 0x7F {
+            126 => {
+                let len = u16::from_be_bytes(buf.get(2..4)?.try_into().ok()?) as usize;
+                pos = 4;
+                len
+            }
+            127 => {
+                let len = u64::from_be_bytes(buf.get(2..10)?.try_into().ok()?) as usize;
+                pos = 10;
+                len
+            }
+            len => len as usize,
+        };
+        let mask = if b1 & 0x80 != 0 {
+            let key: [u8; 4] = buf.get(pos..pos + 4)?.try_into().ok()?;
+            pos += 4;
+            Some(key)
+        } else {
+            None
+        };
+        let mut payload = buf.get(pos..pos + len)?.to_vec();
+        if let Some(key) = mask {
+            for (i, b) in payload.iter_mut().enumerate() {
+                *b ^= key[i % 4];
+            }
+        }
+        Some((WsFrame { opcode: b0 & 0x0F, payload }, pos + len))
+    }
+}
+
+/// Open a WebSocket on `stream`; returns bytes that arrived after the handshake.
+/// The accept hash is not checked: a `101` from the console port is all we need.
+pub fn handshake(stream: &mut TcpStream, host: &str, port: u16) -> Result<Vec<u8>, String> {
+    let key = base64::engine::general_purpose::STANDARD.encode(random_bytes::<16>());
+    let request = format!(
+        "GET / HTTP/1.1\r\nHost: {host}:{port}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\nSec-WebSocket-Protocol: arduino\r\n\r\n"
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("WebSocket handshake failed: {e}"))?;
+
+    let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+    let mut response = Vec::new();
+    let mut buf = [0u8; 512];
+    let header_end = loop {
+        if let Some(end) = response.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if Instant::now() >= deadline {
+            return Err("WebSocket handshake timed out".to_string());
+        }
+        match stream.read(&mut buf) {
+            Ok(0) => return Err("Connection closed during WebSocket handshake".to_string()),
+            Ok(n) => response.extend_from_slice(&buf[..n]),
+            Err(e)
+                if matches!(e.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock) => {}
+            Err(e) => return Err(format!("WebSocket handshake failed: {e}")),
+        }
+    };
+    let status = String::from_utf8_lossy(&response[..header_end]);
+    let status_line = status.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("101") {
+        return Err(format!("Not a WebSocket console: {status_line}"));
+    }
+    Ok(response[header_end..].to_vec())
+}
+
+/// FluidNC's own channel chatter on text frames, not console output
+pub fn is_websocket_control(text: &str) -> bool {
+    ["CURRENT_ID:", "ACTIVE_ID:", "PING:", "DHT:"]
+        .iter()
+        .any(|prefix| text.starts_with(prefix))
+}
+
+/// `multipart/form-data` body in the shape the FluidNC/ESP3D web UI sends
+pub fn multipart_body(boundary: &str, name: &str, bytes: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(bytes.len() + 512);
+    let mut field = |field_name: &str, value: &str| {
+        body.extend(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{field_name}\"\r\n\r\n{value}\r\n"
+            )
+            .as_bytes(),
+        );
+    };
+    field("path", "/");
+    // The size field lets the controller check free space before accepting the file
+    field(&format!("/{name}S"), &bytes.len().to_string());
+    body.extend(
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"myfile[]\"; filename=\"/{name}\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(bytes);
+    body.extend(format!("\r\n--{boundary}--\r\n").as_bytes());
+    body
+}
+
+/// Upload a file through the controller's web server
+pub fn upload(host: &str, config: &FluidNcConfig, name: &str, bytes: &[u8]) -> Result<(), String> {
+    let boundary = format!("all4laser{:016x}", u64::from_le_bytes(random_bytes()));
+    let url = format!("http://{host}:{}{}", config.http_port, config.store.upload_path());
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(UPLOAD_TIMEOUT))
+        .build()
+        .into();
+    agent
+        .post(&url)
+        .header("Content-Type", &format!("multipart/form-data; boundary={boundary}"))
+        .send(&multipart_body(&boundary, name, bytes)[..])
+        .map_err(|e| format!("Upload to {url} failed: {e}"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::{ControllerKind, create_backend};
+    use crate::serial::connection::{SerialConnection, SerialMsg};
+    use crossbeam_channel::{Sender, unbounded};
+    use std::net::TcpListener;
+
+    #[test]
+    fn frames_round_trip_through_decoder() {
+        let long = vec![b'G'; 300];
+        let mut wire = encode_frame(OP_TEXT, b"$I\n");
+        wire.extend(encode_frame(OP_BINARY, &long));
+        // Unmasked server frame
+        wire.extend([0x80 | OP_PING, 2, b'h', b'i']);
+
+        let mut decoder = FrameDecoder::default();
+        let mut frames = decoder.feed(&wire[..7]);
+        assert!(frames.is_empty());
+        frames.extend(decoder.feed(&wire[7..]));
+        assert_eq!(
+            frames,
+            vec![
+                WsFrame { opcode: OP_TEXT, payload: b"$I\n".to_vec() },
+                WsFrame { opcode: OP_BINARY, payload: long },
+                WsFrame { opcode: OP_PING, payload: b"hi".to_vec() },
+            ]
+        );
+    }
+
+    #[test]
+    fn multipart_carries_path_size_and_file() {
+        let body = String::from_utf8(multipart_body("XYZ", "job.gcode", b"G0 X1\n")).unwrap();
+        assert!(body.starts_with("--XYZ\r\nContent-Disposition: form-data; name=\"path\"\r\n\r\n/\r\n"));
+        assert!(body.contains("name=\"/job.gcodeS\"\r\n\r\n6\r\n"));
+        assert!(body.contains("filename=\"/job.gcode\""));
+        assert!(body.ends_with("G0 X1\n\r\n--XYZ--\r\n"));
+        assert_eq!(FileStore::Flash.run_command("job.gcode"), "$LocalFS/Run=/job.gcode");
+    }
+
+    /// Stand-in console: answers `ok` per line and reports every line it got
+    fn spawn_console(lines: Sender<String>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n")
+                .unwrap();
+            // FluidNC greets with its channel id on a text frame
+            stream.write_all(&[0x80 | OP_TEXT, 12]).unwrap();
+            stream.write_all(b"CURRENT_ID:0").unwrap();
+
+            let mut decoder = FrameDecoder::default();
+            while let Ok(n) = stream.read(&mut buf)
+                && n > 0
+            {
+                for frame in decoder.feed(&buf[..n]) {
+                    if frame.opcode == OP_CLOSE {
+                        return;
+                    }
+                    for line in String::from_utf8_lossy(&frame.payload).lines() {
+                        let _ = lines.send(line.to_string());
+                        stream.write_all(&[0x80 | OP_BINARY, 4]).unwrap();
+                        stream.write_all(b"ok\r\n").unwrap();
+                    }
+                }
+            }
+        });
+        port
+    }
+
+    /// Stand-in web server: accepts one upload and reports the request body
+    fn spawn_http(bodies: Sender<String>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            let (header_end, length) = loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    let head = String::from_utf8_lossy(&request[..end]).to_ascii_lowercase();
+                    let length = head
+                        .lines()
+                        .find_map(|l| l.strip_prefix("content-length:"))
+                        .and_then(|v| v.trim().parse::<usize>().ok())
+                        .unwrap();
+                    break (end + 4, length);
+                }
+            };
+            while request.len() < header_end + length {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let head = String::from_utf8_lossy(&request[..header_end]).to_string();
+            let body = String::from_utf8_lossy(&request[header_end..]).to_string();
+            let _ = bodies.send(format!("{head}{body}"));
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}")
+                .unwrap();
+        });
+        port
+    }
+
+    #[test]
+    fn uploads_program_and_runs_it_from_sd() {
+        let (line_tx, line_rx) = unbounded();
+        let (body_tx, body_rx) = unbounded();
+        let config = FluidNcConfig {
+            http_port: spawn_http(body_tx),
+            ws_port: spawn_console(line_tx),
+            store: FileStore::Sd,
+        };
+        let conn =
+            SerialConnection::connect_fluidnc("127.0.0.1", config, create_backend(ControllerKind::Grbl))
+                .unwrap();
+
+        conn.send("$I");
+        assert_eq!(line_rx.recv_timeout(Duration::from_secs(2)).unwrap(), "$I");
+        conn.upload_file(JOB_FILE_NAME, b"G0 X1\nG1 X2 F600\n".to_vec(), true);
+
+        let request = body_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(request.starts_with("POST /upload "), "{request}");
+        assert!(request.contains("G0 X1\nG1 X2 F600\n"));
+        assert_eq!(
+            line_rx.recv_timeout(Duration::from_secs(2)).unwrap(),
+            "$SD/Run=/all4laser.gcode"
+        );
+
+        let mut raws = Vec::new();
+        while let Ok(msg) = conn.rx.recv_timeout(Duration::from_millis(300)) {
+            if let SerialMsg::Parsed { raw, .. } = msg {
+                raws.push(raw);
+            }
+        }
+        assert!(!raws.iter().any(|r| r.starts_with("CURRENT_ID")));
+        assert_eq!(raws.iter().filter(|r| *r == "ok").count(), 2);
+        conn.disconnect();
+    }
+
+    #[test]
+    fn realtime_bytes_pass_a_stalled_upload() {
+        let (line_tx, line_rx) = unbounded();
+        // HTTP server that accepts the upload and never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let http_port = listener.local_addr().unwrap().port();
+        let (release_tx, release_rx) = unbounded::<()>();
+        std::thread::spawn(move || {
+            let (_stream, _) = listener.accept().unwrap();
+            let _ = release_rx.recv_timeout(Duration::from_secs(10));
+        });
+        let config = FluidNcConfig {
+            http_port,
+            ws_port: spawn_console(line_tx),
+            store: FileStore::Sd,
+        };
+        let conn =
+            SerialConnection::connect_fluidnc("127.0.0.1", config, create_backend(ControllerKind::Grbl))
+                .unwrap();
+
+        conn.upload_file(JOB_FILE_NAME, b"G0 X1\n".to_vec(), true);
+        std::thread::sleep(Duration::from_millis(100));
+        conn.send_byte(b'!');
+        assert_eq!(line_rx.recv_timeout(Duration::from_secs(1)).unwrap(), "!");
+        let _ = release_tx.send(());
+        conn.disconnect();
+    }
+}
@@ -1 +1,5 @@
 pub mod connection;
+pub mod fluidnc;
+pub mod rfc2217;
+pub mod session;
+pub mod watchdog;
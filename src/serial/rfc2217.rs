@@ -0,0 +1,477 @@
+#![allow(dead_code)]
+
+//! RFC 2217 (Telnet COM Port Control) framing and client-side option negotiation.
+
+pub const IAC: u8 = 255;
+pub const DONT: u8 = 254;
+pub const DO: u8 = 253;
+pub const WONT: u8 = 252;
+pub const WILL: u8 = 251;
+pub const SB: u8 = 250;
+pub const SE: u8 = 240;
+
+pub const OPT_BINARY: u8 = 0;
+pub const OPT_SGA: u8 = 3;
+pub const OPT_COM_PORT: u8 = 44;
+
+/// Server replies use the client command code plus this
+const SERVER_OFFSET: u8 = 100;
+
+/// SET-CONTROL values
+pub const CONTROL_DTR_ON: u8 = 8;
+pub const CONTROL_DTR_OFF: u8 = 9;
+pub const CONTROL_RTS_ON: u8 = 11;
+pub const CONTROL_RTS_OFF: u8 = 12;
+
+/// SET-PARITY / SET-STOPSIZE values for 8N1
+pub const PARITY_NONE: u8 = 1;
+pub const STOPSIZE_1: u8 = 1;
+
+/// Modem lines from NOTIFY-MODEMSTATE
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ModemState(pub u8);
+
+impl ModemState {
+    pub fn cts(self) -> bool {
+        self.0 & 0x10 != 0
+    }
+
+    pub fn dsr(self) -> bool {
+        self.0 & 0x20 != 0
+    }
+
+    pub fn ring(self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    pub fn carrier(self) -> bool {
+        self.0 & 0x80 != 0
+    }
+
+    /// Asserted lines, e.g. `CTS DSR`, or `-` if none
+    pub fn summary(self) -> String {
+        let lines: Vec<&str> = [
+            (self.cts(), "CTS"),
+            (self.dsr(), "DSR"),
+            (self.ring(), "RI"),
+            (self.carrier(), "CD"),
+        ]
+        .into_iter()
+        .filter_map(|(on, name)| on.then_some(name))
+        .collect();
+        if lines.is_empty() { "-".to_string() } else { lines.join(" ") }
+    }
+}
+
+/// COM-PORT-OPTION subnegotiation commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComPortCommand {
+    SetBaudRate(u32),
+    SetDataSize(u8),
+    SetParity(u8),
+    SetStopSize(u8),
+    SetControl(u8),
+    NotifyLineState(u8),
+    NotifyModemState(ModemState),
+    SetLineStateMask(u8),
+    SetModemStateMask(u8),
+    PurgeData(u8),
+}
+
+impl ComPortCommand {
+    fn code(self) -> u8 {
+        match self {
+            Self::SetBaudRate(_) => 1,
+            Self::SetDataSize(_) => 2,
+            Self::SetParity(_) => 3,
+            Self::SetStopSize(_) => 4,
+            Self::SetControl(_) => 5,
+            Self::NotifyLineState(_) => 6,
+            Self::NotifyModemState(_) => 7,
+            Self::SetLineStateMask(_) => 10,
+            Self::SetModemStateMask(_) => 11,
+            Self::PurgeData(_) => 12,
+        }
+    }
+
+    /// `IAC SB COM-PORT-OPTION <code> <value> IAC SE`; servers add 100 to the code
+    pub fn encode(self, from_server: bool) -> Vec<u8> {
+        let value = match self {
+            Self::SetBaudRate(baud) => baud.to_be_bytes().to_vec(),
+            Self::NotifyModemState(state) => vec![state.0],
+            Self::SetDataSize(v)
+            | Self::SetParity(v)
+            | Self::SetStopSize(v)
+            | Self::SetControl(v)
+            | Self::NotifyLineState(v)
+            | Self::SetLineStateMask(v)
+            | Self::SetModemStateMask(v)
+            | Self::PurgeData(v) => vec![v],
+        };
+        let code = if from_server { self.code() + SERVER_OFFSET } else { self.code() };
+        let mut out = vec![IAC, SB, OPT_COM_PORT];
+        out.extend(escape(&[code]));
+        out.extend(escape(&value));
+        out.extend([IAC, SE]);
+        out
+    }
+
+    /// Parse an unescaped COM-PORT-OPTION payload; the flag is true for server replies
+    pub fn decode(payload: &[u8]) -> Option<(Self, bool)> {
+        let (&code, value) = payload.split_first()?;
+        let (code, from_server) = if code > SERVER_OFFSET {
+            (code - SERVER_OFFSET, true)
+        } else {
+            (code, false)
+        };
+        let byte = value.first().copied();
+        let command = match code {
+            1 => Self::SetBaudRate(u32::from_be_bytes(value.get(..4)?.try_into().ok()?)),
+            2 => Self::SetDataSize(byte?),
+            3 => Self::SetParity(byte?),
+            4 => Self::SetStopSize(byte?),
+            5 => Self::SetControl(byte?),
+            6 => Self::NotifyLineState(byte?),
+            7 => Self::NotifyModemState(ModemState(byte?)),
+            10 => Self::SetLineStateMask(byte?),
+            11 => Self::SetModemStateMask(byte?),
+            12 => Self::PurgeData(byte?),
+            _ => return None,
+        };
+        Some((command, from_server))
+    }
+}
+
+/// Double every IAC so data bytes can't be read as Telnet commands
+pub fn escape(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    for &b in data {
+        out.push(b);
+        if b == IAC {
+            out.push(IAC);
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelnetEvent {
+    Data(Vec<u8>),
+    /// WILL/WONT/DO/DONT and the option
+    Negotiate(u8, u8),
+    ComPort(ComPortCommand, bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum DecodeState {
+    #[default]
+    Data,
+    Iac,
+    Verb(u8),
+    Sub,
+    SubIac,
+}
+
+/// Splits a Telnet byte stream into data and commands; keeps state across reads
+#[derive(Debug, Default)]
+pub struct TelnetDecoder {
+    state: DecodeState,
+    sub: Vec<u8>,
+}
+
+impl TelnetDecoder {
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<TelnetEvent> {
+        let mut events = Vec::new();
+        let mut data = Vec::new();
+        for &b in bytes {
+            self.state = match (self.state, b) {
+                (DecodeState::Data, IAC) => DecodeState::Iac,
+                (DecodeState::Data, _) => {
+                    data.push(b);
+                    DecodeState::Data
+                }
+                (DecodeState::Iac, IAC) => {
+                    data.push(IAC);
+                    DecodeState::Data
+                }
+                (DecodeState::Iac, WILL | WONT | DO | DONT) => DecodeState::Verb(b),
+                (DecodeState::Iac, SB) => {
+                    self.sub.clear();
+                    DecodeState::Sub
+                }
+                // NOP, GA and friends carry nothing we need
+                (DecodeState::Iac, _) => DecodeState::Data,
+                (DecodeState::Verb(verb), option) => {
+                    flush_data(&mut data, &mut events);
+                    events.push(TelnetEvent::Negotiate(verb, option));
+                    DecodeState::Data
+                }
+                (DecodeState::Sub, IAC) => DecodeState::SubIac,
+                (DecodeState::Sub, _) => {
+                    self.sub.push(b);
+                    DecodeState::Sub
+                }
+                (DecodeState::SubIac, IAC) => {
+                    self.sub.push(IAC);
+                    DecodeState::Sub
+                }
+                (DecodeState::SubIac, _) => {
+                    // SE, or a malformed end; either way the subnegotiation is over
+                    if let Some((&OPT_COM_PORT, payload)) = self.sub.split_first()
+                        && let Some((command, from_server)) = ComPortCommand::decode(payload)
+                    {
+                        flush_data(&mut data, &mut events);
+                        events.push(TelnetEvent::ComPort(command, from_server));
+                    }
+                    DecodeState::Data
+                }
+            };
+        }
+        flush_data(&mut data, &mut events);
+        events
+    }
+}
+
+fn flush_data(data: &mut Vec<u8>, events: &mut Vec<TelnetEvent>) {
+    if !data.is_empty() {
+        events.push(TelnetEvent::Data(std::mem::take(data)));
+    }
+}
+
+/// Client side of the option negotiation plus the port state the server reported
+#[derive(Debug, Clone)]
+pub struct Rfc2217Client {
+    /// Options we have agreed to perform (WILL)
+    local: [bool; 256],
+    /// Options we asked the server to perform (DO)
+    remote: [bool; 256],
+    /// None until the server answers our WILL COM-PORT-OPTION
+    pub com_port: Option<bool>,
+    pub baud_rate: Option<u32>,
+    pub modem: Option<ModemState>,
+}
+
+impl Default for Rfc2217Client {
+    fn default() -> Self {
+        Self {
+            local: [false; 256],
+            remote: [false; 256],
+            com_port: None,
+            baud_rate: None,
+            modem: None,
+        }
+    }
+}
+
+impl Rfc2217Client {
+    /// Offer COM-PORT-OPTION and binary, ask for binary and suppress-go-ahead
+    pub fn opening(&mut self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for option in [OPT_COM_PORT, OPT_BINARY, OPT_SGA] {
+            self.local[option as usize] = true;
+            out.extend([IAC, WILL, option]);
+        }
+        for option in [OPT_BINARY, OPT_SGA] {
+            self.remote[option as usize] = true;
+            out.extend([IAC, DO, option]);
+        }
+        out
+    }
+
+    /// Port setup once the server accepted the option: baud, 8N1 and modem line reports
+    pub fn setup(baud: u32) -> Vec<u8> {
+        [
+            ComPortCommand::SetBaudRate(baud),
+            ComPortCommand::SetDataSize(8),
+            ComPortCommand::SetParity(PARITY_NONE),
+            ComPortCommand::SetStopSize(STOPSIZE_1),
+            ComPortCommand::SetModemStateMask(0xFF),
+        ]
+        .into_iter()
+        .flat_map(|c| c.encode(false))
+        .collect()
+    }
+
+    /// Track an event from the server; returns any negotiation reply to send
+    pub fn handle(&mut self, event: &TelnetEvent) -> Vec<u8> {
+        match *event {
+            TelnetEvent::Negotiate(verb, option) => self.negotiate(verb, option),
+            TelnetEvent::ComPort(ComPortCommand::SetBaudRate(baud), true) => {
+                self.baud_rate = Some(baud);
+                Vec::new()
+            }
+            TelnetEvent::ComPort(ComPortCommand::NotifyModemState(state), true) => {
+                self.modem = Some(state);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn negotiate(&mut self, verb: u8, option: u8) -> Vec<u8> {
+        let supported = matches!(option, OPT_BINARY | OPT_SGA | OPT_COM_PORT);
+        let idx = option as usize;
+        match verb {
+            DO => {
+                if option == OPT_COM_PORT {
+                    self.com_port = Some(true);
+                }
+                match (supported, self.local[idx]) {
+                    (true, true) => Vec::new(),
+                    (true, false) => {
+                        self.local[idx] = true;
+                        vec![IAC, WILL, option]
+                    }
+                    (false, _) => vec![IAC, WONT, option],
+                }
+            }
+            DONT => {
+                if option == OPT_COM_PORT {
+                    self.com_port = Some(false);
+                }
+                if std::mem::take(&mut self.local[idx]) { vec![IAC, WONT, option] } else { Vec::new() }
+            }
+            WILL => match (supported && option != OPT_COM_PORT, self.remote[idx]) {
+                (true, true) => Vec::new(),
+                (true, false) => {
+                    self.remote[idx] = true;
+                    vec![IAC, DO, option]
+                }
+                (false, _) => vec![IAC, DONT, option],
+            },
+            WONT => {
+                if std::mem::take(&mut self.remote[idx]) { vec![IAC, DONT, option] } else { Vec::new() }
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Control sequence for a board reset: hold RTS (EN on ESP boards) and drop DTR, then release
+pub const RESET_PULSE: [[u8; 2]; 2] = [
+    [CONTROL_DTR_OFF, CONTROL_RTS_ON],
+    [CONTROL_DTR_ON, CONTROL_RTS_OFF],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::{ControllerKind, create_backend};
+    use crate::serial::connection::{SerialConnection, SerialMsg};
+    use crossbeam_channel::{Receiver, unbounded};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    #[test]
+    fn decoder_splits_data_and_commands() {
+        let mut wire = vec![b'o', IAC, IAC, IAC, DO, OPT_COM_PORT];
+        // 65535 baud puts two IAC bytes inside the subnegotiation
+        wire.extend(ComPortCommand::SetBaudRate(65535).encode(true));
+        wire.extend(b"k\n");
+
+        let mut decoder = TelnetDecoder::default();
+        // Split mid-subnegotiation to check state carries over
+        let mut events = decoder.feed(&wire[..10]);
+        events.extend(decoder.feed(&wire[10..]));
+        assert_eq!(
+            events,
+            vec![
+                TelnetEvent::Data(vec![b'o', IAC]),
+                TelnetEvent::Negotiate(DO, OPT_COM_PORT),
+                TelnetEvent::ComPort(ComPortCommand::SetBaudRate(65535), true),
+                TelnetEvent::Data(b"k\n".to_vec()),
+            ]
+        );
+    }
+
+    /// Minimal RFC 2217 server: accepts (or refuses) COM-PORT-OPTION, acks settings,
+    /// reports CTS+DSR and answers every line with `ok`
+    fn spawn_stand_in(accept: bool) -> (u16, Receiver<ComPortCommand>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = unbounded();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut decoder = TelnetDecoder::default();
+            let mut buf = [0u8; 256];
+            while let Ok(n) = stream.read(&mut buf)
+                && n > 0
+            {
+                for event in decoder.feed(&buf[..n]) {
+                    let reply = match event {
+                        TelnetEvent::Negotiate(WILL, OPT_COM_PORT) => {
+                            vec![IAC, if accept { DO } else { DONT }, OPT_COM_PORT]
+                        }
+                        TelnetEvent::Negotiate(_, _) => Vec::new(),
+                        TelnetEvent::ComPort(command, false) => {
+                            let _ = tx.send(command);
+                            let mut reply = command.encode(true);
+                            if let ComPortCommand::SetModemStateMask(_) = command {
+                                reply.extend(
+                                    ComPortCommand::NotifyModemState(ModemState(0x30)).encode(true),
+                                );
+                            }
+                            reply
+                        }
+                        TelnetEvent::ComPort(_, true) => Vec::new(),
+                        TelnetEvent::Data(data) => {
+                            b"ok\n".repeat(data.iter().filter(|&&b| b == b'\n').count())
+                        }
+                    };
+                    if stream.write_all(&reply).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        (port, rx)
+    }
+
+    fn next_line(conn: &SerialConnection) -> String {
+        loop {
+            match conn.rx.recv_timeout(Duration::from_secs(2)).unwrap() {
+                SerialMsg::Parsed { raw, .. } => return raw,
+                SerialMsg::Connected(_) => {}
+                other => panic!("unexpected {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn connection_negotiates_and_controls_remote_port() {
+        let (port, server) = spawn_stand_in(true);
+        let backend = create_backend(ControllerKind::Grbl);
+        let conn = SerialConnection::connect_rfc2217("127.0.0.1", port, 115200, backend).unwrap();
+
+        conn.send("G0 X1");
+        assert_eq!(next_line(&conn), "ok");
+        assert_eq!(conn.modem_state().map(|m| m.summary()), Some("CTS DSR".to_string()));
+
+        conn.set_baud_rate(57600);
+        conn.reset_board();
+        let seen: Vec<ComPortCommand> = server.iter().take(10).collect();
+        assert_eq!(seen[0], ComPortCommand::SetBaudRate(115200));
+        assert_eq!(seen[5], ComPortCommand::SetBaudRate(57600));
+        assert_eq!(
+            seen[6..],
+            [
+                ComPortCommand::SetControl(CONTROL_DTR_OFF),
+                ComPortCommand::SetControl(CONTROL_RTS_ON),
+                ComPortCommand::SetControl(CONTROL_DTR_ON),
+                ComPortCommand::SetControl(CONTROL_RTS_OFF),
+            ]
+        );
+        conn.disconnect();
+    }
+
+    #[test]
+    fn refused_option_fails_connect() {
+        let (port, _server) = spawn_stand_in(false);
+        let backend = create_backend(ControllerKind::Grbl);
+        let err = SerialConnection::connect_rfc2217("127.0.0.1", port, 115200, backend)
+            .err()
+            .unwrap();
+        assert!(err.contains("COM-PORT"), "{err}");
+    }
+}
@@ -0,0 +1,381 @@
+#![allow(dead_code)]
+
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{SendError, Sender};
+
+use super::connection::SerialMsg;
+
+pub const SESSION_HEADER: &str = "# All4Laser serial session v1";
+
+/// One thing that crossed the wire (or the connection state changing)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// Line written by the host, without the trailing newline
+    Tx(String),
+    /// Real-time byte written by the host (`?`, `!`, overrides…)
+    Realtime(u8),
+    /// Binary upload; only the size is kept
+    Binary(usize),
+    /// Line received from the controller
+    Rx(String),
+    Connected(String),
+    Disconnected(String),
+    Error(String),
+}
+
+/// Event with its offset from the start of the recording.
+/// Stored one per line as `<seconds> <TAG> <payload>`, e.g. `12.345 TX G1 X10`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionRecord {
+    pub at: Duration,
+    pub event: SessionEvent,
+}
+
+impl SessionRecord {
+    pub fn to_line(&self) -> String {
+        let t = self.at.as_secs_f64();
+        let one_line = |s: &str| s.replace(['\r', '\n'], " ");
+        match &self.event {
+            SessionEvent::Tx(line) => format!("{t:.3} TX {}", one_line(line)),
+            SessionEvent::Realtime(byte) => format!("{t:.3} RT 0x{byte:02X}"),
+            SessionEvent::Binary(len) => format!("{t:.3} BIN {len}"),
+            SessionEvent::Rx(line) => format!("{t:.3} RX {}", one_line(line)),
+            SessionEvent::Connected(port) => format!("{t:.3} CONN {}", one_line(port)),
+            SessionEvent::Disconnected(reason) => format!("{t:.3} DISC {}", one_line(reason)),
+            SessionEvent::Error(err) => format!("{t:.3} ERR {}", one_line(err)),
+        }
+    }
+
+    pub fn parse(line: &str) -> Option<Self> {
+        let (time, rest) = line.split_once(' ')?;
+        let secs: f64 = time.parse().ok().filter(|s: &f64| *s >= 0.0)?;
+        let (tag, payload) = rest.split_once(' ').unwrap_or((rest, ""));
+        let event = match tag {
+            "TX" => SessionEvent::Tx(payload.to_string()),
+            "RT" => SessionEvent::Realtime(
+                u8::from_str_radix(payload.trim().trim_start_matches("0x"), 16).ok()?,
+            ),
+            "BIN" => SessionEvent::Binary(payload.trim().parse().ok()?),
+            "RX" => SessionEvent::Rx(payload.to_string()),
+            "CONN" => SessionEvent::Connected(payload.to_string()),
+            "DISC" => SessionEvent::Disconnected(payload.to_string()),
+            "ERR" => SessionEvent::Error(payload.to_string()),
+            _ => return None,
+        };
+        Some(Self {
+            at: Duration::from_secs_f64(secs),
+            event,
+        })
+    }
+}
+
+/// Parse a session file's text; comments and blank lines are skipped
+pub fn parse_session(text: &str) -> Result<Vec<SessionRecord>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            SessionRecord::parse(line)
+                .ok_or_else(|| format!("Invalid session record on line {}: {line}", i + 1))
+        })
+        .collect()
+}
+
+pub fn load_session(path: &Path) -> Result<Vec<SessionRecord>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    parse_session(&text)
+}
+
+/// `sessions/session-<unix time>.log` next to the executable
+pub fn default_session_path() -> PathBuf {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    std::env::current_exe()
+        .unwrap_or_default()
+        .parent()
+        .unwrap_or(Path::new("."))
+        .join("sessions")
+        .join(format!("session-{stamp}.log"))
+}
+
+/// Writes timestamped session records; every record is flushed so a crash keeps the capture
+pub struct SessionRecorder {
+    writer: Box<dyn Write + Send>,
+    started: Instant,
+}
+
+impl SessionRecorder {
+    pub fn create(path: &Path) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        let file = std::fs::File::create(path)
+            .map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+        Ok(Self::from_writer(Box::new(BufWriter::new(file))))
+    }
+
+    pub fn from_writer(mut writer: Box<dyn Write + Send>) -> Self {
+        let _ = writeln!(writer, "{SESSION_HEADER}");
+        Self {
+            writer,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn record(&mut self, event: SessionEvent) {
+        let record = SessionRecord {
+            at: self.started.elapsed(),
+            event,
+        };
+        let _ = writeln!(self.writer, "{}", record.to_line());
+        let _ = self.writer.flush();
+    }
+}
+
+/// Recorder slot shared by a connection's threads; empty while not recording
+#[derive(Clone, Default)]
+pub struct SessionTap(Arc<Mutex<Option<SessionRecorder>>>);
+
+impl SessionTap {
+    pub fn start(&self, recorder: SessionRecorder) {
+        if let Ok(mut slot) = self.0.lock() {
+            *slot = Some(recorder);
+        }
+    }
+
+    pub fn stop(&self) {
+        if let Ok(mut slot) = self.0.lock() {
+            *slot = None;
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.0.lock().map(|slot| slot.is_some()).unwrap_or(false)
+    }
+
+    /// Record the event built by `event`, which only runs while recording
+    pub fn record_with(&self, event: impl FnOnce() -> SessionEvent) {
+        if let Ok(mut slot) = self.0.lock()
+            && let Some(recorder) = slot.as_mut()
+        {
+            recorder.record(event());
+        }
+    }
+}
+
+/// Message sender that records everything going to the app before forwarding it
+#[derive(Clone)]
+pub struct TappedSender {
+    tx: Sender<SerialMsg>,
+    tap: SessionTap,
+}
+
+impl TappedSender {
+    pub fn new(tx: Sender<SerialMsg>, tap: SessionTap) -> Self {
+        Self { tx, tap }
+    }
+
+    pub fn send(&self, msg: SerialMsg) -> Result<(), SendError<SerialMsg>> {
+        // Progress follows from the recorded upload; a replay has nothing to show it for
+        if let SerialMsg::UploadProgress { .. } = msg {
+            return self.tx.send(msg);
+        }
+        self.tap.record_with(|| match &msg {
+            SerialMsg::Parsed { raw, .. } => SessionEvent::Rx(raw.clone()),
+            SerialMsg::Connected(port) => SessionEvent::Connected(port.clone()),
+            SerialMsg::Disconnected(reason) => SessionEvent::Disconnected(reason.clone()),
+            SerialMsg::Error(err) => SessionEvent::Error(err.clone()),
+            SerialMsg::UploadProgress { .. } => unreachable!("upload progress is not recorded"),
+        });
+        self.tx.send(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::{ControllerKind, ControllerResponse, create_backend};
+    use crate::grbl::types::GrblResponse;
+    use crate::serial::connection::SerialConnection;
+
+    /// Writer that keeps its bytes reachable from the test
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn record(ms: u64, event: SessionEvent) -> SessionRecord {
+        SessionRecord {
+            at: Duration::from_millis(ms),
+            event,
+        }
+    }
+
+    #[test]
+    fn records_round_trip_through_text() {
+        let records = vec![
+            record(0, SessionEvent::Connected("/dev/ttyUSB0".into())),
+            record(12, SessionEvent::Tx("G1 X10 Y5 F1200".into())),
+            record(13, SessionEvent::Realtime(b'?')),
+            record(15, SessionEvent::Rx("<Run|MPos:1.000,0.000,0.000|FS:1200,0>".into())),
+            record(20, SessionEvent::Binary(4096)),
+            record(21, SessionEvent::Rx("ok".into())),
+            record(1500, SessionEvent::Error("write failed".into())),
+            record(2000, SessionEvent::Disconnected("Broken pipe".into())),
+        ];
+        let text: String = records
+            .iter()
+            .map(|r| r.to_line() + "\n")
+            .collect::<String>();
+        assert!(text.contains("0.013 RT 0x3F"));
+        let parsed = parse_session(&format!("{SESSION_HEADER}\n{text}")).unwrap();
+        assert_eq!(parsed, records);
+        assert!(parse_session("1.0 XX what").is_err());
+    }
+
+    #[test]
+    fn tap_records_only_while_started() {
+        let buffer = SharedBuffer::default();
+        let tap = SessionTap::default();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let sender = TappedSender::new(tx, tap.clone());
+
+        sender.send(SerialMsg::Error("before".into())).unwrap();
+        tap.start(SessionRecorder::from_writer(Box::new(buffer.clone())));
+        assert!(tap.is_recording());
+        tap.record_with(|| SessionEvent::Tx("G0 X1".into()));
+        sender
+            .send(SerialMsg::Parsed {
+                raw: "ok".into(),
+                response: ControllerResponse::Grbl(GrblResponse::Ok),
+            })
+            .unwrap();
+        tap.stop();
+        sender.send(SerialMsg::Error("after".into())).unwrap();
+        assert_eq!(rx.try_iter().count(), 3);
+
+        let text = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<SessionEvent> = parse_session(&text)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.event)
+            .collect();
+        assert_eq!(
+            events,
+            vec![SessionEvent::Tx("G0 X1".into()), SessionEvent::Rx("ok".into())]
+        );
+    }
+
+    fn next_msg(conn: &SerialConnection) -> SerialMsg {
+        conn.rx
+            .recv_timeout(Duration::from_secs(2))
+            .expect("replay message")
+    }
+
+    fn assert_quiet(conn: &SerialConnection) {
+        assert!(
+            conn.rx.recv_timeout(Duration::from_millis(50)).is_err(),
+            "replay ran ahead of the host"
+        );
+    }
+
+    #[test]
+    fn replay_releases_replies_in_lockstep_with_host_lines() {
+        let session = "\
+# All4Laser serial session v1
+0.000 TX G1 X1 F600
+0.001 RT 0x3F
+0.020 RX ok
+0.021 TX G1 X2
+0.030 RX <Run|MPos:1.500,0.000,0.000|FS:600,0>
+0.040 RX ok
+0.300 RX <Idle|MPos:2.000,0.000,0.000|FS:0,0>
+";
+        let records = parse_session(session).unwrap();
+        let conn = SerialConnection::connect_replay(
+            records,
+            create_backend(ControllerKind::Grbl),
+            false,
+        )
+        .unwrap();
+        assert!(matches!(next_msg(&conn), SerialMsg::Connected(_)));
+        assert_quiet(&conn);
+
+        // Stream like poll_serial does: next line on each ok, done after the last ok
+        let program = ["G1 X1 F600", "G1 X2"];
+        let mut sent = 0;
+        let mut acked = 0;
+        let mut last_status = None;
+        conn.send_byte(b'?');
+        conn.send(program[sent]);
+        sent += 1;
+        while acked < program.len() {
+            match next_msg(&conn) {
+                SerialMsg::Parsed {
+                    response: ControllerResponse::Grbl(GrblResponse::Ok),
+                    ..
+                } => {
+                    acked += 1;
+                    if sent < program.len() {
+                        conn.send(program[sent]);
+                        sent += 1;
+                    }
+                }
+                SerialMsg::Parsed {
+                    response: ControllerResponse::Grbl(GrblResponse::Status(state)),
+                    ..
+                } => last_status = Some(state.status),
+                other => panic!("unexpected {other:?}"),
+            }
+        }
+        assert_eq!(
+            last_status,
+            Some(crate::grbl::types::MacStatus::Run),
+            "status between the two oks"
+        );
+        assert!(matches!(
+            next_msg(&conn),
+            SerialMsg::Parsed {
+                response: ControllerResponse::Grbl(GrblResponse::Status(_)),
+                ..
+            }
+        ));
+        conn.disconnect();
+    }
+
+    #[test]
+    fn replay_reports_divergence_from_the_recording() {
+        let records = parse_session("0.0 TX G0 X0\n0.1 RX ok\n0.2 DISC port closed\n").unwrap();
+        let conn = SerialConnection::connect_replay(
+            records,
+            create_backend(ControllerKind::Grbl),
+            false,
+        )
+        .unwrap();
+        assert!(matches!(next_msg(&conn), SerialMsg::Connected(_)));
+        conn.send("G0 X5");
+        match next_msg(&conn) {
+            SerialMsg::Error(err) => assert!(err.contains("G0 X0") && err.contains("G0 X5")),
+            other => panic!("expected divergence error, got {other:?}"),
+        }
+        assert!(matches!(next_msg(&conn), SerialMsg::Parsed { .. }));
+        assert!(matches!(next_msg(&conn), SerialMsg::Disconnected(_)));
+    }
+}
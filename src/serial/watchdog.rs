@@ -0,0 +1,242 @@
+#![allow(dead_code)]
+
+use std::time::{Duration, Instant};
+
+/// No traffic for this long while status is being polled means the link is dead
+pub const STALL_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often a serial connection checks that its port still exists
+pub const PORT_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const FIRST_RETRY: Duration = Duration::from_secs(1);
+const MAX_RETRY: Duration = Duration::from_secs(10);
+/// Roughly two minutes of retries before giving up
+pub const MAX_ATTEMPTS: u32 = 15;
+
+/// What a serial port looked like when we connected, to find it again after re-enumeration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortIdentity {
+    pub path: String,
+    pub serial_number: Option<String>,
+    pub usb_id: Option<(u16, u16)>,
+}
+
+/// One port the system currently lists
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortCandidate {
+    pub path: String,
+    pub serial_number: Option<String>,
+    pub usb_id: Option<(u16, u16)>,
+}
+
+impl PortCandidate {
+    fn from_info(info: serialport::SerialPortInfo) -> Self {
+        let (serial_number, usb_id) = match info.port_type {
+            serialport::SerialPortType::UsbPort(usb) => (
+                usb.serial_number.filter(|s| !s.trim().is_empty()),
+                Some((usb.vid, usb.pid)),
+            ),
+            _ => (None, None),
+        };
+        Self {
+            path: info.port_name,
+            serial_number,
+            usb_id,
+        }
+    }
+}
+
+/// Ports the system lists right now, with their USB details where available
+pub fn available_ports() -> Vec<PortCandidate> {
+    serialport::available_ports()
+        .map(|ports| ports.into_iter().map(PortCandidate::from_info).collect())
+        .unwrap_or_default()
+}
+
+impl PortIdentity {
+    /// Identity of `path` from the current port list; just the path if it isn't listed
+    pub fn capture(path: &str, ports: &[PortCandidate]) -> Self {
+        let listed = ports.iter().find(|p| p.path == path);
+        Self {
+            path: path.to_string(),
+            serial_number: listed.and_then(|p| p.serial_number.clone()),
+            usb_id: listed.and_then(|p| p.usb_id),
+        }
+    }
+
+    /// Where the device is now: same USB serial number first (the path can change when it
+    /// re-enumerates), then the same path, as long as it isn't a different USB device
+    pub fn locate(&self, ports: &[PortCandidate]) -> Option<String> {
+        if let Some(serial) = &self.serial_number
+            && let Some(port) = ports
+                .iter()
+                .find(|p| p.serial_number.as_ref() == Some(serial) && p.usb_id == self.usb_id)
+        {
+            return Some(port.path.clone());
+        }
+        ports
+            .iter()
+            .find(|p| {
+                p.path == self.path
+                    && (self.serial_number.is_none() || p.serial_number.is_none() || p.serial_number == self.serial_number)
+            })
+            .map(|p| p.path.clone())
+    }
+}
+
+/// Notices a link that stopped answering status polls
+#[derive(Debug)]
+pub struct LinkWatchdog {
+    last_traffic: Instant,
+    last_port_check: Instant,
+    /// The current quiet spell was already reported
+    stall_reported: bool,
+}
+
+impl Default for LinkWatchdog {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+impl LinkWatchdog {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            last_traffic: now,
+            last_port_check: now,
+            stall_reported: false,
+        }
+    }
+
+    pub fn note_traffic(&mut self, now: Instant) {
+        self.last_traffic = now;
+        self.stall_reported = false;
+    }
+
+    /// True once per quiet spell, for stalls that are only warned about
+    pub fn report_stall(&mut self) -> bool {
+        !std::mem::replace(&mut self.stall_reported, true)
+    }
+
+    /// Time since the controller last said anything, once past the stall timeout
+    pub fn stalled(&self, now: Instant) -> Option<Duration> {
+        let quiet = now.saturating_duration_since(self.last_traffic);
+        (quiet >= STALL_TIMEOUT).then_some(quiet)
+    }
+
+    /// True at most once per [`PORT_CHECK_INTERVAL`]
+    pub fn port_check_due(&mut self, now: Instant) -> bool {
+        if now.saturating_duration_since(self.last_port_check) < PORT_CHECK_INTERVAL {
+            return false;
+        }
+        self.last_port_check = now;
+        true
+    }
+}
+
+/// Retry schedule after the link was lost
+#[derive(Debug)]
+pub struct Reconnector {
+    pub reason: String,
+    pub attempts: u32,
+    next_attempt: Instant,
+    delay: Duration,
+}
+
+impl Reconnector {
+    pub fn new(reason: String, now: Instant) -> Self {
+        Self {
+            reason,
+            attempts: 0,
+            next_attempt: now + FIRST_RETRY,
+            delay: FIRST_RETRY,
+        }
+    }
+
+    /// Claims the next attempt if its time has come
+    pub fn attempt_due(&mut self, now: Instant) -> bool {
+        if now < self.next_attempt {
+            return false;
+        }
+        self.attempts += 1;
+        true
+    }
+
+    /// Schedule the next try with a doubling delay; false once out of attempts
+    pub fn failed(&mut self, now: Instant) -> bool {
+        if self.attempts >= MAX_ATTEMPTS {
+            return false;
+        }
+        self.delay = (self.delay * 2).min(MAX_RETRY);
+        self.next_attempt = now + self.delay;
+        true
+    }
+
+    pub fn seconds_until_next(&self, now: Instant) -> u64 {
+        self.next_attempt.saturating_duration_since(now).as_secs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usb(path: &str, serial: &str) -> PortCandidate {
+        PortCandidate {
+            path: path.to_string(),
+            serial_number: Some(serial.to_string()),
+            usb_id: Some((0x1a86, 0x7523)),
+        }
+    }
+
+    #[test]
+    fn finds_device_after_reenumeration() {
+        let before = vec![usb("/dev/ttyUSB0", "A1"), usb("/dev/ttyUSB1", "B2")];
+        let identity = PortIdentity::capture("/dev/ttyUSB0", &before);
+        assert_eq!(identity.serial_number.as_deref(), Some("A1"));
+
+        // Unplugged and back as ttyUSB2 while the other board took ttyUSB0
+        let after = vec![usb("/dev/ttyUSB0", "B2"), usb("/dev/ttyUSB2", "A1")];
+        assert_eq!(identity.locate(&after).as_deref(), Some("/dev/ttyUSB2"));
+        assert_eq!(identity.locate(&[usb("/dev/ttyUSB0", "B2")]), None);
+
+        // Without USB details the path is all there is
+        let plain = PortIdentity::capture("COM3", &[]);
+        let listed = PortCandidate {
+            path: "COM3".to_string(),
+            serial_number: None,
+            usb_id: None,
+        };
+        assert_eq!(plain.locate(std::slice::from_ref(&listed)).as_deref(), Some("COM3"));
+        assert_eq!(plain.locate(&[]), None);
+    }
+
+    #[test]
+    fn stall_detection_and_backoff() {
+        let start = Instant::now();
+        let mut watchdog = LinkWatchdog::new(start);
+        assert_eq!(watchdog.stalled(start + Duration::from_secs(4)), None);
+        watchdog.note_traffic(start + Duration::from_secs(4));
+        assert_eq!(watchdog.stalled(start + Duration::from_secs(8)), None);
+        assert!(watchdog.stalled(start + Duration::from_secs(10)).is_some());
+        assert!(watchdog.report_stall());
+        assert!(!watchdog.report_stall());
+        watchdog.note_traffic(start + Duration::from_secs(11));
+        assert!(watchdog.report_stall());
+        assert!(!watchdog.port_check_due(start + Duration::from_secs(1)));
+        assert!(watchdog.port_check_due(start + Duration::from_secs(3)));
+        assert!(!watchdog.port_check_due(start + Duration::from_secs(4)));
+
+        let mut retry = Reconnector::new("port vanished".to_string(), start);
+        assert!(!retry.attempt_due(start));
+        let mut now = start + FIRST_RETRY;
+        let mut delays = Vec::new();
+        while retry.attempt_due(now) {
+            if !retry.failed(now) {
+                break;
+            }
+            delays.push(retry.seconds_until_next(now));
+            now += Duration::from_secs(retry.seconds_until_next(now));
+        }
+        assert_eq!(retry.attempts, MAX_ATTEMPTS);
+        assert_eq!(&delays[..5], &[2, 4, 8, 10, 10]);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::i18n::tr;
+use crate::serial::rfc2217::ModemState;
 use crate::theme;
 use egui::{ComboBox, RichText, Ui};
 
@@ -7,6 +8,15 @@ pub enum ConnectionMode {
     #[default]
     Serial,
     Network,
+    /// Serial port behind an RFC 2217 server (ser2net, ESP-Link)
+    Rfc2217,
+    /// FluidNC/ESP3D WebSocket console with HTTP job upload
+    FluidNc,
+    RuidaUdp,
+    /// In-process GRBL simulator, no hardware needed
+    Virtual,
+    /// Plays back a recorded serial session
+    Replay,
 }
 
 impl ConnectionMode {
@@ -14,6 +24,11 @@ impl ConnectionMode {
         match self {
             Self::Serial => "Serial",
             Self::Network => "Network (TCP/IP)",
+            Self::Rfc2217 => "Remote serial (RFC 2217)",
+            Self::FluidNc => "FluidNC / ESP3D (WebSocket)",
+            Self::RuidaUdp => "Ruida (UDP)",
+            Self::Virtual => "Virtual machine",
+            Self::Replay => "Replay session",
         }
     }
 }
@@ -23,6 +38,10 @@ pub struct ConnectionAction {
     pub disconnect: bool,
     pub refresh_ports: bool,
     pub test_network: bool,
+    pub browse_session: bool,
+    pub reset_board: bool,
+    /// The baud rate was changed while connected
+    pub baud_changed: bool,
 }
 
 impl Default for ConnectionAction {
@@ -32,6 +51,9 @@ impl Default for ConnectionAction {
             disconnect: false,
             refresh_ports: false,
             test_network: false,
+            browse_session: false,
+            reset_board: false,
+            baud_changed: false,
         }
     }
 }
@@ -47,7 +69,11 @@ pub fn show(
     mode: &mut ConnectionMode,
     network_host: &mut String,
     network_port: &mut String,
+    session_path: &mut String,
+    record_session: &mut bool,
     connected: bool,
+    line_control: bool,
+    modem: Option<ModemState>,
 ) -> ConnectionAction {
     let mut action = ConnectionAction::default();
 
@@ -60,6 +86,7 @@ pub fn show(
         );
         ui.add_space(4.0);
 
+        let previous_mode = *mode;
         ui.horizontal(|ui| {
             ui.label(format!("{}:", tr("Mode")));
             ComboBox::from_id_salt("connection_mode_combo")
@@ -75,8 +102,39 @@ pub fn show(
                         ConnectionMode::Network,
                         tr(ConnectionMode::Network.label()),
                     );
+                    ui.selectable_value(
+                        mode,
+                        ConnectionMode::Rfc2217,
+                        tr(ConnectionMode::Rfc2217.label()),
+                    );
+                    ui.selectable_value(
+                        mode,
+                        ConnectionMode::FluidNc,
+                        tr(ConnectionMode::FluidNc.label()),
+                    );
+                    ui.selectable_value(
+                        mode,
+                        ConnectionMode::RuidaUdp,
+                        tr(ConnectionMode::RuidaUdp.label()),
+                    );
+                    ui.selectable_value(
+                        mode,
+                        ConnectionMode::Virtual,
+                        tr(ConnectionMode::Virtual.label()),
+                    );
+                    ui.selectable_value(
+                        mode,
+                        ConnectionMode::Replay,
+                        tr(ConnectionMode::Replay.label()),
+                    );
                 });
         });
+        if *mode != previous_mode && *mode == ConnectionMode::RuidaUdp {
+            *network_port = crate::ruida::udp::RUIDA_UDP_PORT.to_string();
+        }
+        if *mode != previous_mode && *mode == ConnectionMode::FluidNc {
+            *network_port = crate::serial::fluidnc::DEFAULT_HTTP_PORT.to_string();
+        }
 
         if *mode == ConnectionMode::Serial {
             ui.horizontal(|ui| {
@@ -94,7 +152,40 @@ pub fn show(
                         }
                     });
             });
+        } else if *mode == ConnectionMode::Virtual {
+            ui.label(
+                RichText::new(tr("Simulated GRBL 1.1 using this machine's rates and travel"))
+                    .small()
+                    .color(theme::SUBTEXT),
+            );
+        } else if *mode == ConnectionMode::Replay {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", tr("Session file")));
+                ui.text_edit_singleline(session_path);
+                if ui.button("📂").clicked() {
+                    action.browse_session = true;
+                }
+            });
+        } else {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", tr("Host")));
+                ui.text_edit_singleline(network_host);
+            });
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", tr("Port")));
+                ui.text_edit_singleline(network_port);
+            });
+            if *mode == ConnectionMode::FluidNc {
+                ui.label(
+                    RichText::new(tr("Web UI port; the console is on the next port"))
+                        .small()
+                        .color(theme::SUBTEXT),
+                );
+            }
+        }
 
+        if matches!(*mode, ConnectionMode::Serial | ConnectionMode::Rfc2217) {
+            let previous_baud = *selected_baud;
             ui.horizontal(|ui| {
                 ui.label(format!("{}:", tr("Baud")));
                 let baud_label = format!("{}", get_baud(baud_rates, *selected_baud));
@@ -106,17 +197,35 @@ pub fn show(
                         }
                     });
             });
-        } else {
-            ui.horizontal(|ui| {
-                ui.label(format!("{}:", tr("Host")));
-                ui.text_edit_singleline(network_host);
-            });
+            action.baud_changed = connected && line_control && *selected_baud != previous_baud;
+        }
+
+        if connected && line_control {
             ui.horizontal(|ui| {
-                ui.label(format!("{}:", tr("Port")));
-                ui.text_edit_singleline(network_port);
+                if ui
+                    .button(format!("⟲ {}", tr("Reset board")))
+                    .on_hover_text(tr("Pulse DTR/RTS to restart the controller"))
+                    .clicked()
+                {
+                    action.reset_board = true;
+                }
+                if let Some(modem) = modem {
+                    ui.label(
+                        RichText::new(format!("{}: {}", tr("Lines"), modem.summary()))
+                            .small()
+                            .color(theme::SUBTEXT),
+                    );
+                }
             });
         }
 
+        if *mode != ConnectionMode::Replay {
+            ui.add_enabled(
+                !connected,
+                egui::Checkbox::new(record_session, tr("Record session")),
+            );
+        }
+
         ui.horizontal(|ui| {
             if connected {
                 if ui
@@ -128,6 +237,10 @@ pub fn show(
             } else {
                 let can_connect = if *mode == ConnectionMode::Serial {
                     !ports.is_empty()
+                } else if *mode == ConnectionMode::Virtual {
+                    true
+                } else if *mode == ConnectionMode::Replay {
+                    !session_path.trim().is_empty()
                 } else {
                     !network_host.trim().is_empty() && network_port.trim().parse::<u16>().is_ok()
                 };
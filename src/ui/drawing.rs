@@ -1180,16 +1180,8 @@ pub fn generate_all_gcode_with_settings(state: &DrawingState, layers: &[CutLayer
     
     // Apply auto-optimization if enabled
     if settings.auto_optimization {
-        // Convert String lines to GCodeLine for optimization
-        let gcode_lines_for_opt: Vec<crate::gcode::types::GCodeLine> = gcode_lines
-            .iter()
-            .map(|line| crate::gcode::parser::parse_line(line))
-            .collect();
-        
-        let optimized_lines = crate::gcode::optimizer::optimize(&gcode_lines_for_opt);
-        
-        // Convert back to String lines
-        optimized_lines.iter().map(|line| line.raw.clone()).collect()
+        let options = crate::gcode::optimizer::OptimizeOptions::default();
+        crate::gcode::optimizer::optimize(&gcode_lines, &options).lines
     } else {
         gcode_lines
     }
@@ -0,0 +1,171 @@
+#![allow(dead_code)]
+
+use egui::{Color32, Context, RichText, Sense, Stroke, Window};
+
+use crate::gcode::calibration::{Calibration, JobTiming};
+use crate::gcode::estimation::WorkKind;
+use crate::gcode::generator::date_from_unix;
+use crate::i18n::tr;
+use crate::theme;
+
+/// Jobs listed in the table, newest first
+const LISTED_JOBS: usize = 25;
+
+#[derive(Default)]
+pub struct EstimateReportState {
+    pub is_open: bool,
+}
+
+#[derive(Default)]
+pub struct EstimateReportAction {
+    pub export_csv: bool,
+    pub clear: bool,
+}
+
+fn format_seconds(seconds: f32) -> String {
+    let secs = seconds.max(0.0).round() as u32;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+}
+
+fn error_color(error_pct: f32) -> Color32 {
+    match error_pct.abs() {
+        e if e <= 5.0 => theme::GREEN,
+        e if e <= 15.0 => theme::PEACH,
+        _ => theme::RED,
+    }
+}
+
+/// Error of every job in order, with the ±5% band
+fn error_chart(ui: &mut egui::Ui, timings: &[JobTiming]) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width().max(280.0), 90.0), Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, theme::SURFACE0);
+    let limit = timings
+        .iter()
+        .map(|t| t.error_pct().abs())
+        .fold(10.0f32, f32::max)
+        .min(100.0);
+    let y_of = |pct: f32| rect.center().y - pct.clamp(-limit, limit) / limit * (rect.height() / 2.0 - 6.0);
+    let band = egui::Rect::from_x_y_ranges(rect.x_range(), y_of(5.0)..=y_of(-5.0));
+    painter.rect_filled(band, 0.0, theme::GREEN.gamma_multiply(0.12));
+    painter.hline(rect.x_range(), rect.center().y, Stroke::new(1.0, theme::SUBTEXT));
+
+    let step = rect.width() / timings.len().max(2).saturating_sub(1) as f32;
+    let points: Vec<egui::Pos2> = timings
+        .iter()
+        .enumerate()
+        .map(|(i, t)| egui::pos2(rect.left() + i as f32 * step, y_of(t.error_pct())))
+        .collect();
+    painter.add(egui::Shape::line(points.clone(), Stroke::new(1.5, theme::LAVENDER)));
+    for (point, timing) in points.iter().zip(timings) {
+        painter.circle_filled(*point, 2.5, error_color(timing.error_pct()));
+    }
+    painter.text(
+        rect.left_top() + egui::vec2(4.0, 2.0),
+        egui::Align2::LEFT_TOP,
+        format!("+{limit:.0}%"),
+        egui::FontId::proportional(10.0),
+        theme::SUBTEXT,
+    );
+    painter.text(
+        rect.left_bottom() + egui::vec2(4.0, -2.0),
+        egui::Align2::LEFT_BOTTOM,
+        format!("-{limit:.0}%"),
+        egui::FontId::proportional(10.0),
+        theme::SUBTEXT,
+    );
+}
+
+pub fn show(
+    ctx: &Context,
+    state: &mut EstimateReportState,
+    timings: &[JobTiming],
+    calibration: &Calibration,
+) -> EstimateReportAction {
+    let mut action = EstimateReportAction::default();
+    if !state.is_open {
+        return action;
+    }
+    let mut open = state.is_open;
+    Window::new(format!("⏱ {}", tr("Estimate Accuracy")))
+        .open(&mut open)
+        .default_width(460.0)
+        .show(ctx, |ui| {
+            if timings.is_empty() {
+                ui.label(
+                    RichText::new(tr("No finished jobs measured yet."))
+                        .small()
+                        .color(theme::SUBTEXT),
+                );
+                return;
+            }
+
+            let recent = &timings[timings.len().saturating_sub(10)..];
+            let mean_abs = recent.iter().map(|t| t.error_pct().abs()).sum::<f32>() / recent.len() as f32;
+            ui.label(
+                RichText::new(format!("{} {mean_abs:.1}%", tr("Mean error, last 10 jobs:")))
+                    .color(error_color(mean_abs)),
+            );
+            ui.add_space(4.0);
+            error_chart(ui, timings);
+
+            ui.add_space(6.0);
+            ui.label(RichText::new(tr("Corrections")).strong());
+            egui::Grid::new("estimate_fits").striped(true).show(ui, |ui| {
+                for kind in WorkKind::ALL {
+                    let fit = calibration.get(kind);
+                    ui.label(tr(kind.label()));
+                    if fit.samples == 0 {
+                        ui.label(RichText::new(tr("not measured")).small().color(theme::SUBTEXT));
+                    } else {
+                        ui.label(format!(
+                            "×{:.3}  +{:.1} ms/{}  ({} {})",
+                            fit.scale,
+                            fit.per_segment_s * 1000.0,
+                            tr("move"),
+                            fit.samples,
+                            tr("jobs")
+                        ));
+                    }
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(6.0);
+            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                egui::Grid::new("estimate_jobs")
+                    .striped(true)
+                    .num_columns(5)
+                    .show(ui, |ui| {
+                        for header in ["Date", "Job", "Estimated", "Actual", "Error"] {
+                            ui.label(RichText::new(tr(header)).strong());
+                        }
+                        ui.end_row();
+                        for timing in timings.iter().rev().take(LISTED_JOBS) {
+                            let error = timing.error_pct();
+                            ui.label(RichText::new(date_from_unix(timing.finished_at)).small());
+                            ui.label(RichText::new(&timing.name).small());
+                            ui.label(RichText::new(format_seconds(timing.estimated_s)).small());
+                            ui.label(RichText::new(format_seconds(timing.actual_s)).small());
+                            ui.label(RichText::new(format!("{error:+.1}%")).small().color(error_color(error)));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                if ui.button(format!("💾 {}", tr("Export CSV"))).clicked() {
+                    action.export_csv = true;
+                }
+                if ui
+                    .button(RichText::new(tr("Reset Calibration")).color(theme::RED))
+                    .clicked()
+                {
+                    action.clear = true;
+                }
+            });
+        });
+    state.is_open &= open;
+    action
+}
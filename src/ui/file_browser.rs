@@ -0,0 +1,176 @@
+#![allow(dead_code)]
+
+use egui::{Context, RichText, ScrollArea, Window};
+
+use crate::controller::files::{FileDialect, StoredFile};
+use crate::i18n::tr;
+use crate::theme;
+
+#[derive(Default)]
+pub struct FileBrowserState {
+    pub is_open: bool,
+    pub dialect: Option<FileDialect>,
+    pub files: Vec<StoredFile>,
+    /// A listing request is outstanding
+    pub refreshing: bool,
+    /// Name the current job is uploaded under
+    pub upload_name: String,
+    /// Some while an upload runs; None when the transport can't report progress
+    pub upload_progress: Option<Option<f32>>,
+    pub message: Option<(String, bool)>,
+    pub confirm_delete: Option<String>,
+}
+
+impl FileBrowserState {
+    pub fn open(&mut self, dialect: FileDialect) {
+        self.is_open = true;
+        if self.dialect != Some(dialect) || self.upload_name.trim().is_empty() {
+            self.upload_name = default_upload_name(dialect).to_string();
+        }
+        self.dialect = Some(dialect);
+    }
+
+    pub fn set_message(&mut self, text: impl Into<String>, is_error: bool) {
+        self.message = Some((text.into(), is_error));
+    }
+
+    pub fn is_uploading(&self) -> bool {
+        self.upload_progress.is_some()
+    }
+}
+
+/// Marlin builds without long file names only take 8.3
+fn default_upload_name(dialect: FileDialect) -> &'static str {
+    match dialect {
+        FileDialect::Marlin => "JOB.GCO",
+        _ => crate::serial::fluidnc::JOB_FILE_NAME,
+    }
+}
+
+#[derive(Default)]
+pub struct FileBrowserAction {
+    pub refresh: bool,
+    pub upload: bool,
+    pub run: Option<String>,
+    pub delete: Option<String>,
+}
+
+fn format_size(size: Option<u64>) -> String {
+    match size {
+        Some(b) if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        Some(b) if b >= 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        Some(b) => format!("{b} B"),
+        None => "—".to_string(),
+    }
+}
+
+pub fn show(ctx: &Context, state: &mut FileBrowserState, idle: bool, has_job: bool) -> FileBrowserAction {
+    let mut action = FileBrowserAction::default();
+    if !state.is_open {
+        return action;
+    }
+    let Some(dialect) = state.dialect else {
+        return action;
+    };
+
+    let busy = state.refreshing || state.is_uploading();
+    let mut open = state.is_open;
+    Window::new(format!("💾 {}", tr("Controller Files")))
+        .open(&mut open)
+        .resizable(true)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(dialect.label()).color(theme::SUBTEXT).small());
+                if ui
+                    .add_enabled(idle && !busy, egui::Button::new(format!("↻ {}", tr("Refresh"))))
+                    .clicked()
+                {
+                    action.refresh = true;
+                }
+                if state.refreshing {
+                    ui.spinner();
+                }
+            });
+            ui.separator();
+
+            ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                if state.files.is_empty() && !state.refreshing {
+                    ui.label(RichText::new(tr("No files")).color(theme::SUBTEXT));
+                }
+                egui::Grid::new("controller_files_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for file in &state.files {
+                            ui.label(RichText::new(&file.name).monospace());
+                            ui.label(format_size(file.size));
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .add_enabled(idle && !busy, egui::Button::new("▶"))
+                                    .on_hover_text(tr("Run on controller"))
+                                    .clicked()
+                                {
+                                    action.run = Some(file.name.clone());
+                                }
+                                if ui
+                                    .add_enabled(idle && !busy, egui::Button::new("🗑"))
+                                    .on_hover_text(tr("Delete"))
+                                    .clicked()
+                                {
+                                    state.confirm_delete = Some(file.name.clone());
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+            });
+
+            if let Some(name) = state.confirm_delete.clone() {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(format!("{} {name}?", tr("Delete"))).color(theme::RED));
+                    if ui.button(tr("Delete")).clicked() {
+                        action.delete = Some(name);
+                        state.confirm_delete = None;
+                    }
+                    if ui.button(tr("Cancel")).clicked() {
+                        state.confirm_delete = None;
+                    }
+                });
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", tr("Upload current job as")));
+                ui.add_enabled(!busy, egui::TextEdit::singleline(&mut state.upload_name).desired_width(140.0));
+                if ui
+                    .add_enabled(
+                        idle && !busy && has_job && !state.upload_name.trim().is_empty(),
+                        egui::Button::new(format!("⬆ {}", tr("Upload"))),
+                    )
+                    .clicked()
+                {
+                    action.upload = true;
+                }
+            });
+            match state.upload_progress {
+                Some(Some(progress)) => {
+                    ui.add(egui::ProgressBar::new(progress).show_percentage());
+                }
+                Some(None) => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr("Uploading…"));
+                    });
+                }
+                None => {}
+            }
+
+            if let Some((text, is_error)) = &state.message {
+                let color = if *is_error { theme::RED } else { theme::GREEN };
+                ui.label(RichText::new(text).color(color).small());
+            }
+        });
+    state.is_open &= open;
+    action
+}
@@ -4,7 +4,10 @@ use egui::RichText;
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::gcode::{estimation, parser};
+use crate::gcode::calibration::{Calibration, JobTiming};
+use crate::gcode::planner::PlannerConfig;
+use crate::gcode::{estimation, interpreter};
+use crate::grbl::wcs::CoordSystem;
 use crate::i18n::tr;
 use crate::theme;
 
@@ -19,17 +22,13 @@ fn format_duration(d: Duration) -> String {
     }
 }
 
-fn estimate_job_duration(lines: &[String]) -> Option<Duration> {
+fn estimate_job_duration(lines: &[String], planner: &PlannerConfig, calibration: &Calibration) -> Option<Duration> {
     if lines.is_empty() {
         return None;
     }
-    let parsed: Vec<_> = lines.iter().map(|line| parser::parse_line(line)).collect();
-    let result = estimation::estimate(&parsed);
-    if result.estimated_seconds > 0.0 {
-        Some(result.duration())
-    } else {
-        None
-    }
+    let result = estimation::estimate(lines, &interpreter::interpret(lines), planner);
+    let seconds = calibration.seconds(&result);
+    (seconds > 0.0).then(|| Duration::from_secs_f32(seconds))
 }
 
 #[derive(Clone, Debug)]
@@ -38,6 +37,10 @@ pub struct QueuedJob {
     pub name: String,
     pub lines: Arc<Vec<String>>,
     pub attempts: u32,
+    /// Coordinate system the job runs in; None uses the active one
+    pub wcs: Option<CoordSystem>,
+    /// Planned run time, worked out once when queued
+    pub estimate: Option<Duration>,
 }
 
 #[derive(Clone, Debug)]
@@ -47,6 +50,9 @@ pub struct JobHistoryEntry {
     pub lines: Arc<Vec<String>>,
     pub attempts: u32,
     pub status: String,
+    pub wcs: Option<CoordSystem>,
+    /// Quoted against measured time, once a completed run has been timed
+    pub timing: Option<JobTiming>,
 }
 
 #[derive(Debug)]
@@ -56,6 +62,9 @@ pub struct JobQueueState {
     pub queue: Vec<QueuedJob>,
     pub history: Vec<JobHistoryEntry>,
     pub confirm_remove: Option<usize>,
+    /// Machine limits and learned corrections queued jobs are estimated with
+    planner: PlannerConfig,
+    calibration: Calibration,
     next_id: u64,
 }
 
@@ -67,6 +76,8 @@ impl Default for JobQueueState {
             queue: Vec::new(),
             history: Vec::new(),
             confirm_remove: None,
+            planner: PlannerConfig::default(),
+            calibration: Calibration::default(),
             next_id: 1,
         }
     }
@@ -76,15 +87,52 @@ impl JobQueueState {
     pub fn enqueue_job(&mut self, name: String, lines: Arc<Vec<String>>) -> u64 {
         let id = self.next_id;
         self.next_id += 1;
+        let estimate = estimate_job_duration(&lines, &self.planner, &self.calibration);
         self.queue.push(QueuedJob {
             id,
             name,
             lines,
             attempts: 1,
+            wcs: None,
+            estimate,
         });
         id
     }
 
+    /// Use the active machine's limits and corrections, re-estimating the queue when they changed
+    pub fn set_estimator(&mut self, planner: PlannerConfig, calibration: Calibration) {
+        if self.planner == planner && self.calibration == calibration {
+            return;
+        }
+        self.planner = planner;
+        self.calibration = calibration;
+        for job in &mut self.queue {
+            job.estimate = estimate_job_duration(&job.lines, &planner, &calibration);
+        }
+    }
+
+    /// Attach a measured run to the history entry of job `id`
+    pub fn attach_timing(&mut self, id: u64, timing: JobTiming) {
+        if let Some(entry) = self.history.iter_mut().rev().find(|entry| entry.id == id) {
+            entry.timing = Some(timing);
+        }
+    }
+
+    /// Planned time of everything still queued
+    pub fn queued_duration(&self) -> Duration {
+        self.queue.iter().filter_map(|job| job.estimate).sum()
+    }
+
+    pub fn job_wcs(&self, id: u64) -> Option<CoordSystem> {
+        self.queue.iter().find(|job| job.id == id).and_then(|job| job.wcs)
+    }
+
+    pub fn set_job_wcs(&mut self, id: u64, wcs: Option<CoordSystem>) {
+        if let Some(job) = self.queue.iter_mut().find(|job| job.id == id) {
+            job.wcs = wcs;
+        }
+    }
+
     pub fn pop_next_job(&mut self) -> Option<QueuedJob> {
         if self.queue.is_empty() {
             None
@@ -118,6 +166,8 @@ impl JobQueueState {
             lines: job.lines,
             attempts: job.attempts,
             status: "Completed".to_string(),
+            wcs: job.wcs,
+            timing: None,
         });
     }
 
@@ -128,6 +178,8 @@ impl JobQueueState {
             lines: job.lines,
             attempts: job.attempts,
             status: format!("Failed: {reason}"),
+            wcs: job.wcs,
+            timing: None,
         });
     }
 
@@ -167,6 +219,8 @@ impl JobQueueState {
                         lines: Arc::new(Vec::new()),
                         attempts: parts[2].parse().unwrap_or(1),
                         status: parts[3].to_string(),
+                        wcs: None,
+                        timing: None,
                     });
                 }
             }
@@ -209,6 +263,8 @@ impl JobQueueState {
             lines: job.lines,
             attempts: job.attempts,
             status: "Aborted".to_string(),
+            wcs: job.wcs,
+            timing: None,
         });
     }
 
@@ -222,11 +278,14 @@ impl JobQueueState {
 
         let id = self.next_id;
         self.next_id += 1;
+        let estimate = estimate_job_duration(&last_failed.lines, &self.planner, &self.calibration);
         self.queue.push(QueuedJob {
             id,
             name: format!("{} (retry)", last_failed.name),
             lines: last_failed.lines,
             attempts: last_failed.attempts.saturating_add(1),
+            wcs: last_failed.wcs,
+            estimate,
         });
         Some(id)
     }
@@ -239,6 +298,8 @@ pub struct JobQueueAction {
     pub retry_last_failed: bool,
     pub requeue_from_history: Option<JobHistoryEntry>,
     pub batch_import_paths: Option<Vec<std::path::PathBuf>>,
+    /// Queued job whose coordinate system was changed and needs re-preparing
+    pub wcs_changed: Option<u64>,
 }
 
 pub fn show(
@@ -354,7 +415,17 @@ pub fn show(
             }
 
             ui.add_space(8.0);
-            ui.label(RichText::new(tr("Pending Queue")).strong());
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(tr("Pending Queue")).strong());
+                let total = state.queued_duration();
+                if !total.is_zero() {
+                    ui.label(
+                        RichText::new(format!("{} {}", tr("ETA"), format_duration(total)))
+                            .small()
+                            .color(theme::SUBTEXT),
+                    );
+                }
+            });
             let queue_height = (state.queue.len() as f32 * 24.0).clamp(80.0, 200.0);
             egui::ScrollArea::vertical()
                 .max_height(queue_height)
@@ -369,9 +440,10 @@ pub fn show(
                         let mut move_up_idx = None;
                         let mut move_down_idx = None;
 
-                        for (idx, job) in state.queue.iter().enumerate() {
+                        for (idx, job) in state.queue.iter_mut().enumerate() {
                             ui.horizontal(|ui| {
-                                let eta = estimate_job_duration(job.lines.as_ref())
+                                let eta = job
+                                    .estimate
                                     .map(format_duration)
                                     .unwrap_or_else(|| tr("n/a"));
                                 ui.label(
@@ -388,6 +460,19 @@ pub fn show(
                                     ))
                                     .small(),
                                 );
+                                let before = job.wcs;
+                                egui::ComboBox::from_id_salt(("queue_wcs", job.id))
+                                    .width(56.0)
+                                    .selected_text(job.wcs.map(|s| s.gcode()).unwrap_or("WCS"))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut job.wcs, None, tr("Active"));
+                                        for system in CoordSystem::ALL {
+                                            ui.selectable_value(&mut job.wcs, Some(system), system.gcode());
+                                        }
+                                    });
+                                if job.wcs != before {
+                                    action.wcs_changed = Some(job.id);
+                                }
                                 if ui.button("↑").clicked() {
                                     move_up_idx = Some(idx);
                                 }
@@ -446,6 +531,19 @@ pub fn show(
                                     .small()
                                     .color(color),
                                 );
+                                if let Some(timing) = &entry.timing {
+                                    ui.label(
+                                        RichText::new(format!(
+                                            "{} {} / {} ({:+.1}%)",
+                                            tr("ETA"),
+                                            format_duration(Duration::from_secs_f32(timing.estimated_s)),
+                                            format_duration(Duration::from_secs_f32(timing.actual_s)),
+                                            timing.error_pct()
+                                        ))
+                                        .small()
+                                        .color(theme::SUBTEXT),
+                                    );
+                                }
                                 if ui.small_button("↻").on_hover_text(tr("Requeue this job")).clicked() {
                                     requeue_idx = Some(idx);
                                 }
@@ -487,6 +585,18 @@ mod tests {
         let first = q.pop_next_job().expect("first job should exist");
         assert_eq!(first.name, "C");
         assert_eq!(q.queue.len(), 2);
+
+        // Estimated once on enqueue, again when the machine limits change
+        let before = q.queue[1].estimate.expect("B moves 1 mm");
+        q.set_estimator(
+            PlannerConfig {
+                max_rate: [600.0; 3],
+                ..PlannerConfig::default()
+            },
+            Calibration::default(),
+        );
+        assert!(q.queue[1].estimate.unwrap() > before);
+        assert_eq!(q.queued_duration(), q.queue[1].estimate.unwrap());
     }
 
     #[test]
@@ -5,16 +5,42 @@ use egui::{RichText, Ui, Vec2};
 
 pub struct JogAction {
     pub direction: Option<JogDirection>,
+    /// Button held down in continuous mode
+    pub held: Option<JogDirection>,
+}
+
+/// A jog button: steps on click, or reports itself held in continuous mode
+fn jog_button(
+    ui: &mut Ui,
+    enabled: bool,
+    button: egui::Button,
+    dir: JogDirection,
+    continuous: bool,
+    action: &mut JogAction,
+) {
+    let response = ui.add_enabled(enabled, button);
+    if continuous {
+        if response.is_pointer_button_down_on() {
+            action.held = Some(dir);
+        }
+    } else if response.clicked() {
+        action.direction = Some(dir);
+    }
 }
 
 pub fn show(
     ui: &mut Ui,
     step: &mut f32,
     feed: &mut f32,
+    continuous: &mut bool,
     can_jog: bool,
+    can_jog_z: bool,
     can_home: bool,
 ) -> JogAction {
-    let mut action = JogAction { direction: None };
+    let mut action = JogAction {
+        direction: None,
+        held: None,
+    };
 
     ui.group(|ui| {
         ui.label(
@@ -24,12 +50,19 @@ pub fn show(
                 .size(14.0),
         );
         ui.add_space(4.0);
-        
+
         // Keyboard hint
         ui.label(
-            RichText::new(format!("💡 {}", tr("Use arrow keys to jog")))
-                .small()
-                .color(theme::SUBTEXT),
+            RichText::new(format!(
+                "💡 {}",
+                if *continuous {
+                    tr("Hold arrow keys or buttons to jog")
+                } else {
+                    tr("Use arrow keys to jog")
+                }
+            ))
+            .small()
+            .color(theme::SUBTEXT),
         );
         ui.add_space(4.0);
 
@@ -38,41 +71,51 @@ pub fn show(
 
         // Row 1: NW N NE
         ui.horizontal(|ui| {
-            if ui
-                .add_enabled(can_jog, egui::Button::new("↖").min_size(cardinal))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::NW);
-            }
-            if ui
-                .add_enabled(can_jog, egui::Button::new(RichText::new("↑").strong()).min_size(cardinal))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::N);
-            }
-            if ui
-                .add_enabled(can_jog, egui::Button::new("↗").min_size(cardinal))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::NE);
-            }
+            jog_button(
+                ui,
+                can_jog,
+                egui::Button::new("↖").min_size(cardinal),
+                JogDirection::NW,
+                *continuous,
+                &mut action,
+            );
+            jog_button(
+                ui,
+                can_jog,
+                egui::Button::new(RichText::new("↑").strong()).min_size(cardinal),
+                JogDirection::N,
+                *continuous,
+                &mut action,
+            );
+            jog_button(
+                ui,
+                can_jog,
+                egui::Button::new("↗").min_size(cardinal),
+                JogDirection::NE,
+                *continuous,
+                &mut action,
+            );
             ui.add_space(8.0);
-            if ui
-                .add_enabled(can_jog, egui::Button::new("Z↑").min_size(z_btn))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::Zup);
-            }
+            jog_button(
+                ui,
+                can_jog_z,
+                egui::Button::new("Z↑").min_size(z_btn),
+                JogDirection::Zup,
+                *continuous,
+                &mut action,
+            );
         });
 
         // Row 2: W Home E
         ui.horizontal(|ui| {
-            if ui
-                .add_enabled(can_jog, egui::Button::new(RichText::new("←").strong()).min_size(cardinal))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::W);
-            }
+            jog_button(
+                ui,
+                can_jog,
+                egui::Button::new(RichText::new("←").strong()).min_size(cardinal),
+                JogDirection::W,
+                *continuous,
+                &mut action,
+            );
             if ui
                 .add_enabled(
                     can_jog && can_home,
@@ -82,41 +125,51 @@ pub fn show(
             {
                 action.direction = Some(JogDirection::Home);
             }
-            if ui
-                .add_enabled(can_jog, egui::Button::new(RichText::new("→").strong()).min_size(cardinal))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::E);
-            }
+            jog_button(
+                ui,
+                can_jog,
+                egui::Button::new(RichText::new("→").strong()).min_size(cardinal),
+                JogDirection::E,
+                *continuous,
+                &mut action,
+            );
             ui.add_space(8.0);
-            if ui
-                .add_enabled(can_jog, egui::Button::new("Z↓").min_size(z_btn))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::Zdown);
-            }
+            jog_button(
+                ui,
+                can_jog_z,
+                egui::Button::new("Z↓").min_size(z_btn),
+                JogDirection::Zdown,
+                *continuous,
+                &mut action,
+            );
         });
 
         // Row 3: SW S SE
         ui.horizontal(|ui| {
-            if ui
-                .add_enabled(can_jog, egui::Button::new("↙").min_size(cardinal))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::SW);
-            }
-            if ui
-                .add_enabled(can_jog, egui::Button::new(RichText::new("↓").strong()).min_size(cardinal))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::S);
-            }
-            if ui
-                .add_enabled(can_jog, egui::Button::new("↘").min_size(cardinal))
-                .clicked()
-            {
-                action.direction = Some(JogDirection::SE);
-            }
+            jog_button(
+                ui,
+                can_jog,
+                egui::Button::new("↙").min_size(cardinal),
+                JogDirection::SW,
+                *continuous,
+                &mut action,
+            );
+            jog_button(
+                ui,
+                can_jog,
+                egui::Button::new(RichText::new("↓").strong()).min_size(cardinal),
+                JogDirection::S,
+                *continuous,
+                &mut action,
+            );
+            jog_button(
+                ui,
+                can_jog,
+                egui::Button::new("↘").min_size(cardinal),
+                JogDirection::SE,
+                *continuous,
+                &mut action,
+            );
         });
 
         ui.add_space(8.0);
@@ -137,6 +190,7 @@ pub fn show(
             }
             ui.label("mm");
         });
+        ui.checkbox(continuous, tr("Continuous (hold to jog)"));
 
         // Feed rate
         ui.horizontal(|ui| {
@@ -1,4 +1,5 @@
 use crate::grbl::types::GrblState;
+use crate::grbl::wcs::{CoordSystem, NamedOffset, WcsTable};
 use crate::theme;
 use egui::{Grid, RichText, Ui};
 
@@ -15,6 +16,14 @@ pub struct MachineStateAction {
     pub toggle_focus: bool,
     pub quick_pos: Option<QuickPosition>,
     pub confirm_focus: bool,
+    /// Re-read `$#` and `$G`
+    pub refresh_offsets: bool,
+    pub select_wcs: Option<CoordSystem>,
+    /// Make the current position the XY origin of this system
+    pub zero_wcs: Option<CoordSystem>,
+    pub save_named: Option<(String, CoordSystem)>,
+    pub apply_named: Option<usize>,
+    pub delete_named: Option<usize>,
 }
 
 use crate::i18n::tr;
@@ -22,6 +31,8 @@ use crate::i18n::tr;
 pub fn show(
     ui: &mut Ui,
     state: &GrblState,
+    wcs: &WcsTable,
+    named_offsets: &[NamedOffset],
     is_focused: bool,
     connected: bool,
 ) -> MachineStateAction {
@@ -29,6 +40,12 @@ pub fn show(
         toggle_focus: false,
         quick_pos: None,
         confirm_focus: false,
+        refresh_offsets: false,
+        select_wcs: None,
+        zero_wcs: None,
+        save_named: None,
+        apply_named: None,
+        delete_named: None,
     };
 
     ui.group(|ui| {
@@ -108,6 +125,20 @@ pub fn show(
             );
         });
 
+        if !state.pins.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("{}:", tr("Inputs"))).color(theme::SUBTEXT));
+                ui.label(
+                    RichText::new(state.pins.active_letters())
+                        .color(theme::PEACH)
+                        .monospace(),
+                )
+                .on_hover_text(tr("Active input pins (D door, P probe, X/Y/Z limits)"));
+            });
+        }
+
+        ui.add_space(4.0);
+        show_work_offsets(ui, wcs, named_offsets, connected, &mut action);
         ui.add_space(4.0);
 
         let focus_label = if is_focused {
@@ -173,3 +204,102 @@ pub fn show(
 
     action
 }
+
+fn offset_text(offset: Option<[f32; 3]>) -> String {
+    match offset {
+        Some([x, y, z]) => format!("{x:.3}, {y:.3}, {z:.3}"),
+        None => "—".to_string(),
+    }
+}
+
+fn show_work_offsets(
+    ui: &mut Ui,
+    wcs: &WcsTable,
+    named_offsets: &[NamedOffset],
+    connected: bool,
+    action: &mut MachineStateAction,
+) {
+    egui::CollapsingHeader::new(RichText::new(tr("Work Offsets")).color(theme::LAVENDER))
+        .id_salt("work_offsets")
+        .show(ui, |ui| {
+            Grid::new("work_offsets_grid")
+                .num_columns(3)
+                .spacing([8.0, 2.0])
+                .show(ui, |ui| {
+                    for system in CoordSystem::ALL {
+                        let active = wcs.active == Some(system);
+                        let color = if active { theme::GREEN } else { theme::SUBTEXT };
+                        if ui
+                            .add_enabled(
+                                connected,
+                                egui::Button::new(RichText::new(system.gcode()).color(color).monospace())
+                                    .selected(active),
+                            )
+                            .on_hover_text(tr("Make this the active coordinate system"))
+                            .clicked()
+                        {
+                            action.select_wcs = Some(system);
+                        }
+                        ui.label(RichText::new(offset_text(wcs.offset(system))).monospace().small());
+                        if ui
+                            .add_enabled(connected, egui::Button::new("⌖").small())
+                            .on_hover_text(tr("Set XY origin here"))
+                            .clicked()
+                        {
+                            action.zero_wcs = Some(system);
+                        }
+                        ui.end_row();
+                    }
+                    ui.label(RichText::new("G92").color(theme::SUBTEXT).monospace());
+                    ui.label(RichText::new(offset_text(wcs.g92)).monospace().small());
+                    ui.end_row();
+                    if let Some(tlo) = wcs.tlo {
+                        ui.label(RichText::new("TLO").color(theme::SUBTEXT).monospace());
+                        ui.label(RichText::new(format!("{tlo:.3}")).monospace().small());
+                        ui.end_row();
+                    }
+                });
+            if ui
+                .add_enabled(connected, egui::Button::new(format!("⟳ {}", tr("Refresh"))))
+                .clicked()
+            {
+                action.refresh_offsets = true;
+            }
+
+            ui.add_space(4.0);
+            ui.label(RichText::new(tr("Named offsets")).small().color(theme::SUBTEXT));
+            let mut apply = None;
+            let mut delete = None;
+            for (i, named) in named_offsets.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} ({})", named.name, named.system.gcode()))
+                        .on_hover_text(offset_text(Some(named.offset)));
+                    if ui.add_enabled(connected, egui::Button::new(tr("Apply")).small()).clicked() {
+                        apply = Some(i);
+                    }
+                    if ui.small_button("🗑").clicked() {
+                        delete = Some(i);
+                    }
+                });
+            }
+            action.apply_named = apply;
+            action.delete_named = delete;
+
+            let active = wcs.active.unwrap_or(CoordSystem::G54);
+            let name_id = ui.id().with("named_offset_name");
+            let mut name = ui.data_mut(|d| d.get_temp::<String>(name_id)).unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut name).hint_text(tr("Jig name")).desired_width(100.0));
+                let can_save = !name.trim().is_empty() && wcs.offset(active).is_some();
+                if ui
+                    .add_enabled(can_save, egui::Button::new(format!("💾 {}", active.gcode())))
+                    .on_hover_text(tr("Save the active offset under this name"))
+                    .clicked()
+                {
+                    action.save_named = Some((name.trim().to_string(), active));
+                    name.clear();
+                }
+            });
+            ui.data_mut(|d| d.insert_temp(name_id, name));
+        });
+}
@@ -8,6 +8,7 @@ pub mod cut_list;
 pub mod cut_palette;
 pub mod cut_settings;
 pub mod drawing;
+pub mod estimate_report;
 pub mod gcode_editor;
 pub mod generators;
 pub mod grid_array;
@@ -27,6 +28,9 @@ pub mod power_speed_test;
 pub mod preferences;
 pub mod preflight;
 pub mod preview_panel;
+pub mod file_browser;
+pub mod profile_sync;
+pub mod resume;
 pub mod settings_dialog;
 pub mod shortcuts;
 pub mod status_bar;
@@ -365,6 +365,23 @@ pub fn build_preflight_report(ctx: &PreflightContext) -> PreflightReport {
             ));
         }
     }
+    // Loaded G-code: arcs count with their full sweep
+    if ctx.shapes.is_empty()
+        && let Some((min_x, min_y, max_x, max_y)) = ctx.loaded_file.and_then(|f| f.bounds())
+        && (min_x < -0.1 || min_y < -0.1 || max_x > ws_x + 0.1 || max_y > ws_y + 0.1)
+    {
+        report.add_warning(format!(
+            "{} ({:.1},{:.1} - {:.1},{:.1}) {} ({:.0}x{:.0}mm).",
+            tr("Program"),
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            tr("extends outside workspace bounds"),
+            ws_x,
+            ws_y
+        ));
+    }
 
     // F94: Interlock safety checks
     if ctx.machine_profile.interlock_lid_enabled {
@@ -0,0 +1,131 @@
+#![allow(dead_code)]
+
+use egui::{Context, RichText, Window};
+
+use crate::config::profile_sync::{ProfileField, SyncRow};
+use crate::i18n::tr;
+use crate::theme;
+
+#[derive(Default)]
+pub struct ProfileSyncState {
+    pub is_open: bool,
+    /// Waiting for the controller's answer
+    pub reading: bool,
+    pub rows: Vec<SyncRow>,
+    /// Rows to copy into the profile
+    pub selected: Vec<bool>,
+    pub error: Option<String>,
+
+    // Requests for the app
+    pub apply_requested: bool,
+    pub reread_requested: bool,
+}
+
+impl ProfileSyncState {
+    pub fn reading() -> Self {
+        Self {
+            is_open: true,
+            reading: true,
+            ..Default::default()
+        }
+    }
+
+    /// Show the comparison with the differing values ticked
+    pub fn set_rows(&mut self, rows: Vec<SyncRow>) {
+        self.selected = rows.iter().map(SyncRow::differs).collect();
+        self.rows = rows;
+        self.reading = false;
+        self.error = None;
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.reading = false;
+        self.error = Some(error);
+    }
+
+    /// Ticked fields and their controller values
+    pub fn chosen(&self) -> Vec<(ProfileField, f32)> {
+        self.rows
+            .iter()
+            .zip(&self.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(row, _)| (row.field, row.controller))
+            .collect()
+    }
+}
+
+fn format_value(value: f32) -> String {
+    let text = format!("{value:.3}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+pub fn show(ctx: &Context, state: &mut ProfileSyncState, can_read: bool) {
+    if !state.is_open {
+        return;
+    }
+    let mut open = state.is_open;
+    Window::new(format!("⟳ {}", tr("Sync from Controller")))
+        .open(&mut open)
+        .resizable(false)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            if state.reading {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(tr("Reading machine settings…"));
+                });
+                return;
+            }
+            if let Some(err) = &state.error {
+                ui.label(RichText::new(err).color(theme::RED));
+            }
+
+            if !state.rows.is_empty() {
+                let differing = state.rows.iter().filter(|r| r.differs()).count();
+                ui.label(
+                    RichText::new(format!("{differing} {}", tr("value(s) differ from the profile")))
+                        .color(if differing > 0 { theme::PEACH } else { theme::GREEN }),
+                );
+                ui.add_space(4.0);
+                egui::Grid::new("profile_sync_grid")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("");
+                        ui.label(RichText::new(tr("Profile")).strong());
+                        ui.label(RichText::new(tr("Controller")).strong());
+                        ui.label("");
+                        ui.end_row();
+                        for (row, selected) in state.rows.iter().zip(state.selected.iter_mut()) {
+                            ui.checkbox(selected, row.field.label());
+                            let color = if row.differs() { theme::PEACH } else { theme::SUBTEXT };
+                            ui.label(RichText::new(format_value(row.profile)).color(color));
+                            ui.label(RichText::new(format_value(row.controller)).color(color));
+                            ui.label(RichText::new(row.field.unit()).small().color(theme::SUBTEXT));
+                            ui.end_row();
+                        }
+                    });
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let any = state.selected.iter().any(|s| *s);
+                if ui
+                    .add_enabled(any, egui::Button::new(RichText::new(tr("Apply to Profile")).color(theme::GREEN)))
+                    .clicked()
+                {
+                    state.apply_requested = true;
+                }
+                if ui
+                    .add_enabled(can_read, egui::Button::new(format!("↻ {}", tr("Refresh"))))
+                    .clicked()
+                {
+                    state.reread_requested = true;
+                }
+                if ui.button(tr("Cancel")).clicked() {
+                    state.is_open = false;
+                }
+            });
+        });
+    state.is_open &= open;
+}
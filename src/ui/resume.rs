@@ -0,0 +1,151 @@
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use egui::{Context, RichText, ScrollArea, Window};
+
+use crate::gcode::file::GCodeFile;
+use crate::gcode::resume::{self, ResumePlan};
+use crate::i18n::tr;
+use crate::theme;
+
+#[derive(Default)]
+pub struct ResumeDialogState {
+    pub is_open: bool,
+    /// Prepared program the checkpoint refers to
+    pub lines: Arc<Vec<String>>,
+    /// Last acknowledged line when the job stopped
+    pub checkpoint: usize,
+    /// Chosen resume line (0-based)
+    pub line: usize,
+    pub plan: Option<Result<ResumePlan, String>>,
+    /// What is left to run, shown in the preview while the dialog is open
+    pub preview: Option<GCodeFile>,
+    planned_line: Option<usize>,
+
+    // Requests for the app
+    pub start_requested: bool,
+}
+
+impl ResumeDialogState {
+    pub fn open(lines: Arc<Vec<String>>, checkpoint: usize) -> Self {
+        let line = resume::suggested_resume_line(&lines, checkpoint);
+        Self {
+            is_open: true,
+            lines,
+            checkpoint,
+            line,
+            ..Default::default()
+        }
+    }
+
+    /// Rebuild the plan and preview after the resume line changed
+    fn refresh(&mut self) {
+        if self.planned_line == Some(self.line) {
+            return;
+        }
+        self.planned_line = Some(self.line);
+        let plan = ResumePlan::build(&self.lines, self.line);
+        self.preview = plan
+            .as_ref()
+            .ok()
+            .map(|plan| GCodeFile::from_lines("resume", &plan.program(&self.lines)));
+        self.plan = Some(plan);
+    }
+
+    /// Program to stream if the plan is valid
+    pub fn program(&self) -> Option<Vec<String>> {
+        match &self.plan {
+            Some(Ok(plan)) => Some(plan.program(&self.lines)),
+            _ => None,
+        }
+    }
+}
+
+pub fn show(ctx: &Context, state: &mut ResumeDialogState, can_start: bool) {
+    if !state.is_open {
+        return;
+    }
+    state.refresh();
+
+    let mut open = state.is_open;
+    let last = state.lines.len().saturating_sub(1);
+    Window::new(format!("⏯ {}", tr("Resume Job")))
+        .open(&mut open)
+        .resizable(false)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            ui.label(format!(
+                "{}: {} / {}",
+                tr("Checkpoint"),
+                state.checkpoint + 1,
+                state.lines.len()
+            ));
+            ui.horizontal(|ui| {
+                ui.label(tr("Resume at line"));
+                let mut display = state.line + 1;
+                if ui
+                    .add(egui::DragValue::new(&mut display).range(1..=last + 1))
+                    .changed()
+                {
+                    state.line = display.saturating_sub(1).min(last);
+                }
+                if ui.button(tr("Path start")).on_hover_text(tr("Back up to the rapid that starts this path")).clicked() {
+                    state.line = resume::path_start(&state.lines, state.line);
+                }
+                if ui.button(tr("Checkpoint")).clicked() {
+                    state.line = state.checkpoint.min(last);
+                }
+            });
+            if let Some(text) = state.lines.get(state.line) {
+                ui.label(RichText::new(text).monospace().small().color(theme::SUBTEXT));
+            }
+
+            ui.add_space(6.0);
+            match &state.plan {
+                Some(Ok(plan)) => {
+                    ui.label(RichText::new(tr("Preamble")).strong());
+                    ScrollArea::vertical().max_height(140.0).show(ui, |ui| {
+                        for line in plan.preamble.iter().chain(std::iter::once(&plan.first_line)) {
+                            ui.label(RichText::new(line).monospace().small());
+                        }
+                    });
+                    ui.label(
+                        RichText::new(format!(
+                            "{} {}",
+                            state.lines.len() - plan.start_line,
+                            tr("line(s) remaining")
+                        ))
+                        .small()
+                        .color(theme::SUBTEXT),
+                    );
+                }
+                Some(Err(err)) => {
+                    ui.label(RichText::new(err).color(theme::RED));
+                }
+                None => {}
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let valid = matches!(state.plan, Some(Ok(_)));
+                if ui
+                    .add_enabled(
+                        can_start && valid,
+                        egui::Button::new(RichText::new(format!("▶ {}", tr("Resume"))).color(theme::GREEN)),
+                    )
+                    .clicked()
+                {
+                    state.start_requested = true;
+                }
+                if ui.button(tr("Cancel")).clicked() {
+                    state.is_open = false;
+                }
+            });
+        });
+
+    state.is_open &= open;
+    if !state.is_open {
+        state.preview = None;
+    }
+}
@@ -1,13 +1,77 @@
+use crate::grbl::settings::{self, GrblSettingsSnapshot};
 use crate::i18n::tr;
 use crate::theme;
-use egui::{Context, Grid, RichText, ScrollArea, Window};
+use egui::{ComboBox, Context, Grid, RichText, ScrollArea, Window};
 use std::collections::BTreeMap;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SettingsTab {
+    #[default]
+    Board,
+    Backups,
+}
+
 #[derive(Default, Clone)]
 pub struct SettingsDialogState {
     pub is_open: bool,
+    /// Values as last read from the board
     pub settings: BTreeMap<i32, String>,
-    pub pending_writes: Vec<(i32, String)>,
+    /// Edited text, by setting id
+    pub edits: BTreeMap<i32, String>,
+    pub tab: SettingsTab,
+    /// Copy of the active profile's backups
+    pub snapshots: Vec<GrblSettingsSnapshot>,
+    /// Diff sides: None is the board
+    pub diff_left: Option<usize>,
+    pub diff_right: Option<usize>,
+    /// Progress or result of the last write/restore
+    pub status: Option<String>,
+    pub busy: bool,
+
+    // Requests for the app
+    pub refresh_requested: bool,
+    pub write_requested: Option<BTreeMap<i32, String>>,
+    pub take_snapshot: bool,
+    pub delete_snapshot: Option<usize>,
+}
+
+impl SettingsDialogState {
+    pub fn open(snapshots: Vec<GrblSettingsSnapshot>) -> Self {
+        Self {
+            is_open: true,
+            snapshots,
+            refresh_requested: true,
+            ..Default::default()
+        }
+    }
+
+    /// Edits that differ from the board, or the first validation error
+    fn pending_changes(&self) -> Result<BTreeMap<i32, String>, String> {
+        let mut changes = BTreeMap::new();
+        for (&id, text) in &self.edits {
+            let value = settings::validate(id, text)?;
+            if self
+                .settings
+                .get(&id)
+                .is_some_and(|board| settings::same_value(board, &value))
+            {
+                continue;
+            }
+            changes.insert(id, value);
+        }
+        Ok(changes)
+    }
+
+    fn side(&self, index: Option<usize>) -> BTreeMap<i32, String> {
+        match index.and_then(|i| self.snapshots.get(i)) {
+            Some(snapshot) => snapshot.values.clone(),
+            None => self.settings.clone(),
+        }
+    }
+}
+
+fn snapshot_label(snapshot: &GrblSettingsSnapshot) -> String {
+    format!("{} ({})", snapshot.label, snapshot.values.len())
 }
 
 pub fn show(ctx: &Context, state: &mut SettingsDialogState) {
@@ -19,109 +83,179 @@ pub fn show(ctx: &Context, state: &mut SettingsDialogState) {
     Window::new(tr("GRBL Settings"))
         .open(&mut open)
         .resizable(true)
-        .default_width(500.0)
-        .default_height(400.0)
+        .default_width(620.0)
+        .default_height(460.0)
         .show(ctx, |ui| {
-            ui.label(
-                RichText::new(tr("Machine Firmware Settings"))
-                    .color(theme::LAVENDER)
-                    .strong(),
-            );
-            ui.add_space(8.0);
-
-            ScrollArea::vertical().show(ui, |ui| {
-                Grid::new("grbl_settings_grid")
-                    .num_columns(3)
-                    .spacing([16.0, 8.0])
-                    .striped(true)
-                    .show(ui, |ui| {
-                        ui.label(RichText::new(tr("ID")).strong());
-                        ui.label(RichText::new(tr("Value")).strong());
-                        ui.label(RichText::new(tr("Description")).strong());
-                        ui.end_row();
-
-                        let mut updates = Vec::new();
-                        for (&id, val) in &state.settings {
-                            ui.label(format!("${id}"));
-
-                            let mut edit_val = val.clone();
-                            if ui.text_edit_singleline(&mut edit_val).changed() {
-                                updates.push((id, edit_val));
-                            }
-
-                            ui.label(get_setting_description(id));
-                            ui.end_row();
-                        }
-
-                        for (id, val) in updates {
-                            state.settings.insert(id, val);
-                        }
-
-                        if state.settings.is_empty() {
-                            ui.label(tr("Waiting for settings..."));
-                            ui.end_row();
-                        }
-                    });
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut state.tab, SettingsTab::Board, tr("Machine Firmware Settings"));
+                ui.selectable_value(&mut state.tab, SettingsTab::Backups, tr("Backups"));
             });
+            ui.separator();
 
-            ui.add_space(8.0);
+            match state.tab {
+                SettingsTab::Board => show_board(ui, state),
+                SettingsTab::Backups => show_backups(ui, state),
+            }
 
-            ui.horizontal(|ui| {
+            if let Some(status) = &state.status {
+                ui.add_space(4.0);
+                ui.label(RichText::new(status).small().color(theme::SUBTEXT));
+            }
+        });
+
+    state.is_open = open;
+}
+
+fn show_board(ui: &mut egui::Ui, state: &mut SettingsDialogState) {
+    ScrollArea::vertical().max_height(340.0).show(ui, |ui| {
+        Grid::new("grbl_settings_grid")
+            .num_columns(4)
+            .spacing([12.0, 6.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(RichText::new(tr("ID")).strong());
+                ui.label(RichText::new(tr("Name")).strong());
+                ui.label(RichText::new(tr("Value")).strong());
+                ui.label(RichText::new(tr("Unit")).strong());
+                ui.end_row();
+
+                for (&id, val) in &state.settings {
+                    let def = settings::definition(id);
+                    ui.label(format!("${id}"));
+                    let name = ui.label(def.map(|d| d.name).unwrap_or("Unknown setting"));
+                    if let Some(d) = def {
+                        name.on_hover_text(d.description);
+                    }
+
+                    let edit = state.edits.entry(id).or_insert_with(|| settings::clean_value(val));
+                    let error = settings::validate(id, edit).err();
+                    let response = ui.add(egui::TextEdit::singleline(edit).desired_width(110.0));
+                    if let Some(err) = error {
+                        response.on_hover_text(RichText::new(err).color(theme::RED));
+                        ui.label(RichText::new("⚠").color(theme::RED));
+                    } else {
+                        ui.label(def.map(|d| d.unit).unwrap_or(""));
+                    }
+                    ui.end_row();
+                }
+
+                if state.settings.is_empty() {
+                    ui.label(tr("Waiting for settings..."));
+                    ui.end_row();
+                }
+            });
+    });
+
+    for issue in settings::cross_check(&state.edits) {
+        ui.label(RichText::new(format!("⚠ {issue}")).small().color(theme::PEACH));
+    }
+
+    ui.add_space(8.0);
+    let pending = state.pending_changes();
+    ui.horizontal(|ui| {
+        let can_write = !state.busy && matches!(&pending, Ok(changes) if !changes.is_empty());
+        let label = match &pending {
+            Ok(changes) if !changes.is_empty() => format!("{} ({})", tr("Save to Board"), changes.len()),
+            _ => tr("Save to Board").to_string(),
+        };
+        if ui
+            .add_enabled(can_write, egui::Button::new(RichText::new(label).color(theme::GREEN)))
+            .clicked()
+            && let Ok(changes) = &pending
+        {
+            state.write_requested = Some(changes.clone());
+        }
+        if ui.add_enabled(!state.busy, egui::Button::new(tr("Refresh"))).clicked() {
+            state.refresh_requested = true;
+        }
+        if ui
+            .add_enabled(
+                !state.busy && !state.settings.is_empty(),
+                egui::Button::new(format!("💾 {}", tr("Snapshot to profile"))),
+            )
+            .clicked()
+        {
+            state.take_snapshot = true;
+        }
+    });
+    if let Err(err) = pending {
+        ui.label(RichText::new(err).small().color(theme::RED));
+    }
+}
+
+fn show_backups(ui: &mut egui::Ui, state: &mut SettingsDialogState) {
+    if state.snapshots.is_empty() {
+        ui.label(tr("No backups in this machine profile yet."));
+        return;
+    }
+
+    let mut restore = None;
+    Grid::new("grbl_snapshot_grid")
+        .num_columns(3)
+        .spacing([12.0, 6.0])
+        .striped(true)
+        .show(ui, |ui| {
+            for (i, snapshot) in state.snapshots.iter().enumerate() {
+                ui.label(snapshot_label(snapshot)).on_hover_text(&snapshot.firmware);
                 if ui
-                    .button(RichText::new(tr("Save to Board")).color(theme::GREEN))
+                    .add_enabled(
+                        !state.busy && !state.settings.is_empty(),
+                        egui::Button::new(format!("⤴ {}", tr("Restore"))),
+                    )
+                    .on_hover_text(tr("Write this backup to the board and read it back to verify"))
                     .clicked()
                 {
-                    state.pending_writes.clear();
-                    for (&id, val) in &state.settings {
-                        state.pending_writes.push((id, val.clone()));
-                    }
+                    restore = Some(i);
                 }
-                if ui.button(tr("Refresh")).clicked() {
-                    state.settings.clear();
-                    state.pending_writes.push((-1, "$$".to_string())); // Special marker
+                if ui.add_enabled(!state.busy, egui::Button::new("🗑")).clicked() {
+                    state.delete_snapshot = Some(i);
                 }
-            });
+                ui.end_row();
+            }
         });
+    if let Some(i) = restore {
+        state.write_requested = Some(state.snapshots[i].values.clone());
+    }
 
-    state.is_open = open;
-}
+    ui.add_space(8.0);
+    ui.label(RichText::new(tr("Compare")).strong());
+    let side_label = |state: &SettingsDialogState, side: Option<usize>| match side.and_then(|i| state.snapshots.get(i)) {
+        Some(s) => snapshot_label(s),
+        None => tr("Board").to_string(),
+    };
+    ui.horizontal(|ui| {
+        for (salt, side) in [("diff_left", state.diff_left), ("diff_right", state.diff_right)] {
+            let mut choice = side;
+            ComboBox::from_id_salt(salt)
+                .selected_text(side_label(state, choice))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut choice, None, tr("Board"));
+                    for (i, s) in state.snapshots.iter().enumerate() {
+                        ui.selectable_value(&mut choice, Some(i), snapshot_label(s));
+                    }
+                });
+            if salt == "diff_left" {
+                state.diff_left = choice;
+            } else {
+                state.diff_right = choice;
+            }
+        }
+    });
 
-fn get_setting_description(id: i32) -> &'static str {
-    match id {
-        0 => "Step pulse time, microseconds",
-        1 => "Step idle delay, milliseconds",
-        2 => "Step port invert, mask",
-        3 => "Direction port invert, mask",
-        4 => "Step enable invert, boolean",
-        5 => "Limit pins invert, boolean",
-        6 => "Probe pin invert, boolean",
-        10 => "Status report options, mask",
-        11 => "Junction deviation, millimeters",
-        12 => "Arc tolerance, millimeters",
-        13 => "Report inches, boolean",
-        20 => "Soft limits enable, boolean",
-        21 => "Hard limits enable, boolean",
-        22 => "Homing cycle enable, boolean",
-        23 => "Homing direction invert, mask",
-        24 => "Homing locate feed rate, mm/min",
-        25 => "Homing search seek rate, mm/min",
-        26 => "Homing switch debounce delay, ms",
-        27 => "Homing switch pull-off distance, mm",
-        30 => "Maximum spindle speed, RPM",
-        31 => "Minimum spindle speed, RPM",
-        32 => "Laser-mode enable, boolean",
-        100 => "X-axis steps per millimeter",
-        101 => "Y-axis steps per millimeter",
-        102 => "Z-axis steps per millimeter",
-        110 => "X-axis maximum rate, mm/min",
-        111 => "Y-axis maximum rate, mm/min",
-        112 => "Z-axis maximum rate, mm/min",
-        120 => "X-axis acceleration, mm/sec^2",
-        121 => "Y-axis acceleration, mm/sec^2",
-        122 => "Z-axis acceleration, mm/sec^2",
-        130 => "X-axis maximum travel, millimeters",
-        131 => "Y-axis maximum travel, millimeters",
-        132 => "Z-axis maximum travel, millimeters",
-        _ => "Unknown setting",
+    let changes = settings::diff(&state.side(state.diff_left), &state.side(state.diff_right));
+    if changes.is_empty() {
+        ui.label(RichText::new(tr("Identical")).color(theme::GREEN));
+        return;
     }
+    ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+        Grid::new("grbl_diff_grid").num_columns(4).striped(true).show(ui, |ui| {
+            for change in changes {
+                ui.label(format!("${}", change.id));
+                ui.label(settings::definition(change.id).map(|d| d.name).unwrap_or(""));
+                ui.label(change.left.as_deref().unwrap_or("—"));
+                ui.label(RichText::new(change.right.as_deref().unwrap_or("—")).color(theme::PEACH));
+                ui.end_row();
+            }
+        });
+    });
 }
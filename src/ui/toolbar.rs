@@ -65,11 +65,13 @@ pub struct ToolbarAction {
     pub open_tiling: bool,
     pub open_nesting: bool,
     pub open_job_queue: bool,
+    pub open_estimate_report: bool,
     pub open_test_fire: bool,
     pub open_preferences: bool,
     pub export_lbrn2: bool,
     pub export_svg: bool,
     pub export_job_report: bool,
+    pub export_machine_file: bool,
     pub save_job_template: bool,
     pub load_job_template: bool,
 
@@ -112,11 +114,13 @@ impl Default for ToolbarAction {
             open_tiling: false,
             open_nesting: false,
             open_job_queue: false,
+            open_estimate_report: false,
             open_test_fire: false,
             open_preferences: false,
             export_lbrn2: false,
             export_svg: false,
             export_job_report: false,
+            export_machine_file: false,
             save_job_template: false,
             load_job_template: false,
 
@@ -168,10 +172,12 @@ impl ToolbarAction {
         self.open_tiling |= other.open_tiling;
         self.open_nesting |= other.open_nesting;
         self.open_job_queue |= other.open_job_queue;
+        self.open_estimate_report |= other.open_estimate_report;
         self.open_test_fire |= other.open_test_fire;
         self.export_lbrn2 |= other.export_lbrn2;
         self.export_svg |= other.export_svg;
         self.export_job_report |= other.export_job_report;
+        self.export_machine_file |= other.export_machine_file;
         self.save_job_template |= other.save_job_template;
         self.load_job_template |= other.load_job_template;
         self.zoom_in |= other.zoom_in;
@@ -299,6 +305,13 @@ pub fn show(
                 action.export_job_report = true;
                 ui.close();
             }
+            if ui
+                .add_enabled(has_file, egui::Button::new(format!("🗄 {}", tr("Export Machine File"))))
+                .clicked()
+            {
+                action.export_machine_file = true;
+                ui.close();
+            }
         });
 
         ui.separator();
@@ -520,6 +533,10 @@ pub fn show(
                 action.open_job_queue = true;
                 ui.close();
             }
+            if ui.button(format!("⏱ {}", tr("Estimate Accuracy"))).clicked() {
+                action.open_estimate_report = true;
+                ui.close();
+            }
             if ui.button(format!("⌨ {}", tr("Shortcuts"))).clicked() {
                 action.open_shortcuts = true;
                 ui.close();
@@ -627,6 +644,13 @@ pub fn show_menu_bar(
                 action.export_job_report = true;
                 ui.close();
             }
+            if ui
+                .add_enabled(has_file, egui::Button::new(format!("🗄 {}", tr("Export Machine File"))))
+                .clicked()
+            {
+                action.export_machine_file = true;
+                ui.close();
+            }
         });
 
         // Edit / Édition
@@ -711,6 +735,10 @@ pub fn show_menu_bar(
                 action.open_job_queue = true;
                 ui.close();
             }
+            if ui.button(format!("⏱ {}", tr("Estimate Accuracy"))).clicked() {
+                action.open_estimate_report = true;
+                ui.close();
+            }
             if ui.button(format!("⌨ {}", tr("Shortcuts"))).clicked() {
                 action.open_shortcuts = true;
                 ui.close();